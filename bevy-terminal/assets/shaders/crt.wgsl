@@ -0,0 +1,65 @@
+// CRT Material Shader (CrtMaterial, `crt` feature)
+//
+// Draws the terminal texture through a curved tube: barrel distortion,
+// scanlines on every texel row, bloom around bright texels and darkened
+// corners.
+
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+struct CrtSettings {
+    scanline_intensity: f32,
+    curvature: f32,
+    bloom: f32,
+    vignette: f32,
+};
+
+@group(2) @binding(0) var<uniform> settings: CrtSettings;
+@group(2) @binding(1) var screen_texture: texture_2d<f32>;
+@group(2) @binding(2) var screen_sampler: sampler;
+
+const TAU: f32 = 6.283185307;
+
+// Barrel distortion: push uv outward by its squared distance from the center
+fn distort(uv: vec2<f32>) -> vec2<f32> {
+    let centered = uv * 2.0 - 1.0;
+    let bent = centered * (1.0 + settings.curvature * dot(centered, centered));
+    return bent * 0.5 + 0.5;
+}
+
+// Explicit level, so sampling past the tube's edge needs no uniform control flow
+fn sample(uv: vec2<f32>) -> vec3<f32> {
+    return textureSampleLevel(screen_texture, screen_sampler, uv, 0.0).rgb;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let uv = distort(in.uv);
+    let size = vec2<f32>(textureDimensions(screen_texture));
+    let texel = 1.0 / size;
+    var color = sample(uv);
+
+    // Bloom: the bright parts of the eight neighbours 1.5 texels out glow
+    // over this one
+    var glow = vec3<f32>(0.0);
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            if (x != 0 || y != 0) {
+                let neighbour = sample(uv + vec2<f32>(f32(x), f32(y)) * texel * 1.5);
+                glow += max(neighbour - vec3<f32>(0.5), vec3<f32>(0.0));
+            }
+        }
+    }
+    color += glow / 8.0 * settings.bloom;
+
+    // Scanlines: darkest between texel rows
+    let row = fract(uv.y * size.y);
+    color *= 1.0 - settings.scanline_intensity * (0.5 + 0.5 * cos(row * TAU));
+
+    // Vignette: fades toward the edges, untouched at the center
+    let edge = uv * (1.0 - uv);
+    color *= mix(1.0, sqrt(clamp(edge.x * edge.y * 16.0, 0.0, 1.0)), settings.vignette);
+
+    // Past the bent edge is the tube's black border
+    let inside = all(uv >= vec2<f32>(0.0)) && all(uv <= vec2<f32>(1.0));
+    return vec4<f32>(select(vec3<f32>(0.0), color, inside), 1.0);
+}
@@ -7,6 +7,29 @@ struct TerminalUniforms {
     cell_height: u32,
     atlas_cols: u32,
     atlas_rows: u32,
+    // 0 = none, 1 = top, 2 = bottom (STATUS_BAR_* in gpu_types.rs)
+    status_bar: u32,
+    watermark_col: u32,
+    watermark_row: u32,
+    // 0 = no watermark
+    watermark_len: u32,
+    watermark_opacity: f32,
+    // TerminalClock::blink_phase, 0.0-1.0
+    blink_phase: f32,
+    // 0 = hard, 1 = fade (CURSOR_BLINK_* in gpu_types.rs)
+    cursor_blink: u32,
+    // Packed like cell colors
+    cursor_color: u32,
+    // 0 = block, 1 = bar, 2 = underline (CURSOR_STYLE_* in gpu_types.rs)
+    cursor_style: u32,
+    // Packed like cell colors, alpha is the highlight's opacity
+    selection_color: u32,
+    // TerminalTheme::cursor_alpha, 0.0-1.0
+    cursor_opacity: f32,
+    // Pixel the dispatch starts at (a lone cursor cell, or 0,0)
+    origin_x: u32,
+    origin_y: u32,
+    _padding0: u32,
 };
 
 struct TerminalCell {
@@ -16,10 +39,44 @@ struct TerminalCell {
     flags: u32,
 };
 
+// Glyph index of a space: background only, no atlas sample (GLYPH_BLANK in gpu_types.rs)
+const GLYPH_BLANK: u32 = 0xFFFFFFFFu;
+
+// GpuTerminalCell::flags bits (CELL_FLAG_* in gpu_types.rs)
+const CELL_FLAG_BOLD: u32 = 1u;
+const CELL_FLAG_ITALIC: u32 = 2u;
+const CELL_FLAG_UNDERLINE: u32 = 4u;
+const CELL_FLAG_INVERSE: u32 = 8u;
+const CELL_FLAG_WIDE: u32 = 16u;
+const CELL_FLAG_WIDE_SPACER: u32 = 32u;
+const CELL_FLAG_CURSOR: u32 = 256u;
+const CELL_FLAG_SELECTED: u32 = 512u;
+const CELL_FLAG_COLOR_GLYPH: u32 = 1024u;
+// Horizontal shift per pixel of height for faux italic
+const ITALIC_SLANT: f32 = 0.2;
+// How far bold text moves toward white
+const BOLD_LIGHTEN: f32 = 0.25;
+
 @group(0) @binding(0) var<uniform> uniforms: TerminalUniforms;
 @group(0) @binding(1) var<storage, read> grid: array<TerminalCell>;
 @group(0) @binding(2) var atlas_texture: texture_2d<f32>;
+// Storage format follows TerminalConfig::texture_format (texture_format_shader_def)
+#ifdef OUTPUT_BGRA8UNORM
+@group(0) @binding(3) var output_texture: texture_storage_2d<bgra8unorm, write>;
+#else
+#ifdef OUTPUT_RGBA16FLOAT
+@group(0) @binding(3) var output_texture: texture_storage_2d<rgba16float, write>;
+#else
+#ifdef OUTPUT_RGBA32FLOAT
+@group(0) @binding(3) var output_texture: texture_storage_2d<rgba32float, write>;
+#else
 @group(0) @binding(3) var output_texture: texture_storage_2d<rgba8unorm, write>;
+#endif
+#endif
+#endif
+@group(0) @binding(4) var<storage, read> status_bar: array<TerminalCell>;
+// Watermark glyphs; only glyph_index and fg_color are used
+@group(0) @binding(5) var<storage, read> watermark: array<TerminalCell>;
 
 fn unpack_color(packed: u32) -> vec4<f32> {
     // Packed as 0xAABBGGRR (little endian)
@@ -30,11 +87,38 @@ fn unpack_color(packed: u32) -> vec4<f32> {
     return vec4<f32>(r, g, b, a);
 }
 
+const CURSOR_BLINK_FADE: u32 = 1u;
+const TAU: f32 = 6.283185307;
+
+// Cursor alpha for the current blink phase (CursorBlinkStyle::alpha)
+fn cursor_alpha() -> f32 {
+    if (uniforms.cursor_blink == CURSOR_BLINK_FADE) {
+        return 0.5 + 0.5 * cos(uniforms.blink_phase * TAU);
+    }
+    return select(0.0, 1.0, uniforms.blink_phase < 0.5);
+}
+
+const CURSOR_STYLE_BLOCK: u32 = 0u;
+const CURSOR_STYLE_BAR: u32 = 1u;
+const CURSOR_STYLE_UNDERLINE: u32 = 2u;
+
+// Whether the cursor covers this texel of its cell (CursorStyle::covers)
+fn cursor_covers(intra_x: u32, intra_y: u32) -> bool {
+    if (uniforms.cursor_style == CURSOR_STYLE_BAR) {
+        return intra_x < max(uniforms.cell_width / 8u, 1u);
+    }
+    if (uniforms.cursor_style == CURSOR_STYLE_UNDERLINE) {
+        return intra_y + max(uniforms.cell_height / 8u, 1u) >= uniforms.cell_height;
+    }
+    return true;
+}
+
 @compute @workgroup_size(8, 8, 1)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
-    let pixel = vec2<u32>(global_id.xy);
+    let pixel = vec2<u32>(global_id.xy) + vec2<u32>(uniforms.origin_x, uniforms.origin_y);
+    let status_rows = select(0u, 1u, uniforms.status_bar != 0u);
     let width = uniforms.term_cols * uniforms.cell_width;
-    let height = uniforms.term_rows * uniforms.cell_height;
+    let height = (uniforms.term_rows + status_rows) * uniforms.cell_height;
 
     if (pixel.x >= width || pixel.y >= height) {
         return;
@@ -43,9 +127,16 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     // Identify which cell we are in
     let cell_x = pixel.x / uniforms.cell_width;
     let cell_y = pixel.y / uniforms.cell_height;
-    let cell_index = cell_y * uniforms.term_cols + cell_x;
 
-    let cell = grid[cell_index];
+    // The status bar row takes the top or bottom texture row; the grid shifts around it
+    var cell: TerminalCell;
+    if ((uniforms.status_bar == 1u && cell_y == 0u)
+        || (uniforms.status_bar == 2u && cell_y == uniforms.term_rows)) {
+        cell = status_bar[cell_x];
+    } else {
+        let grid_y = select(cell_y, cell_y - 1u, uniforms.status_bar == 1u);
+        cell = grid[grid_y * uniforms.term_cols + cell_x];
+    }
 
     // Identify pixel within cell
     let intra_x = pixel.x % uniforms.cell_width;
@@ -57,23 +148,89 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     // Glyph index 0 -> col 0, row 0
     // Glyph index 1 -> col 1, row 0
     let glyph_idx = cell.glyph_index;
-    let atlas_col = glyph_idx % uniforms.atlas_cols;
-    let atlas_row = glyph_idx / uniforms.atlas_cols;
 
-    let atlas_x = atlas_col * uniforms.cell_width + intra_x;
-    let atlas_y = atlas_row * uniforms.cell_height + intra_y;
+    // A wide character spans its cell and the spacer after it, the glyph
+    // two atlas slots wide; positions below are within that span
+    let spacer = (cell.flags & CELL_FLAG_WIDE_SPACER) != 0u;
+    let span_x = intra_x + select(0u, uniforms.cell_width, spacer);
+    let span_width = select(1u, 2u, spacer || (cell.flags & CELL_FLAG_WIDE) != 0u) * uniforms.cell_width;
+
+    // Faux italic: shear the upright glyph, shifting rows above the cell
+    // middle right and rows below it left
+    var glyph_x = i32(span_x);
+    if ((cell.flags & CELL_FLAG_ITALIC) != 0u) {
+        let from_middle = f32(uniforms.cell_height) * 0.5 - f32(intra_y);
+        glyph_x -= i32(round(from_middle * ITALIC_SLANT));
+    }
 
-    // Load glyph pixel (using 0 mip level)
-    // textureLoad requires i32 coordinates
-    let glyph_color = textureLoad(atlas_texture, vec2<i32>(i32(atlas_x), i32(atlas_y)), 0);
-    let alpha = glyph_color.a; // Alpha contains the glyph shape
+    // Load glyph pixel (using 0 mip level) from the slot glyph_x falls in.
+    // textureLoad requires i32 coordinates; blank cells and sheared samples
+    // outside the glyph's span are empty
+    var texel = vec4<f32>(0.0);
+    if (glyph_idx != GLYPH_BLANK && glyph_x >= 0 && glyph_x < i32(span_width)) {
+        let slot = glyph_idx + u32(glyph_x) / uniforms.cell_width;
+        let atlas_x = (slot % uniforms.atlas_cols) * uniforms.cell_width + u32(glyph_x) % uniforms.cell_width;
+        let atlas_y = (slot / uniforms.atlas_cols) * uniforms.cell_height + intra_y;
+        texel = textureLoad(atlas_texture, vec2<i32>(i32(atlas_x), i32(atlas_y)), 0);
+    }
+    var alpha = texel.a; // Alpha contains the glyph shape
+    // Underline: a solid foreground row at the bottom of the cell
+    if ((cell.flags & CELL_FLAG_UNDERLINE) != 0u && intra_y == uniforms.cell_height - 1u) {
+        alpha = 1.0;
+    }
 
     // Unpack foreground and background colors
-    let fg = unpack_color(cell.fg_color);
-    let bg = unpack_color(cell.bg_color);
+    var fg = unpack_color(cell.fg_color);
+    var bg = unpack_color(cell.bg_color);
+
+    // Bold brightens the foreground; inverse swaps it with the background
+    if ((cell.flags & CELL_FLAG_BOLD) != 0u) {
+        fg = vec4<f32>(mix(fg.rgb, vec3<f32>(1.0), BOLD_LIGHTEN), fg.a);
+    }
+    if ((cell.flags & CELL_FLAG_INVERSE) != 0u) {
+        let swapped = fg;
+        fg = bg;
+        bg = swapped;
+    }
+    // Color emoji bring their own colors in place of the foreground
+    if ((cell.flags & CELL_FLAG_COLOR_GLYPH) != 0u && texel.a > 0.0) {
+        fg = vec4<f32>(texel.rgb, fg.a);
+    }
 
-    // Blend foreground/background based on glyph alpha
-    let final_color = mix(bg, fg, alpha);
+    // Cursor, blending back to normal as the blink hides it
+    // (CursorStyle::composite). A block goes over the text, which shows in
+    // the background color; a bar or underline goes beneath it. Below full
+    // opacity the cell shows through.
+    if ((cell.flags & CELL_FLAG_CURSOR) != 0u && cursor_covers(span_x, intra_y)) {
+        let blink = cursor_alpha() * uniforms.cursor_opacity;
+        if (uniforms.cursor_style == CURSOR_STYLE_BLOCK) {
+            let inverted_fg = bg;
+            fg = mix(fg, inverted_fg, blink);
+        }
+        bg = mix(bg, unpack_color(uniforms.cursor_color), blink);
+    }
+
+    // Blend foreground/background based on glyph alpha. Atlas texels are white
+    // with coverage in alpha, so edges fade fg -> bg (never through black).
+    var final_color = mix(bg, fg, alpha);
+
+    // Selection: a translucent highlight over the cell, text included, so
+    // the text stays readable (TerminalTheme::selection_alpha)
+    if ((cell.flags & CELL_FLAG_SELECTED) != 0u) {
+        let highlight = unpack_color(uniforms.selection_color);
+        final_color = vec4<f32>(mix(final_color.rgb, highlight.rgb, highlight.a), final_color.a);
+    }
+
+    // Watermark: upright glyphs blended over whatever is underneath
+    if (cell_y == uniforms.watermark_row
+        && cell_x >= uniforms.watermark_col
+        && cell_x - uniforms.watermark_col < uniforms.watermark_len) {
+        let mark = watermark[cell_x - uniforms.watermark_col];
+        let mark_x = (mark.glyph_index % uniforms.atlas_cols) * uniforms.cell_width + intra_x;
+        let mark_y = (mark.glyph_index / uniforms.atlas_cols) * uniforms.cell_height + intra_y;
+        let mark_alpha = textureLoad(atlas_texture, vec2<i32>(i32(mark_x), i32(mark_y)), 0).a;
+        final_color = mix(final_color, unpack_color(mark.fg_color), mark_alpha * uniforms.watermark_opacity);
+    }
 
     // Write to output
     textureStore(output_texture, vec2<i32>(i32(pixel.x), i32(pixel.y)), final_color);
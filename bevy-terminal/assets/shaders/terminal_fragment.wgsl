@@ -0,0 +1,237 @@
+// Terminal Fragment Shader
+//
+// Fallback for devices without compute shaders or storage textures (WebGL2).
+// One instanced quad per cell, drawn into the terminal texture as a render
+// target. Per-pixel output matches terminal.wgsl.
+
+struct TerminalUniforms {
+    term_cols: u32,
+    term_rows: u32,
+    cell_width: u32,
+    cell_height: u32,
+    atlas_cols: u32,
+    atlas_rows: u32,
+    // 0 = none, 1 = top, 2 = bottom (STATUS_BAR_* in gpu_types.rs)
+    status_bar: u32,
+    watermark_col: u32,
+    watermark_row: u32,
+    // 0 = no watermark
+    watermark_len: u32,
+    watermark_opacity: f32,
+    // TerminalClock::blink_phase, 0.0-1.0
+    blink_phase: f32,
+    // 0 = hard, 1 = fade (CURSOR_BLINK_* in gpu_types.rs)
+    cursor_blink: u32,
+    // Packed like cell colors
+    cursor_color: u32,
+    // 0 = block, 1 = bar, 2 = underline (CURSOR_STYLE_* in gpu_types.rs)
+    cursor_style: u32,
+    // Packed like cell colors, alpha is the highlight's opacity
+    selection_color: u32,
+    // TerminalTheme::cursor_alpha, 0.0-1.0
+    cursor_opacity: f32,
+    // Compute backend only
+    origin_x: u32,
+    origin_y: u32,
+    _padding0: u32,
+};
+
+// GpuCellInstance, minus padding
+struct CellInstance {
+    @location(0) glyph_index: u32,
+    @location(1) fg_color: u32,
+    @location(2) bg_color: u32,
+    @location(3) flags: u32,
+    @location(4) column: u32,
+    @location(5) row: u32,
+    @location(6) kind: u32,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) @interpolate(flat) glyph_index: u32,
+    @location(1) @interpolate(flat) fg_color: u32,
+    @location(2) @interpolate(flat) bg_color: u32,
+    @location(3) @interpolate(flat) flags: u32,
+    @location(4) @interpolate(flat) column: u32,
+    @location(5) @interpolate(flat) row: u32,
+    @location(6) @interpolate(flat) kind: u32,
+};
+
+// Glyph index of a space: background only, no atlas sample (GLYPH_BLANK in gpu_types.rs)
+const GLYPH_BLANK: u32 = 0xFFFFFFFFu;
+
+// GpuTerminalCell::flags bits (CELL_FLAG_* in gpu_types.rs)
+const CELL_FLAG_BOLD: u32 = 1u;
+const CELL_FLAG_ITALIC: u32 = 2u;
+const CELL_FLAG_UNDERLINE: u32 = 4u;
+const CELL_FLAG_INVERSE: u32 = 8u;
+const CELL_FLAG_WIDE: u32 = 16u;
+const CELL_FLAG_WIDE_SPACER: u32 = 32u;
+const CELL_FLAG_CURSOR: u32 = 256u;
+const CELL_FLAG_SELECTED: u32 = 512u;
+const CELL_FLAG_COLOR_GLYPH: u32 = 1024u;
+// Horizontal shift per pixel of height for faux italic
+const ITALIC_SLANT: f32 = 0.2;
+// How far bold text moves toward white
+const BOLD_LIGHTEN: f32 = 0.25;
+
+// GpuCellInstance::kind (INSTANCE_* in gpu_types.rs)
+const INSTANCE_WATERMARK: u32 = 1u;
+
+@group(0) @binding(0) var<uniform> uniforms: TerminalUniforms;
+@group(0) @binding(1) var atlas_texture: texture_2d<f32>;
+
+fn unpack_color(packed: u32) -> vec4<f32> {
+    // Packed as 0xAABBGGRR (little endian)
+    let r = f32(packed & 0xFFu) / 255.0;
+    let g = f32((packed >> 8u) & 0xFFu) / 255.0;
+    let b = f32((packed >> 16u) & 0xFFu) / 255.0;
+    let a = f32((packed >> 24u) & 0xFFu) / 255.0;
+    return vec4<f32>(r, g, b, a);
+}
+
+const CURSOR_BLINK_FADE: u32 = 1u;
+const TAU: f32 = 6.283185307;
+
+// Cursor alpha for the current blink phase (CursorBlinkStyle::alpha)
+fn cursor_alpha() -> f32 {
+    if (uniforms.cursor_blink == CURSOR_BLINK_FADE) {
+        return 0.5 + 0.5 * cos(uniforms.blink_phase * TAU);
+    }
+    return select(0.0, 1.0, uniforms.blink_phase < 0.5);
+}
+
+const CURSOR_STYLE_BLOCK: u32 = 0u;
+const CURSOR_STYLE_BAR: u32 = 1u;
+const CURSOR_STYLE_UNDERLINE: u32 = 2u;
+
+// Whether the cursor covers this texel of its cell (CursorStyle::covers)
+fn cursor_covers(intra_x: u32, intra_y: u32) -> bool {
+    if (uniforms.cursor_style == CURSOR_STYLE_BAR) {
+        return intra_x < max(uniforms.cell_width / 8u, 1u);
+    }
+    if (uniforms.cursor_style == CURSOR_STYLE_UNDERLINE) {
+        return intra_y + max(uniforms.cell_height / 8u, 1u) >= uniforms.cell_height;
+    }
+    return true;
+}
+
+@vertex
+fn vertex(@builtin(vertex_index) vertex_index: u32, instance: CellInstance) -> VertexOutput {
+    // Two triangles: (0,0) (1,0) (0,1) / (0,1) (1,0) (1,1)
+    let corner = vec2<f32>(
+        select(0.0, 1.0, vertex_index == 1u || vertex_index == 4u || vertex_index == 5u),
+        select(0.0, 1.0, vertex_index == 2u || vertex_index == 3u || vertex_index == 5u),
+    );
+
+    let status_rows = select(0u, 1u, uniforms.status_bar != 0u);
+    let size = vec2<f32>(
+        f32(uniforms.term_cols * uniforms.cell_width),
+        f32((uniforms.term_rows + status_rows) * uniforms.cell_height),
+    );
+    let cell_size = vec2<f32>(f32(uniforms.cell_width), f32(uniforms.cell_height));
+    let pixel = (vec2<f32>(f32(instance.column), f32(instance.row)) + corner) * cell_size;
+
+    var out: VertexOutput;
+    // Texture row 0 is the top: flip y into clip space
+    out.position = vec4<f32>(pixel.x / size.x * 2.0 - 1.0, 1.0 - pixel.y / size.y * 2.0, 0.0, 1.0);
+    out.glyph_index = instance.glyph_index;
+    out.fg_color = instance.fg_color;
+    out.bg_color = instance.bg_color;
+    out.flags = instance.flags;
+    out.column = instance.column;
+    out.row = instance.row;
+    out.kind = instance.kind;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Pixel centers sit at .5, so truncation gives the texel
+    let intra_x = u32(in.position.x) - in.column * uniforms.cell_width;
+    let intra_y = u32(in.position.y) - in.row * uniforms.cell_height;
+
+    let atlas_col = in.glyph_index % uniforms.atlas_cols;
+    let atlas_row = in.glyph_index / uniforms.atlas_cols;
+
+    // Watermark: upright glyphs blended over the cell drawn beneath
+    if (in.kind == INSTANCE_WATERMARK) {
+        let mark_x = atlas_col * uniforms.cell_width + intra_x;
+        let mark_y = atlas_row * uniforms.cell_height + intra_y;
+        let mark_alpha = textureLoad(atlas_texture, vec2<i32>(i32(mark_x), i32(mark_y)), 0).a;
+        let mark = unpack_color(in.fg_color);
+        return vec4<f32>(mark.rgb, mark_alpha * uniforms.watermark_opacity);
+    }
+
+    // A wide character spans its cell and the spacer after it, the glyph
+    // two atlas slots wide; positions below are within that span
+    let spacer = (in.flags & CELL_FLAG_WIDE_SPACER) != 0u;
+    let span_x = intra_x + select(0u, uniforms.cell_width, spacer);
+    let span_width = select(1u, 2u, spacer || (in.flags & CELL_FLAG_WIDE) != 0u) * uniforms.cell_width;
+
+    // Faux italic: shear the upright glyph, shifting rows above the cell
+    // middle right and rows below it left
+    var glyph_x = i32(span_x);
+    if ((in.flags & CELL_FLAG_ITALIC) != 0u) {
+        let from_middle = f32(uniforms.cell_height) * 0.5 - f32(intra_y);
+        glyph_x -= i32(round(from_middle * ITALIC_SLANT));
+    }
+
+    // Sample the slot glyph_x falls in; blank cells and sheared samples
+    // outside the glyph's span are empty
+    var texel = vec4<f32>(0.0);
+    if (in.glyph_index != GLYPH_BLANK && glyph_x >= 0 && glyph_x < i32(span_width)) {
+        let slot = in.glyph_index + u32(glyph_x) / uniforms.cell_width;
+        let atlas_x = (slot % uniforms.atlas_cols) * uniforms.cell_width + u32(glyph_x) % uniforms.cell_width;
+        let atlas_y = (slot / uniforms.atlas_cols) * uniforms.cell_height + intra_y;
+        texel = textureLoad(atlas_texture, vec2<i32>(i32(atlas_x), i32(atlas_y)), 0);
+    }
+    var alpha = texel.a; // Alpha contains the glyph shape
+    // Underline: a solid foreground row at the bottom of the cell
+    if ((in.flags & CELL_FLAG_UNDERLINE) != 0u && intra_y == uniforms.cell_height - 1u) {
+        alpha = 1.0;
+    }
+
+    var fg = unpack_color(in.fg_color);
+    var bg = unpack_color(in.bg_color);
+
+    // Bold brightens the foreground; inverse swaps it with the background
+    if ((in.flags & CELL_FLAG_BOLD) != 0u) {
+        fg = vec4<f32>(mix(fg.rgb, vec3<f32>(1.0), BOLD_LIGHTEN), fg.a);
+    }
+    if ((in.flags & CELL_FLAG_INVERSE) != 0u) {
+        let swapped = fg;
+        fg = bg;
+        bg = swapped;
+    }
+    // Color emoji bring their own colors in place of the foreground
+    if ((in.flags & CELL_FLAG_COLOR_GLYPH) != 0u && texel.a > 0.0) {
+        fg = vec4<f32>(texel.rgb, fg.a);
+    }
+
+    // Cursor, blending back to normal as the blink hides it
+    // (CursorStyle::composite). A block goes over the text, which shows in
+    // the background color; a bar or underline goes beneath it. Below full
+    // opacity the cell shows through.
+    if ((in.flags & CELL_FLAG_CURSOR) != 0u && cursor_covers(span_x, intra_y)) {
+        let blink = cursor_alpha() * uniforms.cursor_opacity;
+        if (uniforms.cursor_style == CURSOR_STYLE_BLOCK) {
+            let inverted_fg = bg;
+            fg = mix(fg, inverted_fg, blink);
+        }
+        bg = mix(bg, unpack_color(uniforms.cursor_color), blink);
+    }
+
+    var color = mix(bg, fg, alpha);
+
+    // Selection: a translucent highlight over the cell, text included, so
+    // the text stays readable (TerminalTheme::selection_alpha)
+    if ((in.flags & CELL_FLAG_SELECTED) != 0u) {
+        let highlight = unpack_color(uniforms.selection_color);
+        color = vec4<f32>(mix(color.rgb, highlight.rgb, highlight.a), color.a);
+    }
+
+    // Cell colors are opaque, so the alpha blend replaces what's underneath
+    return color;
+}
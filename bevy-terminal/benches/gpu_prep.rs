@@ -0,0 +1,45 @@
+//! Times `prepare_terminal_cpu_buffer` on a near-empty and a full grid.
+//!
+//! Run with `cargo bench -p bevy-terminal --bench gpu_prep`. Spaces take the
+//! `GLYPH_BLANK` fast path (no atlas lookup here, no atlas sample in the
+//! shader), so the near-empty grid is the cheaper of the two.
+
+use bevy::prelude::*;
+use bevy_terminal::atlas::GlyphAtlas;
+use bevy_terminal::font::FontMetrics;
+use bevy_terminal::gpu_prep::prepare_terminal_cpu_buffer;
+use bevy_terminal::TerminalState;
+use std::time::{Duration, Instant};
+
+const FRAMES: u32 = 2_000;
+
+fn bench(name: &str, output: &[u8]) -> Duration {
+    let font_metrics = FontMetrics::load_cascadia_mono().expect("Font load failed");
+    let atlas = GlyphAtlas::generate_mvp(&font_metrics).expect("Atlas failed");
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes(output);
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(atlas)
+        .add_systems(Update, prepare_terminal_cpu_buffer);
+    app.world_mut().spawn(term_state);
+    app.update();
+
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        app.update();
+    }
+    let per_frame = start.elapsed() / FRAMES;
+    println!("{:<12} {:>8.1?} per frame", name, per_frame);
+    per_frame
+}
+
+fn main() {
+    // A shell prompt on an otherwise empty screen
+    bench("near-empty", b"user@host:~$ ");
+
+    // Every cell holds a glyph
+    let full: Vec<u8> = (0..120 * 30).map(|i| b'!' + (i % 94) as u8).collect();
+    bench("full", &full);
+}
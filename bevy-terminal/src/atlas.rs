@@ -2,18 +2,33 @@
 //!
 //! Pre-renders all required characters to a large texture atlas.
 //! Supports ASCII, box-drawing, and block element characters.
+//!
+//! Characters outside the pre-rendered set are rasterized on demand into
+//! free slots (`rasterize_missing_glyphs`), evicting the glyphs least
+//! recently on screen once the atlas is full. Changed slots reach the GPU
+//! texture as small region writes (`AtlasUploads`), not a whole re-upload.
+//! Wide (double-width) characters take two consecutive slots, one per cell.
 
 use ab_glyph::{point, Font, Glyph, ScaleFont};
+use alacritty_terminal::term::cell::Flags;
 use anyhow::{Context, Result};
+use bevy::asset::RenderAssetUsages;
 use bevy::prelude::*;
-use std::collections::HashMap;
-use log::info;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use std::collections::{HashMap, HashSet};
+use log::{debug, info, warn};
 
-use crate::font::FontMetrics;
+use crate::config::{FallbackGlyph, TerminalConfig};
+use crate::font::{FontMetrics, GlyphPlacement};
+use crate::gpu_types::GLYPH_BLANK;
+use crate::terminal::TerminalState;
 
-/// Atlas texture size (4096×4096 for high quality).
+/// Largest atlas texture size (4096×4096 for high quality).
 pub const ATLAS_SIZE: u32 = 4096;
 
+/// Square atlas sizes `generate` chooses from, smallest first.
+pub const ATLAS_SIZE_OPTIONS: [u32; 5] = [256, 512, 1024, 2048, ATLAS_SIZE];
+
 /// Character sets to pre-render in the atlas.
 pub struct CharacterSets;
 
@@ -69,34 +84,132 @@ pub struct GlyphAtlas {
     pub cell_width: u32,
     /// Cell height in pixels
     pub cell_height: u32,
+    /// Slots handed out so far (characters and clusters); later ones are free
+    next_slot: u32,
+    /// Baseline the characters were rasterized with
+    baseline: f32,
+    /// Characters rasterized on demand after generation
+    dynamic: DynamicGlyphs,
+    /// Slots rasterized since the last `take_dirty_slots`
+    dirty_slots: Vec<u32>,
+    /// Slot holding the tofu box, once `insert_tofu` drew it
+    tofu_slot: Option<u32>,
+    /// Grapheme clusters rasterized on demand after generation
+    #[cfg(feature = "emoji")]
+    clusters: ClusterSlots,
+}
+
+/// LRU bookkeeping for characters rasterized by `GlyphAtlas::insert_glyph`.
+///
+/// Generated characters aren't tracked here, so they are never evicted.
+#[derive(Default)]
+struct DynamicGlyphs {
+    /// Character to the last frame (see `next_frame`) it was on screen
+    last_seen: HashMap<char, u64>,
+    /// Characters rasterized two slots wide (`insert_wide_glyph`)
+    wide: HashSet<char>,
+    /// Slots freed by eviction
+    free: Vec<u32>,
+    /// Current frame
+    frame: u64,
+}
+
+impl DynamicGlyphs {
+    /// Take `count` consecutive slots from the free list, if it has them.
+    fn take_free_run(&mut self, count: u32) -> Option<u32> {
+        let first = *self
+            .free
+            .iter()
+            .find(|&&first| (1..count).all(|offset| self.free.contains(&(first + offset))))?;
+        self.free.retain(|slot| !(first..first + count).contains(slot));
+        Some(first)
+    }
+}
+
+/// Atlas slots handed out to grapheme clusters (`emoji` feature).
+#[cfg(feature = "emoji")]
+#[derive(Default)]
+struct ClusterSlots {
+    /// Cluster text to the glyph index of its first cell
+    index_map: HashMap<String, u32>,
+    /// Clusters drawn in color from the emoji font
+    color: HashSet<String>,
+}
+
+/// Pixels of one atlas slot, for a partial texture upload.
+#[derive(Clone, Debug)]
+pub struct AtlasRegion {
+    /// Left edge in atlas pixels
+    pub x: u32,
+    /// Top edge in atlas pixels
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// RGBA texels, row by row
+    pub data: Vec<u8>,
+}
+
+/// Atlas slots rasterized this frame, for the render world to write into
+/// the atlas texture.
+///
+/// Set by `upload_atlas_slots`. Uploading single slots keeps runtime glyphs
+/// cheap; re-uploading the image would copy the whole atlas (up to 64MB).
+#[derive(Resource, Default)]
+pub struct AtlasUploads {
+    /// Atlas image the regions belong to
+    pub handle: Option<Handle<Image>>,
+    pub regions: Vec<AtlasRegion>,
 }
 
 impl GlyphAtlas {
-    /// Generate atlas from font metrics and character set.
+    /// Generate the smallest atlas from `ATLAS_SIZE_OPTIONS` that fits `chars`.
+    ///
+    /// An ASCII-only atlas is a few hundred KB instead of the 64MB of a full
+    /// 4096×4096 texture. Use `generate_with_size` to force a size.
+    pub fn generate(font_metrics: &FontMetrics, chars: &[char]) -> Result<Self> {
+        let (cell_width, cell_height) = atlas_cell_size(font_metrics);
+        let size = fitting_atlas_size(cell_width, cell_height, chars.len()).unwrap_or(ATLAS_SIZE);
+        Self::generate_with_size(font_metrics, chars, size)
+    }
+
+    /// Smallest power-of-two square atlas (at least 256²) that holds `chars`
+    /// at the font's cell size, as (width, height).
+    ///
+    /// Unlike `generate` this isn't capped at `ATLAS_SIZE`, so a result
+    /// beyond it tells you the set won't fit. Each RGBA texel is 4 bytes.
+    pub fn required_size(font_metrics: &FontMetrics, chars: &[char]) -> (u32, u32) {
+        let (cell_width, cell_height) = atlas_cell_size(font_metrics);
+        let mut size = ATLAS_SIZE_OPTIONS[0];
+        while atlas_capacity(size, cell_width, cell_height) < chars.len() {
+            size *= 2;
+        }
+        (size, size)
+    }
+
+    /// Generate a `size`×`size` atlas from font metrics and character set.
     ///
     /// Rasterizes all characters to a single texture and builds UV map.
     ///
     /// # Arguments
     /// * `font_metrics` - Loaded font with cell dimensions
     /// * `chars` - Characters to pre-render
+    /// * `size` - Atlas width and height in pixels
     ///
     /// # Returns
     /// Atlas texture with UV coordinate map
-    pub fn generate(font_metrics: &FontMetrics, chars: &[char]) -> Result<Self> {
-        let atlas_width = ATLAS_SIZE;
-        let atlas_height = ATLAS_SIZE;
+    pub fn generate_with_size(font_metrics: &FontMetrics, chars: &[char], size: u32) -> Result<Self> {
+        let atlas_width = size;
+        let atlas_height = size;
 
         // Allocate RGBA texture (initialized to transparent black)
         let mut texture_data = vec![0u8; (atlas_width * atlas_height * 4) as usize];
 
         // Cell dimensions (rounded up for pixel alignment)
-        let cell_width = font_metrics.cell_width.ceil() as u32;
-        let cell_height = font_metrics.cell_height.ceil() as u32;
+        let (cell_width, cell_height) = atlas_cell_size(font_metrics);
 
         // Calculate atlas layout
         let cells_per_row = atlas_width / cell_width;
-        let cells_per_column = atlas_height / cell_height;
-        let max_chars = (cells_per_row * cells_per_column) as usize;
+        let max_chars = atlas_capacity(size, cell_width, cell_height);
 
         if chars.len() > max_chars {
             anyhow::bail!(
@@ -113,6 +226,12 @@ impl GlyphAtlas {
         let mut uv_map = HashMap::new();
         let mut glyph_index_map = HashMap::new();
         let scaled_font = font_metrics.font.as_scaled(font_metrics.scale);
+        let baseline = match font_metrics.placement {
+            GlyphPlacement::Baseline => font_metrics.baseline,
+            GlyphPlacement::Top => top_aligned_baseline(&scaled_font, chars)
+                .unwrap_or(font_metrics.baseline),
+            GlyphPlacement::Offset(offset) => font_metrics.baseline + offset,
+        };
 
         info!(
             "🎨 Generating glyph atlas: {} chars, {}×{} cells, {}×{} atlas",
@@ -135,13 +254,16 @@ impl GlyphAtlas {
             rasterize_glyph(
                 &scaled_font,
                 character,
-                font_metrics.baseline,
+                baseline,
                 &mut texture_data,
                 atlas_width,
-                cell_x,
-                cell_y,
-                cell_width,
-                cell_height,
+                GlyphCell {
+                    x: cell_x,
+                    y: cell_y,
+                    width: cell_width,
+                    height: cell_height,
+                },
+                font_metrics.crisp_box_drawing && is_box_drawing(character),
             );
 
             // Calculate UV coordinates (normalized 0.0-1.0)
@@ -170,6 +292,13 @@ impl GlyphAtlas {
             glyph_index_map,
             cell_width,
             cell_height,
+            next_slot: chars.len() as u32,
+            baseline,
+            dynamic: DynamicGlyphs::default(),
+            dirty_slots: Vec::new(),
+            tofu_slot: None,
+            #[cfg(feature = "emoji")]
+            clusters: ClusterSlots::default(),
         })
     }
 
@@ -180,6 +309,25 @@ impl GlyphAtlas {
             .context("Failed to generate MVP glyph atlas")
     }
 
+    /// Build the Bevy `Image` for this atlas.
+    ///
+    /// Texels hold coverage in alpha over white, which is linear data, so the
+    /// format is `Rgba8Unorm` (not sRGB). Kept in both worlds so the compute
+    /// shader and CPU-side tooling (debug views, sprites) share one image.
+    pub fn to_bevy_image(&self) -> Image {
+        Image::new(
+            Extent3d {
+                width: self.atlas_width,
+                height: self.atlas_height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            self.texture_data.clone(),
+            TextureFormat::Rgba8Unorm,
+            RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+        )
+    }
+
     /// Get UV coordinates for a character.
     ///
     /// Returns None if character is not in atlas.
@@ -188,25 +336,632 @@ impl GlyphAtlas {
     }
 
     /// Get the linear index for a character in the atlas.
+    ///
+    /// This is the `glyph_index` the shaders sample: slots run left to right,
+    /// top to bottom, so the glyph sits at column `index % cols`, row
+    /// `index / cols` with `cols = atlas_width / cell_width`, the same cell
+    /// as `get_uv`. Returns None if the character is not in the atlas.
     pub fn get_glyph_index(&self, character: char) -> Option<u32> {
         self.glyph_index_map.get(&character).copied()
     }
+
+    /// Glyph index to draw for characters missing from the atlas.
+    ///
+    /// `GLYPH_BLANK` for `FallbackGlyph::Blank`, and whenever the fallback
+    /// itself has no slot yet (a missing character, or tofu before
+    /// `insert_tofu`).
+    pub fn fallback_index(&self, fallback: FallbackGlyph) -> u32 {
+        match fallback {
+            FallbackGlyph::Char(character) => self.get_glyph_index(character).unwrap_or(GLYPH_BLANK),
+            FallbackGlyph::Tofu => self.tofu_slot.unwrap_or(GLYPH_BLANK),
+            FallbackGlyph::Blank => GLYPH_BLANK,
+        }
+    }
+
+    /// Glyph index of the tofu box, if drawn (see `insert_tofu`).
+    pub fn tofu_index(&self) -> Option<u32> {
+        self.tofu_slot
+    }
+
+    /// Draw the tofu box used by `FallbackGlyph::Tofu` into a free slot.
+    ///
+    /// Returns its glyph index, or None when the atlas is full. Never
+    /// evicted. Only `texture_data` changes; the slot is queued for
+    /// `take_dirty_slots`.
+    pub fn insert_tofu(&mut self) -> Option<u32> {
+        if let Some(index) = self.tofu_slot {
+            return Some(index);
+        }
+        let index = self.allocate_slot()?;
+        let cell = self.slot_cell(index);
+        self.clear_slot(&cell);
+        draw_tofu(&mut self.texture_data, self.atlas_width, &cell);
+        self.tofu_slot = Some(index);
+        self.dirty_slots.push(index);
+        Some(index)
+    }
+
+    /// Characters in the atlas, in glyph index order.
+    ///
+    /// Regenerating from this list keeps every glyph index stable.
+    pub fn chars(&self) -> Vec<char> {
+        let mut chars: Vec<(u32, char)> = self
+            .glyph_index_map
+            .iter()
+            .map(|(&character, &index)| (index, character))
+            .collect();
+        chars.sort_unstable();
+        chars.into_iter().map(|(_, character)| character).collect()
+    }
+
+    /// Glyph slots in the atlas (used and free).
+    pub fn capacity(&self) -> usize {
+        atlas_capacity(self.atlas_width, self.cell_width, self.cell_height)
+    }
+
+    /// Whether `glyph_count` glyphs fit in an atlas this size.
+    pub fn fits(&self, glyph_count: usize) -> bool {
+        glyph_count <= self.capacity()
+    }
+
+    /// Whether a character has a glyph in the atlas.
+    pub fn contains(&self, character: char) -> bool {
+        self.glyph_index_map.contains_key(&character)
+    }
+
+    /// Fraction of characters in `text` present in the atlas (0.0-1.0).
+    ///
+    /// Missing characters render as the fallback glyph. Control characters
+    /// (newlines, tabs) are ignored; text without printable characters is
+    /// fully covered.
+    pub fn coverage(&self, text: &str) -> f32 {
+        let (present, total) = text
+            .chars()
+            .filter(|c| !c.is_control())
+            .fold((0usize, 0usize), |(present, total), c| {
+                (present + usize::from(self.contains(c)), total + 1)
+            });
+
+        if total == 0 {
+            1.0
+        } else {
+            present as f32 / total as f32
+        }
+    }
+
+    /// Start a new frame for glyph eviction.
+    ///
+    /// Mark this frame's on-screen characters with `touch` afterwards.
+    pub fn next_frame(&mut self) {
+        self.dynamic.frame += 1;
+    }
+
+    /// Record that `character` is on screen this frame, so `insert_glyph`
+    /// won't evict it. Returns whether the atlas has a glyph for it.
+    pub fn touch(&mut self, character: char) -> bool {
+        match self.dynamic.last_seen.get_mut(&character) {
+            Some(seen) => {
+                *seen = self.dynamic.frame;
+                true
+            }
+            None => self.contains(character),
+        }
+    }
+
+    /// Rasterize `character` into a free slot and return its glyph index.
+    ///
+    /// Once the atlas is full, the on-demand glyph least recently on screen
+    /// (see `touch`) gives up its slot. Generated characters and glyphs on
+    /// screen this frame are never evicted; with nothing left to evict this
+    /// returns None and the character keeps its fallback glyph.
+    ///
+    /// Only `texture_data` changes; the slot is queued for
+    /// `take_dirty_slots`.
+    pub fn insert_glyph(&mut self, font_metrics: &FontMetrics, character: char) -> Option<u32> {
+        self.insert_dynamic(font_metrics, character, 1)
+    }
+
+    /// Rasterize a wide (double-width) `character` across two consecutive
+    /// slots, like `insert_glyph`. The glyph index is the left half's; the
+    /// shaders draw the right half in the spacer cell (`CELL_FLAG_WIDE`).
+    ///
+    /// A character already in the atlas keeps its glyph, even if narrow.
+    pub fn insert_wide_glyph(&mut self, font_metrics: &FontMetrics, character: char) -> Option<u32> {
+        self.insert_dynamic(font_metrics, character, 2)
+    }
+
+    /// Whether `character` has a two-slot glyph (`insert_wide_glyph`).
+    pub fn is_wide(&self, character: char) -> bool {
+        self.dynamic.wide.contains(&character)
+    }
+
+    /// Rasterize `character` centered across `width` free slots.
+    fn insert_dynamic(&mut self, font_metrics: &FontMetrics, character: char, width: u32) -> Option<u32> {
+        if let Some(index) = self.get_glyph_index(character) {
+            self.touch(character);
+            return Some(index);
+        }
+
+        let first = self.allocate_slots(width)?;
+        let scaled_font = font_metrics.font.as_scaled(font_metrics.scale);
+        let aliased = font_metrics.crisp_box_drawing && is_box_drawing(character);
+        if width == 1 {
+            let cell = self.slot_cell(first);
+            self.clear_slot(&cell);
+            rasterize_glyph(
+                &scaled_font,
+                character,
+                self.baseline,
+                &mut self.texture_data,
+                self.atlas_width,
+                cell,
+                aliased,
+            );
+            self.dirty_slots.push(first);
+        } else {
+            // The slots may wrap onto the next atlas row: draw the glyph into
+            // a strip, then copy it in
+            let strip_width = width * self.cell_width;
+            let mut strip = vec![0u8; (strip_width * self.cell_height * 4) as usize];
+            rasterize_glyph(
+                &scaled_font,
+                character,
+                self.baseline,
+                &mut strip,
+                strip_width,
+                GlyphCell {
+                    x: 0,
+                    y: 0,
+                    width: strip_width,
+                    height: self.cell_height,
+                },
+                aliased,
+            );
+            self.write_strip(first, width, &strip);
+        }
+
+        self.uv_map.insert(character, self.slot_uv(first));
+        self.glyph_index_map.insert(character, first);
+        self.dynamic.last_seen.insert(character, self.dynamic.frame);
+        if width > 1 {
+            self.dynamic.wide.insert(character);
+        }
+        Some(first)
+    }
+
+    /// `count` consecutive free slots for one glyph: recycled ones, never
+    /// used ones, or those of the on-demand glyphs least recently on screen.
+    fn allocate_slots(&mut self, count: u32) -> Option<u32> {
+        loop {
+            if let Some(first) = self.dynamic.take_free_run(count) {
+                return Some(first);
+            }
+            if self.fits((self.next_slot + count) as usize) {
+                self.next_slot += count;
+                return Some(self.next_slot - count);
+            }
+            self.evict_least_recent()?;
+        }
+    }
+
+    /// A free slot for one glyph (see `allocate_slots`).
+    fn allocate_slot(&mut self) -> Option<u32> {
+        self.allocate_slots(1)
+    }
+
+    /// Free the slots of the on-demand glyph least recently on screen.
+    ///
+    /// Returns None when every on-demand glyph is on screen this frame.
+    fn evict_least_recent(&mut self) -> Option<()> {
+        let frame = self.dynamic.frame;
+        let (&evicted, _) = self
+            .dynamic
+            .last_seen
+            .iter()
+            .filter(|&(_, &seen)| seen < frame)
+            .min_by_key(|&(_, &seen)| seen)?;
+        self.dynamic.last_seen.remove(&evicted);
+        self.uv_map.remove(&evicted);
+        debug!("♻️  Evicting glyph {:?} from the atlas", evicted);
+        let width = if self.dynamic.wide.remove(&evicted) { 2 } else { 1 };
+        let first = self.glyph_index_map.remove(&evicted)?;
+        self.dynamic.free.extend(first..first + width);
+        Some(())
+    }
+
+    /// Copy a strip `width` cells wide into the slots from `first` on,
+    /// queueing them for `take_dirty_slots`.
+    fn write_strip(&mut self, first: u32, width: u32, strip: &[u8]) {
+        let strip_width = width * self.cell_width;
+        let row_bytes = (self.cell_width * 4) as usize;
+        for cell in 0..width {
+            let index = first + cell;
+            let GlyphCell { x, y, .. } = self.slot_cell(index);
+            for row in 0..self.cell_height {
+                let src = ((row * strip_width + cell * self.cell_width) * 4) as usize;
+                let dst = (((y + row) * self.atlas_width + x) * 4) as usize;
+                self.texture_data[dst..dst + row_bytes].copy_from_slice(&strip[src..src + row_bytes]);
+            }
+            self.dirty_slots.push(index);
+        }
+    }
+
+    /// Make a recycled slot transparent again.
+    fn clear_slot(&mut self, cell: &GlyphCell) {
+        for y in cell.y..cell.y + cell.height {
+            let start = ((y * self.atlas_width + cell.x) * 4) as usize;
+            self.texture_data[start..start + (cell.width * 4) as usize].fill(0);
+        }
+    }
+
+    /// Slots rasterized since the last call, in order.
+    pub fn take_dirty_slots(&mut self) -> Vec<u32> {
+        std::mem::take(&mut self.dirty_slots)
+    }
+
+    /// Pixels of slot `index`, for uploading it alone.
+    pub fn slot_region(&self, index: u32) -> AtlasRegion {
+        let GlyphCell { x, y, width, height } = self.slot_cell(index);
+        let mut data = Vec::with_capacity((width * height * 4) as usize);
+        for row in y..y + height {
+            let start = ((row * self.atlas_width + x) * 4) as usize;
+            data.extend_from_slice(&self.texture_data[start..start + (width * 4) as usize]);
+        }
+        AtlasRegion { x, y, width, height, data }
+    }
+
+    /// Pixel rectangle of slot `index`.
+    fn slot_cell(&self, index: u32) -> GlyphCell {
+        let cells_per_row = self.atlas_width / self.cell_width;
+        GlyphCell {
+            x: (index % cells_per_row) * self.cell_width,
+            y: (index / cells_per_row) * self.cell_height,
+            width: self.cell_width,
+            height: self.cell_height,
+        }
+    }
+
+    /// UV rectangle of slot `index`.
+    fn slot_uv(&self, index: u32) -> Rect {
+        let cell = self.slot_cell(index);
+        let (width, height) = (self.atlas_width as f32, self.atlas_height as f32);
+        Rect {
+            min: Vec2::new(cell.x as f32 / width, cell.y as f32 / height),
+            max: Vec2::new((cell.x + cell.width) as f32 / width, (cell.y + cell.height) as f32 / height),
+        }
+    }
+}
+
+/// Rasterizes on-screen characters the atlas has no glyph for.
+///
+/// System: Update
+/// Runs: Every frame, after polling and before `prepare_terminal_cpu_buffer`
+///
+/// Also marks every on-screen glyph as used this frame, so eviction only
+/// picks glyphs that have scrolled out of view, and makes sure each
+/// terminal's configured `FallbackGlyph` has a slot. All terminals share
+/// the atlas.
+pub fn rasterize_missing_glyphs(
+    terminals: Query<(&TerminalState, &TerminalConfig)>,
+    font_metrics: Option<Res<FontMetrics>>,
+    atlas: Option<ResMut<GlyphAtlas>>,
+    mut warned_full: Local<bool>,
+) {
+    let (Some(font_metrics), Some(mut atlas)) = (font_metrics, atlas) else {
+        return;
+    };
+
+    // Marking glyphs used is bookkeeping, not a change to the atlas
+    let mut missing: Vec<(char, bool)> = Vec::new();
+    {
+        let atlas = atlas.bypass_change_detection();
+        atlas.next_frame();
+        for (_, config) in &terminals {
+            if let FallbackGlyph::Char(character) = config.fallback_glyph {
+                if !atlas.touch(character) && !missing.contains(&(character, false)) {
+                    missing.push((character, false));
+                }
+            }
+        }
+        for (term_state, _) in &terminals {
+            let term = term_state.term.lock();
+            for indexed in term.grid().display_iter() {
+                let character = indexed.cell.c;
+                if character != ' '
+                    && character != '\0'
+                    && !atlas.touch(character)
+                    && !missing.iter().any(|&(missing, _)| missing == character)
+                {
+                    missing.push((character, indexed.cell.flags.contains(Flags::WIDE_CHAR)));
+                }
+            }
+        }
+    }
+    let tofu = terminals.iter().any(|(_, config)| config.fallback_glyph == FallbackGlyph::Tofu);
+    if tofu && atlas.tofu_index().is_none() && atlas.insert_tofu().is_none() {
+        warn!("⚠️  Glyph atlas full, no room for the tofu fallback glyph");
+    }
+    if missing.is_empty() {
+        return;
+    }
+
+    let mut added = 0;
+    for &(character, wide) in &missing {
+        let index = match wide {
+            true => atlas.insert_wide_glyph(&font_metrics, character),
+            false => atlas.insert_glyph(&font_metrics, character),
+        };
+        if index.is_none() {
+            if !std::mem::replace(&mut *warned_full, true) {
+                warn!("⚠️  Glyph atlas full, {:?} gets the fallback glyph", character);
+            }
+            break;
+        }
+        added += 1;
+    }
+    if added > 0 {
+        debug!("🔤 Rasterized {} glyphs on demand", added);
+    }
+}
+
+/// Queues slots rasterized this frame for the GPU and copies them into the
+/// main-world atlas image.
+///
+/// System: Update
+/// Runs: Every frame, after the glyph and cluster rasterizers
+pub fn upload_atlas_slots(
+    atlas: Option<ResMut<GlyphAtlas>>,
+    mut uploads: ResMut<AtlasUploads>,
+    mut images: Option<ResMut<Assets<Image>>>,
+) {
+    let Some(mut atlas) = atlas else {
+        return;
+    };
+    if atlas.dirty_slots.is_empty() {
+        return;
+    }
+
+    let regions: Vec<AtlasRegion> = atlas
+        .take_dirty_slots()
+        .into_iter()
+        .map(|index| atlas.slot_region(index))
+        .collect();
+
+    // Untracked: a change event would re-upload the whole image
+    let image = atlas
+        .texture_handle
+        .as_ref()
+        .zip(images.as_deref_mut())
+        .and_then(|(handle, images)| images.get_mut_untracked(handle));
+    if let Some(data) = image.and_then(|image| image.data.as_mut()) {
+        for region in &regions {
+            let row_bytes = (region.width * 4) as usize;
+            for (row, pixels) in region.data.chunks_exact(row_bytes).enumerate() {
+                let start = (((region.y + row as u32) * atlas.atlas_width + region.x) * 4) as usize;
+                data[start..start + row_bytes].copy_from_slice(pixels);
+            }
+        }
+    }
+
+    uploads.handle = atlas.texture_handle.clone();
+    uploads.regions = regions;
+}
+
+#[cfg(feature = "emoji")]
+impl GlyphAtlas {
+    /// Glyph index of a rasterized cluster's first cell; the cluster's
+    /// other cells follow at consecutive indices.
+    pub fn get_cluster_index(&self, cluster: &str) -> Option<u32> {
+        self.clusters.index_map.get(cluster).copied()
+    }
+
+    /// The cluster's slots hold color pixels (`CELL_FLAG_COLOR_GLYPH`).
+    pub fn is_color_cluster(&self, cluster: &str) -> bool {
+        self.clusters.color.contains(cluster)
+    }
+
+    /// Rasterize a grapheme cluster spanning `width` cells into free slots.
+    ///
+    /// Returns the first glyph index (see `get_cluster_index`), or None when
+    /// the atlas is full. Only `texture_data` changes; the slots are queued
+    /// for `take_dirty_slots`.
+    ///
+    /// Clusters come from `font_metrics.emoji_font` in color when it has a
+    /// bitmap for them. Otherwise they are monochrome outlines from the
+    /// terminal font: regional indicator flags draw as their two letters
+    /// (🇺🇸 → "US"), anything else as its base character centered across the
+    /// cells.
+    pub fn insert_cluster(&mut self, font_metrics: &FontMetrics, cluster: &str, width: usize) -> Option<u32> {
+        if let Some(index) = self.get_cluster_index(cluster) {
+            return Some(index);
+        }
+
+        let width = width.max(1) as u32;
+        if !self.fits((self.next_slot + width) as usize) {
+            return None;
+        }
+
+        // Draw the whole cluster into a strip, then copy it into one slot per cell
+        let strip_width = width * self.cell_width;
+        let color = font_metrics
+            .emoji_font
+            .as_ref()
+            .and_then(|emoji_font| emoji_font.render(cluster, strip_width, self.cell_height));
+        let strip = match color {
+            Some(strip) => {
+                self.clusters.color.insert(cluster.to_string());
+                strip
+            }
+            None => self.outline_strip(font_metrics, cluster, strip_width),
+        };
+
+        let first = self.next_slot;
+        self.write_strip(first, width, &strip);
+        self.next_slot += width;
+        self.clusters.index_map.insert(cluster.to_string(), first);
+        Some(first)
+    }
+
+    /// A monochrome `strip_width`-wide strip of `cluster` in the terminal
+    /// font, for clusters without a color bitmap.
+    fn outline_strip(&self, font_metrics: &FontMetrics, cluster: &str, strip_width: u32) -> Vec<u8> {
+        let mut strip = vec![0u8; (strip_width * self.cell_height * 4) as usize];
+        let scaled_font = font_metrics.font.as_scaled(font_metrics.scale);
+        let flag_letters: Option<Vec<char>> = cluster.chars().map(regional_indicator_letter).collect();
+        let glyphs = match flag_letters {
+            Some(letters) => letters
+                .into_iter()
+                .take((strip_width / self.cell_width) as usize)
+                .enumerate()
+                .map(|(cell, letter)| (letter, cell as u32 * self.cell_width, self.cell_width))
+                .collect(),
+            None => cluster
+                .chars()
+                .find(|&c| !is_cluster_modifier(c))
+                .map(|base| vec![(base, 0, strip_width)])
+                .unwrap_or_default(),
+        };
+        for (character, x, glyph_width) in glyphs {
+            rasterize_glyph(
+                &scaled_font,
+                character,
+                self.baseline,
+                &mut strip,
+                strip_width,
+                GlyphCell {
+                    x,
+                    y: 0,
+                    width: glyph_width,
+                    height: self.cell_height,
+                },
+                false,
+            );
+        }
+        strip
+    }
+}
+
+/// `A`-`Z` for a regional indicator symbol (U+1F1E6–U+1F1FF).
+#[cfg(feature = "emoji")]
+fn regional_indicator_letter(character: char) -> Option<char> {
+    let offset = (character as u32).checked_sub(0x1F1E6).filter(|&offset| offset < 26)?;
+    char::from_u32('A' as u32 + offset)
+}
+
+/// Joiners, variation selectors, skin tones and tags: parts of a cluster
+/// with no glyph of their own.
+#[cfg(feature = "emoji")]
+fn is_cluster_modifier(character: char) -> bool {
+    matches!(
+        character,
+        '\u{200D}' | '\u{FE0E}' | '\u{FE0F}' | '\u{1F3FB}'..='\u{1F3FF}' | '\u{E0020}'..='\u{E007F}'
+    )
+}
+
+/// Smallest size in `ATLAS_SIZE_OPTIONS` holding `glyph_count` cells, if any.
+pub fn fitting_atlas_size(cell_width: u32, cell_height: u32, glyph_count: usize) -> Option<u32> {
+    ATLAS_SIZE_OPTIONS
+        .into_iter()
+        .find(|&size| atlas_capacity(size, cell_width, cell_height) >= glyph_count)
+}
+
+/// Glyph cells in a `size`×`size` atlas.
+pub fn atlas_capacity(size: u32, cell_width: u32, cell_height: u32) -> usize {
+    ((size / cell_width.max(1)) * (size / cell_height.max(1))) as usize
+}
+
+/// Atlas cell size for a font, rounded up to whole pixels.
+fn atlas_cell_size(font_metrics: &FontMetrics) -> (u32, u32) {
+    (font_metrics.cell_width.ceil() as u32, font_metrics.cell_height.ceil() as u32)
+}
+
+/// Baseline that puts the highest ink among `chars` on the cell's top row.
+///
+/// Returns None when no character has an outline.
+fn top_aligned_baseline<F: Font>(scaled_font: &impl ScaleFont<F>, chars: &[char]) -> Option<f32> {
+    chars
+        .iter()
+        .filter(|&&character| !is_missing(scaled_font.font(), character))
+        .filter_map(|&character| {
+            let glyph = Glyph {
+                id: scaled_font.font().glyph_id(character),
+                scale: scaled_font.scale(),
+                position: point(0.0, 0.0),
+            };
+            scaled_font.outline_glyph(glyph).map(|outlined| outlined.px_bounds().min.y)
+        })
+        .reduce(f32::min)
+        .map(|top| -top)
+}
+
+/// Whether the font has no glyph for a printable `character`.
+///
+/// Missing characters map to glyph 0 (`.notdef`). Whitespace and control
+/// characters are meant to be blank, so they never count as missing.
+fn is_missing<F: Font>(font: &F, character: char) -> bool {
+    font.glyph_id(character).0 == 0 && !character.is_whitespace() && !character.is_control()
+}
+
+/// Draws a hollow box ("tofu") in `cell`, marking a character the font lacks.
+///
+/// A missing glyph would otherwise render blank and pass for a space.
+fn draw_tofu(texture_data: &mut [u8], atlas_width: u32, cell: &GlyphCell) {
+    let inset_x = (cell.width / 8).max(1);
+    let inset_y = (cell.height / 8).max(1);
+    let (left, right) = (cell.x + inset_x, cell.x + cell.width.saturating_sub(inset_x + 1));
+    let (top, bottom) = (cell.y + inset_y, cell.y + cell.height.saturating_sub(inset_y + 1));
+
+    for y in top..=bottom {
+        for x in left..=right {
+            let edge = x == left || x == right || y == top || y == bottom;
+            let pixel_index = ((y * atlas_width + x) * 4) as usize;
+            if edge && pixel_index + 3 < texture_data.len() {
+                texture_data[pixel_index..pixel_index + 4].copy_from_slice(&[255; 4]);
+            }
+        }
+    }
+}
+
+/// Box-drawing (U+2500–U+257F) and block elements (U+2580–U+259F).
+fn is_box_drawing(character: char) -> bool {
+    ('\u{2500}'..='\u{259F}').contains(&character)
+}
+
+/// Pixel rectangle of one glyph cell in the atlas.
+struct GlyphCell {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
 }
 
 /// Rasterize a single glyph to the atlas texture.
 ///
 /// Renders the glyph with anti-aliasing and writes to the RGBA buffer.
+/// `aliased` thresholds coverage at 50% instead, for solid lines and fills.
+/// Characters the font lacks draw as tofu (see `draw_tofu`).
 fn rasterize_glyph<F: Font>(
     scaled_font: &impl ScaleFont<F>,
     character: char,
     baseline: f32,
     texture_data: &mut [u8],
     atlas_width: u32,
-    cell_x: u32,
-    cell_y: u32,
-    cell_width: u32,
-    cell_height: u32,
+    cell: GlyphCell,
+    aliased: bool,
 ) {
+    let GlyphCell {
+        x: cell_x,
+        y: cell_y,
+        width: cell_width,
+        height: cell_height,
+    } = cell;
+
+    if is_missing(scaled_font.font(), character) {
+        debug!("🔲 Font has no glyph for {:?} (U+{:04X}), drawing tofu", character, character as u32);
+        draw_tofu(texture_data, atlas_width, &cell);
+        return;
+    }
+
     // Get glyph outline
     let glyph_id = scaled_font.font().glyph_id(character);
     let glyph = Glyph {
@@ -225,54 +980,50 @@ fn rasterize_glyph<F: Font>(
 
     let bounds = outlined.px_bounds();
 
-    // Center glyph horizontally in cell
+    // Center glyph horizontally in cell; one wider than the cell loses
+    // equal parts on both sides
     let glyph_width = bounds.width();
-    let horizontal_offset = ((cell_width as f32 - glyph_width) / 2.0).max(0.0);
+    let horizontal_offset = (cell_width as f32 - glyph_width) / 2.0;
 
     // Position glyph on baseline
     // We want glyph-space y=0 (which is the baseline for `ab_glyph` when position is `point(0.0,0.0)`) to map to cell-space y=baseline.
     // `outlined.draw` provides `glyph_y` relative to `outlined.px_bounds().min.y`.
     // So, `pixel_y_in_cell = baseline + (outlined.px_bounds().min.y + glyph_y_from_draw)`
     let vertical_offset_correction = baseline + bounds.min.y;
+    let atlas_height = (texture_data.len() / 4) as u32 / atlas_width.max(1);
 
     // Rasterize glyph
     outlined.draw(|glyph_x, glyph_y, coverage| {
         // Calculate pixel position in cell
-        let pixel_x = horizontal_offset + glyph_x as f32;
-        let pixel_y = vertical_offset_correction + glyph_y as f32;
+        let pixel_x = (horizontal_offset + glyph_x as f32).floor();
+        let pixel_y = (vertical_offset_correction + glyph_y as f32).floor();
 
-        // Convert to atlas coordinates
-        let atlas_x = cell_x as f32 + pixel_x;
-        let atlas_y = cell_y as f32 + pixel_y;
-
-        // Bounds check
-        if atlas_x < 0.0
-            || atlas_y < 0.0
-            || atlas_x >= atlas_width as f32
-            || atlas_y >= atlas_width as f32
-        {
+        // Clip to the cell: ascenders above its top or overhangs past its
+        // sides would otherwise land in the neighboring glyph's cell
+        if pixel_x < 0.0 || pixel_y < 0.0 || pixel_x >= cell_width as f32 || pixel_y >= cell_height as f32 {
             return;
         }
 
-        let atlas_x = atlas_x as u32;
-        let atlas_y = atlas_y as u32;
-
-        // Check if pixel is within cell bounds
-        if atlas_x >= cell_x + cell_width || atlas_y >= cell_y + cell_height {
+        // Convert to atlas coordinates
+        let atlas_x = cell_x + pixel_x as u32;
+        let atlas_y = cell_y + pixel_y as u32;
+        if atlas_x >= atlas_width || atlas_y >= atlas_height {
             return;
         }
 
         // Calculate pixel index in RGBA buffer
         let pixel_index = ((atlas_y * atlas_width + atlas_x) * 4) as usize;
 
-        if pixel_index + 3 < texture_data.len() {
-            // Write white glyph with alpha (coverage determines transparency)
-            let alpha = (coverage * 255.0) as u8;
-            texture_data[pixel_index] = 255; // R
-            texture_data[pixel_index + 1] = 255; // G
-            texture_data[pixel_index + 2] = 255; // B
-            texture_data[pixel_index + 3] = alpha; // A
-        }
+        // Write white glyph with alpha (coverage determines transparency)
+        let alpha = match aliased {
+            true if coverage >= 0.5 => 255,
+            true => 0,
+            false => (coverage * 255.0) as u8,
+        };
+        texture_data[pixel_index] = 255; // R
+        texture_data[pixel_index + 1] = 255; // G
+        texture_data[pixel_index + 2] = 255; // B
+        texture_data[pixel_index + 3] = alpha; // A
     });
 }
 
@@ -298,13 +1049,113 @@ mod tests {
         assert_eq!(all.len(), ascii.len() + box_drawing.len() + blocks.len());
     }
 
+    #[test]
+    fn test_coverage() {
+        let font_metrics = FontMetrics::load_cascadia_mono()
+            .expect("Should load font");
+        let atlas = GlyphAtlas::generate(&font_metrics, &CharacterSets::ascii())
+            .expect("Should generate atlas");
+
+        assert!(atlas.contains('A'));
+        assert!(!atlas.contains('日'));
+
+        assert_eq!(atlas.coverage("hello"), 1.0);
+        assert_eq!(atlas.coverage("ab日本"), 0.5);
+        assert_eq!(atlas.coverage("日本語"), 0.0);
+        // Control characters don't count either way
+        assert_eq!(atlas.coverage("ab\n日本\n"), 0.5);
+        assert_eq!(atlas.coverage(""), 1.0);
+    }
+
+    #[test]
+    fn test_glyph_texels_are_white() {
+        // The shader blends `mix(bg, fg, alpha)` and ignores the atlas RGB.
+        // Keeping glyph texels white (coverage only in alpha, not
+        // premultiplied) means anti-aliased edges fade from fg to bg rather
+        // than through black, which would show as dark halos on bright
+        // backgrounds if anything ever sampled the RGB.
+        let font_metrics = FontMetrics::load_cascadia_mono()
+            .expect("Should load font");
+        let atlas = GlyphAtlas::generate(&font_metrics, &['W', '@', 'g'])
+            .expect("Should generate atlas");
+
+        let mut edge_texels = 0;
+        for texel in atlas.texture_data.chunks_exact(4) {
+            let alpha = texel[3];
+            if alpha > 0 {
+                assert_eq!(&texel[..3], &[255, 255, 255], "Glyph texel with alpha {} isn't white", alpha);
+            }
+            if alpha > 0 && alpha < 255 {
+                edge_texels += 1;
+            }
+        }
+        assert!(edge_texels > 0, "Glyphs should have anti-aliased edges");
+    }
+
+    #[test]
+    fn test_to_bevy_image() {
+        let font_metrics = FontMetrics::load_cascadia_mono()
+            .expect("Should load font");
+        let atlas = GlyphAtlas::generate(&font_metrics, &CharacterSets::ascii())
+            .expect("Should generate atlas");
+
+        let image = atlas.to_bevy_image();
+        assert_eq!(image.width(), atlas.atlas_width);
+        assert_eq!(image.height(), atlas.atlas_height);
+        assert_eq!(image.texture_descriptor.format, TextureFormat::Rgba8Unorm);
+        assert_eq!(
+            image.asset_usage,
+            RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD
+        );
+        assert_eq!(image.data.as_deref(), Some(atlas.texture_data.as_slice()));
+    }
+
+    #[test]
+    fn test_crisp_box_drawing() {
+        let font_metrics = FontMetrics::load_cascadia_mono()
+            .expect("Should load font");
+        let chars = ['A', '─', '│', '╭', '▀', '▒', '█'];
+
+        // Alpha values of every texel in `character`'s cell
+        let cell_alphas = |atlas: &GlyphAtlas, character: char| -> Vec<u8> {
+            let uv = atlas.get_uv(character).unwrap();
+            let x0 = (uv.min.x * atlas.atlas_width as f32).round() as u32;
+            let y0 = (uv.min.y * atlas.atlas_height as f32).round() as u32;
+            (y0..y0 + atlas.cell_height)
+                .flat_map(|y| (x0..x0 + atlas.cell_width).map(move |x| (x, y)))
+                .map(|(x, y)| atlas.texture_data[((y * atlas.atlas_width + x) * 4 + 3) as usize])
+                .collect()
+        };
+
+        let crisp = FontMetrics::load_cascadia_mono()
+            .expect("Should load font")
+            .with_crisp_box_drawing(true);
+        let atlas = GlyphAtlas::generate(&crisp, &chars).expect("Should generate atlas");
+        for &character in &chars[1..] {
+            let alphas = cell_alphas(&atlas, character);
+            assert!(alphas.contains(&255), "{:?} should have ink", character);
+            assert!(
+                alphas.iter().all(|&a| a == 0 || a == 255),
+                "{:?} should be fully opaque or transparent",
+                character
+            );
+        }
+        // Text keeps its anti-aliasing
+        assert!(cell_alphas(&atlas, 'A').iter().any(|&a| a > 0 && a < 255));
+
+        // Default: box-drawing is anti-aliased like text
+        let smooth = GlyphAtlas::generate(&font_metrics, &chars)
+            .expect("Should generate atlas");
+        assert!(cell_alphas(&smooth, '╭').iter().any(|&a| a > 0 && a < 255));
+    }
+
     #[test]
     fn test_generate_atlas() {
         let font_metrics = FontMetrics::load_cascadia_mono()
             .expect("Should load font");
 
         let chars = vec!['A', 'B', 'C', '1', '2', '3'];
-        let atlas = GlyphAtlas::generate(&font_metrics, &chars)
+        let atlas = GlyphAtlas::generate_with_size(&font_metrics, &chars, ATLAS_SIZE)
             .expect("Should generate atlas");
 
         // Verify atlas properties
@@ -325,6 +1176,492 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_minimal_atlas_size() {
+        let font_metrics = FontMetrics::load_cascadia_mono()
+            .expect("Should load font");
+
+        let atlas = GlyphAtlas::generate(&font_metrics, &CharacterSets::ascii())
+            .expect("Should generate atlas");
+
+        // ASCII fits in a fraction of the full 4096² texture
+        assert!(atlas.atlas_width <= 512, "ASCII atlas is {}²", atlas.atlas_width);
+        assert_eq!(atlas.atlas_width, atlas.atlas_height);
+        assert!(atlas.atlas_width.is_power_of_two());
+        assert!(atlas.texture_data.len() * 64 <= (ATLAS_SIZE * ATLAS_SIZE * 4) as usize);
+        assert_eq!(
+            atlas.texture_data.len(),
+            (atlas.atlas_width * atlas.atlas_height * 4) as usize
+        );
+
+        // Every glyph cell lies inside the smaller texture
+        let cols = atlas.atlas_width / atlas.cell_width;
+        let rows = atlas.atlas_height / atlas.cell_height;
+        assert!((cols * rows) as usize >= atlas.glyph_index_map.len());
+    }
+
+    #[test]
+    fn test_fitting_atlas_size() {
+        assert_eq!(fitting_atlas_size(8, 16, 1), Some(256));
+        assert_eq!(fitting_atlas_size(8, 16, 32 * 16), Some(256));
+        assert_eq!(fitting_atlas_size(8, 16, 32 * 16 + 1), Some(512));
+        assert_eq!(fitting_atlas_size(64, 64, 1_000_000), None);
+    }
+
+    #[test]
+    fn test_required_size() {
+        let font_metrics = FontMetrics::load_cascadia_mono().expect("Should load font");
+        let (cell_width, cell_height) = atlas_cell_size(&font_metrics);
+        // Only the count matters for sizing
+        let chars_of = |count: usize| vec!['M'; count];
+
+        assert_eq!(GlyphAtlas::required_size(&font_metrics, &[]), (256, 256));
+        assert_eq!(GlyphAtlas::required_size(&font_metrics, &CharacterSets::ascii()), (256, 256));
+
+        // One glyph past a size's capacity doubles it
+        let full_256 = atlas_capacity(256, cell_width, cell_height);
+        assert_eq!(GlyphAtlas::required_size(&font_metrics, &chars_of(full_256)), (256, 256));
+        assert_eq!(GlyphAtlas::required_size(&font_metrics, &chars_of(full_256 + 1)), (512, 512));
+
+        // Not capped at ATLAS_SIZE
+        let full_4096 = atlas_capacity(ATLAS_SIZE, cell_width, cell_height);
+        assert_eq!(
+            GlyphAtlas::required_size(&font_metrics, &chars_of(full_4096 + 1)),
+            (ATLAS_SIZE * 2, ATLAS_SIZE * 2)
+        );
+
+        // Agrees with what generate picks
+        let chars = CharacterSets::all_mvp();
+        let atlas = GlyphAtlas::generate(&font_metrics, &chars).expect("Should generate atlas");
+        assert_eq!(GlyphAtlas::required_size(&font_metrics, &chars), (atlas.atlas_width, atlas.atlas_height));
+        assert_eq!(atlas.capacity(), atlas_capacity(atlas.atlas_width, cell_width, cell_height));
+        assert!(atlas.fits(chars.len()));
+        assert!(!atlas.fits(atlas.capacity() + 1));
+    }
+
+    /// First and last cell rows containing ink for `character`.
+    fn ink_rows(atlas: &GlyphAtlas, character: char) -> (u32, u32) {
+        let index = atlas.get_glyph_index(character).expect("Character should be in atlas");
+        let cols = atlas.atlas_width / atlas.cell_width;
+        let cell_x = (index % cols) * atlas.cell_width;
+        let cell_y = (index / cols) * atlas.cell_height;
+
+        let inked: Vec<u32> = (0..atlas.cell_height)
+            .filter(|&y| {
+                (0..atlas.cell_width).any(|x| {
+                    let pixel = ((cell_y + y) * atlas.atlas_width + cell_x + x) * 4;
+                    atlas.texture_data[pixel as usize + 3] > 0
+                })
+            })
+            .collect();
+        (*inked.first().unwrap(), *inked.last().unwrap())
+    }
+
+    /// A blocky "pixel" font with metrics unlike Cascadia Mono's: 1000
+    /// units per em, a 1000-unit ascent over glyphs at most 700 tall, and
+    /// only `M` (a 700-unit block) and `x` (400 units) besides the space.
+    fn pixel_font() -> Vec<u8> {
+        fn be16(values: &[i32]) -> Vec<u8> {
+            values.iter().flat_map(|&value| (value as u16).to_be_bytes()).collect()
+        }
+        fn be32(values: &[u32]) -> Vec<u8> {
+            values.iter().flat_map(|value| value.to_be_bytes()).collect()
+        }
+        // One clockwise rectangle, every point on the curve
+        let block = |top: i32| {
+            let mut glyph = be16(&[1, 100, 0, 500, top, 3, 0]);
+            glyph.extend([0x01; 4]);
+            glyph.extend(be16(&[100, 0, 400, 0]));
+            glyph.extend(be16(&[0, top, 0, -top]));
+            glyph
+        };
+        let glyf = [block(700), block(400)].concat();
+
+        let mut head = vec![0; 54];
+        head[..4].copy_from_slice(&be32(&[0x0001_0000]));
+        head[12..16].copy_from_slice(&be32(&[0x5F0F_3CF5]));
+        head[18..20].copy_from_slice(&be16(&[1000]));
+        let mut hhea = be16(&[1, 0, 1000, -200, 0]);
+        hhea.resize(34, 0);
+        hhea.extend(be16(&[4]));
+        let tables: [(&[u8; 4], Vec<u8>); 7] = [
+            (b"cmap", [be16(&[0, 1, 3, 10]), be32(&[12]), be16(&[12, 0]), be32(&[52, 0, 3, 32, 32, 1, 77, 77, 2, 120, 120, 3])].concat()),
+            (b"glyf", glyf.clone()),
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"hmtx", be16(&[600, 0, 600, 0, 600, 100, 600, 100])),
+            (b"loca", be16(&[0, 0, 0, glyf.len() as i32 / 4, glyf.len() as i32 / 2])),
+            (b"maxp", [be32(&[0x0000_5000]), be16(&[4])].concat()),
+        ];
+
+        // Table directory sorted by tag, tables 4-byte aligned after it
+        let mut font = [be32(&[0x0001_0000]), be16(&[tables.len() as i32, 0, 0, 0])].concat();
+        let directory_len = font.len() + 16 * tables.len();
+        let mut data = Vec::new();
+        for (tag, table) in &tables {
+            font.extend(*tag);
+            font.extend(be32(&[0, (directory_len + data.len()) as u32, table.len() as u32]));
+            data.extend(table);
+            data.resize(data.len().next_multiple_of(4), 0);
+        }
+        font.extend(data);
+        font
+    }
+
+    #[test]
+    fn test_glyph_placement() {
+        const CASCADIA: &[u8] = include_bytes!("../assets/fonts/CascadiaMono-Regular.ttf");
+        let pixel = pixel_font();
+        let fonts: [(&[u8], Vec<char>, f32); 3] = [
+            (CASCADIA, CharacterSets::ascii(), 14.0),
+            (CASCADIA, CharacterSets::ascii(), 24.0),
+            (&pixel, vec![' ', 'M', 'x'], 16.0),
+        ];
+
+        for (font, chars, size) in fonts {
+            let atlas_with = |placement| {
+                let font_metrics = FontMetrics::load(font, size)
+                    .expect("Should load font")
+                    .with_placement(placement);
+                GlyphAtlas::generate(&font_metrics, &chars).expect("Should generate atlas")
+            };
+
+            let baseline = atlas_with(GlyphPlacement::Baseline);
+            let top = atlas_with(GlyphPlacement::Top);
+            let offset = atlas_with(GlyphPlacement::Offset(2.0));
+
+            // Top alignment puts the tallest glyph on the first row
+            let top_row = |atlas: &GlyphAtlas| {
+                chars
+                    .iter()
+                    .filter(|&&c| c != ' ')
+                    .map(|&c| ink_rows(atlas, c).0)
+                    .min()
+                    .unwrap()
+            };
+            assert_eq!(top_row(&top), 0, "{}px: tallest glyph should touch the cell top", size);
+
+            // A manual offset shifts every glyph down by that many pixels
+            let (base_first, base_last) = ink_rows(&baseline, 'x');
+            let (offset_first, offset_last) = ink_rows(&offset, 'x');
+            assert_eq!(offset_first, base_first + 2, "{}px: offset should move ink down", size);
+            assert_eq!(offset_last, base_last + 2, "{}px: offset should move ink down", size);
+
+            // Glyphs keep their relative alignment: 'x' never rises above 'M'
+            for atlas in [&baseline, &top, &offset] {
+                assert!(ink_rows(atlas, 'x').0 > ink_rows(atlas, 'M').0);
+                assert_eq!(ink_rows(atlas, 'x').1, ink_rows(atlas, 'M').1);
+            }
+        }
+
+        // On the baseline, the pixel font's tall ascent leaves its glyphs well
+        // below the cell top; top alignment is what pulls them up
+        let font_metrics = FontMetrics::load(&pixel, 16.0).expect("Should load font");
+        let baseline = GlyphAtlas::generate(&font_metrics, &[' ', 'M', 'x']).expect("Should generate atlas");
+        assert!(ink_rows(&baseline, 'M').0 >= 3, "16px: 300 units above 'M' should stay blank");
+    }
+
+    /// Golden images live here, one 8-bit grayscale PGM of glyph alpha per
+    /// character and size. PGM keeps the harness dependency-free and still
+    /// opens in most image viewers.
+    const GOLDEN_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden");
+
+    /// Alpha texels may drift this much (float rounding across platforms);
+    /// a moved or reshaped glyph changes far more.
+    const GOLDEN_TOLERANCE: u8 = 2;
+
+    /// Alpha of `character`'s cell, row by row.
+    fn cell_alpha(atlas: &GlyphAtlas, character: char) -> Vec<u8> {
+        let index = atlas.get_glyph_index(character).expect("Character should be in atlas");
+        let cols = atlas.atlas_width / atlas.cell_width;
+        let cell_x = (index % cols) * atlas.cell_width;
+        let cell_y = (index / cols) * atlas.cell_height;
+
+        (0..atlas.cell_height)
+            .flat_map(|y| (0..atlas.cell_width).map(move |x| (x, y)))
+            .map(|(x, y)| atlas.texture_data[(((cell_y + y) * atlas.atlas_width + cell_x + x) * 4 + 3) as usize])
+            .collect()
+    }
+
+    fn write_pgm(path: &std::path::Path, width: u32, height: u32, pixels: &[u8]) {
+        let mut bytes = format!("P5\n{} {}\n255\n", width, height).into_bytes();
+        bytes.extend_from_slice(pixels);
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory).expect("Should create golden directory");
+        }
+        std::fs::write(path, bytes).expect("Should write golden image");
+    }
+
+    /// Width, height and pixels of a binary PGM written by `write_pgm`.
+    fn read_pgm(bytes: &[u8]) -> Option<(u32, u32, &[u8])> {
+        // Header: magic, width, height, max value, each followed by one whitespace byte
+        let mut fields = Vec::new();
+        let mut start = 0;
+        for (position, byte) in bytes.iter().enumerate() {
+            if byte.is_ascii_whitespace() {
+                fields.push(std::str::from_utf8(&bytes[start..position]).ok()?);
+                start = position + 1;
+                if fields.len() == 4 {
+                    break;
+                }
+            }
+        }
+        match fields[..] {
+            ["P5", width, height, "255"] => Some((width.parse().ok()?, height.parse().ok()?, &bytes[start..])),
+            _ => None,
+        }
+    }
+
+    /// Compares `character`'s rasterized cell against its golden image.
+    ///
+    /// Set `UPDATE_GOLDEN=1` to (re)write the baselines instead, e.g. after
+    /// an intended rendering change:
+    /// `UPDATE_GOLDEN=1 cargo test -p bevy-terminal --lib golden`. A missing
+    /// baseline fails like a mismatch. Failing comparisons leave the rendered
+    /// cell next to the baseline as `*.actual.pgm`.
+    fn assert_golden(atlas: &GlyphAtlas, character: char, name: &str) {
+        let (width, height) = (atlas.cell_width, atlas.cell_height);
+        let actual = cell_alpha(atlas, character);
+        let path = std::path::Path::new(GOLDEN_DIR).join(format!("{}.pgm", name));
+
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            write_pgm(&path, width, height, &actual);
+            return;
+        }
+        let Ok(expected) = std::fs::read(&path) else {
+            write_pgm(&path.with_extension("actual.pgm"), width, height, &actual);
+            panic!(
+                "{} is missing; run with UPDATE_GOLDEN=1 to write it, then review and commit it",
+                path.display()
+            );
+        };
+        let (golden_width, golden_height, golden) =
+            read_pgm(&expected).unwrap_or_else(|| panic!("{} isn't a binary 8-bit PGM", path.display()));
+        let differing = golden.iter().zip(&actual).filter(|(a, b)| a.abs_diff(**b) > GOLDEN_TOLERANCE).count();
+        if (golden_width, golden_height) != (width, height) || golden.len() != actual.len() || differing > 0 {
+            write_pgm(&path.with_extension("actual.pgm"), width, height, &actual);
+            panic!(
+                "{:?} no longer matches {} ({}x{} rendered, {}x{} expected, {} texels differ); \
+                 rerun with UPDATE_GOLDEN=1 if the change is intended",
+                character,
+                path.display(),
+                width,
+                height,
+                golden_width,
+                golden_height,
+                differing
+            );
+        }
+    }
+
+    #[test]
+    fn test_golden_glyphs() {
+        // Catches baseline and centering regressions that UV and count
+        // checks miss. Baselines are for the font `scripts/download-fonts.sh`
+        // pins.
+        const FONT: &[u8] = include_bytes!("../assets/fonts/CascadiaMono-Regular.ttf");
+        let chars = CharacterSets::ascii();
+
+        for size in [14.0, 24.0] {
+            let font_metrics = FontMetrics::load(FONT, size).expect("Should load font");
+            let atlas = GlyphAtlas::generate(&font_metrics, &chars).expect("Should generate atlas");
+            // Cap height, x-height, descender, tall punctuation, narrow
+            for (character, name) in [('A', "A"), ('x', "x"), ('g', "g"), ('{', "brace"), ('|', "bar")] {
+                assert_golden(&atlas, character, &format!("glyph_{}_{}px", name, size as u32));
+            }
+        }
+    }
+
+    #[test]
+    fn test_pgm_round_trip() {
+        let path = std::env::temp_dir().join(format!("bevy-terminal-golden-{}.pgm", std::process::id()));
+        let pixels: Vec<u8> = (0..12).map(|value| value * 20).collect();
+        write_pgm(&path, 4, 3, &pixels);
+        let bytes = std::fs::read(&path).expect("Should read back");
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(read_pgm(&bytes), Some((4, 3, pixels.as_slice())));
+        assert_eq!(read_pgm(b"P6\n4 3\n255\n"), None);
+        assert_eq!(read_pgm(b"P5"), None);
+    }
+
+    #[test]
+    fn test_glyph_index_matches_uv() {
+        // The shaders find a glyph from its index alone; the CPU side uses
+        // the UV map. Both must name the same cell, runtime glyphs included.
+        let font_metrics = FontMetrics::load_cascadia_mono()
+            .expect("Should load font");
+        let mut atlas = GlyphAtlas::generate_mvp(&font_metrics).expect("Should generate atlas");
+        atlas.insert_glyph(&font_metrics, 'é').expect("Should have room");
+
+        let cols = atlas.atlas_width / atlas.cell_width;
+        for (&character, uv) in &atlas.uv_map {
+            let index = atlas.get_glyph_index(character).expect("Every UV should have an index");
+            let x = (index % cols * atlas.cell_width) as f32 / atlas.atlas_width as f32;
+            let y = (index / cols * atlas.cell_height) as f32 / atlas.atlas_height as f32;
+            assert_eq!(uv.min, Vec2::new(x, y), "{:?} (index {})", character, index);
+        }
+        assert_eq!(atlas.uv_map.len(), atlas.glyph_index_map.len());
+    }
+
+    #[test]
+    fn test_insert_glyph_evicts_least_recently_seen() {
+        let font_metrics = FontMetrics::load_cascadia_mono()
+            .expect("Should load font");
+        let mut atlas = GlyphAtlas::generate(&font_metrics, &CharacterSets::ascii())
+            .expect("Should generate atlas");
+        let free = atlas.capacity() - CharacterSets::ascii().len();
+
+        // 'é' shows up first, then enough others to fill the atlas
+        atlas.next_frame();
+        assert!(!atlas.touch('é'));
+        let accented = atlas.insert_glyph(&font_metrics, 'é').expect("Should have room");
+        atlas.next_frame();
+        let others: Vec<char> = ('\u{100}'..).take(free - 1).collect();
+        for &character in &others {
+            assert!(atlas.insert_glyph(&font_metrics, character).is_some());
+        }
+        assert_eq!(atlas.take_dirty_slots().len(), free);
+
+        // Full: the glyph gone longest from the screen gives up its slot,
+        // which is redrawn from scratch
+        atlas.next_frame();
+        assert_eq!(atlas.insert_glyph(&font_metrics, 'ü'), Some(accented));
+        assert!(!atlas.contains('é'));
+        assert_eq!(atlas.take_dirty_slots(), vec![accented]);
+        let fresh = GlyphAtlas::generate(&font_metrics, &['ü']).expect("Should generate atlas");
+        let region = atlas.slot_region(accented);
+        assert_eq!(region.data, fresh.slot_region(0).data);
+        assert_eq!((region.width, region.height), (atlas.cell_width, atlas.cell_height));
+
+        // Nothing can go while every runtime glyph is on screen, and the
+        // generated set is never evicted
+        atlas.next_frame();
+        for &character in others.iter().chain(&['ü']) {
+            assert!(atlas.touch(character));
+        }
+        assert_eq!(atlas.insert_glyph(&font_metrics, 'ñ'), None);
+        assert!(CharacterSets::ascii().into_iter().all(|character| atlas.contains(character)));
+    }
+
+    #[test]
+    fn test_evicting_narrow_glyphs_makes_room_for_wide() {
+        let font_metrics = FontMetrics::load_cascadia_mono()
+            .expect("Should load font");
+        let mut atlas = GlyphAtlas::generate(&font_metrics, &CharacterSets::ascii())
+            .expect("Should generate atlas");
+        let free = atlas.capacity() - CharacterSets::ascii().len();
+
+        // Fill the atlas with narrow glyphs, one per frame so the first ones
+        // are the least recently seen
+        let narrow: Vec<char> = ('\u{100}'..).take(free).collect();
+        let first_narrow = atlas.next_slot;
+        for &character in &narrow {
+            atlas.next_frame();
+            assert!(atlas.insert_glyph(&font_metrics, character).is_some());
+        }
+
+        // A wide glyph needs two neighbouring slots: the two oldest go
+        atlas.next_frame();
+        let first = atlas.insert_wide_glyph(&font_metrics, '日').expect("Should evict");
+        assert_eq!(first, first_narrow);
+        assert!(atlas.is_wide('日'));
+        assert_eq!(atlas.take_dirty_slots().split_off(free), vec![first, first + 1]);
+        assert!(!atlas.contains(narrow[0]) && !atlas.contains(narrow[1]));
+        assert!(narrow[2..].iter().all(|&character| atlas.contains(character)));
+
+        // Once it's the only glyph off screen, evicting it frees both slots
+        atlas.next_frame();
+        for &character in &narrow[2..] {
+            atlas.touch(character);
+        }
+        assert_eq!(atlas.insert_glyph(&font_metrics, 'ü'), Some(first));
+        assert_eq!(atlas.insert_glyph(&font_metrics, 'ñ'), Some(first + 1));
+        assert!(!atlas.contains('日') && !atlas.is_wide('日'));
+    }
+
+    #[test]
+    fn test_glyphs_stay_in_their_cell() {
+        let font_metrics = FontMetrics::load_cascadia_mono()
+            .expect("Should load font");
+        let scaled_font = font_metrics.font.as_scaled(font_metrics.scale);
+        let (cell_width, cell_height) = atlas_cell_size(&font_metrics);
+
+        // Draws `character` into the middle of a 3x3 grid of cells the
+        // given size, returning the grid's alpha
+        let rasterize = |character: char, width: u32, height: u32, baseline: f32| {
+            let grid_width = width * 3;
+            let mut texture_data = vec![0u8; (grid_width * height * 3 * 4) as usize];
+            let cell = GlyphCell {
+                x: width,
+                y: height,
+                width,
+                height,
+            };
+            rasterize_glyph(&scaled_font, character, baseline, &mut texture_data, grid_width, cell, false);
+            texture_data.chunks_exact(4).map(|texel| texel[3]).collect::<Vec<u8>>()
+        };
+        let assert_contained = |alpha: &[u8], width: u32, height: u32, what: &str| {
+            let grid_width = width * 3;
+            let mut ink = 0;
+            for (index, &texel) in alpha.iter().enumerate() {
+                let (x, y) = (index as u32 % grid_width, index as u32 / grid_width);
+                let inside = (width..width * 2).contains(&x) && (height..height * 2).contains(&y);
+                assert!(inside || texel == 0, "{} bled into ({}, {})", what, x, y);
+                ink += u32::from(inside && texel > 0);
+            }
+            assert!(ink > 0, "{} drew nothing", what);
+        };
+
+        // A tall glyph on a baseline near the cell top reaches above it
+        let alpha = rasterize('{', cell_width, cell_height, cell_height as f32 / 4.0);
+        assert_contained(&alpha, cell_width, cell_height, "raised '{'");
+
+        // A short cell clips the glyph top and bottom
+        let short = cell_height / 2;
+        let alpha = rasterize('{', cell_width, short, short as f32 * 0.75);
+        assert_contained(&alpha, cell_width, short, "'{' in a short cell");
+
+        // A glyph wider than its cell stays centered, clipped on both
+        // sides: the narrow cell shows the middle of the full glyph
+        let wide = cell_width * 4;
+        let narrow = cell_width / 2 + cell_width / 2 % 2;
+        let full = rasterize('W', wide, cell_height, font_metrics.baseline);
+        let clipped = rasterize('W', narrow, cell_height, font_metrics.baseline);
+        assert_contained(&clipped, narrow, cell_height, "'W' in a narrow cell");
+        let shift = (wide - narrow) / 2;
+        for y in 0..cell_height {
+            for x in 0..narrow {
+                let full_texel = full[((cell_height + y) * wide * 3 + wide + shift + x) as usize];
+                let clipped_texel = clipped[((cell_height + y) * narrow * 3 + narrow + x) as usize];
+                assert_eq!(clipped_texel, full_texel, "'W' at ({}, {}) should match the centered glyph", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_missing_glyph_draws_tofu() {
+        // Plane 16 private use: no font ships a glyph for it
+        const MISSING: char = '\u{10FFFD}';
+        let font_metrics = FontMetrics::load_cascadia_mono()
+            .expect("Should load font");
+        assert!(is_missing(&font_metrics.font, MISSING));
+        assert!(!is_missing(&font_metrics.font, ' '));
+
+        let atlas = GlyphAtlas::generate(&font_metrics, &[' ', MISSING])
+            .expect("Should generate atlas");
+
+        // The missing character gets a visible box, spanning most of the cell
+        let (first, last) = ink_rows(&atlas, MISSING);
+        assert!(last - first >= atlas.cell_height / 2, "Tofu should be tall: rows {}..={}", first, last);
+
+        // Spaces stay blank
+        let cell_alpha = |x: u32, y: u32| atlas.texture_data[((y * atlas.atlas_width + x) * 4 + 3) as usize];
+        let space = atlas.get_glyph_index(' ').unwrap();
+        assert_eq!(space, 0);
+        assert!((0..atlas.cell_height).all(|y| (0..atlas.cell_width).all(|x| cell_alpha(x, y) == 0)));
+    }
+
     #[test]
     fn test_generate_mvp_atlas() {
         let font_metrics = FontMetrics::load_cascadia_mono()
@@ -0,0 +1,104 @@
+//! Shared clock for time-based terminal visuals.
+//!
+//! Cursor blink, text blink, bell flashes and similar effects all read
+//! `TerminalClock` instead of `Time`, so they stay in step with each other
+//! and don't depend on the render frame rate.
+//!
+//! The clock advances in fixed `timestep` increments: frame time is
+//! accumulated and only whole steps are applied, so the same elapsed time
+//! always produces the same state regardless of how it was split into frames.
+//!
+//! ## Reading the clock
+//!
+//! - `blink_phase` runs from 0.0 to 1.0 once per `blink_period`. Effects that
+//!   blink should be visible while `blink_on()` (the first half of the cycle)
+//!   or fade using the phase directly.
+//! - `time` is total terminal time, for effects with their own durations
+//!   (e.g. a bell flash started at `time` lasting 150ms).
+//!
+//! Tests set `manual = true` and drive the clock with `advance`/`set_time`.
+
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// Fixed-step clock for terminal effects.
+#[derive(Resource, Clone, Debug)]
+pub struct TerminalClock {
+    /// Terminal time elapsed, a whole number of timesteps
+    pub time: Duration,
+    /// Position in the blink cycle (0.0-1.0)
+    pub blink_phase: f32,
+    /// Length of one blink cycle (on + off)
+    pub blink_period: Duration,
+    /// Fixed step the clock advances by
+    pub timestep: Duration,
+    /// When true `advance_terminal_clock` leaves the clock alone (for tests)
+    pub manual: bool,
+    /// Frame time not yet consumed by a whole step
+    accumulator: Duration,
+}
+
+impl Default for TerminalClock {
+    fn default() -> Self {
+        Self::new(Duration::from_secs_f64(1.0 / 120.0), Duration::from_secs(1))
+    }
+}
+
+impl TerminalClock {
+    pub fn new(timestep: Duration, blink_period: Duration) -> Self {
+        Self {
+            time: Duration::ZERO,
+            blink_phase: 0.0,
+            blink_period,
+            timestep,
+            manual: false,
+            accumulator: Duration::ZERO,
+        }
+    }
+
+    /// Feed `delta` of frame time, applying as many whole timesteps as fit.
+    pub fn advance(&mut self, delta: Duration) {
+        if self.timestep.is_zero() {
+            self.set_time(self.time + delta);
+            return;
+        }
+
+        self.accumulator += delta;
+        while self.accumulator >= self.timestep {
+            self.accumulator -= self.timestep;
+            self.time += self.timestep;
+        }
+        self.update_phase();
+    }
+
+    /// Jump to `time`, discarding any partial step.
+    pub fn set_time(&mut self, time: Duration) {
+        self.time = time;
+        self.accumulator = Duration::ZERO;
+        self.update_phase();
+    }
+
+    /// Whether blinking content is in the visible half of the cycle.
+    pub fn blink_on(&self) -> bool {
+        self.blink_phase < 0.5
+    }
+
+    fn update_phase(&mut self) {
+        self.blink_phase = if self.blink_period.is_zero() {
+            0.0
+        } else {
+            let period = self.blink_period.as_nanos();
+            (self.time.as_nanos() % period) as f32 / period as f32
+        };
+    }
+}
+
+/// Advances the terminal clock by the frame's delta time.
+///
+/// System: Update
+/// Runs: Every frame (no-op while `TerminalClock::manual`)
+pub fn advance_terminal_clock(time: Res<Time>, mut clock: ResMut<TerminalClock>) {
+    if !clock.manual {
+        clock.advance(time.delta());
+    }
+}
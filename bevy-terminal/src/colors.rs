@@ -1,51 +1,230 @@
-use alacritty_terminal::vte::ansi::Color;
+use alacritty_terminal::term::color::Colors;
+use alacritty_terminal::vte::ansi::{Color, NamedColor};
+use bevy::prelude::*;
 
 /// Background color used for terminal (Tokyo Night Dark)
 pub const TOKYO_NIGHT_BG: [u8; 3] = [0x1a, 0x1b, 0x26];
 
+/// Default palette's cursor color (Tokyo Night foreground)
+pub const DEFAULT_CURSOR_COLOR: [u8; 3] = [0xc0, 0xca, 0xf5];
+
+/// How much dim colors (SGR 2, `NamedColor::DimRed`, ...) darken the normal
+/// ones, as in alacritty.
+pub const DIM_FACTOR: f32 = 0.66;
+
+/// Colors the terminal draws with until a program overrides them (OSC
+/// 4/10/11/12): the 16 ANSI colors plus the default foreground, background
+/// and cursor.
+///
+/// `TerminalPlugin` reads it as a resource, defaulting to Tokyo Night. Insert
+/// or modify it at any time to switch themes; every terminal repaints with
+/// the new colors on the next frame.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TerminalPalette {
+    /// Black, red, green, yellow, blue, magenta, cyan and white, then their
+    /// bright variants (indices 0-15)
+    pub ansi: [[u8; 3]; 16],
+    /// Default text color
+    pub foreground: [u8; 3],
+    /// Default cell background, also the texture's clear color
+    pub background: [u8; 3],
+    /// Cursor color
+    pub cursor: [u8; 3],
+}
+
+impl TerminalPalette {
+    /// Tokyo Night Dark, the default.
+    pub const TOKYO_NIGHT: Self = Self {
+        ansi: [
+            [0x1a, 0x1b, 0x26],
+            [0xf7, 0x76, 0x8e],
+            [0x9e, 0xce, 0x6a],
+            [0xe0, 0xaf, 0x68],
+            [0x7a, 0xa2, 0xf7],
+            [0xbb, 0x9a, 0xf7],
+            [0x7d, 0xcf, 0xff],
+            [0xc0, 0xca, 0xf5],
+            [0x41, 0x4b, 0x6b],
+            [0xf7, 0x76, 0x8e],
+            [0x9e, 0xce, 0x6a],
+            [0xe0, 0xaf, 0x68],
+            [0x7a, 0xa2, 0xf7],
+            [0xbb, 0x9a, 0xf7],
+            [0x7d, 0xcf, 0xff],
+            [0xc0, 0xca, 0xf5],
+        ],
+        foreground: [0xc0, 0xca, 0xf5],
+        background: TOKYO_NIGHT_BG,
+        cursor: DEFAULT_CURSOR_COLOR,
+    };
+}
+
+impl Default for TerminalPalette {
+    fn default() -> Self {
+        Self::TOKYO_NIGHT
+    }
+}
+
+/// Palette color for an alacritty color index (0-255 palette, then
+/// `NamedColor::Foreground`, `Background`, `Cursor`, ...).
+///
+/// Used when the program hasn't overridden the color (OSC 4/10/11/12).
+pub fn default_color(index: usize, palette: &TerminalPalette) -> [u8; 3] {
+    match index {
+        0..=255 => indexed_color(index as u8, palette),
+        _ => named_color(index, palette),
+    }
+}
+
+/// Palette color for a `NamedColor` index. The dim colors are their normal
+/// color scaled by `DIM_FACTOR`; the bright foreground is the foreground.
+fn named_color(index: usize, palette: &TerminalPalette) -> [u8; 3] {
+    const DIM_BLACK: usize = NamedColor::DimBlack as usize;
+    const DIM_WHITE: usize = NamedColor::DimWhite as usize;
+    match index {
+        0..=15 => palette.ansi[index],
+        DIM_BLACK..=DIM_WHITE => dim(palette.ansi[index - DIM_BLACK]),
+        _ if index == NamedColor::Background as usize => palette.background,
+        _ if index == NamedColor::Cursor as usize => palette.cursor,
+        _ if index == NamedColor::DimForeground as usize => dim(palette.foreground),
+        _ => palette.foreground, // Foreground, BrightForeground
+    }
+}
+
+/// `color` darkened by `DIM_FACTOR`.
+pub fn dim(color: [u8; 3]) -> [u8; 3] {
+    color.map(|channel| (channel as f32 * DIM_FACTOR) as u8)
+}
+
+/// RGB for a cell color: the program's override (OSC 4/10/11) from `colors`
+/// if set, otherwise the palette's.
+pub fn resolve_color(color: Color, colors: &Colors, palette: &TerminalPalette) -> [u8; 3] {
+    let index = match color {
+        Color::Spec(rgb) => return [rgb.r, rgb.g, rgb.b],
+        Color::Named(named) => named as usize,
+        Color::Indexed(index) => index as usize,
+    };
+    match colors[index] {
+        Some(rgb) => [rgb.r, rgb.g, rgb.b],
+        None => convert_alacritty_color(color, palette),
+    }
+}
+
+/// Foreground RGB for a dim (SGR 2) cell: the dim variant of a named color,
+/// otherwise the color darkened by `DIM_FACTOR`, as alacritty draws it.
+pub fn resolve_dim_color(color: Color, colors: &Colors, palette: &TerminalPalette) -> [u8; 3] {
+    match color {
+        Color::Named(named) => resolve_color(Color::Named(named.to_dim()), colors, palette),
+        color => dim(resolve_color(color, colors, palette)),
+    }
+}
+
 /// Convert alacritty color to RGB array.
 ///
-/// Handles named colors (using Tokyo Night theme) and RGB colors.
-pub fn convert_alacritty_color(color: Color) -> [u8; 3] {
+/// Handles named colors (from `palette`), the 256-color palette and RGB colors.
+pub fn convert_alacritty_color(color: Color, palette: &TerminalPalette) -> [u8; 3] {
     match color {
-        Color::Named(named) => {
-            use alacritty_terminal::vte::ansi::NamedColor;
-            match named {
-                NamedColor::Black => [0x1a, 0x1b, 0x26],
-                NamedColor::Red => [0xf7, 0x76, 0x8e],
-                NamedColor::Green => [0x9e, 0xce, 0x6a],
-                NamedColor::Yellow => [0xe0, 0xaf, 0x68],
-                NamedColor::Blue => [0x7a, 0xa2, 0xf7],
-                NamedColor::Magenta => [0xbb, 0x9a, 0xf7],
-                NamedColor::Cyan => [0x7d, 0xcf, 0xff],
-                NamedColor::White => [0xc0, 0xca, 0xf5],
-                NamedColor::BrightBlack => [0x41, 0x4b, 0x6b],
-                NamedColor::BrightRed => [0xf7, 0x76, 0x8e],
-                NamedColor::BrightGreen => [0x9e, 0xce, 0x6a],
-                NamedColor::BrightYellow => [0xe0, 0xaf, 0x68],
-                NamedColor::BrightBlue => [0x7a, 0xa2, 0xf7],
-                NamedColor::BrightMagenta => [0xbb, 0x9a, 0xf7],
-                NamedColor::BrightCyan => [0x7d, 0xcf, 0xff],
-                NamedColor::BrightWhite => [0xc0, 0xca, 0xf5],
-                NamedColor::Foreground => [0xc0, 0xca, 0xf5],
-                NamedColor::Background => TOKYO_NIGHT_BG,
-                _ => [0xc0, 0xca, 0xf5], // Default to foreground
-            }
-        }
+        Color::Named(named) => named_color(named as usize, palette),
         Color::Spec(rgb) => [rgb.r, rgb.g, rgb.b],
-        Color::Indexed(index) => {
-            // 256-color palette - for MVP, just use a simple mapping
-            match index {
-                0 => [0x1a, 0x1b, 0x26],  // Black
-                1 => [0xf7, 0x76, 0x8e],  // Red
-                2 => [0x9e, 0xce, 0x6a],  // Green
-                3 => [0xe0, 0xaf, 0x68],  // Yellow
-                4 => [0x7a, 0xa2, 0xf7],  // Blue
-                5 => [0xbb, 0x9a, 0xf7],  // Magenta
-                6 => [0x7d, 0xcf, 0xff],  // Cyan
-                7 => [0xc0, 0xca, 0xf5],  // White
-                _ => [0xc0, 0xca, 0xf5],  // Default
-            }
+        Color::Indexed(index) => indexed_color(index, palette),
+    }
+}
+
+/// Channel levels of the xterm 6×6×6 color cube (indices 16-231).
+const CUBE_LEVELS: [u8; 6] = [0x00, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+
+/// xterm 256-color palette entry.
+///
+/// 0-15 are the palette's ANSI colors (normal, then bright), 16-231 the
+/// 6×6×6 color cube, and 232-255 a 24-step grayscale ramp from near-black
+/// to near-white.
+pub fn indexed_color(index: u8, palette: &TerminalPalette) -> [u8; 3] {
+    match index {
+        0..=15 => palette.ansi[index as usize],
+        16..=231 => {
+            let cube = index - 16;
+            [
+                CUBE_LEVELS[(cube / 36) as usize],
+                CUBE_LEVELS[(cube / 6 % 6) as usize],
+                CUBE_LEVELS[(cube % 6) as usize],
+            ]
         }
+        232..=255 => {
+            let level = 8 + 10 * (index - 232);
+            [level; 3]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::vte::ansi::Rgb;
+
+    #[test]
+    fn test_indexed_palette() {
+        let palette = TerminalPalette::default();
+
+        // Base colors follow the palette
+        assert_eq!(indexed_color(1, &palette), convert_alacritty_color(Color::Named(NamedColor::Red), &palette));
+        assert_eq!(indexed_color(8, &palette), convert_alacritty_color(Color::Named(NamedColor::BrightBlack), &palette));
+
+        // Color cube corners and a few well-known entries
+        assert_eq!(indexed_color(16, &palette), [0x00, 0x00, 0x00]);
+        assert_eq!(indexed_color(21, &palette), [0x00, 0x00, 0xff]);
+        assert_eq!(indexed_color(196, &palette), [0xff, 0x00, 0x00]);
+        assert_eq!(indexed_color(208, &palette), [0xff, 0x87, 0x00]);
+        assert_eq!(indexed_color(231, &palette), [0xff, 0xff, 0xff]);
+
+        // Grayscale ramp
+        assert_eq!(indexed_color(232, &palette), [0x08; 3]);
+        assert_eq!(indexed_color(244, &palette), [0x80; 3]);
+        assert_eq!(indexed_color(255, &palette), [0xee; 3]);
+
+        // Reached through the generic conversion too
+        assert_eq!(convert_alacritty_color(Color::Indexed(196), &palette), [0xff, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn test_custom_palette() {
+        let mut palette = TerminalPalette {
+            foreground: [0x33, 0xff, 0x33],
+            background: [0x00, 0x11, 0x00],
+            cursor: [0x66, 0xff, 0x66],
+            ..Default::default()
+        };
+        palette.ansi[2] = [0x00, 0xcc, 0x00];
+
+        assert_eq!(convert_alacritty_color(Color::Named(NamedColor::Green), &palette), [0x00, 0xcc, 0x00]);
+        assert_eq!(indexed_color(2, &palette), [0x00, 0xcc, 0x00]);
+        assert_eq!(default_color(NamedColor::Foreground as usize, &palette), [0x33, 0xff, 0x33]);
+        assert_eq!(default_color(NamedColor::Background as usize, &palette), [0x00, 0x11, 0x00]);
+        assert_eq!(default_color(NamedColor::Cursor as usize, &palette), [0x66, 0xff, 0x66]);
+
+        // The cube and grayscale ramp are fixed
+        assert_eq!(indexed_color(196, &palette), [0xff, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn test_dim_colors() {
+        let palette = TerminalPalette {
+            foreground: [0xc8, 0x64, 0x00],
+            ..Default::default()
+        };
+        let named = |named: NamedColor| convert_alacritty_color(Color::Named(named), &palette);
+
+        // Two thirds of the normal color, truncated
+        assert_eq!(named(NamedColor::DimForeground), [0x84, 0x42, 0x00]);
+        assert_eq!(named(NamedColor::DimRed), dim(palette.ansi[1]));
+        assert_eq!(named(NamedColor::DimWhite), dim(palette.ansi[7]));
+        assert_eq!(default_color(NamedColor::DimBlack as usize, &palette), dim(palette.ansi[0]));
+        assert_eq!(named(NamedColor::BrightForeground), palette.foreground);
+
+        // SGR 2 dims named colors through the palette, others by the factor
+        let colors = Colors::default();
+        assert_eq!(resolve_dim_color(Color::Named(NamedColor::Green), &colors, &palette), dim(palette.ansi[2]));
+        assert_eq!(resolve_dim_color(Color::Named(NamedColor::BrightGreen), &colors, &palette), palette.ansi[2]);
+        let orange = Color::Spec(Rgb { r: 0xff, g: 0x80, b: 0x00 });
+        assert_eq!(resolve_dim_color(orange, &colors, &palette), [0xa8, 0x54, 0x00]);
     }
-}
\ No newline at end of file
+}
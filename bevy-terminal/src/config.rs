@@ -0,0 +1,528 @@
+//! Plugin configuration.
+//!
+//! `TerminalPlugin` carries a `TerminalConfig`. It is inserted as a resource
+//! for the plugin-wide settings (font, atlas, render backend, clock, poll
+//! rate) and as a component on the primary terminal; every other terminal
+//! carries its own. Defaults match the MVP behavior.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::TextureFormat;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::clock::TerminalClock;
+use crate::colors::TOKYO_NIGHT_BG;
+use crate::font::{FontSource, GlyphPlacement, FONT_SIZE};
+use crate::gpu_types::{
+    CURSOR_BLINK_FADE, CURSOR_BLINK_HARD, CURSOR_STYLE_BAR, CURSOR_STYLE_BLOCK, CURSOR_STYLE_UNDERLINE,
+};
+use crate::paste::PasteConfig;
+use crate::renderer::TERMINAL_TEXTURE_FORMAT;
+use crate::terminal::DEFAULT_EVENT_CAPACITY;
+
+/// Terminal plugin configuration.
+///
+/// As a component it configures its terminal entity. The resource copy
+/// supplies the settings shared by every terminal: the font and glyph
+/// options, texture format, render backend, headless mode, clock, blink
+/// period and poll interval.
+#[derive(Resource, Component, Clone)]
+pub struct TerminalConfig {
+    /// Spawn a shell PTY at startup. Disable when the terminal is fed only
+    /// by a custom `SourceResource`.
+    pub spawn_pty: bool,
+    /// Restart the shell after it exits (or its reader dies): the grid is
+    /// reset and a fresh PTY spawned, sending `TerminalEventKind::ProcessExited`
+    /// then `TerminalEventKind::Spawned`. Off by default, so the exit is final.
+    pub respawn: bool,
+    /// Wait before the first respawn; doubles for each shell that exits
+    /// soon after being respawned. 250ms by default.
+    pub respawn_delay: Duration,
+    /// Respawns in a row (of shells exiting within 10 seconds) before giving
+    /// up with `TerminalEventKind::Error`. 5 by default.
+    pub respawn_limit: u32,
+    /// Skip rendering: no shaders, render node, glyph atlas image or
+    /// terminal texture. The PTY, `TerminalState` and `TerminalCpuBuffer`
+    /// still update, so it works under `MinimalPlugins` for logic-only tests
+    /// and servers without a GPU. Off by default.
+    pub headless: bool,
+    /// Program the PTY runs instead of the detected shell (`$SHELL`, then
+    /// bash/zsh/sh; PowerShell on Windows), e.g. `python` or the game's own
+    /// REPL. Found on `PATH` unless it's a path. `None` by default.
+    pub shell: Option<String>,
+    /// Arguments for `shell`; ignored while it's `None`.
+    pub shell_args: Vec<String>,
+    /// Extra environment variables for the PTY program, on top of the
+    /// game's own environment, e.g. `GAME_SAVE_DIR` or a custom `PATH`.
+    ///
+    /// Set after `TERM=xterm-256color`, so a `TERM` entry overrides it.
+    pub env: HashMap<String, String>,
+    /// Have bash mark its prompts and commands with OSC 133, for
+    /// `TerminalEventKind::PromptStart` and friends (see `shell_integration`).
+    /// Other shells are started as usual. Off by default.
+    pub shell_integration: bool,
+    /// Working directory for the PTY program. `None` uses the game's
+    /// current directory, falling back to the home directory.
+    pub cwd: Option<PathBuf>,
+    /// Render into this game-owned image instead of a plugin-owned texture.
+    ///
+    /// The image must use `texture_format`, include `TextureUsages::STORAGE_BINDING`,
+    /// and be sized to the grid (`cols × cell_width` by `rows × cell_height`).
+    /// An invalid target is logged and the plugin falls back to its own texture.
+    pub target: Option<Handle<Image>>,
+    /// Optional status bar row baked into the texture above or below the grid.
+    ///
+    /// The texture grows by one cell row. Off by default.
+    pub status_bar: Option<StatusBarConfig>,
+    /// Floating text (e.g. "DEMO") blended over the texture on top of the
+    /// grid and status bar. Off by default.
+    pub watermark: Option<WatermarkConfig>,
+    /// Reset a scrolled-up viewport to the bottom whenever output arrives.
+    ///
+    /// When off the view stays put and `TerminalState::has_unseen_output()`
+    /// reports the new output instead. On by default.
+    pub scroll_on_output: bool,
+    /// Minimum real time between PTY/source polls. `None` polls every frame.
+    ///
+    /// Useful with many terminals. Keep it short (~8ms, 120 polls/sec) so
+    /// interactive typing still echoes promptly.
+    pub poll_interval: Option<Duration>,
+    /// Most output bytes parsed per poll; the rest carries over to later
+    /// frames. `None` parses everything as it arrives; `Some(0)` is treated
+    /// as 1 byte so output still drains.
+    ///
+    /// Smooths frame times when a program dumps a large burst (e.g. `cat`
+    /// of a big file) at the cost of the burst taking a few frames to show.
+    pub max_bytes_per_frame: Option<usize>,
+    /// Terminal font. Defaults to the embedded Cascadia Mono.
+    ///
+    /// A font that fails to load is reported as `TerminalEventKind::Error` and
+    /// the terminal isn't rendered.
+    pub font: FontSource,
+    /// Font size in points, 14 by default. Cell size, atlas, texture size
+    /// and shader uniforms all follow from it.
+    pub font_size: f32,
+    /// Color bitmap font (CBDT or sbix, e.g. Noto Color Emoji) that flags
+    /// and other multi-codepoint emoji are drawn from. Without one flags
+    /// draw as their two letters in the terminal font.
+    ///
+    /// Failures load like `font`'s.
+    #[cfg(feature = "emoji")]
+    pub emoji_font: Option<FontSource>,
+    /// Vertical glyph placement used when building the atlas.
+    pub glyph_placement: GlyphPlacement,
+    /// What a character without an atlas glyph draws as: `?` (the default),
+    /// a hollow box, or nothing. Grid characters only need it while the
+    /// atlas is full; status bar and watermark text always falls back.
+    pub fallback_glyph: FallbackGlyph,
+    /// Render box-drawing and block characters without anti-aliasing so
+    /// borders are solid. Off by default.
+    pub crisp_box_drawing: bool,
+    /// Output texture format: `Rgba8Unorm` (default), `Bgra8Unorm`,
+    /// `Rgba16Float` or `Rgba32Float`.
+    ///
+    /// Formats the device can't write as a storage texture are logged and
+    /// replaced by `Rgba8Unorm`.
+    pub texture_format: TextureFormat,
+    /// How the grid is drawn into the texture. `Auto` (the default) uses the
+    /// compute shader where the device supports it and the fragment shader
+    /// elsewhere (WebGL2, older mobile GPUs).
+    pub render_backend: RenderBackend,
+    /// Chunking for large pastes (see `PtyResource::paste`).
+    pub paste: PasteConfig,
+    /// Length of one blink cycle for cursor/text blink (see `TerminalClock`).
+    pub blink_period: Duration,
+    /// Draw the cursor at the terminal's cursor position (unless the
+    /// program hides it). On by default.
+    pub show_cursor: bool,
+    /// Cursor shape: a block over the text (default), or a bar or
+    /// underline beneath it.
+    pub cursor_style: CursorStyle,
+    /// Cursor blink: hard on/off (default) or a smooth fade. A zero
+    /// `blink_period` keeps the cursor solid.
+    pub cursor_blink: CursorBlinkStyle,
+    /// Selection highlight color and opacity, and cursor opacity.
+    pub theme: TerminalTheme,
+    /// Fixed step of the effect clock. Smaller is smoother, larger is cheaper.
+    pub clock_timestep: Duration,
+    /// Approximate byte cap on scrollback memory; oldest lines are evicted
+    /// first. `None` keeps the 10,000 line limit only.
+    pub history_budget: Option<usize>,
+    /// Animated placeholder shown at the cursor until the first output
+    /// arrives. Off by default.
+    pub loading_indicator: Option<LoadingIndicator>,
+    /// Take the initial grid and PTY size from the `COLUMNS` and `LINES`
+    /// environment variables when both are set (see `env_grid_size`), for
+    /// launchers and CI. Off by default.
+    pub size_from_env: bool,
+    /// Part of the grid drawn into the texture, for a grid bigger than what
+    /// is shown. Change it at runtime to pan. `None` (the default) draws the
+    /// whole grid.
+    pub viewport: Option<GridViewport>,
+    /// Most alacritty events (replies to terminal queries, title changes,
+    /// bells) queued between frames. Beyond this they are dropped and counted
+    /// in `TerminalStats::dropped_events`. Default `DEFAULT_EVENT_CAPACITY`.
+    pub event_capacity: usize,
+}
+
+impl Default for TerminalConfig {
+    fn default() -> Self {
+        Self {
+            spawn_pty: true,
+            respawn: false,
+            respawn_delay: Duration::from_millis(250),
+            respawn_limit: 5,
+            headless: false,
+            shell: None,
+            shell_args: Vec::new(),
+            env: HashMap::new(),
+            shell_integration: false,
+            cwd: None,
+            target: None,
+            status_bar: None,
+            watermark: None,
+            scroll_on_output: true,
+            poll_interval: None,
+            max_bytes_per_frame: None,
+            font: FontSource::Embedded,
+            font_size: FONT_SIZE,
+            #[cfg(feature = "emoji")]
+            emoji_font: None,
+            glyph_placement: GlyphPlacement::Baseline,
+            fallback_glyph: FallbackGlyph::default(),
+            crisp_box_drawing: false,
+            texture_format: TERMINAL_TEXTURE_FORMAT,
+            render_backend: RenderBackend::Auto,
+            paste: PasteConfig::default(),
+            blink_period: Duration::from_secs(1),
+            show_cursor: true,
+            cursor_style: CursorStyle::Block,
+            cursor_blink: CursorBlinkStyle::Hard,
+            theme: TerminalTheme::default(),
+            clock_timestep: Duration::from_secs_f64(1.0 / 120.0),
+            history_budget: None,
+            loading_indicator: None,
+            size_from_env: false,
+            viewport: None,
+            event_capacity: DEFAULT_EVENT_CAPACITY,
+        }
+    }
+}
+
+impl TerminalConfig {
+    /// Texture rows beyond the terminal grid (the status bar, when enabled).
+    pub fn extra_rows(&self) -> usize {
+        usize::from(self.status_bar.is_some())
+    }
+
+    /// Cells of a `cols × rows` grid drawn into the texture: `viewport`
+    /// fitted to the grid, or all of it.
+    pub fn visible_grid(&self, cols: usize, rows: usize) -> GridViewport {
+        self.viewport.map_or(GridViewport::full(cols, rows), |viewport| viewport.fit(cols, rows))
+    }
+}
+
+/// Window of `cols × rows` grid cells whose top-left cell is at column
+/// `col`, row `row` (row 0 is the top of the screen).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GridViewport {
+    pub col: usize,
+    pub row: usize,
+    pub cols: usize,
+    pub rows: usize,
+}
+
+impl GridViewport {
+    /// The whole of a `cols × rows` grid.
+    pub fn full(cols: usize, rows: usize) -> Self {
+        Self { col: 0, row: 0, cols, rows }
+    }
+
+    /// This window inside a `cols × rows` grid: shrunk to the grid if
+    /// bigger, then moved back inside if it hangs off the edge. At least one
+    /// cell.
+    pub fn fit(self, cols: usize, rows: usize) -> Self {
+        let (cols, rows) = (cols.max(1), rows.max(1));
+        let width = self.cols.clamp(1, cols);
+        let height = self.rows.clamp(1, rows);
+        Self {
+            col: self.col.min(cols - width),
+            row: self.row.min(rows - height),
+            cols: width,
+            rows: height,
+        }
+    }
+}
+
+/// Column bounds for sizes read by `env_grid_size`.
+pub const ENV_COLS_RANGE: RangeInclusive<usize> = 20..=500;
+/// Row bounds for sizes read by `env_grid_size`.
+pub const ENV_ROWS_RANGE: RangeInclusive<usize> = 5..=200;
+
+/// Grid size from the `COLUMNS` (or `COLS`) and `LINES` environment
+/// variables, clamped to `ENV_COLS_RANGE` × `ENV_ROWS_RANGE`.
+///
+/// None unless both are set to positive integers.
+pub fn env_grid_size() -> Option<(usize, usize)> {
+    let var = |name| {
+        let value = std::env::var(name).ok()?;
+        value.trim().parse::<usize>().ok().filter(|&value| value > 0)
+    };
+    let cols = var("COLUMNS").or_else(|| var("COLS"))?;
+    let rows = var("LINES")?;
+    Some((
+        cols.clamp(*ENV_COLS_RANGE.start(), *ENV_COLS_RANGE.end()),
+        rows.clamp(*ENV_ROWS_RANGE.start(), *ENV_ROWS_RANGE.end()),
+    ))
+}
+
+/// Renderer that draws the grid into the terminal texture.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderBackend {
+    /// Compute where supported, otherwise fragment.
+    #[default]
+    Auto,
+    /// Compute shader writing a storage texture, one thread per pixel.
+    /// Needs compute shaders and storage textures.
+    Compute,
+    /// One instanced quad per cell drawn into the texture as a render
+    /// target. Runs anywhere Bevy renders, including WebGL2.
+    Fragment,
+    /// Every texel shaded on the CPU and uploaded with the image (see
+    /// `software`). Needs no GPU rendering support, but is slow; for
+    /// fallback and pixel tests. Never picked by `Auto`.
+    Cpu,
+}
+
+/// Glyph drawn for a character the atlas has no glyph for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FallbackGlyph {
+    /// Another character's glyph, `?` by default. Rasterized on demand when
+    /// the atlas lacks it too.
+    Char(char),
+    /// A hollow box, as drawn for characters the font lacks.
+    Tofu,
+    /// Nothing: the cell shows only its background.
+    Blank,
+}
+
+impl Default for FallbackGlyph {
+    fn default() -> Self {
+        FallbackGlyph::Char('?')
+    }
+}
+
+/// Placeholder drawn before the first byte of output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LoadingIndicator {
+    /// Block cursor blinking with `blink_period`.
+    #[default]
+    Cursor,
+    /// `|/-\` spinner, one revolution per `blink_period`.
+    Spinner,
+}
+
+impl LoadingIndicator {
+    /// Character to draw at `clock`'s current time.
+    pub fn frame(self, clock: &TerminalClock) -> char {
+        match self {
+            LoadingIndicator::Cursor if clock.blink_on() => '█',
+            LoadingIndicator::Cursor => ' ',
+            LoadingIndicator::Spinner => {
+                const FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+                FRAMES[((clock.blink_phase * 4.0) as usize).min(3)]
+            }
+        }
+    }
+}
+
+/// How the cursor blinks over one `blink_period`.
+///
+/// The shader computes the cursor's alpha from `TerminalClock::blink_phase`
+/// (the `blink_phase` uniform); `alpha` is the same curve on the CPU.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CursorBlinkStyle {
+    /// Fully visible for the first half of the cycle, hidden for the second.
+    #[default]
+    Hard,
+    /// Fades out and back in following a cosine, fully hidden at mid-cycle.
+    Fade,
+}
+
+impl CursorBlinkStyle {
+    /// `TerminalUniforms::cursor_blink` value (`CURSOR_BLINK_*`).
+    pub fn uniform(self) -> u32 {
+        match self {
+            CursorBlinkStyle::Hard => CURSOR_BLINK_HARD,
+            CursorBlinkStyle::Fade => CURSOR_BLINK_FADE,
+        }
+    }
+
+    /// Cursor alpha (0.0-1.0) at `phase` of the blink cycle.
+    pub fn alpha(self, phase: f32) -> f32 {
+        match self {
+            CursorBlinkStyle::Hard if phase < 0.5 => 1.0,
+            CursorBlinkStyle::Hard => 0.0,
+            CursorBlinkStyle::Fade => 0.5 + 0.5 * (phase * std::f32::consts::TAU).cos(),
+        }
+    }
+}
+
+/// Cursor shape, which also decides whether it's drawn over or beneath the
+/// cell's text.
+///
+/// The shader composites the cursor per style (the `cursor_style` uniform);
+/// `covers` and `composite` are the same math on the CPU.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CursorStyle {
+    /// Solid cell over the text, which shows through in the background color.
+    #[default]
+    Block,
+    /// Thin bar at the cell's left edge, beneath the text.
+    Bar,
+    /// Thin line along the cell's bottom, beneath the text.
+    Underline,
+}
+
+impl CursorStyle {
+    /// `TerminalUniforms::cursor_style` value (`CURSOR_STYLE_*`).
+    pub fn uniform(self) -> u32 {
+        match self {
+            CursorStyle::Block => CURSOR_STYLE_BLOCK,
+            CursorStyle::Bar => CURSOR_STYLE_BAR,
+            CursorStyle::Underline => CURSOR_STYLE_UNDERLINE,
+        }
+    }
+
+    /// Whether the cursor covers texel (`x`, `y`) of a `cell_width` ×
+    /// `cell_height` cell. Bars and underlines are an eighth of the cell
+    /// thick, at least one texel.
+    pub fn covers(self, x: u32, y: u32, cell_width: u32, cell_height: u32) -> bool {
+        match self {
+            CursorStyle::Block => true,
+            CursorStyle::Bar => x < (cell_width / 8).max(1),
+            CursorStyle::Underline => y + (cell_height / 8).max(1) >= cell_height,
+        }
+    }
+
+    /// Color of a covered texel whose glyph coverage is `glyph_alpha`, with
+    /// the cursor at `blink` alpha (`CursorBlinkStyle::alpha` times
+    /// `TerminalTheme::cursor_alpha`).
+    ///
+    /// A block inverts the cell: the text is drawn in `bg` on `cursor`. Bars
+    /// and underlines replace only the background, so the glyph stays on
+    /// top in `fg`.
+    pub fn composite(self, fg: Vec4, bg: Vec4, cursor: Vec4, blink: f32, glyph_alpha: f32) -> Vec4 {
+        let fg = match self {
+            CursorStyle::Block => fg.lerp(bg, blink),
+            CursorStyle::Bar | CursorStyle::Underline => fg,
+        };
+        bg.lerp(cursor, blink).lerp(fg, glyph_alpha)
+    }
+}
+
+/// Highlight colors drawn over the grid.
+///
+/// The selection is a translucent layer over the selected cells, text
+/// included, so selected text stays readable in its own colors. The shader
+/// blends it with the `selection_color` uniform.
+///
+/// `cursor_color` overrides the `TerminalPalette` cursor for this terminal;
+/// programs can still change it (OSC 12) and query it, and OSC 112 restores
+/// it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TerminalTheme {
+    /// Selection highlight color, Tokyo Night's by default.
+    pub selection_color: [u8; 3],
+    /// Selection opacity (0.0-1.0), 0.5 by default. At 1.0 the highlight
+    /// hides the text.
+    pub selection_alpha: f32,
+    /// Cursor opacity (0.0-1.0), scaling the blink alpha. 1.0 (the default)
+    /// draws it solid.
+    pub cursor_alpha: f32,
+    /// Cursor color. `None` (the default) uses `TerminalPalette::cursor`.
+    pub cursor_color: Option<[u8; 3]>,
+}
+
+impl Default for TerminalTheme {
+    fn default() -> Self {
+        Self {
+            selection_color: [0x33, 0x46, 0x7c],
+            selection_alpha: 0.5,
+            cursor_alpha: 1.0,
+            cursor_color: None,
+        }
+    }
+}
+
+/// Where the status bar row sits relative to the grid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StatusBarPosition {
+    #[default]
+    Top,
+    Bottom,
+}
+
+/// Text shown in the status bar.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum StatusBarText {
+    /// Title set by the running program (OSC 0/2), blank until one is set.
+    #[default]
+    Title,
+    /// Fixed string.
+    Custom(String),
+}
+
+/// Status bar row configuration.
+///
+/// Text longer than the grid width is truncated.
+#[derive(Clone, Debug)]
+pub struct StatusBarConfig {
+    pub position: StatusBarPosition,
+    pub text: StatusBarText,
+    /// Text color (RGB)
+    pub fg: [u8; 3],
+    /// Bar background color (RGB)
+    pub bg: [u8; 3],
+}
+
+impl Default for StatusBarConfig {
+    fn default() -> Self {
+        Self {
+            position: StatusBarPosition::Top,
+            text: StatusBarText::Title,
+            fg: TOKYO_NIGHT_BG,
+            bg: [0x7a, 0xa2, 0xf7], // Tokyo Night blue
+        }
+    }
+}
+
+/// Watermark overlay configuration.
+///
+/// The text sits on one texture cell row starting at (`column`, `row`),
+/// counted from the top-left of the texture (a top status bar is row 0).
+/// Text running past the right edge is clipped.
+#[derive(Clone, Debug)]
+pub struct WatermarkConfig {
+    pub text: String,
+    pub column: u32,
+    pub row: u32,
+    /// Text color (RGB)
+    pub color: [u8; 3],
+    /// 0.0 (invisible) to 1.0 (opaque glyphs)
+    pub opacity: f32,
+}
+
+impl Default for WatermarkConfig {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            column: 0,
+            row: 0,
+            color: [0xff, 0xff, 0xff],
+            opacity: 0.25,
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! CRT look for the terminal texture (`crt` feature).
+//!
+//! `CrtMaterial` is a `Material2d` that draws the terminal texture through a
+//! curved tube: barrel distortion, scanlines on every texel row, a soft bloom
+//! around bright text and darkened corners. `spawn_terminal_crt` puts it on a
+//! quad the size of the texture, as `spawn_terminal_sprite` does for a sprite:
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use bevy_terminal::crt::{spawn_terminal_crt, CrtMaterial, CrtSettings};
+//! use bevy_terminal::prelude::*;
+//!
+//! fn spawn_crt(
+//!     mut commands: Commands,
+//!     mut meshes: ResMut<Assets<Mesh>>,
+//!     mut materials: ResMut<Assets<CrtMaterial>>,
+//!     terminal: Single<(Entity, &TerminalTexture), (With<PrimaryTerminal>, Added<TerminalTexture>)>,
+//! ) {
+//!     let (terminal, texture) = *terminal;
+//!     let settings = CrtSettings::default();
+//!     spawn_terminal_crt(&mut commands, &mut meshes, &mut materials, terminal, texture, settings, Transform::default());
+//! }
+//!
+//! App::new()
+//!     .add_plugins((DefaultPlugins, TerminalPlugin::default()))
+//!     .add_systems(Update, spawn_crt)
+//!     .run();
+//! ```
+//!
+//! `TerminalPlugin` adds `CrtPlugin` when the feature is enabled.
+
+use bevy::asset::{load_internal_asset, uuid_handle};
+use bevy::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, ShaderType};
+use bevy::shader::ShaderRef;
+use bevy::sprite_render::{Material2d, Material2dPlugin, MeshMaterial2d};
+use log::error;
+
+use crate::renderer::TerminalTexture;
+
+pub const CRT_SHADER_HANDLE: Handle<Shader> = uuid_handle!("be77e7aa-0000-0000-0000-000000000003");
+
+/// Effect strengths for `CrtMaterial`, each 0.0 (off) to 1.0.
+#[derive(Clone, Copy, Debug, PartialEq, ShaderType)]
+pub struct CrtSettings {
+    /// How dark the gaps between texel rows get. 0.25 by default.
+    pub scanline_intensity: f32,
+    /// Barrel distortion: how far the corners bend outward. 0.08 by default.
+    pub curvature: f32,
+    /// Glow added around bright texels. 0.3 by default.
+    pub bloom: f32,
+    /// How dark the corners get. 0.3 by default.
+    pub vignette: f32,
+}
+
+impl Default for CrtSettings {
+    fn default() -> Self {
+        Self {
+            scanline_intensity: 0.25,
+            curvature: 0.08,
+            bloom: 0.3,
+            vignette: 0.3,
+        }
+    }
+}
+
+/// `Material2d` showing the terminal texture as a CRT screen.
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug)]
+pub struct CrtMaterial {
+    #[uniform(0)]
+    pub settings: CrtSettings,
+    /// Usually `TerminalTexture::handle`
+    #[texture(1)]
+    #[sampler(2)]
+    pub texture: Handle<Image>,
+}
+
+impl Material2d for CrtMaterial {
+    fn fragment_shader() -> ShaderRef {
+        CRT_SHADER_HANDLE.into()
+    }
+}
+
+/// Marks quads spawned by `spawn_terminal_crt`, with the terminal entity
+/// they show.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TerminalCrt(pub Entity);
+
+/// Registers `CrtMaterial` and keeps `TerminalCrt` quads fitted to their
+/// terminal's texture.
+pub struct CrtPlugin;
+
+impl Plugin for CrtPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(app, CRT_SHADER_HANDLE, "../assets/shaders/crt.wgsl", Shader::from_wgsl);
+
+        app.add_plugins(Material2dPlugin::<CrtMaterial>::default())
+            .add_systems(PostUpdate, fit_crt_screens);
+    }
+}
+
+/// Spawns a quad showing `terminal`'s texture through `CrtMaterial` and
+/// returns its entity.
+///
+/// Sized like `spawn_terminal_sprite`: at scale 1.0 one texel covers one
+/// world unit. Add your own components with
+/// `commands.entity(entity).insert(...)`.
+pub fn spawn_terminal_crt(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<CrtMaterial>,
+    terminal: Entity,
+    texture: &TerminalTexture,
+    settings: CrtSettings,
+    transform: Transform,
+) -> Entity {
+    let material = CrtMaterial {
+        settings,
+        texture: texture.handle.clone(),
+    };
+    commands
+        .spawn((
+            Mesh2d(meshes.add(Rectangle::new(texture.width as f32, texture.height as f32))),
+            MeshMaterial2d(materials.add(material)),
+            transform,
+            TerminalCrt(terminal),
+        ))
+        .id()
+}
+
+/// Resizes `TerminalCrt` quads to their resized terminal texture.
+///
+/// System: PostUpdate
+/// Runs: Every frame; acts on quads whose terminal's `TerminalTexture`
+/// changed
+///
+/// The texture is replaced in place under the same handle, so each
+/// material is also marked changed to rebind the new image.
+pub fn fit_crt_screens(
+    textures: Query<&TerminalTexture, Changed<TerminalTexture>>,
+    screens: Query<(&Mesh2d, &MeshMaterial2d<CrtMaterial>, &TerminalCrt)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<CrtMaterial>>,
+) {
+    for (mesh, material, &TerminalCrt(terminal)) in &screens {
+        let Ok(texture) = textures.get(terminal) else {
+            continue;
+        };
+        let quad = Rectangle::new(texture.width as f32, texture.height as f32);
+        if let Err(error) = meshes.insert(&mesh.0, quad.into()) {
+            error!("❌ Failed to resize CRT quad: {}", error);
+        }
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.texture = texture.handle.clone();
+        }
+    }
+}
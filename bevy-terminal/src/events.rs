@@ -1,14 +1,186 @@
 //! Terminal events.
+//!
+//! Also dispatches events raised by alacritty (via `EventProxy`) each frame,
+//! and reports dead reader threads.
 
+use alacritty_terminal::event::Event as AlacEvent;
+use alacritty_terminal::vte::ansi::Rgb;
 use bevy::prelude::*;
+use log::{error, info};
 
-/// Events emitted by the terminal system
-#[derive(Event, Debug)]
-pub enum TerminalEvent {
+use crate::pty::PtyResource;
+use crate::source::SourceResource;
+use crate::terminal::TerminalState;
+
+/// Something that happened to one terminal.
+///
+/// Registered as a message by `TerminalPlugin`; read with
+/// `MessageReader<TerminalEvent>` and match on `kind`.
+#[derive(Event, Message, Debug)]
+pub struct TerminalEvent {
+    /// The terminal entity it happened to
+    pub terminal: Entity,
+    pub kind: TerminalEventKind,
+}
+
+impl TerminalEvent {
+    pub fn new(terminal: Entity, kind: TerminalEventKind) -> Self {
+        Self { terminal, kind }
+    }
+}
+
+/// What a `TerminalEvent` reports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TerminalEventKind {
     /// PTY and terminal spawned successfully
     Spawned,
     /// PTY process exited
     ProcessExited { exit_code: Option<i32> },
     /// Error occurred
     Error { message: String },
+    /// Ctrl+Shift+V was pressed. The plugin has no clipboard access: answer
+    /// with a `TerminalPaste` holding the clipboard text.
+    PasteRequested,
+    /// Ctrl+Shift+C was pressed with text selected. Put `text` on the
+    /// clipboard; the plugin can't.
+    CopyRequested { text: String },
+    /// The app is exiting and the terminal has been torn down (see
+    /// `shutdown_terminal_on_exit`). `transcript` is the history and screen
+    /// text (`TerminalState::transcript`), for saving.
+    Shutdown { transcript: String },
+    /// The shell started drawing its prompt (OSC 133 A)
+    PromptStart,
+    /// The prompt is drawn and the user is typing a command (OSC 133 B)
+    CommandStart,
+    /// The command is running; its output follows (OSC 133 C)
+    CommandExecuted,
+    /// The command finished (OSC 133 D), with its exit code if the shell
+    /// reported one
+    CommandEnd { exit_code: Option<i32> },
+    /// The program changed the title (OSC 0/2), e.g. to the current
+    /// directory. Empty when it reset the title. Also readable any time
+    /// with `TerminalState::title`.
+    TitleChanged { title: String },
+    /// The program rang the bell (BEL, `\a`); play a sound or flash the
+    /// screen
+    Bell,
+}
+
+/// Drains each terminal's alacritty events and acts on them.
+///
+/// System: Update
+/// Runs: Every frame, after `poll_pty` and before keyboard input
+///
+/// `PtyWrite` carries terminal replies (DA, DSR, CPR, CSI 18t size, ...) that
+/// must reach the PTY or the querying program hangs. Running between output
+/// parsing and keyboard input keeps replies ordered ahead of this frame's
+/// keystrokes.
+///
+/// Events the game cares about (`TerminalEventKind::Bell`, `TitleChanged`) are
+/// sent on.
+pub fn dispatch_terminal_events(
+    mut terminals: Query<(Entity, &mut TerminalState, Option<&PtyResource>)>,
+    mut events: MessageWriter<TerminalEvent>,
+) {
+    for (terminal, mut term_state, pty) in &mut terminals {
+        let kinds = handle_alacritty_events(&mut term_state, pty);
+        if !kinds.is_empty() {
+            events.write_batch(kinds.into_iter().map(|kind| TerminalEvent::new(terminal, kind)));
+        }
+    }
+}
+
+/// `dispatch_terminal_events` for one terminal, returning what to send.
+fn handle_alacritty_events(term_state: &mut TerminalState, pty: Option<&PtyResource>) -> Vec<TerminalEventKind> {
+    let mut terminal_events = Vec::new();
+    for event in term_state.drain_events() {
+        match event {
+            AlacEvent::PtyWrite(text) => {
+                let Some(pty) = pty else {
+                    continue;
+                };
+                if let Err(error) = pty.write_all(text.as_bytes()) {
+                    error!("❌ Failed to write terminal response to PTY: {}", error);
+                }
+            }
+            // Color queries (OSC 4/10/11/12 with `?`) answer with the current color
+            AlacEvent::ColorRequest(index, format) => {
+                let Some(pty) = pty else {
+                    continue;
+                };
+                let [r, g, b] = term_state.color(index);
+                if let Err(error) = pty.write_all(format(Rgb { r, g, b }).as_bytes()) {
+                    error!("❌ Failed to write color report to PTY: {}", error);
+                }
+            }
+            AlacEvent::Title(title) => {
+                if term_state.title.as_ref() != Some(&title) {
+                    terminal_events.push(TerminalEventKind::TitleChanged { title: title.clone() });
+                }
+                term_state.title = Some(title);
+            }
+            AlacEvent::ResetTitle => {
+                let previous = term_state.title.take();
+                if previous.is_some() {
+                    terminal_events.push(TerminalEventKind::TitleChanged { title: String::new() });
+                }
+            }
+            // Only standalone BELs arrive here; the parser consumes BELs
+            // that terminate OSC strings
+            AlacEvent::Bell => terminal_events.push(TerminalEventKind::Bell),
+            _ => {}
+        }
+    }
+    terminal_events
+}
+
+/// Reports reader threads that died, so the game learns the terminal is dead.
+///
+/// System: Update
+/// Runs: Every frame, after polling
+///
+/// Each failure sends `TerminalEventKind::Error` with the panic message followed
+/// by `TerminalEventKind::ProcessExited` (no exit code; nothing more will arrive).
+#[allow(clippy::type_complexity)]
+pub fn report_reader_failures(
+    mut terminals: Query<(Entity, Option<&mut PtyResource>, Option<&mut SourceResource>), With<TerminalState>>,
+    mut events: MessageWriter<TerminalEvent>,
+) {
+    for (terminal, pty, source) in &mut terminals {
+        let failures = [
+            pty.and_then(|mut pty| {
+                let failure = pty.failure.take();
+                // The failure already announces the exit
+                pty.exit_reported |= failure.is_some();
+                failure
+            }),
+            source.and_then(|mut source| source.failure.take()),
+        ];
+
+        for message in failures.into_iter().flatten() {
+            events.write(TerminalEvent::new(terminal, TerminalEventKind::Error { message }));
+            events.write(TerminalEvent::new(terminal, TerminalEventKind::ProcessExited { exit_code: None }));
+        }
+    }
+}
+
+/// Reports each shell exiting, once.
+///
+/// System: Update
+/// Runs: Every frame
+///
+/// Sends `TerminalEventKind::ProcessExited` with the shell's exit code (None if
+/// it couldn't be read). Skipped when a reader failure already reported the
+/// terminal as dead.
+pub fn report_process_exit(mut ptys: Query<(Entity, &mut PtyResource)>, mut events: MessageWriter<TerminalEvent>) {
+    for (terminal, mut pty) in &mut ptys {
+        if pty.exit_reported || pty.failure.is_some() {
+            continue;
+        }
+        if let Some(exit_code) = pty.try_exit_code() {
+            info!("🔚 Shell exited with code {:?}", exit_code);
+            pty.exit_reported = true;
+            events.write(TerminalEvent::new(terminal, TerminalEventKind::ProcessExited { exit_code }));
+        }
+    }
 }
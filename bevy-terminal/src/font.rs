@@ -1,14 +1,17 @@
 //! Font loading and metrics calculation.
 //!
-//! Loads Cascadia Mono and calculates cell dimensions for terminal rendering.
+//! Loads Cascadia Mono (or a game-supplied font, see `FontSource`) and
+//! calculates cell dimensions for terminal rendering.
 //! Uses ab_glyph for font parsing and rasterization.
 
 use anyhow::{Context, Result};
 use ab_glyph::{Font, FontVec, PxScale, ScaleFont};
 use bevy::prelude::*;
 use log::info;
+use std::borrow::Cow;
+use std::path::PathBuf;
 
-/// Font size in points for terminal text (MVP: hardcoded 14pt).
+/// Default font size in points (see `TerminalConfig::font_size`).
 pub const FONT_SIZE: f32 = 14.0;
 
 /// Font metrics and loaded font data.
@@ -24,10 +27,87 @@ pub struct FontMetrics {
     pub cell_width: f32,
     /// Height of each terminal cell in pixels
     pub cell_height: f32,
-    /// Font scale for rendering at 14pt
+    /// Font scale (the font size in pixels)
     pub scale: PxScale,
     /// Baseline offset from top of cell
     pub baseline: f32,
+    /// How glyphs are positioned vertically within the cell
+    pub placement: GlyphPlacement,
+    /// Rasterize box-drawing and block elements (U+2500–U+259F) without
+    /// anti-aliasing so borders and fills are solid. Text stays smooth.
+    pub crisp_box_drawing: bool,
+    /// Color font for multi-codepoint emoji (see `TerminalConfig::emoji_font`)
+    #[cfg(feature = "emoji")]
+    pub emoji_font: Option<crate::grapheme::EmojiFont>,
+}
+
+/// Where the terminal font comes from.
+///
+/// Anything ab_glyph can parse works (TTF/OTF). Monospace fonts only: cell
+/// width is taken from 'M'.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum FontSource {
+    /// Cascadia Mono, embedded in the crate.
+    #[default]
+    Embedded,
+    /// Font file read from disk at startup.
+    Path(PathBuf),
+    /// Font file contents, e.g. from the game's own `include_bytes!`.
+    Bytes(Vec<u8>),
+}
+
+impl FontSource {
+    /// Load the font at `font_size` points.
+    pub fn load(&self, font_size: f32) -> Result<FontMetrics> {
+        match self {
+            FontSource::Embedded => FontMetrics::load(CASCADIA_MONO, font_size)
+                .context("Failed to load Cascadia Mono font"),
+            FontSource::Path(path) => FontMetrics::load(&self.read()?, font_size)
+                .with_context(|| format!("Failed to load font {}", path.display())),
+            FontSource::Bytes(bytes) => FontMetrics::load(bytes, font_size),
+        }
+    }
+
+    /// The font file's contents, read from disk for `Path`.
+    pub fn read(&self) -> Result<Cow<'_, [u8]>> {
+        match self {
+            FontSource::Embedded => Ok(Cow::Borrowed(CASCADIA_MONO)),
+            FontSource::Path(path) => std::fs::read(path)
+                .map(Cow::Owned)
+                .with_context(|| format!("Failed to read font file {}", path.display())),
+            FontSource::Bytes(bytes) => Ok(Cow::Borrowed(bytes)),
+        }
+    }
+}
+
+impl From<PathBuf> for FontSource {
+    fn from(path: PathBuf) -> Self {
+        FontSource::Path(path)
+    }
+}
+
+impl From<Vec<u8>> for FontSource {
+    fn from(bytes: Vec<u8>) -> Self {
+        FontSource::Bytes(bytes)
+    }
+}
+
+/// Embedded Cascadia Mono font file.
+const CASCADIA_MONO: &[u8] = include_bytes!("../assets/fonts/CascadiaMono-Regular.ttf");
+
+/// Vertical glyph placement within a cell.
+///
+/// Outline fonts want `Baseline`. Bitmap/pixel fonts with unreliable ascent
+/// metrics often look right with `Top` or a small manual `Offset`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GlyphPlacement {
+    /// Baseline sits `ascent` pixels below the cell top (font metrics).
+    #[default]
+    Baseline,
+    /// The tallest glyph in the atlas touches the cell top.
+    Top,
+    /// Baseline placement shifted by this many pixels (positive = down).
+    Offset(f32),
 }
 
 impl FontMetrics {
@@ -73,17 +153,35 @@ impl FontMetrics {
             cell_height,
             scale,
             baseline,
+            placement: GlyphPlacement::Baseline,
+            crisp_box_drawing: false,
+            #[cfg(feature = "emoji")]
+            emoji_font: None,
         })
     }
 
-    /// Load Cascadia Mono from embedded bytes.
-    ///
-    /// This is the MVP font path - uses include_bytes!() for simplicity.
-    pub fn load_cascadia_mono() -> Result<Self> {
-        const CASCADIA_MONO: &[u8] = include_bytes!(
-            "../assets/fonts/CascadiaMono-Regular.ttf"
-        );
+    /// Use a different vertical glyph placement when building the atlas.
+    pub fn with_placement(mut self, placement: GlyphPlacement) -> Self {
+        self.placement = placement;
+        self
+    }
 
+    /// Choose whether box-drawing/block glyphs skip anti-aliasing (default off).
+    pub fn with_crisp_box_drawing(mut self, enabled: bool) -> Self {
+        self.crisp_box_drawing = enabled;
+        self
+    }
+
+    /// Draw flags and other multi-codepoint emoji from `emoji_font`'s color
+    /// bitmaps.
+    #[cfg(feature = "emoji")]
+    pub fn with_emoji_font(mut self, emoji_font: crate::grapheme::EmojiFont) -> Self {
+        self.emoji_font = Some(emoji_font);
+        self
+    }
+
+    /// Load the embedded Cascadia Mono at the default `FONT_SIZE`.
+    pub fn load_cascadia_mono() -> Result<Self> {
         Self::load(CASCADIA_MONO, FONT_SIZE)
             .context("Failed to load Cascadia Mono font")
     }
@@ -121,4 +219,20 @@ mod tests {
         assert_eq!(m_width, i_width, "Font should be monospace (M vs i)");
         assert_eq!(m_width, at_width, "Font should be monospace (M vs @)");
     }
+
+    #[test]
+    fn test_font_sources() {
+        let embedded = FontSource::Embedded.load(FONT_SIZE).expect("Embedded font");
+        let bytes = FontSource::from(CASCADIA_MONO.to_vec()).load(FONT_SIZE).expect("Font bytes");
+        assert_eq!(bytes.cell_width, embedded.cell_width);
+        assert_eq!(bytes.cell_height, embedded.cell_height);
+
+        let path = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/fonts/CascadiaMono-Regular.ttf"));
+        let from_path = FontSource::from(path).load(FONT_SIZE).expect("Font path");
+        assert_eq!(from_path.cell_width, embedded.cell_width);
+
+        let missing = FontSource::Path(PathBuf::from("/nonexistent/font.ttf")).load(FONT_SIZE);
+        assert!(format!("{:#}", missing.err().unwrap()).contains("/nonexistent/font.ttf"));
+        assert!(FontSource::Bytes(b"not a font".to_vec()).load(FONT_SIZE).is_err());
+    }
 }
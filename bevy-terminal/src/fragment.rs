@@ -0,0 +1,292 @@
+//! Fragment-shader render backend (`RenderBackend::Fragment`).
+//!
+//! Draws one instanced quad per cell straight into the terminal texture as a
+//! render target, so it needs neither compute shaders nor storage textures
+//! and runs on WebGL2 and older mobile GPUs. Watermark glyphs are extra
+//! quads, alpha blended after the cells. The output matches the compute
+//! backend pixel for pixel.
+
+use bevy::{
+    mesh::VertexBufferLayout,
+    prelude::*,
+    render::{
+        render_asset::RenderAssets,
+        render_resource::*,
+        renderer::{RenderContext, RenderDevice, RenderQueue},
+        texture::GpuImage,
+    },
+};
+use std::borrow::Cow;
+
+use crate::gpu_types::{GpuCellInstance, GLYPH_BLANK, INSTANCE_CELL, INSTANCE_WATERMARK, STATUS_BAR_TOP};
+use crate::render_node::{ExtractedTerminalData, RenderTerminal, RenderTerminals, TerminalDispatch};
+use crate::renderer::TerminalTextureFormat;
+use crate::terminal::TERMINAL_FRAGMENT_SHADER_HANDLE;
+
+/// Quads to draw this frame: grid and status bar cells, then watermark glyphs.
+///
+/// Rows are texture rows, so the grid moves down one when the status bar is
+/// on top. Watermark glyphs that are blank or off the texture are dropped.
+pub fn cell_instances(data: &ExtractedTerminalData) -> Vec<GpuCellInstance> {
+    let cols = data.term_cols.max(1);
+    let grid_top = u32::from(data.status_bar == STATUS_BAR_TOP);
+    let status_row = if data.status_bar == STATUS_BAR_TOP { 0 } else { data.term_rows };
+    let instance = |cell, column, row, kind| GpuCellInstance {
+        cell,
+        column,
+        row,
+        kind,
+        _padding: 0,
+    };
+
+    let grid = data
+        .cells
+        .iter()
+        .enumerate()
+        .map(|(i, &cell)| instance(cell, i as u32 % cols, grid_top + i as u32 / cols, INSTANCE_CELL));
+    let status = data
+        .status_cells
+        .iter()
+        .enumerate()
+        .map(|(i, &cell)| instance(cell, i as u32, status_row, INSTANCE_CELL));
+    let watermark = data
+        .watermark_cells
+        .iter()
+        .enumerate()
+        .map(|(i, &cell)| instance(cell, data.watermark_col + i as u32, data.watermark_row, INSTANCE_WATERMARK))
+        .filter(|mark| mark.cell.glyph_index != GLYPH_BLANK && mark.column < data.term_cols);
+
+    grid.chain(status).chain(watermark).collect()
+}
+
+#[derive(Resource)]
+pub struct TerminalFragmentPipeline {
+    pub layout: BindGroupLayout,
+    pub pipeline_id: CachedRenderPipelineId,
+}
+
+impl FromWorld for TerminalFragmentPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let format = world.get_resource::<TerminalTextureFormat>().copied().unwrap_or_default().0;
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(
+            Some("terminal_fragment_layout"),
+            &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::VERTEX_FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: false },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+            ],
+        );
+
+        // glyph, fg, bg, flags, column, row, kind; the padding word is skipped
+        let attributes = (0..7)
+            .map(|location| VertexAttribute {
+                format: VertexFormat::Uint32,
+                offset: location as u64 * 4,
+                shader_location: location,
+            })
+            .collect();
+        let instances = VertexBufferLayout {
+            array_stride: std::mem::size_of::<GpuCellInstance>() as u64,
+            step_mode: VertexStepMode::Instance,
+            attributes,
+        };
+
+        let shader = TERMINAL_FRAGMENT_SHADER_HANDLE;
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let pipeline_id = pipeline_cache.queue_render_pipeline(RenderPipelineDescriptor {
+            label: Some(Cow::Borrowed("terminal_fragment")),
+            layout: vec![layout.clone()],
+            push_constant_ranges: vec![],
+            vertex: VertexState {
+                shader: shader.clone(),
+                shader_defs: vec![],
+                entry_point: Some(Cow::Borrowed("vertex")),
+                buffers: vec![instances],
+            },
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            fragment: Some(FragmentState {
+                shader,
+                shader_defs: vec![],
+                entry_point: Some(Cow::Borrowed("fragment")),
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            zero_initialize_workgroup_memory: false,
+        });
+
+        Self { layout, pipeline_id }
+    }
+}
+
+pub struct TerminalFragmentResources {
+    pub uniform_buffer: Buffer,
+    pub instance_buffer: Buffer,
+    pub instance_count: u32,
+    pub bind_group: BindGroup,
+    /// Atlas texture view the bind group points at
+    pub atlas_view: TextureViewId,
+}
+
+/// Uploads this frame's uniforms and cell instances.
+///
+/// System: Render (Prepare)
+/// Runs: Every frame, when the fragment backend is active
+///
+/// Like `prepare_gpu_resources`, the buffers are refilled in place while
+/// the instance count and atlas stay the same.
+pub fn prepare_fragment_resources(
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    pipeline: Res<TerminalFragmentPipeline>,
+    gpu_images: Res<RenderAssets<GpuImage>>,
+    mut terminals: ResMut<RenderTerminals>,
+) {
+    for terminal in terminals.0.values_mut() {
+        prepare_terminal_fragment_resources(&render_device, &render_queue, &pipeline, &gpu_images, terminal);
+    }
+}
+
+/// `prepare_fragment_resources` for one terminal.
+fn prepare_terminal_fragment_resources(
+    render_device: &RenderDevice,
+    render_queue: &RenderQueue,
+    pipeline: &TerminalFragmentPipeline,
+    gpu_images: &RenderAssets<GpuImage>,
+    terminal: &mut RenderTerminal,
+) {
+    let Some(output_gpu_image) = gpu_images.get(&terminal.data.texture_handle) else {
+        return;
+    };
+    let Some(atlas_gpu_image) = gpu_images.get(&terminal.data.atlas_texture_handle) else {
+        return;
+    };
+
+    let can_clear = output_gpu_image
+        .texture
+        .usage()
+        .contains(TextureUsages::RENDER_ATTACHMENT);
+    terminal.select_dispatch(can_clear);
+
+    let data = &terminal.data;
+    let uniforms = data.uniforms();
+    let instances = cell_instances(data);
+    let atlas_view = atlas_gpu_image.texture_view.id();
+    if let Some(resources) = terminal.fragment.as_ref().filter(|resources| {
+        resources.instance_count as usize == instances.len() && resources.atlas_view == atlas_view
+    }) {
+        render_queue.write_buffer(&resources.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+        render_queue.write_buffer(&resources.instance_buffer, 0, bytemuck::cast_slice(&instances));
+        return;
+    }
+
+    let uniform_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+        label: Some("terminal_fragment_uniforms"),
+        contents: bytemuck::bytes_of(&uniforms),
+        usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+    });
+
+    let instance_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+        label: Some("terminal_cell_instances"),
+        contents: bytemuck::cast_slice(&instances),
+        usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+    });
+
+    let bind_group = render_device.create_bind_group(
+        Some("terminal_fragment_bind_group"),
+        &pipeline.layout,
+        &[
+            BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: BindingResource::TextureView(&atlas_gpu_image.texture_view),
+            },
+        ],
+    );
+
+    terminal.fragment = Some(TerminalFragmentResources {
+        uniform_buffer,
+        instance_buffer,
+        instance_count: instances.len() as u32,
+        bind_group,
+        atlas_view,
+    });
+}
+
+/// Clears a terminal's texture and draws the cells, or for a blank grid
+/// just the cursor cell.
+pub(crate) fn run_fragment_pass(render_context: &mut RenderContext, world: &World, terminal: &RenderTerminal) {
+    let (Some(resources), dispatch) = (&terminal.fragment, terminal.dispatch) else {
+        return;
+    };
+    if dispatch == TerminalDispatch::Skip {
+        return;
+    }
+
+    let extracted = &terminal.data;
+    let gpu_images = world.resource::<RenderAssets<GpuImage>>();
+    let Some(output) = gpu_images.get(&extracted.texture_handle) else {
+        return;
+    };
+    let pipeline_cache = world.resource::<PipelineCache>();
+    let pipeline_id = world.resource::<TerminalFragmentPipeline>().pipeline_id;
+    // Until the pipeline compiles the texture just shows the background
+    let pipeline = pipeline_cache.get_render_pipeline(pipeline_id);
+
+    let [r, g, b] = extracted.background.map(|c| c as f32 / 255.0);
+    let mut pass = render_context.command_encoder().begin_render_pass(&RenderPassDescriptor {
+        label: Some("terminal_fragment_pass"),
+        color_attachments: &[Some(RenderPassColorAttachment {
+            view: &output.texture_view,
+            depth_slice: None,
+            resolve_target: None,
+            ops: Operations {
+                load: LoadOp::Clear(LinearRgba::new(r, g, b, 1.0).into()),
+                store: StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+
+    // A blank grid has no status bar, so its cells are the first instances
+    let instances = match dispatch {
+        TerminalDispatch::Full => 0..resources.instance_count,
+        _ => match extracted.cursor_cell {
+            Some(cursor) => cursor..cursor + 1,
+            None => return,
+        },
+    };
+    if let Some(pipeline) = pipeline {
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &resources.bind_group, &[]);
+        pass.set_vertex_buffer(0, *resources.instance_buffer.slice(..));
+        pass.draw(0..6, instances);
+    }
+}
@@ -1,79 +1,340 @@
 use bevy::prelude::*;
-use crate::gpu_types::GpuTerminalCell;
+use log::info;
+use bytemuck::Zeroable;
+use crate::clock::TerminalClock;
+use crate::config::{FallbackGlyph, StatusBarConfig, StatusBarText, TerminalConfig, WatermarkConfig};
+use crate::gpu_types::{
+    GpuTerminalCell, CELL_FLAG_BOLD, CELL_FLAG_CURSOR, CELL_FLAG_INVERSE, CELL_FLAG_ITALIC,
+    CELL_FLAG_SELECTED, CELL_FLAG_UNDERLINE, CELL_FLAG_WIDE, CELL_FLAG_WIDE_SPACER, GLYPH_BLANK,
+};
 use crate::terminal::TerminalState;
 use crate::atlas::GlyphAtlas;
-use crate::colors::{convert_alacritty_color, TOKYO_NIGHT_BG};
-use alacritty_terminal::index::{Column, Line};
+use crate::colors::{resolve_color, resolve_dim_color};
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::cell::Flags;
+use alacritty_terminal::term::TermMode;
+use alacritty_terminal::vte::ansi::{Color, NamedColor};
 
-/// Resource holding the CPU-side buffer of terminal cells.
+/// Component holding the CPU-side buffer of a terminal's cells.
 ///
 /// This is updated every frame from the Alacritty grid and then uploaded to the GPU.
-#[derive(Resource, Default)]
+#[derive(Component, Default)]
 pub struct TerminalCpuBuffer {
     pub cells: Vec<GpuTerminalCell>,
+    /// One row of `cols` cells for the status bar (blank when disabled)
+    pub status_cells: Vec<GpuTerminalCell>,
+    /// One cell per watermark character (empty when disabled)
+    pub watermark_cells: Vec<GpuTerminalCell>,
+    /// Every cell is a default-background space (e.g. after `reset()` or before
+    /// any output), so the texture is a flat background fill plus the cursor
+    pub blank: bool,
+    /// Index into `cells` of the drawn cursor, if any
+    pub cursor_cell: Option<usize>,
+    /// `TerminalState::mark_dirty` was called since the last update: the
+    /// render node must redraw even if nothing seems to have changed
+    pub force_redraw: bool,
+    /// `TerminalState::redraw_generation` at the last update
+    pub redraw_generation: u64,
+    /// Default background (`TerminalState::background_color`) the cells were
+    /// built with, also the texture's clear color
+    pub background: [u8; 3],
+    /// Clusters on screen that aren't in the atlas yet, with their row's
+    /// range in `cells`, for `grapheme::rasterize_clusters`
+    #[cfg(feature = "emoji")]
+    pub missing_clusters: Vec<(std::ops::Range<usize>, crate::grapheme::GraphemeCluster)>,
 }
 
-/// Updates the CPU buffer from the terminal grid.
+/// Updates each terminal's CPU buffer from its grid.
 pub fn prepare_terminal_cpu_buffer(
-    term_state: Res<TerminalState>,
+    mut terminals: Query<(&mut TerminalCpuBuffer, &TerminalState, &TerminalConfig)>,
     atlas: Res<GlyphAtlas>,
-    mut cpu_buffer: ResMut<TerminalCpuBuffer>,
+    clock: Option<Res<TerminalClock>>,
 ) {
+    for (mut cpu_buffer, term_state, config) in &mut terminals {
+        fill_cpu_buffer(&mut cpu_buffer, term_state, &atlas, Some(config), clock.as_deref());
+    }
+}
+
+/// Builds one terminal's cells, status bar and watermark into `cpu_buffer`,
+/// as `prepare_terminal_cpu_buffer` does for each terminal.
+pub fn fill_cpu_buffer(
+    cpu_buffer: &mut TerminalCpuBuffer,
+    term_state: &TerminalState,
+    atlas: &GlyphAtlas,
+    config: Option<&TerminalConfig>,
+    clock: Option<&TerminalClock>,
+) {
+    let generation = term_state.redraw_generation();
+    cpu_buffer.force_redraw = generation != cpu_buffer.redraw_generation;
+    cpu_buffer.redraw_generation = generation;
+
+    // The grid is frozen mid-update; keep showing the last complete frame
+    if term_state.in_synchronized_update()
+        && !cpu_buffer.cells.is_empty()
+        && !cpu_buffer.force_redraw
+    {
+        return;
+    }
+
+    #[cfg(feature = "emoji")]
+    cpu_buffer.missing_clusters.clear();
+
+    term_state.check_dimensions();
+    let visible = term_state.visible_grid(config);
     let term = term_state.term.lock();
     let grid = term.grid();
-    let rows = term_state.rows;
-    let cols = term_state.cols;
+    let rows = visible.rows;
+    let cols = visible.cols;
+    let colors = term.colors();
+    let palette = term_state.palette();
+
+    // A new default background (OSC 11) repaints every blank cell, so even
+    // a blank grid must redraw
+    let background = resolve_color(Color::Named(NamedColor::Background), colors, palette);
+    if background != cpu_buffer.background {
+        cpu_buffer.background = background;
+        cpu_buffer.force_redraw = true;
+    }
+    let default_bg = pack_color(background);
+    let fallback = atlas.fallback_index(config.as_ref().map_or_else(FallbackGlyph::default, |config| config.fallback_glyph));
 
     // Resize buffer if needed
     let total_cells = rows * cols;
     if cpu_buffer.cells.len() != total_cells {
-        let bg_packed = pack_color(TOKYO_NIGHT_BG);
-        info!("Initializing CPU buffer with {} cells. Default BG: {:X}", total_cells, bg_packed);
+        info!("Initializing CPU buffer with {} cells. Default BG: {:X}", total_cells, default_bg);
         cpu_buffer.cells.resize(total_cells, GpuTerminalCell {
-            glyph_index: 0,
+            glyph_index: GLYPH_BLANK,
             fg_color: 0,
-            bg_color: bg_packed, // Default to opaque blue
+            bg_color: default_bg,
             flags: 0,
         });
     }
 
-    // Fill buffer
-    let mut updates = 0;
+    // Fill buffer from the visible part of the screen, which sits
+    // `display_offset` lines up into history when scrolled back
+    let display_offset = grid.display_offset() as i32;
+    let selection = term.selection.as_ref().and_then(|selection| selection.to_range(&term));
+    let columns = visible.col..visible.col + cols;
     for row in 0..rows {
-        for col in 0..cols {
-            let line = Line(row as i32);
-            let column = Column(col);
+        let line = Line((visible.row + row) as i32 - display_offset);
+        let mut col = 0;
+        while col < cols {
+            let column = Column(visible.col + col);
             let cell = &grid[line][column];
             
-            // Map char to atlas index
+            // Map char to atlas index; spaces (the common case) skip the lookup.
+            // `rasterize_missing_glyphs` adds other characters as they appear,
+            // so the fallback only shows while the atlas is full.
             let glyph_index = if cell.c == '\0' || cell.c == ' ' {
-                 atlas.get_glyph_index(' ').unwrap_or(0)
+                GLYPH_BLANK
+            } else {
+                atlas.get_glyph_index(cell.c).unwrap_or(fallback)
+            };
+            // Double-width characters with a two-slot glyph span the spacer
+            // cell after them; others (e.g. the fallback) stay in their cell
+            let wide = cell.flags.contains(Flags::WIDE_CHAR) && atlas.is_wide(cell.c);
+
+            // Pack colors (RGBA u32); dim text (SGR 2) gets the darker foreground
+            let fg = if cell.flags.contains(Flags::DIM) {
+                resolve_dim_color(cell.fg, colors, palette)
             } else {
-                atlas.get_glyph_index(cell.c).unwrap_or_else(|| {
-                     atlas.get_glyph_index('?').unwrap_or(0)
-                })
+                resolve_color(cell.fg, colors, palette)
             };
+            let fg = pack_color(fg);
+            let bg = pack_color(resolve_color(cell.bg, colors, palette));
 
-            // Pack colors (RGBA u32)
-            let fg = pack_color(convert_alacritty_color(cell.fg));
-            let bg = pack_color(convert_alacritty_color(cell.bg));
+            let mut flags = cell_flags(cell.flags);
+            if selection.as_ref().is_some_and(|range| range.contains(Point::new(line, column))) {
+                flags |= CELL_FLAG_SELECTED;
+            }
+            if wide {
+                flags |= CELL_FLAG_WIDE;
+            }
 
             let index = row * cols + col;
-            cpu_buffer.cells[index] = GpuTerminalCell {
+            let gpu_cell = GpuTerminalCell {
                 glyph_index,
                 fg_color: fg,
                 bg_color: bg,
-                flags: 0,
+                flags,
             };
-            updates += 1;
+            cpu_buffer.cells[index] = gpu_cell;
+
+            // The spacer after a wide character draws the glyph's right half
+            // in the same colors, instead of its own blank cell. One cut off
+            // by the viewport's right edge shows only the left half.
+            if wide && col + 1 < cols {
+                cpu_buffer.cells[index + 1] = GpuTerminalCell {
+                    flags: flags & !CELL_FLAG_WIDE | CELL_FLAG_WIDE_SPACER,
+                    ..gpu_cell
+                };
+                col += 2;
+            } else {
+                col += 1;
+            }
         }
+
+        #[cfg(feature = "emoji")]
+        crate::grapheme::apply_clusters(
+            cpu_buffer,
+            row * cols..(row + 1) * cols,
+            &grid[line][Column(columns.start)..Column(columns.end)],
+            atlas,
+        );
     }
-    
+
+    // Nothing from the shell yet: animate a placeholder at the cursor. When
+    // scrolled back far enough, or outside the visible part, it isn't drawn.
+    let cursor = term.grid().cursor.point;
+    let cursor_row = usize::try_from(cursor.line.0 + display_offset).ok();
+    let cursor_index = match (cursor_row, cursor.column.0) {
+        (Some(row), column) if (visible.row..visible.row + rows).contains(&row) && columns.contains(&column) => {
+            (row - visible.row) * cols + column - visible.col
+        }
+        _ => usize::MAX,
+    };
+    let loading_indicator = config
+        .as_ref()
+        .and_then(|config| config.loading_indicator)
+        .filter(|_| !term_state.has_received_output());
+    if let (Some(indicator), Some(clock)) = (loading_indicator, clock.as_ref()) {
+        if let Some(cell) = cpu_buffer.cells.get_mut(cursor_index) {
+            cell.glyph_index = match indicator.frame(clock) {
+                ' ' => GLYPH_BLANK,
+                frame => atlas.get_glyph_index(frame).unwrap_or(cell.glyph_index),
+            };
+        }
+    }
+
+    // Block cursor, inverted by the shader. The loading indicator stands in
+    // for it until the first output.
+    let show_cursor = config.as_ref().is_some_and(|config| config.show_cursor)
+        && loading_indicator.is_none()
+        && term.mode().contains(TermMode::SHOW_CURSOR);
+    let cursor_drawn = show_cursor
+        && match cpu_buffer.cells.get_mut(cursor_index) {
+            Some(cell) => {
+                cell.flags |= CELL_FLAG_CURSOR;
+                // On a wide character it covers both cells
+                if cell.flags & CELL_FLAG_WIDE != 0 {
+                    if let Some(spacer) = cpu_buffer.cells.get_mut(cursor_index + 1) {
+                        if spacer.flags & CELL_FLAG_WIDE_SPACER != 0 {
+                            spacer.flags |= CELL_FLAG_CURSOR;
+                        }
+                    }
+                }
+                true
+            }
+            None => false,
+        };
+
+    let status_bar = config.as_ref().and_then(|config| config.status_bar.as_ref());
+
+    let watermark = config.as_ref().and_then(|config| config.watermark.as_ref());
+
+    // The cursor cell counts as blank; the render node draws it on its own
+    cpu_buffer.cursor_cell = cursor_drawn.then_some(cursor_index);
+    cpu_buffer.blank = status_bar.is_none()
+        && watermark.is_none()
+        && cpu_buffer
+            .cells
+            .iter()
+            .all(|cell| {
+                cell.glyph_index == GLYPH_BLANK
+                    && cell.bg_color == default_bg
+                    && cell.flags & (CELL_FLAG_UNDERLINE | CELL_FLAG_INVERSE | CELL_FLAG_SELECTED) == 0
+            });
+
+    cpu_buffer.status_cells = match status_bar {
+        Some(status_bar) => {
+            let text = match &status_bar.text {
+                StatusBarText::Title => term_state.title.as_deref().unwrap_or(""),
+                StatusBarText::Custom(text) => text.as_str(),
+            };
+            status_bar_cells(text, cols, status_bar, atlas, fallback)
+        }
+        // Storage bindings can't be empty, so upload a blank row regardless
+        None => vec![GpuTerminalCell::zeroed(); cols],
+    };
+
+    cpu_buffer.watermark_cells = watermark
+        .map(|watermark| watermark_cells(watermark, atlas, fallback))
+        .unwrap_or_default();
+}
+
+/// Maps alacritty cell attributes to `CELL_FLAG_*` bits for the shader.
+pub fn cell_flags(flags: Flags) -> u32 {
+    let mut bits = 0;
+    if flags.contains(Flags::BOLD) {
+        bits |= CELL_FLAG_BOLD;
+    }
+    if flags.contains(Flags::ITALIC) {
+        bits |= CELL_FLAG_ITALIC;
+    }
+    if flags.intersects(Flags::ALL_UNDERLINES) {
+        bits |= CELL_FLAG_UNDERLINE;
+    }
+    if flags.contains(Flags::INVERSE) {
+        bits |= CELL_FLAG_INVERSE;
+    }
+    bits
+}
+
+/// Lays out status bar text as one row of cells, truncated/padded to `cols`.
+fn status_bar_cells(
+    text: &str,
+    cols: usize,
+    status_bar: &StatusBarConfig,
+    atlas: &GlyphAtlas,
+    fallback: u32,
+) -> Vec<GpuTerminalCell> {
+    let space = atlas.get_glyph_index(' ').unwrap_or(0);
+    let fg = pack_color(status_bar.fg);
+    let bg = pack_color(status_bar.bg);
+
+    let mut chars = text.chars().filter(|c| !c.is_control());
+    (0..cols)
+        .map(|_| {
+            let glyph_index = match chars.next() {
+                Some(c) => atlas.get_glyph_index(c).unwrap_or(fallback),
+                None => space,
+            };
+            GpuTerminalCell {
+                glyph_index,
+                fg_color: fg,
+                bg_color: bg,
+                flags: 0,
+            }
+        })
+        .collect()
+}
+
+/// Lays out watermark text, one cell per character.
+fn watermark_cells(watermark: &WatermarkConfig, atlas: &GlyphAtlas, fallback: u32) -> Vec<GpuTerminalCell> {
+    let fg = pack_color(watermark.color);
+
+    watermark
+        .text
+        .chars()
+        .filter(|c| !c.is_control())
+        .map(|c| GpuTerminalCell {
+            glyph_index: atlas.get_glyph_index(c).unwrap_or(fallback),
+            fg_color: fg,
+            bg_color: 0,
+            flags: 0,
+        })
+        .collect()
+}
+
+/// Packs `rgb` like `pack_color`, with `alpha` (0.0-1.0) in the top byte.
+pub(crate) fn pack_color_alpha(rgb: [u8; 3], alpha: f32) -> u32 {
+    let a = (alpha.clamp(0.0, 1.0) * 255.0).round() as u32;
+    pack_color(rgb) & 0x00ff_ffff | (a << 24)
 }
 
 // Helper: Pack [u8; 3] rgb into u32 (0xFFBBGGRR for little endian / GPU)
 // We assume alpha is 255.
-fn pack_color(rgb: [u8; 3]) -> u32 {
+pub(crate) fn pack_color(rgb: [u8; 3]) -> u32 {
     let r = rgb[0] as u32;
     let g = rgb[1] as u32;
     let b = rgb[2] as u32;
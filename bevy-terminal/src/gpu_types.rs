@@ -6,12 +6,12 @@ use bytemuck::{Pod, Zeroable};
 /// This struct must match the alignment requirements of WGSL (16-byte alignment is safest for arrays of structs,
 /// though standard u32 arrays can be tighter).
 /// We will pack it into 16 bytes:
-/// - u32 glyph_index
+/// - u32 glyph_index (`GLYPH_BLANK` for spaces)
 /// - u32 fg_color (0xAABBGGRR)
 /// - u32 bg_color (0xAABBGGRR)
-/// - u32 flags (unused for now, padding)
+/// - u32 flags (`CELL_FLAG_*` bits)
 #[repr(C)]
-#[derive(Copy, Clone, Pod, Zeroable, Debug)]
+#[derive(Copy, Clone, Pod, Zeroable, Debug, PartialEq)]
 pub struct GpuTerminalCell {
     pub glyph_index: u32,
     pub fg_color: u32,
@@ -19,6 +19,32 @@ pub struct GpuTerminalCell {
     pub flags: u32,
 }
 
+/// One quad drawn by the fragment backend: a cell and where it goes.
+///
+/// `column`/`row` are texture cells (a top status bar is row 0).
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable, Debug, PartialEq)]
+pub struct GpuCellInstance {
+    pub cell: GpuTerminalCell,
+    pub column: u32,
+    pub row: u32,
+    /// `INSTANCE_*`
+    pub kind: u32,
+    pub _padding: u32,
+}
+
+/// `GpuCellInstance::kind` values.
+///
+/// Cells fill their quad; watermark glyphs blend over the cell beneath.
+pub const INSTANCE_CELL: u32 = 0;
+pub const INSTANCE_WATERMARK: u32 = 1;
+
+/// `GpuTerminalCell::glyph_index` for a cell with no glyph (a space).
+///
+/// The shader fills it with the background color without sampling the atlas.
+/// Spaces are most cells on a typical screen, so this skips most texture loads.
+pub const GLYPH_BLANK: u32 = u32::MAX;
+
 /// Uniforms for the terminal renderer.
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable, Debug, Resource)]
@@ -30,5 +56,70 @@ pub struct TerminalUniforms {
     // Atlas info
     pub atlas_cols: u32,
     pub atlas_rows: u32,
-    pub _padding: [u32; 2], // Ensure 16-byte alignment
+    /// Status bar row placement (`STATUS_BAR_*`)
+    pub status_bar: u32,
+    /// Watermark overlay start cell (texture cells, status bar included)
+    pub watermark_col: u32,
+    pub watermark_row: u32,
+    /// Watermark cells to draw; 0 disables the overlay
+    pub watermark_len: u32,
+    pub watermark_opacity: f32,
+    /// `TerminalClock::blink_phase` (0.0-1.0)
+    pub blink_phase: f32,
+    /// Cursor blink curve (`CURSOR_BLINK_*`)
+    pub cursor_blink: u32,
+    /// Cursor color (0xAABBGGRR)
+    pub cursor_color: u32,
+    /// Cursor shape and compositing order (`CURSOR_STYLE_*`)
+    pub cursor_style: u32,
+    /// Selection highlight (0xAABBGGRR), alpha is its opacity
+    pub selection_color: u32,
+    /// `TerminalTheme::cursor_alpha`, scaling the blink alpha
+    pub cursor_opacity: f32,
+    /// Texture pixel of the first compute thread: the texture's corner, or
+    /// the cursor cell's when only that cell is redrawn
+    pub origin_x: u32,
+    pub origin_y: u32,
+    pub _padding: u32, // Ensure 16-byte alignment
 }
+
+/// `TerminalUniforms::status_bar` values.
+pub const STATUS_BAR_NONE: u32 = 0;
+pub const STATUS_BAR_TOP: u32 = 1;
+pub const STATUS_BAR_BOTTOM: u32 = 2;
+
+/// `TerminalUniforms::cursor_blink` values (see `CursorBlinkStyle`).
+pub const CURSOR_BLINK_HARD: u32 = 0;
+pub const CURSOR_BLINK_FADE: u32 = 1;
+
+/// `TerminalUniforms::cursor_style` values (see `CursorStyle`).
+pub const CURSOR_STYLE_BLOCK: u32 = 0;
+pub const CURSOR_STYLE_BAR: u32 = 1;
+pub const CURSOR_STYLE_UNDERLINE: u32 = 2;
+
+/// `GpuTerminalCell::flags` bits. Unlisted bits are reserved and zero.
+///
+/// Bits 0-7 are attributes from the alacritty cell, bits 8+ are overlays
+/// added by the plugin:
+///
+/// - bit 0: bold, drawn with a brightened foreground
+/// - bit 1: italic, drawn as a sheared upright glyph (faux italic)
+/// - bit 2: underline (any style), a foreground line on the cell's bottom row
+/// - bit 3: inverse, foreground and background swapped
+/// - bit 4: wide, a double-width glyph spanning this cell and the next
+/// - bit 5: wide spacer, the right half of the wide glyph before it (same
+///   glyph index, colors and attributes)
+/// - bit 8: cursor, drawn blinking in the configured `CursorStyle`
+/// - bit 9: selected, drawn under the translucent `TerminalTheme` selection
+///   highlight
+/// - bit 10: color glyph, the atlas texels carry their own colors (a color
+///   emoji) and replace the foreground
+pub const CELL_FLAG_BOLD: u32 = 1 << 0;
+pub const CELL_FLAG_ITALIC: u32 = 1 << 1;
+pub const CELL_FLAG_UNDERLINE: u32 = 1 << 2;
+pub const CELL_FLAG_INVERSE: u32 = 1 << 3;
+pub const CELL_FLAG_WIDE: u32 = 1 << 4;
+pub const CELL_FLAG_WIDE_SPACER: u32 = 1 << 5;
+pub const CELL_FLAG_CURSOR: u32 = 1 << 8;
+pub const CELL_FLAG_SELECTED: u32 = 1 << 9;
+pub const CELL_FLAG_COLOR_GLYPH: u32 = 1 << 10;
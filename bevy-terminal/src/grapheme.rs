@@ -0,0 +1,348 @@
+//! Grapheme cluster rendering (`emoji` feature).
+//!
+//! alacritty lays text out one codepoint at a time: a flag's two regional
+//! indicators land in neighbouring cells, and joiners, variation selectors
+//! and skin tones ride along as zero-width extras on the cell before them.
+//! Drawn cell by cell that's a pair of boxes (or a man, a woman and a girl
+//! for a family emoji).
+//!
+//! `prepare_terminal_cpu_buffer` segments each visible row into extended
+//! grapheme clusters as it builds the cells. Clusters already in the atlas
+//! point their cells at the cluster's slots (one per cell it covers); new
+//! ones are left for `rasterize_clusters`, which rasterizes them once and
+//! patches the cells in the same frame.
+//!
+//! With `TerminalConfig::emoji_font` set, clusters are drawn from that
+//! font's color bitmaps (CBDT or sbix, e.g. Noto Color Emoji), flags
+//! through its ligatures. Without one, or for clusters it has no bitmap
+//! for, flags draw as their two letters in the terminal font.
+
+use std::io::Cursor;
+use std::ops::Range;
+
+use alacritty_terminal::index::Line;
+use alacritty_terminal::term::cell::{Cell, Flags};
+use anyhow::{anyhow, bail, Result};
+use bevy::prelude::*;
+use log::{info, warn};
+use ttf_parser::gsub::SubstitutionSubtable;
+use ttf_parser::{Face, GlyphId, RasterGlyphImage, RasterImageFormat};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::atlas::GlyphAtlas;
+use crate::font::{FontMetrics, FontSource};
+use crate::gpu_prep::TerminalCpuBuffer;
+use crate::gpu_types::{GpuTerminalCell, CELL_FLAG_COLOR_GLYPH, CELL_FLAG_WIDE_SPACER};
+use crate::terminal::TerminalState;
+
+/// A grapheme cluster and the grid cells it covers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GraphemeCluster {
+    /// First column of the cluster
+    pub column: usize,
+    /// Cells covered, including wide character spacers
+    pub width: usize,
+    /// The cluster's codepoints
+    pub text: String,
+}
+
+impl GraphemeCluster {
+    /// More than one codepoint, so it needs a cluster glyph.
+    pub fn is_multi_codepoint(&self) -> bool {
+        self.text.chars().nth(1).is_some()
+    }
+}
+
+/// Segments one grid row into grapheme clusters.
+///
+/// Spacer cells belong to the wide character before them; empty cells are
+/// spaces.
+pub fn row_clusters(cells: &[Cell]) -> Vec<GraphemeCluster> {
+    let spacers = Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER;
+
+    // Row text plus the column and cell count behind each byte offset
+    let mut text = String::new();
+    let mut spans: Vec<(usize, usize, usize)> = Vec::new();
+    for (column, cell) in cells.iter().enumerate() {
+        if cell.flags.intersects(spacers) {
+            continue;
+        }
+        let start = text.len();
+        text.push(if cell.c == '\0' { ' ' } else { cell.c });
+        text.extend(cell.zerowidth().into_iter().flatten());
+        let width = if cell.flags.contains(Flags::WIDE_CHAR) { 2 } else { 1 };
+        spans.push((start, column, width));
+    }
+
+    // A cluster covers every cell whose text starts inside it; one that
+    // starts mid-cell (a stray zero-width character) shares that cell's end
+    let mut spans = spans.into_iter().peekable();
+    let mut clusters = Vec::new();
+    let mut last_end = 0;
+    for (start, grapheme) in text.grapheme_indices(true) {
+        let end = start + grapheme.len();
+        let mut column = None;
+        while let Some(&(span_start, span_column, span_width)) = spans.peek() {
+            if span_start >= end {
+                break;
+            }
+            column.get_or_insert(span_column);
+            last_end = span_column + span_width;
+            spans.next();
+        }
+        let column = column.unwrap_or(last_end.saturating_sub(1));
+        clusters.push(GraphemeCluster {
+            column,
+            width: last_end.saturating_sub(column).max(1),
+            text: grapheme.to_string(),
+        });
+    }
+    clusters
+}
+
+impl TerminalState {
+    /// Grapheme clusters on screen line `line` (0 = top of the screen).
+    pub fn grapheme_clusters(&self, line: usize) -> Vec<GraphemeCluster> {
+        let term = self.term.lock();
+        row_clusters(&term.grid()[Line(line as i32)][..])
+    }
+}
+
+/// Points the cells of each multi-codepoint cluster on a row at its atlas
+/// slots.
+///
+/// `row` is the row's range in `cpu_buffer.cells`. Clusters not yet
+/// rasterized keep their per-character glyphs and are queued in
+/// `cpu_buffer.missing_clusters` for `rasterize_clusters`.
+pub(crate) fn apply_clusters(cpu_buffer: &mut TerminalCpuBuffer, row: Range<usize>, cells: &[Cell], atlas: &GlyphAtlas) {
+    for cluster in row_clusters(cells).into_iter().filter(GraphemeCluster::is_multi_codepoint) {
+        match atlas.get_cluster_index(&cluster.text) {
+            Some(first) => {
+                let color = atlas.is_color_cluster(&cluster.text);
+                point_at_cluster(&mut cpu_buffer.cells[row.clone()], &cluster, first, color);
+            }
+            None => cpu_buffer.missing_clusters.push((row.clone(), cluster)),
+        }
+    }
+}
+
+/// Points `cluster`'s cells on `row` at the slots starting at `first`.
+///
+/// A wide spacer gets the same index as the cell before it, as the shaders
+/// already offset it by one slot.
+fn point_at_cluster(row: &mut [GpuTerminalCell], cluster: &GraphemeCluster, first: u32, color: bool) {
+    let columns = cluster.column..(cluster.column + cluster.width).min(row.len());
+    for (offset, column) in columns.enumerate() {
+        let cell = &mut row[column];
+        let spacer = cell.flags & CELL_FLAG_WIDE_SPACER != 0;
+        cell.glyph_index = first + offset as u32 - u32::from(spacer);
+        if color {
+            cell.flags |= CELL_FLAG_COLOR_GLYPH;
+        }
+    }
+}
+
+/// Rasterizes the clusters the CPU buffers found missing from the atlas and
+/// points their cells at the new slots.
+///
+/// System: Update
+/// Runs: Every frame, after the CPU buffers are prepared
+///
+/// New slots reach the GPU through `upload_atlas_slots`.
+pub fn rasterize_clusters(
+    mut cpu_buffers: Query<&mut TerminalCpuBuffer>,
+    font_metrics: Option<Res<FontMetrics>>,
+    atlas: Option<ResMut<GlyphAtlas>>,
+    mut warned_full: Local<bool>,
+) {
+    let (Some(font_metrics), Some(mut atlas)) = (font_metrics, atlas) else {
+        return;
+    };
+
+    let mut added = 0;
+    for mut cpu_buffer in cpu_buffers.iter_mut().filter(|cpu_buffer| !cpu_buffer.missing_clusters.is_empty()) {
+        for (row, cluster) in std::mem::take(&mut cpu_buffer.missing_clusters) {
+            let first = match atlas.get_cluster_index(&cluster.text) {
+                Some(first) => first,
+                None => match atlas.insert_cluster(&font_metrics, &cluster.text, cluster.width) {
+                    Some(first) => {
+                        added += 1;
+                        first
+                    }
+                    None => {
+                        if !std::mem::replace(&mut *warned_full, true) {
+                            warn!("⚠️  Glyph atlas full, can't rasterize {:?}", cluster.text);
+                        }
+                        continue;
+                    }
+                },
+            };
+            let color = atlas.is_color_cluster(&cluster.text);
+            point_at_cluster(&mut cpu_buffer.cells[row], &cluster, first, color);
+        }
+    }
+
+    if added > 0 {
+        info!("🧩 Rasterized {} grapheme clusters", added);
+    }
+}
+
+/// A color bitmap font for grapheme clusters (`TerminalConfig::emoji_font`).
+///
+/// Only CBDT and sbix bitmaps are read; outline color formats (COLR, SVG)
+/// are not supported.
+#[derive(Clone)]
+pub struct EmojiFont {
+    data: Vec<u8>,
+}
+
+impl EmojiFont {
+    /// Reads and checks the font at `source`.
+    pub fn load(source: &FontSource) -> Result<Self> {
+        let data = source.read()?.into_owned();
+        let face = Face::parse(&data, 0).map_err(|error| anyhow!("Failed to parse emoji font: {}", error))?;
+        if face.tables().cbdt.is_none() && face.tables().sbix.is_none() {
+            bail!("Emoji font has no color bitmaps (CBDT or sbix)");
+        }
+        Ok(Self { data })
+    }
+
+    /// `cluster` as a `width`×`height` RGBA image (straight alpha), its
+    /// bitmap scaled to fit and centered.
+    ///
+    /// None when the font has no bitmap for the cluster.
+    pub fn render(&self, cluster: &str, width: u32, height: u32) -> Option<Vec<u8>> {
+        let face = Face::parse(&self.data, 0).ok()?;
+        let glyph = cluster_glyph(&face, cluster)?;
+        let image = face.glyph_raster_image(glyph, height.min(u16::MAX as u32) as u16)?;
+        let (image_width, image_height, pixels) = decode_raster_image(&image)?;
+        Some(fit_image(&pixels, image_width, image_height, width, height))
+    }
+}
+
+/// The glyph drawing `cluster`: its only character's, or the GSUB ligature
+/// of all of them (flags, ZWJ sequences). Emoji presentation selectors are
+/// dropped first, as fonts map the bare characters.
+fn cluster_glyph(face: &Face, cluster: &str) -> Option<GlyphId> {
+    let glyphs: Vec<GlyphId> = cluster
+        .chars()
+        .filter(|&c| c != '\u{FE0F}')
+        .map(|c| face.glyph_index(c))
+        .collect::<Option<_>>()?;
+    match glyphs[..] {
+        [] => None,
+        [glyph] => Some(glyph),
+        [first, ref rest @ ..] => ligature(face, first, rest),
+    }
+}
+
+/// The ligature glyph for `first` followed by `rest`, from any GSUB
+/// ligature lookup.
+fn ligature(face: &Face, first: GlyphId, rest: &[GlyphId]) -> Option<GlyphId> {
+    let gsub = face.tables().gsub?;
+    gsub.lookups
+        .into_iter()
+        .flat_map(|lookup| lookup.subtables.into_iter::<SubstitutionSubtable>())
+        .find_map(|subtable| {
+            let SubstitutionSubtable::Ligature(ligatures) = subtable else {
+                return None;
+            };
+            let set = ligatures.ligature_sets.get(ligatures.coverage.get(first)?)?;
+            set.into_iter()
+                .find(|ligature| ligature.components.into_iter().eq(rest.iter().copied()))
+                .map(|ligature| ligature.glyph)
+        })
+}
+
+/// A bitmap's width, height and RGBA pixels (straight alpha).
+///
+/// PNG (CBDT, sbix) and premultiplied BGRA (CBDT) are supported.
+fn decode_raster_image(image: &RasterGlyphImage) -> Option<(u32, u32, Vec<u8>)> {
+    match image.format {
+        RasterImageFormat::PNG => decode_png(image.data),
+        RasterImageFormat::BitmapPremulBgra32 => {
+            let (width, height) = (u32::from(image.width), u32::from(image.height));
+            let pixels = image.data.get(..(width * height * 4) as usize)?;
+            let pixels = pixels
+                .chunks_exact(4)
+                .flat_map(|bgra| {
+                    let alpha = bgra[3];
+                    let straight = |channel: u8| match alpha {
+                        0 => 0,
+                        _ => (u32::from(channel) * 255 / u32::from(alpha)).min(255) as u8,
+                    };
+                    [straight(bgra[2]), straight(bgra[1]), straight(bgra[0]), alpha]
+                })
+                .collect();
+            Some((width, height, pixels))
+        }
+        _ => None,
+    }
+}
+
+/// A PNG's width, height and RGBA pixels.
+fn decode_png(data: &[u8]) -> Option<(u32, u32, Vec<u8>)> {
+    let mut decoder = png::Decoder::new(Cursor::new(data));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().ok()?;
+    let mut buffer = vec![0; reader.output_buffer_size()?];
+    let info = reader.next_frame(&mut buffer).ok()?;
+    let pixels = buffer[..info.buffer_size()]
+        .chunks_exact(info.color_type.samples())
+        .flat_map(|pixel| match *pixel {
+            [r, g, b, a] => [r, g, b, a],
+            [r, g, b] => [r, g, b, 255],
+            [luma, a] => [luma, luma, luma, a],
+            [luma] => [luma, luma, luma, 255],
+            _ => [0; 4],
+        })
+        .collect();
+    Some((info.width, info.height, pixels))
+}
+
+/// Scales an RGBA image to fit `width`×`height` keeping its aspect ratio,
+/// centered on a transparent background.
+///
+/// Each output pixel averages the source pixels under it, weighted by
+/// alpha so transparent pixels don't darken the edges.
+fn fit_image(pixels: &[u8], image_width: u32, image_height: u32, width: u32, height: u32) -> Vec<u8> {
+    let mut output = vec![0u8; (width * height * 4) as usize];
+    if image_width == 0 || image_height == 0 {
+        return output;
+    }
+    let scale = (width as f32 / image_width as f32).min(height as f32 / image_height as f32);
+    let fit_width = ((image_width as f32 * scale).round() as u32).clamp(1, width);
+    let fit_height = ((image_height as f32 * scale).round() as u32).clamp(1, height);
+    let (left, top) = ((width - fit_width) / 2, (height - fit_height) / 2);
+
+    // Source pixels under output pixel `index` of `count`, at least one
+    let source_range = |index: u32, count: u32, size: u32| {
+        let start = index * size / count;
+        start..((index + 1) * size).div_ceil(count).max(start + 1)
+    };
+    for y in 0..fit_height {
+        for x in 0..fit_width {
+            let mut sum = [0u32; 4];
+            let mut count = 0;
+            for source_y in source_range(y, fit_height, image_height) {
+                for source_x in source_range(x, fit_width, image_width) {
+                    let offset = ((source_y * image_width + source_x) * 4) as usize;
+                    let [r, g, b, a] = [0, 1, 2, 3].map(|channel| u32::from(pixels[offset + channel]));
+                    sum = [sum[0] + r * a, sum[1] + g * a, sum[2] + b * a, sum[3] + a];
+                    count += 1;
+                }
+            }
+            if sum[3] == 0 {
+                continue;
+            }
+            let offset = (((top + y) * width + left + x) * 4) as usize;
+            output[offset..offset + 4].copy_from_slice(&[
+                (sum[0] / sum[3]) as u8,
+                (sum[1] / sum[3]) as u8,
+                (sum[2] / sum[3]) as u8,
+                (sum[3] / count) as u8,
+            ]);
+        }
+    }
+    output
+}
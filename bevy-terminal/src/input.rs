@@ -2,62 +2,786 @@
 //!
 //! Captures Bevy keyboard events and converts them to appropriate byte sequences
 //! for the PTY. Handles:
-//! - ASCII characters (a-z, 0-9, symbols)
+//! - Typed text as UTF-8, following the keyboard layout (é on AZERTY, ß on QWERTZ)
+//! - Text committed by an input method (Japanese, Chinese, Korean)
 //! - Special keys (Enter, Backspace, Tab, Escape)
 //! - Arrow keys (ANSI escape sequences)
-//! - Control sequences (Ctrl+C, Ctrl+D, etc.)
+//! - Function keys F1-F12 (xterm sequences, with Shift/Ctrl variants)
+//! - Numeric keypad, in numeric or application mode (DECKPAM)
+//! - Control sequences (Ctrl+C, Ctrl+D, Ctrl+[ as ESC, etc.)
+//!
+//! Keys go to the focused terminal (`TerminalFocus`, the `PrimaryTerminal`
+//! unless set). Mouse clicks, drags and the wheel over a terminal sprite are
+//! reported to the terminal it shows when the program turned on mouse
+//! tracking (tmux, vim, htop). Otherwise the wheel scrolls the focused
+//! terminal through history, and dragging with the left button selects text
+//! for Ctrl+Shift+C to copy.
 
+use bevy::ecs::system::SystemParam;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::mouse::{MouseButtonInput, MouseScrollUnit, MouseWheel};
 use bevy::prelude::*;
-use std::io::Write;
-use log::{error, trace};
+use bevy::window::{CursorMoved, Ime};
+use log::trace;
 
+use crate::atlas::GlyphAtlas;
+use crate::config::{StatusBarPosition, TerminalConfig};
+use crate::events::{TerminalEvent, TerminalEventKind};
+use crate::instance::PrimaryTerminal;
 use crate::pty::PtyResource;
+use crate::renderer::TerminalTexture;
+use crate::sprite::TerminalSprite;
+use crate::terminal::TerminalState;
 
 /// Controls whether keyboard input is routed to the terminal.
 ///
-/// When false, keyboard events are ignored by the terminal.
+/// When false, keyboard events are ignored by the terminal, except keys in
+/// `bypass` (e.g. Ctrl+C so the player can still kill a runaway process).
 /// Use this to implement game-specific input modes (e.g., zoom controls).
+///
+/// ## Grabs
+///
+/// `grab()` routes every key to the terminal until the matching `release()`,
+/// whatever `enabled` says. Grabs stack: a command prompt and a dialog
+/// opened from it can each grab and release without undoing the other's,
+/// which a single shared bool can't do. Game systems should skip their own
+/// key bindings while `is_grabbed()`.
+///
+/// A grab means "keys go to the terminal" rather than to a particular one;
+/// which terminal gets them is up to `TerminalFocus`.
 #[derive(Resource, Default)]
 pub struct TerminalInputEnabled {
     pub enabled: bool,
+    /// Keys sent to the terminal even while disabled. Empty by default.
+    pub bypass: Vec<BypassKey>,
+    /// Outstanding grabs. Use `grab`/`release` rather than setting it.
+    pub grabs: u32,
+    /// What Backspace sends. Change it at runtime for hosts expecting `^H`.
+    pub erase_char: EraseChar,
+}
+
+/// Byte sent by the Backspace key.
+///
+/// Terminals disagree here: modern ones send DEL (`^?`), legacy hosts and
+/// some serial consoles expect BS (`^H`) and echo `^?` otherwise. Delete
+/// always sends `\x1b[3~`, and Ctrl+Backspace always sends `^W` (the tty's
+/// word erase, also bound by readline, zsh and vim).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EraseChar {
+    /// `\x7f` (DEL, `^?`)
+    #[default]
+    Delete,
+    /// `\x08` (BS, `^H`)
+    Backspace,
+}
+
+impl EraseChar {
+    /// The byte Backspace sends.
+    pub fn byte(self) -> u8 {
+        match self {
+            Self::Delete => 0x7f,
+            Self::Backspace => 0x08,
+        }
+    }
+}
+
+impl TerminalInputEnabled {
+    /// Whether `key` (with Ctrl held or not) should reach the terminal.
+    pub fn allows(&self, key: KeyCode, ctrl: bool) -> bool {
+        self.routes_all() || self.bypass.contains(&BypassKey { key, ctrl })
+    }
+
+    /// Send every key to the terminal until a matching `release`.
+    pub fn grab(&mut self) {
+        self.grabs += 1;
+    }
+
+    /// Undo one `grab`. Extra releases are ignored.
+    pub fn release(&mut self) {
+        self.grabs = self.grabs.saturating_sub(1);
+    }
+
+    /// Whether any grab is outstanding.
+    pub fn is_grabbed(&self) -> bool {
+        self.grabs > 0
+    }
+
+    /// Enabled or grabbed: every key goes to the terminal.
+    fn routes_all(&self) -> bool {
+        self.enabled || self.is_grabbed()
+    }
+}
+
+/// The terminal that keyboard, IME and mouse wheel scrolling input goes to.
+///
+/// Without this resource, or while it holds `None`, input goes to the
+/// `PrimaryTerminal`. Point it at another terminal entity to type into that
+/// one instead, e.g. when the player walks up to a second screen.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TerminalFocus(pub Option<Entity>);
+
+/// The terminal entity `TerminalFocus` picks.
+fn focused_terminal(focus: Option<&TerminalFocus>, primary: &Query<Entity, With<PrimaryTerminal>>) -> Option<Entity> {
+    focus.and_then(|focus| focus.0).or_else(|| primary.iter().next())
+}
+
+/// A key (and whether Ctrl is held) that bypasses `TerminalInputEnabled`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BypassKey {
+    pub key: KeyCode,
+    pub ctrl: bool,
+}
+
+impl BypassKey {
+    /// Ctrl+C (interrupt)
+    pub const CTRL_C: Self = Self { key: KeyCode::KeyC, ctrl: true };
+    /// Ctrl+D (EOF)
+    pub const CTRL_D: Self = Self { key: KeyCode::KeyD, ctrl: true };
+    /// Ctrl+Z (suspend)
+    pub const CTRL_Z: Self = Self { key: KeyCode::KeyZ, ctrl: true };
+}
+
+/// Bytes for game code to send to a terminal's shell, as if typed.
+///
+/// Registered as a message by `TerminalPlugin`; write it from any system
+/// (e.g. `TerminalWrite::new(terminal, "ls\r")` when the player enters a
+/// room). Writes go out in order, just before this frame's keystrokes. Sent
+/// to a terminal with no PTY running, they are dropped.
+#[derive(Message, Clone, Debug, PartialEq, Eq)]
+pub struct TerminalWrite {
+    /// The terminal entity to write to
+    pub terminal: Entity,
+    pub bytes: Vec<u8>,
+}
+
+impl TerminalWrite {
+    pub fn new(terminal: Entity, bytes: impl Into<Vec<u8>>) -> Self {
+        Self { terminal, bytes: bytes.into() }
+    }
+}
+
+/// Queues `TerminalWrite` bytes for their terminals' PTYs.
+///
+/// System: Update
+/// Runs: Every frame, just before `handle_keyboard_input`
+///
+/// Runs in the input chain so it never interleaves with a keystroke's bytes;
+/// `flush_pty_writes` sends both in one write at the end of the chain.
+pub fn apply_terminal_writes(mut writes: MessageReader<TerminalWrite>, ptys: Query<&PtyResource>) {
+    for TerminalWrite { terminal, bytes } in writes.read() {
+        let Ok(pty) = ptys.get(*terminal) else {
+            continue;
+        };
+        pty.queue_write(bytes);
+        trace!("⌨️  Queued {} scripted bytes for PTY", bytes.len());
+    }
+}
+
+/// What a key press does in the focused terminal.
+enum KeyAction {
+    /// Ctrl+Shift+V: `TerminalEventKind::PasteRequested`
+    Paste,
+    /// Ctrl+Shift+C: the selection as `TerminalEventKind::CopyRequested`
+    Copy,
+    /// Bytes for the PTY
+    Send(Vec<u8>),
+}
+
+/// Keyboard routing to the focused terminal, as `handle_keyboard_input`
+/// applies it.
+///
+/// Game systems that share keys with the terminal can ask `will_consume`
+/// and skip their own action for keys the terminal takes:
+///
+/// ```no_run
+/// use bevy::input::keyboard::KeyboardInput;
+/// use bevy::prelude::*;
+/// use bevy_terminal::TerminalKeyboard;
+///
+/// fn zoom_out(mut keys: MessageReader<KeyboardInput>, terminal: TerminalKeyboard) {
+///     for event in keys.read() {
+///         if event.key_code == KeyCode::Escape && !terminal.will_consume(event) {
+///             // zoom out
+///         }
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct TerminalKeyboard<'w, 's> {
+    keyboard: Res<'w, ButtonInput<KeyCode>>,
+    focus: Option<Res<'w, TerminalFocus>>,
+    primary: Query<'w, 's, Entity, With<PrimaryTerminal>>,
+    terminals: Query<'w, 's, (&'static PtyResource, &'static TerminalState)>,
+    input_enabled: Option<Res<'w, TerminalInputEnabled>>,
+}
+
+impl TerminalKeyboard<'_, '_> {
+    /// Whether `handle_keyboard_input` sends this key press to the focused
+    /// terminal, as bytes or as a paste or copy request.
+    ///
+    /// False when the focused terminal has no PTY running, the key isn't
+    /// allowed (see `TerminalInputEnabled`) or it maps to nothing. Uses the
+    /// modifiers held now, the press's text (for keyboard layouts) and the
+    /// terminal's keypad mode.
+    pub fn will_consume(&self, event: &KeyboardInput) -> bool {
+        self.focused()
+            .is_some_and(|(_, _, term_state)| self.action(event, term_state).is_some())
+    }
+
+    /// The focused terminal, if it has a PTY to type into.
+    fn focused(&self) -> Option<(Entity, &PtyResource, &TerminalState)> {
+        let terminal = focused_terminal(self.focus.as_deref(), &self.primary)?;
+        let (pty, term_state) = self.terminals.get(terminal).ok()?;
+        Some((terminal, pty, term_state))
+    }
+
+    /// What `event` does in the terminal showing `term_state`, or None if
+    /// the terminal doesn't take it.
+    fn action(&self, event: &KeyboardInput, term_state: &TerminalState) -> Option<KeyAction> {
+        let key = event.key_code;
+        let shift = self.keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        let ctrl = self.keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+        let alt = self.keyboard.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+
+        // Every key goes through when the game never set up input routing
+        let input_enabled = self.input_enabled.as_deref();
+        if input_enabled.is_some_and(|input_enabled| !input_enabled.allows(key, ctrl)) {
+            return None;
+        }
+        if ctrl && shift {
+            match key {
+                KeyCode::KeyV => return Some(KeyAction::Paste),
+                // Never an interrupt, even with nothing selected
+                KeyCode::KeyC => return Some(KeyAction::Copy),
+                _ => {}
+            }
+        }
+
+        // Application keypad sequences win over the digits the keys type
+        let erase_char = input_enabled.map_or(EraseChar::default(), |input_enabled| input_enabled.erase_char);
+        term_state
+            .app_keypad()
+            .then(|| application_keypad_bytes(key))
+            .flatten()
+            .or_else(|| typed_text(event.text.as_deref(), ctrl, alt).map(|text| text.as_bytes().to_vec()))
+            .or_else(|| key_bytes(key, shift, ctrl, erase_char))
+            .map(KeyAction::Send)
+    }
 }
 
-/// Handles keyboard input and sends it to the PTY.
+/// Handles keyboard input and sends it to the focused terminal's PTY.
 ///
 /// System: Update
 /// Runs: Every frame
 ///
+/// Keys that type text send it as UTF-8, so the keyboard layout decides the
+/// character; Ctrl combinations and keys without text (Enter, arrows, F-keys)
+/// go through the keycode table instead. Key repeat sends the key again.
+/// Ctrl+Shift+V sends `TerminalEventKind::PasteRequested` instead of a key,
+/// and Ctrl+Shift+C sends the selected text as
+/// `TerminalEventKind::CopyRequested`.
 /// Supports Shift and Ctrl modifiers for proper terminal interaction.
-/// Respects TerminalInputEnabled resource to allow game-specific input modes.
+/// Respects TerminalInputEnabled resource (including its bypass keys and
+/// grabs) to allow game-specific input modes; `TerminalKeyboard` tells game
+/// systems which keys that leaves to the terminal.
 pub fn handle_keyboard_input(
-    keyboard: Res<ButtonInput<KeyCode>>,
-    pty: Res<PtyResource>,
+    mut key_events: MessageReader<KeyboardInput>,
+    keyboard: TerminalKeyboard,
+    mut events: MessageWriter<TerminalEvent>,
+) {
+    let Some((terminal, pty, term_state)) = keyboard.focused() else {
+        key_events.clear();
+        return;
+    };
+
+    // Process every key press this frame, repeats included
+    for event in key_events.read().filter(|event| event.state.is_pressed()) {
+        match keyboard.action(event, term_state) {
+            Some(KeyAction::Paste) => {
+                events.write(TerminalEvent::new(terminal, TerminalEventKind::PasteRequested));
+            }
+            Some(KeyAction::Copy) => {
+                let text = term_state.copy_selection();
+                if !text.is_empty() {
+                    events.write(TerminalEvent::new(terminal, TerminalEventKind::CopyRequested { text }));
+                }
+            }
+            Some(KeyAction::Send(bytes)) => {
+                pty.queue_write(&bytes);
+                trace!("⌨️  Queued {} bytes for PTY", bytes.len());
+            }
+            None => {}
+        }
+    }
+}
+
+/// Text a key press should send as-is, or None to use the keycode table.
+///
+/// Ctrl combinations keep their control codes (Ctrl+C is 0x03, not "c"),
+/// except Ctrl+Alt, which is AltGr on Windows (€, @ on many layouts).
+/// Control characters (Enter's "\r", Tab, Backspace, Escape) also defer to
+/// the table, which knows about `EraseChar` and modifiers.
+fn typed_text(text: Option<&str>, ctrl: bool, alt: bool) -> Option<&str> {
+    let text = text.filter(|text| !text.is_empty() && !text.chars().any(char::is_control))?;
+    (!ctrl || alt).then_some(text)
+}
+
+/// Sends text committed by an input method to the focused terminal's PTY.
+///
+/// System: Update
+/// Runs: Every frame, after `handle_keyboard_input`, when the app has windows
+///
+/// Input methods compose text (Japanese kana to kanji, Chinese pinyin)
+/// before committing it. Only the committed text is sent; the composition
+/// in progress isn't drawn. Needs `Window::ime_enabled`, and only applies
+/// while `TerminalInputEnabled` routes all keys to the terminal.
+pub fn handle_ime_input(
+    mut ime: MessageReader<Ime>,
+    focus: Option<Res<TerminalFocus>>,
+    primary: Query<Entity, With<PrimaryTerminal>>,
+    ptys: Query<&PtyResource>,
     input_enabled: Option<Res<TerminalInputEnabled>>,
 ) {
-    // Check if terminal input is enabled (defaults to true if resource not present)
-    let enabled = input_enabled.map(|r| r.enabled).unwrap_or(true);
-    if !enabled {
+    let enabled = input_enabled.as_ref().map(|r| r.routes_all()).unwrap_or(true);
+    let pty = focused_terminal(focus.as_deref(), &primary).and_then(|terminal| ptys.get(terminal).ok());
+    let (true, Some(pty)) = (enabled, pty) else {
+        ime.clear();
         return;
+    };
+
+    for event in ime.read() {
+        if let Ime::Commit { value, .. } = event {
+            pty.queue_write(value.as_bytes());
+            trace!("⌨️  Queued {} bytes of IME text for PTY", value.len());
+        }
     }
-    // Check modifier state
-    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
-    let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
-
-    // Process all just-pressed keys this frame
-    for key in keyboard.get_just_pressed() {
-        if let Some(bytes) = keycode_to_bytes(*key, shift, ctrl) {
-            // Write to PTY
-            if let Ok(mut writer) = pty.writer.try_lock() {
-                if let Err(error) = writer.write_all(&bytes) {
-                    error!("❌ Failed to write to PTY: {}", error);
-                } else if let Err(error) = writer.flush() {
-                    error!("❌ Failed to flush PTY writer: {}", error);
-                } else {
-                    trace!("⌨️  Sent {} bytes to PTY", bytes.len());
-                }
+}
+
+/// Mouse reporting the program asked for (DECSET 1000/1002/1003/1006).
+///
+/// See `TerminalState::mouse_mode`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MouseMode {
+    /// Report presses, releases and the wheel (1000; implied by 1002/1003)
+    pub clicks: bool,
+    /// Also report motion while a button is held (1002)
+    pub drag: bool,
+    /// Also report all motion (1003)
+    pub motion: bool,
+    /// SGR encoding (1006) instead of legacy X10 bytes
+    pub sgr: bool,
+}
+
+/// `mouse_report` button code for the wheel scrolling up; down is one more.
+pub const MOUSE_WHEEL_UP: u8 = 64;
+/// Added to the button code of motion reports.
+pub const MOUSE_MOTION: u8 = 32;
+/// Button code for motion with no button held (and legacy releases).
+pub const MOUSE_NO_BUTTON: u8 = 3;
+
+/// Encodes one mouse report for the cell at `column`, `row` (0-based).
+///
+/// `button` is the xterm code: 0-2 for left/middle/right, plus
+/// `MOUSE_MOTION`, `MOUSE_WHEEL_UP` and the modifier bits 4/8/16 for
+/// Shift/Alt/Ctrl. SGR reports are `\x1b[<b;x;yM` (`m` on release). Legacy
+/// reports can't say which button was released, and positions past 222
+/// don't fit their bytes, which yields None.
+pub fn mouse_report(button: u8, column: usize, row: usize, release: bool, sgr: bool) -> Option<Vec<u8>> {
+    if sgr {
+        let action = if release { 'm' } else { 'M' };
+        return Some(format!("\x1b[<{};{};{}{}", button, column + 1, row + 1, action).into_bytes());
+    }
+
+    let button = if release { MOUSE_NO_BUTTON | (button & !3) } else { button };
+    let encode = |value: usize| u8::try_from(value + 33).ok();
+    Some(vec![0x1b, b'[', b'M', 32 + button, encode(column)?, encode(row)?])
+}
+
+/// Grid cell under `world` on a terminal sprite with transform `sprite`.
+///
+/// Assumes the centered anchor and no `custom_size`, as
+/// `spawn_terminal_sprite` sets up. `grid_top` is 1 when a status bar sits
+/// above the grid. None outside the grid, including over the status bar.
+pub fn cell_at(
+    world: Vec2,
+    sprite: &GlobalTransform,
+    texture: &TerminalTexture,
+    cell_size: UVec2,
+    grid_top: usize,
+    cols: usize,
+    rows: usize,
+) -> Option<(usize, usize)> {
+    let local = sprite.affine().inverse().transform_point3(world.extend(0.0));
+    let x = local.x + texture.width as f32 / 2.0;
+    let y = texture.height as f32 / 2.0 - local.y;
+    if x < 0.0 || y < 0.0 || cell_size.x == 0 || cell_size.y == 0 {
+        return None;
+    }
+
+    let column = (x / cell_size.x as f32) as usize;
+    let row = ((y / cell_size.y as f32) as usize).checked_sub(grid_top)?;
+    (column < cols && row < rows).then_some((column, row))
+}
+
+/// Terminal entity and grid cell under the window cursor `position`, seen
+/// through the first active camera on the first `TerminalSprite` it is
+/// over. `terminal` looks up the texture, state and config of the terminal
+/// a sprite shows. Only cells drawn into the texture
+/// (`TerminalState::visible_grid`) can be pointed at.
+fn pointer_cell<'a>(
+    position: Vec2,
+    cameras: &Query<(&Camera, &GlobalTransform)>,
+    sprites: &Query<(&GlobalTransform, &TerminalSprite)>,
+    atlas: &GlyphAtlas,
+    terminal: impl Fn(Entity) -> Option<(&'a TerminalTexture, &'a TerminalState, &'a TerminalConfig)>,
+) -> Option<(Entity, (usize, usize))> {
+    let (camera, camera_transform) = cameras.iter().find(|(camera, _)| camera.is_active)?;
+    let world = camera.viewport_to_world_2d(camera_transform, position).ok()?;
+    let cell_size = UVec2::new(atlas.cell_width, atlas.cell_height);
+    sprites.iter().find_map(|(sprite, &TerminalSprite(entity))| {
+        let (texture, term_state, config) = terminal(entity)?;
+        let grid_top = config
+            .status_bar
+            .as_ref()
+            .is_some_and(|status_bar| status_bar.position == StatusBarPosition::Top);
+        let visible = term_state.visible_grid(Some(config));
+        let (column, row) = cell_at(world, sprite, texture, cell_size, usize::from(grid_top), visible.cols, visible.rows)?;
+        Some((entity, (visible.col + column, visible.row + row)))
+    })
+}
+
+/// Pointer state `handle_mouse_input` carries between frames.
+#[derive(Default)]
+pub struct MouseTracker {
+    /// Last window cursor position
+    cursor: Option<Vec2>,
+    /// Last terminal and cell the cursor was over, for motion and releases
+    /// off the grid
+    cell: Option<(Entity, (usize, usize))>,
+    /// Terminal and button code held since its press was reported
+    held: Option<(Entity, u8)>,
+}
+
+/// Reports mouse input over a terminal sprite to the PTY of the terminal it
+/// shows.
+///
+/// System: Update
+/// Runs: Every frame, after `handle_keyboard_input`
+///
+/// Only while that terminal's program has mouse tracking on
+/// (`TerminalState::mouse_mode`) and `TerminalInputEnabled` routes all keys
+/// to the terminal; bypass keys don't cover the mouse. Positions come from
+/// the first active camera and the first `TerminalSprite` under the cursor;
+/// a held button keeps reporting to the terminal it was pressed on.
+#[allow(clippy::too_many_arguments)]
+pub fn handle_mouse_input(
+    mut buttons: MessageReader<MouseButtonInput>,
+    mut wheel: MessageReader<MouseWheel>,
+    mut moved: MessageReader<CursorMoved>,
+    keyboard: Option<Res<ButtonInput<KeyCode>>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    sprites: Query<(&GlobalTransform, &TerminalSprite)>,
+    atlas: Res<GlyphAtlas>,
+    terminals: Query<(&TerminalTexture, &TerminalState, &TerminalConfig, &PtyResource)>,
+    input_enabled: Option<Res<TerminalInputEnabled>>,
+    mut tracker: Local<MouseTracker>,
+) {
+    let cursor_moved = moved.read().last().map(|event| event.position);
+    if cursor_moved.is_some() {
+        tracker.cursor = cursor_moved;
+    }
+
+    let enabled = input_enabled.as_ref().map(|r| r.routes_all()).unwrap_or(true);
+    let pointed = tracker.cursor.filter(|_| enabled).and_then(|position| {
+        pointer_cell(position, &cameras, &sprites, &atlas, |entity| {
+            terminals.get(entity).ok().map(|(texture, term_state, config, _)| (texture, term_state, config))
+        })
+    });
+    let terminal = tracker.held.map(|(terminal, _)| terminal).or(pointed.map(|(terminal, _)| terminal));
+    let found = terminal.and_then(|terminal| terminals.get(terminal).ok());
+    let Some((terminal, (_, term_state, _, pty))) = terminal.zip(found).filter(|_| enabled) else {
+        buttons.clear();
+        wheel.clear();
+        tracker.held = None;
+        return;
+    };
+    let mode = term_state.mouse_mode();
+    if !mode.clicks {
+        buttons.clear();
+        wheel.clear();
+        tracker.held = None;
+        return;
+    }
+
+    let cell = pointed.filter(|(pointed, _)| *pointed == terminal).map(|(_, cell)| cell);
+    let last_cell = tracker.cell.filter(|(last, _)| *last == terminal).map(|(_, cell)| cell);
+
+    let held = |keys: [KeyCode; 2]| keyboard.as_ref().is_some_and(|keyboard| keyboard.any_pressed(keys));
+    let modifiers = if held([KeyCode::ShiftLeft, KeyCode::ShiftRight]) { 4 } else { 0 }
+        | if held([KeyCode::AltLeft, KeyCode::AltRight]) { 8 } else { 0 }
+        | if held([KeyCode::ControlLeft, KeyCode::ControlRight]) { 16 } else { 0 };
+
+    let mut reports = Vec::new();
+
+    // Motion: once per cell crossed, with the held button if any
+    if let (Some((column, row)), Some(_)) = (cell, cursor_moved) {
+        let button = match tracker.held {
+            Some((_, button)) if mode.drag || mode.motion => Some(button),
+            None if mode.motion => Some(MOUSE_NO_BUTTON),
+            _ => None,
+        };
+        if let Some(button) = button.filter(|_| last_cell != cell) {
+            reports.extend(mouse_report(button | MOUSE_MOTION | modifiers, column, row, false, mode.sgr));
+        }
+    }
+
+    for event in buttons.read() {
+        let button = match event.button {
+            MouseButton::Left => 0,
+            MouseButton::Middle => 1,
+            MouseButton::Right => 2,
+            _ => continue,
+        };
+        if event.state.is_pressed() {
+            // Presses off the grid belong to the game
+            let Some((column, row)) = cell else {
+                continue;
+            };
+            tracker.held = Some((terminal, button));
+            reports.extend(mouse_report(button | modifiers, column, row, false, mode.sgr));
+        } else if tracker.held == Some((terminal, button)) {
+            // The release always follows its press, at the last cell if off the grid
+            tracker.held = None;
+            if let Some((column, row)) = cell.or(last_cell) {
+                reports.extend(mouse_report(button | modifiers, column, row, true, mode.sgr));
             }
         }
     }
+
+    for event in wheel.read() {
+        let Some((column, row)) = cell.filter(|_| event.y != 0.0) else {
+            continue;
+        };
+        let button = if event.y > 0.0 { MOUSE_WHEEL_UP } else { MOUSE_WHEEL_UP + 1 };
+        reports.extend(mouse_report(button | modifiers, column, row, false, mode.sgr));
+    }
+
+    if let Some(cell) = cell {
+        tracker.cell = Some((terminal, cell));
+    }
+    for report in reports {
+        trace!("🖱️ Mouse report: {:?}", String::from_utf8_lossy(&report));
+        pty.queue_write(&report);
+    }
+}
+
+/// Pointer state `handle_mouse_selection` carries between frames.
+#[derive(Default)]
+pub struct SelectionTracker {
+    /// Last window cursor position
+    cursor: Option<Vec2>,
+    /// Terminal and grid cell the left button went down on
+    /// (`TerminalState::viewport_to_grid`)
+    anchor: Option<(Entity, (usize, i32))>,
+}
+
+/// Selects text by dragging the left button over a terminal sprite.
+///
+/// System: Update
+/// Runs: Every frame, after `handle_mouse_input`
+///
+/// A press clears the selection and a drag selects from the pressed cell to
+/// the one under the pointer, in the terminal the press landed on. Cells are
+/// kept in grid lines, so a selection started and finished at different
+/// scroll positions covers the history in between. Only while
+/// `TerminalInputEnabled` routes all keys to the terminal and the program
+/// isn't taking mouse reports.
+#[allow(clippy::too_many_arguments)]
+pub fn handle_mouse_selection(
+    mut buttons: MessageReader<MouseButtonInput>,
+    mut moved: MessageReader<CursorMoved>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    sprites: Query<(&GlobalTransform, &TerminalSprite)>,
+    atlas: Res<GlyphAtlas>,
+    mut terminals: Query<(&TerminalTexture, &mut TerminalState, &TerminalConfig)>,
+    input_enabled: Option<Res<TerminalInputEnabled>>,
+    mut tracker: Local<SelectionTracker>,
+) {
+    let cursor_moved = moved.read().last().map(|event| event.position);
+    if cursor_moved.is_some() {
+        tracker.cursor = cursor_moved;
+    }
+
+    let enabled = input_enabled.as_ref().map(|r| r.routes_all()).unwrap_or(true);
+    let pointed = tracker.cursor.filter(|_| enabled).and_then(|position| {
+        pointer_cell(position, &cameras, &sprites, &atlas, |entity| terminals.get(entity).ok())
+    });
+    // A drag stays in the terminal it started in
+    let terminal = tracker.anchor.map(|(terminal, _)| terminal).or(pointed.map(|(terminal, _)| terminal));
+    let found = terminal.and_then(|terminal| terminals.get_mut(terminal).ok());
+    let (Some(terminal), Some((_, mut term_state, _))) = (terminal, found) else {
+        buttons.clear();
+        tracker.anchor = None;
+        return;
+    };
+    if !enabled || term_state.mouse_mode().clicks {
+        buttons.clear();
+        tracker.anchor = None;
+        return;
+    }
+
+    let cell = pointed.filter(|(pointed, _)| *pointed == terminal).map(|(_, cell)| cell);
+    let anchor = tracker.anchor.map(|(_, anchor)| anchor);
+
+    // Drag first, so a release in the same frame keeps the last cell
+    if let (Some(anchor), Some((column, row)), Some(_)) = (anchor, cell, cursor_moved) {
+        let end = term_state.viewport_to_grid(column, row);
+        // Wobbling within the pressed cell is still a click
+        if end != anchor || term_state.has_selection() {
+            term_state.set_selection(anchor, end);
+        }
+    }
+
+    for event in buttons.read().filter(|event| event.button == MouseButton::Left) {
+        if event.state.is_pressed() {
+            // Presses off the grid belong to the game
+            let Some((column, row)) = cell else {
+                continue;
+            };
+            term_state.clear_selection();
+            tracker.anchor = Some((terminal, term_state.viewport_to_grid(column, row)));
+        } else {
+            tracker.anchor = None;
+        }
+    }
+}
+
+/// Lines one wheel notch scrolls the viewport.
+pub const WHEEL_SCROLL_LINES: f32 = 3.0;
+
+/// Lines a wheel event scrolls the viewport (positive = up into history).
+///
+/// Pixel deltas (touchpads) move one line per `cell_height` pixels.
+pub fn wheel_scroll_lines(event: &MouseWheel, cell_height: u32) -> f32 {
+    match event.unit {
+        MouseScrollUnit::Line => event.y * WHEEL_SCROLL_LINES,
+        MouseScrollUnit::Pixel => event.y / cell_height.max(1) as f32,
+    }
+}
+
+/// Scrolls the focused terminal's viewport through history with the mouse
+/// wheel.
+///
+/// System: Update
+/// Runs: Every frame
+///
+/// Only while `TerminalInputEnabled` routes all keys to the terminal and the
+/// program isn't taking mouse reports, which get the wheel instead. The
+/// alternate screen has no history to scroll. Alacritty clamps the offset
+/// at both ends, and new output returns to the bottom (see
+/// `TerminalState::with_scroll_on_output`).
+pub fn scroll_viewport_on_wheel(
+    mut wheel: MessageReader<MouseWheel>,
+    focus: Option<Res<TerminalFocus>>,
+    primary: Query<Entity, With<PrimaryTerminal>>,
+    mut terminals: Query<&mut TerminalState>,
+    atlas: Option<Res<GlyphAtlas>>,
+    input_enabled: Option<Res<TerminalInputEnabled>>,
+    mut partial_lines: Local<f32>,
+) {
+    let enabled = input_enabled.as_ref().map(|r| r.routes_all()).unwrap_or(true);
+    let term_state = focused_terminal(focus.as_deref(), &primary).and_then(|terminal| terminals.get_mut(terminal).ok());
+    let Some(mut term_state) = term_state.filter(|term_state| {
+        enabled && !term_state.mouse_mode().clicks && !term_state.is_alt_screen()
+    }) else {
+        wheel.clear();
+        *partial_lines = 0.0;
+        return;
+    };
+
+    let cell_height = atlas.as_ref().map_or(1, |atlas| atlas.cell_height);
+    *partial_lines += wheel.read().map(|event| wheel_scroll_lines(event, cell_height)).sum::<f32>();
+
+    // Touchpads scroll a fraction of a line at a time; keep the remainder
+    let lines = partial_lines.trunc();
+    if lines != 0.0 {
+        term_state.scroll_viewport(lines as i32);
+        *partial_lines -= lines;
+    }
+}
+
+/// `keycode_to_bytes`, with plain Backspace sending `erase_char`.
+fn key_bytes(key: KeyCode, shift: bool, ctrl: bool, erase_char: EraseChar) -> Option<Vec<u8>> {
+    if key == KeyCode::Backspace && !ctrl {
+        return Some(vec![erase_char.byte()]);
+    }
+    keycode_to_bytes(key, shift, ctrl)
+}
+
+/// xterm sequence for F1-F12, or None for other keys.
+///
+/// F1-F4 are SS3 sequences (`\x1bOP`), F5-F12 numbered ones (`\x1b[15~`).
+/// With Shift or Ctrl the modifier goes in as a parameter, 1 + 1 for Shift
+/// + 4 for Ctrl: Shift+F1 is `\x1b[1;2P`, Ctrl+F5 `\x1b[15;5~`.
+fn function_key_bytes(key: KeyCode, shift: bool, ctrl: bool) -> Option<Vec<u8>> {
+    use KeyCode::*;
+
+    let (number, final_byte) = match key {
+        F1 => (1, 'P'),
+        F2 => (1, 'Q'),
+        F3 => (1, 'R'),
+        F4 => (1, 'S'),
+        F5 => (15, '~'),
+        F6 => (17, '~'),
+        F7 => (18, '~'),
+        F8 => (19, '~'),
+        F9 => (20, '~'),
+        F10 => (21, '~'),
+        F11 => (23, '~'),
+        F12 => (24, '~'),
+        _ => return None,
+    };
+
+    let modifier = 1 + u8::from(shift) + 4 * u8::from(ctrl);
+    let sequence = match (final_byte, modifier) {
+        ('~', 1) => format!("\x1b[{}~", number),
+        ('~', _) => format!("\x1b[{};{}~", number, modifier),
+        (_, 1) => format!("\x1bO{}", final_byte),
+        (_, _) => format!("\x1b[1;{}{}", modifier, final_byte),
+    };
+    Some(sequence.into_bytes())
+}
+
+/// Keypad sequence in application mode (DECKPAM, `\x1b=`), or None for
+/// other keys.
+///
+/// Programs like `mc` and `vim` turn this on to tell the keypad from the
+/// digit row. In numeric mode the keypad types its characters instead.
+fn application_keypad_bytes(key: KeyCode) -> Option<Vec<u8>> {
+    use KeyCode::*;
+
+    let final_byte = match key {
+        Numpad0 => b'p',
+        Numpad1 => b'q',
+        Numpad2 => b'r',
+        Numpad3 => b's',
+        Numpad4 => b't',
+        Numpad5 => b'u',
+        Numpad6 => b'v',
+        Numpad7 => b'w',
+        Numpad8 => b'x',
+        Numpad9 => b'y',
+        NumpadMultiply => b'j',
+        NumpadAdd => b'k',
+        NumpadComma => b'l',
+        NumpadSubtract => b'm',
+        NumpadDecimal => b'n',
+        NumpadDivide => b'o',
+        NumpadEqual => b'X',
+        NumpadEnter => b'M',
+        _ => return None,
+    };
+    Some(vec![0x1b, b'O', final_byte])
 }
 
 /// Converts Bevy KeyCode to terminal byte sequences.
@@ -67,6 +791,11 @@ pub fn handle_keyboard_input(
 fn keycode_to_bytes(key: KeyCode, shift: bool, ctrl: bool) -> Option<Vec<u8>> {
     use KeyCode::*;
 
+    // Function keys carry their modifiers in the sequence
+    if let Some(bytes) = function_key_bytes(key, shift, ctrl) {
+        return Some(bytes);
+    }
+
     // Ctrl sequences take precedence (Ctrl+C, Ctrl+D, etc.)
     if ctrl {
         return match key {
@@ -96,6 +825,12 @@ fn keycode_to_bytes(key: KeyCode, shift: bool, ctrl: bool) -> Option<Vec<u8>> {
             KeyX => Some(vec![0x18]), // Ctrl+X
             KeyY => Some(vec![0x19]), // Ctrl+Y
             KeyZ => Some(vec![0x1A]), // Ctrl+Z (SUB - Suspend)
+            BracketLeft => Some(vec![0x1B]), // Ctrl+[ (ESC)
+            Backslash => Some(vec![0x1C]), // Ctrl+\ (FS - SIGQUIT)
+            BracketRight => Some(vec![0x1D]), // Ctrl+] (GS - telnet escape)
+            Digit6 => Some(vec![0x1E]), // Ctrl+^ (RS), with or without Shift like xterm
+            Minus => Some(vec![0x1F]), // Ctrl+_ (US - undo in emacs)
+            Backspace => Some(vec![0x17]), // Ctrl+W (ETB - word erase)
             _ => None,
         };
     }
@@ -186,6 +921,25 @@ fn keycode_to_bytes(key: KeyCode, shift: bool, ctrl: bool) -> Option<Vec<u8>> {
         Delete => Some(b"\x1b[3~".to_vec()),
         Insert => Some(b"\x1b[2~".to_vec()),
 
+        // Keypad in numeric mode (see `application_keypad_bytes`)
+        Numpad0 => Some(b"0".to_vec()),
+        Numpad1 => Some(b"1".to_vec()),
+        Numpad2 => Some(b"2".to_vec()),
+        Numpad3 => Some(b"3".to_vec()),
+        Numpad4 => Some(b"4".to_vec()),
+        Numpad5 => Some(b"5".to_vec()),
+        Numpad6 => Some(b"6".to_vec()),
+        Numpad7 => Some(b"7".to_vec()),
+        Numpad8 => Some(b"8".to_vec()),
+        Numpad9 => Some(b"9".to_vec()),
+        NumpadAdd => Some(b"+".to_vec()),
+        NumpadSubtract => Some(b"-".to_vec()),
+        NumpadMultiply => Some(b"*".to_vec()),
+        NumpadDivide => Some(b"/".to_vec()),
+        NumpadDecimal => Some(b".".to_vec()),
+        NumpadEqual => Some(b"=".to_vec()),
+        NumpadEnter => Some(b"\r".to_vec()),
+
         // Modifiers themselves and other unmapped keys
         _ => None,
     }
@@ -230,6 +984,15 @@ mod tests {
         assert_eq!(keycode_to_bytes(KeyCode::KeyZ, false, true), Some(vec![0x1A])); // Ctrl+Z
     }
 
+    #[test]
+    fn test_ctrl_symbols() {
+        assert_eq!(keycode_to_bytes(KeyCode::BracketLeft, false, true), Some(vec![0x1B])); // Ctrl+[ = ESC
+        assert_eq!(keycode_to_bytes(KeyCode::Backslash, false, true), Some(vec![0x1C])); // Ctrl+\
+        assert_eq!(keycode_to_bytes(KeyCode::BracketRight, false, true), Some(vec![0x1D])); // Ctrl+]
+        assert_eq!(keycode_to_bytes(KeyCode::Digit6, true, true), Some(vec![0x1E])); // Ctrl+^
+        assert_eq!(keycode_to_bytes(KeyCode::Minus, true, true), Some(vec![0x1F])); // Ctrl+_
+    }
+
     #[test]
     fn test_special_keys() {
         assert_eq!(keycode_to_bytes(KeyCode::Enter, false, false), Some(b"\r".to_vec()));
@@ -237,6 +1000,25 @@ mod tests {
         assert_eq!(keycode_to_bytes(KeyCode::Backspace, false, false), Some(b"\x7f".to_vec()));
     }
 
+    #[test]
+    fn test_erase_char() {
+        use KeyCode::{Backspace, Delete};
+
+        // Default: DEL, as most modern terminals send
+        assert_eq!(key_bytes(Backspace, false, false, EraseChar::Delete), Some(vec![0x7f]));
+        assert_eq!(key_bytes(Backspace, true, false, EraseChar::Delete), Some(vec![0x7f]));
+        // Legacy hosts: BS
+        assert_eq!(key_bytes(Backspace, false, false, EraseChar::Backspace), Some(vec![0x08]));
+        assert_eq!(key_bytes(Backspace, true, false, EraseChar::Backspace), Some(vec![0x08]));
+
+        // Ctrl+Backspace erases a word either way; Delete never changes
+        for erase_char in [EraseChar::Delete, EraseChar::Backspace] {
+            assert_eq!(key_bytes(Backspace, false, true, erase_char), Some(vec![0x17]));
+            assert_eq!(key_bytes(Delete, false, false, erase_char), Some(b"\x1b[3~".to_vec()));
+        }
+        assert_eq!(TerminalInputEnabled::default().erase_char, EraseChar::Delete);
+    }
+
     #[test]
     fn test_arrow_keys() {
         assert_eq!(keycode_to_bytes(KeyCode::ArrowUp, false, false), Some(b"\x1b[A".to_vec()));
@@ -245,6 +1027,219 @@ mod tests {
         assert_eq!(keycode_to_bytes(KeyCode::ArrowLeft, false, false), Some(b"\x1b[D".to_vec()));
     }
 
+    #[test]
+    fn test_function_keys() {
+        assert_eq!(keycode_to_bytes(KeyCode::F1, false, false), Some(b"\x1bOP".to_vec()));
+        assert_eq!(keycode_to_bytes(KeyCode::F4, false, false), Some(b"\x1bOS".to_vec()));
+        assert_eq!(keycode_to_bytes(KeyCode::F5, false, false), Some(b"\x1b[15~".to_vec()));
+        assert_eq!(keycode_to_bytes(KeyCode::F10, false, false), Some(b"\x1b[21~".to_vec()));
+        assert_eq!(keycode_to_bytes(KeyCode::F12, false, false), Some(b"\x1b[24~".to_vec()));
+
+        // Modifiers as a parameter
+        assert_eq!(keycode_to_bytes(KeyCode::F1, true, false), Some(b"\x1b[1;2P".to_vec()));
+        assert_eq!(keycode_to_bytes(KeyCode::F5, false, true), Some(b"\x1b[15;5~".to_vec()));
+        assert_eq!(keycode_to_bytes(KeyCode::F12, true, true), Some(b"\x1b[24;6~".to_vec()));
+    }
+
+    #[test]
+    fn test_keypad() {
+        // Numeric mode types the characters
+        assert_eq!(keycode_to_bytes(KeyCode::Numpad7, false, false), Some(b"7".to_vec()));
+        assert_eq!(keycode_to_bytes(KeyCode::NumpadAdd, false, false), Some(b"+".to_vec()));
+        assert_eq!(keycode_to_bytes(KeyCode::NumpadEnter, false, false), Some(b"\r".to_vec()));
+
+        // Application mode sends SS3 sequences
+        assert_eq!(application_keypad_bytes(KeyCode::Numpad0), Some(b"\x1bOp".to_vec()));
+        assert_eq!(application_keypad_bytes(KeyCode::Numpad9), Some(b"\x1bOy".to_vec()));
+        assert_eq!(application_keypad_bytes(KeyCode::NumpadEnter), Some(b"\x1bOM".to_vec()));
+        assert_eq!(application_keypad_bytes(KeyCode::Digit1), None);
+
+        let mut term_state = TerminalState::new();
+        assert!(!term_state.app_keypad());
+        term_state.process_bytes(b"\x1b=");
+        assert!(term_state.app_keypad());
+        term_state.process_bytes(b"\x1b>");
+        assert!(!term_state.app_keypad());
+    }
+
+    #[test]
+    fn test_bypass_keys_while_disabled() {
+        let input = TerminalInputEnabled {
+            enabled: false,
+            bypass: vec![BypassKey::CTRL_C],
+            ..default()
+        };
+        assert!(input.allows(KeyCode::KeyC, true));
+        assert!(!input.allows(KeyCode::KeyC, false), "Plain 'c' is not Ctrl+C");
+        assert!(!input.allows(KeyCode::KeyD, true));
+
+        // Nothing bypasses by default
+        assert!(!TerminalInputEnabled::default().allows(KeyCode::KeyC, true));
+
+        // Enabled input allows everything
+        let enabled = TerminalInputEnabled { enabled: true, ..default() };
+        assert!(enabled.allows(KeyCode::KeyA, false));
+    }
+
+    #[test]
+    fn test_grab_and_release() {
+        let mut input = TerminalInputEnabled::default();
+        assert!(!input.allows(KeyCode::KeyA, false));
+
+        // Nested grabs: the outer one still holds after the inner releases
+        input.grab();
+        input.grab();
+        assert!(input.allows(KeyCode::KeyA, false));
+        input.release();
+        assert!(input.is_grabbed());
+        assert!(input.allows(KeyCode::KeyA, false));
+        input.release();
+        assert!(!input.is_grabbed());
+        assert!(!input.allows(KeyCode::KeyA, false));
+
+        // Unbalanced releases don't wrap around into a grab
+        input.release();
+        assert!(!input.is_grabbed());
+
+        // Releasing never disables input the game enabled
+        input.enabled = true;
+        input.grab();
+        input.release();
+        assert!(input.allows(KeyCode::KeyA, false));
+    }
+
+    #[test]
+    fn test_will_consume() {
+        use bevy::ecs::system::SystemState;
+        use bevy::input::keyboard::Key;
+        use bevy::input::ButtonState;
+
+        let press = |key_code, text: Option<&str>| KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(bevy::input::keyboard::NativeKey::Unidentified),
+            state: ButtonState::Pressed,
+            text: text.map(Into::into),
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        };
+        let consumed = |world: &mut World, event: &KeyboardInput| {
+            let mut state = SystemState::<TerminalKeyboard>::new(world);
+            state.get(world).will_consume(event)
+        };
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+
+        // Nothing to type into: no focus, then a focused terminal without a PTY
+        assert!(!consumed(&mut world, &press(KeyCode::KeyE, Some("e"))));
+        let terminal = world.spawn((PrimaryTerminal, TerminalState::new())).id();
+        assert!(!consumed(&mut world, &press(KeyCode::KeyE, Some("e"))));
+
+        let pty = PtyResource::new().expect("PTY spawn failed");
+        world.entity_mut(terminal).insert(pty);
+        assert!(consumed(&mut world, &press(KeyCode::KeyE, Some("e"))));
+        assert!(consumed(&mut world, &press(KeyCode::Escape, None)));
+        // Keys the table doesn't know still type their layout's text
+        assert!(consumed(&mut world, &press(KeyCode::IntlBackslash, Some("<"))));
+        assert!(!consumed(&mut world, &press(KeyCode::IntlBackslash, None)));
+
+        // Modifier-only keys send nothing
+        for key in [KeyCode::ShiftLeft, KeyCode::ControlRight, KeyCode::AltLeft] {
+            assert!(!consumed(&mut world, &press(key, None)));
+        }
+
+        // Application keypad mode gives keypad keys a sequence of their own,
+        // even keys that type nothing otherwise
+        assert!(!consumed(&mut world, &press(KeyCode::NumpadComma, None)));
+        world.get_mut::<TerminalState>(terminal).unwrap().process_bytes(b"\x1b=");
+        assert!(consumed(&mut world, &press(KeyCode::NumpadComma, None)));
+
+        // Disabled: only bypass keys get through, with their modifiers held
+        world.insert_resource(TerminalInputEnabled {
+            enabled: false,
+            bypass: vec![BypassKey::CTRL_C],
+            ..default()
+        });
+        assert!(!consumed(&mut world, &press(KeyCode::KeyE, Some("e"))));
+        assert!(!consumed(&mut world, &press(KeyCode::KeyC, Some("c"))));
+        world.resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::ControlLeft);
+        assert!(consumed(&mut world, &press(KeyCode::KeyC, Some("c"))));
+
+        // Grabs route everything again
+        world.resource_mut::<TerminalInputEnabled>().grab();
+        assert!(consumed(&mut world, &press(KeyCode::KeyE, Some("e"))));
+
+        // Another terminal focused, without a PTY
+        let other = world.spawn(TerminalState::new()).id();
+        world.insert_resource(TerminalFocus(Some(other)));
+        assert!(!consumed(&mut world, &press(KeyCode::KeyE, Some("e"))));
+    }
+
+    #[test]
+    fn test_typed_text() {
+        // Layout-dependent characters go out as typed, in UTF-8
+        assert_eq!(typed_text(Some("é"), false, false), Some("é"));
+        assert_eq!(typed_text(Some("Q"), false, false), Some("Q"));
+        assert_eq!("é".as_bytes(), [0xc3, 0xa9]);
+
+        // AltGr arrives as Ctrl+Alt on Windows
+        assert_eq!(typed_text(Some("€"), true, true), Some("€"));
+
+        // Ctrl keeps its control codes
+        assert_eq!(typed_text(Some("c"), true, false), None);
+        assert_eq!(keycode_to_bytes(KeyCode::KeyC, false, true), Some(vec![0x03]));
+
+        // Keys without printable text use the keycode table
+        for text in [None, Some(""), Some("\r"), Some("\t"), Some("\u{8}"), Some("\u{1b}")] {
+            assert_eq!(typed_text(text, false, false), None);
+        }
+    }
+
+    #[test]
+    fn test_mouse_reports() {
+        // SGR: 1-based, M on press, m on release, modifiers in the button code
+        assert_eq!(mouse_report(0, 0, 0, false, true), Some(b"\x1b[<0;1;1M".to_vec()));
+        assert_eq!(mouse_report(2, 9, 4, true, true), Some(b"\x1b[<2;10;5m".to_vec()));
+        assert_eq!(mouse_report(MOUSE_WHEEL_UP | 16, 300, 2, false, true), Some(b"\x1b[<80;301;3M".to_vec()));
+        assert_eq!(mouse_report(MOUSE_MOTION, 1, 1, false, true), Some(b"\x1b[<32;2;2M".to_vec()));
+
+        // Legacy: offset by 32, releases don't say which button
+        assert_eq!(mouse_report(0, 0, 0, false, false), Some(b"\x1b[M !!".to_vec()));
+        assert_eq!(mouse_report(2, 9, 4, true, false), Some(b"\x1b[M#*%".to_vec()));
+        assert_eq!(mouse_report(0, 300, 0, false, false), None);
+    }
+
+    #[test]
+    fn test_cell_at() {
+        // 10×5 cells of 8×16 texels, centered on (100, 0) at 2× scale
+        let texture = TerminalTexture { handle: Handle::default(), width: 80, height: 80 };
+        let sprite = GlobalTransform::from(Transform::from_xyz(100.0, 0.0, 0.0).with_scale(Vec3::splat(2.0)));
+        let cell_size = UVec2::new(8, 16);
+        let at = |x, y, grid_top| cell_at(Vec2::new(x, y), &sprite, &texture, cell_size, grid_top, 10, 5);
+
+        assert_eq!(at(21.0, 79.0, 0), Some((0, 0)));
+        assert_eq!(at(179.0, -79.0, 0), Some((9, 4)));
+        assert_eq!(at(100.0, 0.0, 0), Some((5, 2)));
+        assert_eq!(at(19.0, 0.0, 0), None);
+        assert_eq!(at(100.0, 81.0, 0), None);
+
+        // A top status bar takes the first texture row
+        assert_eq!(at(21.0, 79.0, 1), None);
+        assert_eq!(at(21.0, 47.0, 1), Some((0, 0)));
+    }
+
+    #[test]
+    fn test_mouse_mode_tracks_decset() {
+        let mut term_state = TerminalState::new();
+        assert_eq!(term_state.mouse_mode(), MouseMode::default());
+
+        term_state.process_bytes(b"\x1b[?1002h\x1b[?1006h");
+        let mode = term_state.mouse_mode();
+        assert!(mode.clicks && mode.drag && mode.sgr && !mode.motion);
+
+        term_state.process_bytes(b"\x1b[?1002l");
+        assert!(!term_state.mouse_mode().clicks);
+    }
+
     #[test]
     fn test_unmapped_keys() {
         assert_eq!(keycode_to_bytes(KeyCode::ShiftLeft, false, false), None);
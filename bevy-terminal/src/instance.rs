@@ -0,0 +1,52 @@
+//! Terminals as entities.
+//!
+//! Every terminal is an entity carrying its own `TerminalState`,
+//! `TerminalConfig`, `TerminalCpuBuffer`, `TerminalStats` and, once set up,
+//! `PtyResource` and `TerminalTexture`. `TerminalPlugin` spawns one, marked
+//! `PrimaryTerminal`; `spawn_terminal` adds more, which run through the same
+//! systems: PTY polling, respawn, events, stats, texture and rendering.
+//!
+//! Terminals share the plugin's font and glyph atlas, so the font settings
+//! of a spawned terminal's config are ignored.
+//!
+//! Messages name their terminal: `TerminalWrite`, `TerminalPaste`,
+//! `ResizeTerminal` and `TerminalEvent` all carry the entity. Keyboard input
+//! goes to the terminal in `TerminalFocus` (the primary one by default) and
+//! mouse input to the terminal whose `TerminalSprite` is under the pointer.
+//!
+//! Despawning the entity kills its shell and frees its GPU buffers.
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use bevy_terminal::prelude::*;
+//!
+//! fn spawn_second_terminal(mut commands: Commands, mut writes: MessageWriter<TerminalWrite>) {
+//!     let entity = spawn_terminal(&mut commands, TerminalConfig::default());
+//!     writes.write(TerminalWrite::new(entity, "echo hello\n"));
+//! }
+//! ```
+
+use bevy::prelude::*;
+use log::info;
+
+use crate::config::TerminalConfig;
+use crate::terminal::TerminalPlugin;
+
+/// Marks the terminal `TerminalPlugin` spawns.
+///
+/// Keyboard input goes to it unless `TerminalFocus` names another terminal.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct PrimaryTerminal;
+
+/// Spawn a terminal configured by `config` and return its entity.
+///
+/// The shell starts in the next `Update` when `config.spawn_pty` is set; if
+/// it fails a `TerminalEventKind::Error` is sent and the terminal runs
+/// without one. The texture is added once the glyph atlas exists (never when
+/// headless).
+pub fn spawn_terminal(commands: &mut Commands, config: TerminalConfig) -> Entity {
+    let term_state = TerminalPlugin { config: config.clone() }.initial_terminal_state();
+    let entity = commands.spawn((term_state, config)).id();
+    info!("🖥️  Terminal spawned: {:?}", entity);
+    entity
+}
@@ -14,35 +14,79 @@
 //! fn main() {
 //!     App::new()
 //!         .add_plugins(DefaultPlugins)
-//!         .add_plugins(TerminalPlugin)
+//!         .add_plugins(TerminalPlugin::default())
 //!         .run();
 //! }
 //!
-//! fn use_terminal(terminal_texture: Res<TerminalTexture>) {
+//! fn use_terminal(terminal_texture: Single<&TerminalTexture, With<PrimaryTerminal>>) {
 //!     // terminal_texture.handle is Handle<Image>
 //!     // Use it anywhere: sprites, UI, materials
 //! }
 //! ```
+//!
+//! Each terminal is an entity; `spawn_terminal` adds more (see `instance`).
+//!
+//! With the `crt` feature, `crt::CrtMaterial` shows the texture as a curved,
+//! scanlined CRT screen.
 
 pub mod atlas;
+pub mod clock;
 mod colors;
-mod events;
+pub mod config;
+#[cfg(feature = "crt")]
+pub mod crt;
+pub mod events;
 pub mod font;
+pub mod fragment;
 pub mod gpu_types;
 pub mod gpu_prep;
+#[cfg(feature = "emoji")]
+pub mod grapheme;
 pub mod render_node;
 pub mod input;
+pub mod instance;
+pub mod paste;
 pub mod pty;
 pub mod renderer;
+pub mod resize;
+pub mod shell_integration;
+pub mod shutdown;
+pub mod snapshot;
+pub mod software;
+pub mod source;
+pub mod sprite;
+pub mod stats;
 mod terminal;
 
+pub use clock::TerminalClock;
+pub use colors::{TerminalPalette, DEFAULT_CURSOR_COLOR};
+pub use config::{CursorBlinkStyle, CursorStyle, FallbackGlyph, GridViewport, LoadingIndicator, RenderBackend, StatusBarConfig, StatusBarPosition, StatusBarText, TerminalConfig, TerminalTheme, WatermarkConfig};
+pub use font::{FontSource, GlyphPlacement};
+pub use events::{TerminalEvent, TerminalEventKind};
+pub use input::{EraseChar, MouseMode, TerminalFocus, TerminalKeyboard, TerminalWrite};
+pub use instance::{spawn_terminal, PrimaryTerminal};
+pub use paste::{PasteConfig, TerminalPaste};
 pub use renderer::TerminalTexture;
-pub use terminal::{TerminalPlugin, TerminalState};
+pub use resize::ResizeTerminal;
+pub use source::{InputSource, SourceResource};
+pub use sprite::{spawn_terminal_sprite, TerminalAutosize, TerminalSprite};
+pub use stats::TerminalStats;
+pub use terminal::{apply_terminal_palette, flush_expired_synchronized_updates, initialize_font_and_atlas, CellInfo, TerminalPlugin, TerminalState, DEFAULT_EVENT_CAPACITY};
 
 /// Re-export commonly used types
 pub mod prelude {
-    pub use crate::events::TerminalEvent;
-    pub use crate::input::TerminalInputEnabled;
+    pub use crate::clock::TerminalClock;
+    pub use crate::colors::TerminalPalette;
+    pub use crate::config::{CursorBlinkStyle, CursorStyle, FallbackGlyph, GridViewport, LoadingIndicator, RenderBackend, StatusBarConfig, StatusBarPosition, StatusBarText, TerminalConfig, TerminalTheme, WatermarkConfig};
+    pub use crate::events::{TerminalEvent, TerminalEventKind};
+    pub use crate::font::{FontSource, GlyphPlacement};
+    pub use crate::input::{BypassKey, EraseChar, TerminalFocus, TerminalInputEnabled, TerminalKeyboard, TerminalWrite};
+    pub use crate::instance::{spawn_terminal, PrimaryTerminal};
+    pub use crate::paste::TerminalPaste;
     pub use crate::renderer::TerminalTexture;
+    pub use crate::resize::ResizeTerminal;
+    pub use crate::source::{InputSource, SourceResource};
+    pub use crate::sprite::{spawn_terminal_sprite, TerminalAutosize, TerminalSprite};
+    pub use crate::stats::TerminalStats;
     pub use crate::terminal::TerminalPlugin;
 }
@@ -0,0 +1,217 @@
+//! Pasting text into the PTY.
+//!
+//! Large pastes are written in small chunks with short pauses by the PTY's
+//! writer thread, so neither the PTY buffer nor the reading program gets
+//! overrun and the main loop never blocks. Input sent after a paste waits
+//! for it, so keys typed meanwhile never end up inside it. Pastes are wrapped in bracketed
+//! paste markers when the program enabled them (`\x1b[?2004h`); otherwise
+//! they are sent as the keystrokes that would type them (`paste_keystrokes`).
+//!
+//! Games paste by sending a `TerminalPaste` message, for example from their
+//! clipboard on `TerminalEventKind::PasteRequested` (Ctrl+Shift+V).
+
+use bevy::prelude::*;
+use std::io::Write;
+use std::thread;
+use std::time::Duration;
+use log::{error, info};
+
+use crate::config::TerminalConfig;
+use crate::pty::{PtyResource, WriterMessage};
+use crate::terminal::TerminalState;
+
+/// Start of a bracketed paste.
+pub const BRACKETED_PASTE_START: &[u8] = b"\x1b[200~";
+/// End of a bracketed paste.
+pub const BRACKETED_PASTE_END: &[u8] = b"\x1b[201~";
+
+/// Paste chunking configuration.
+#[derive(Clone, Debug)]
+pub struct PasteConfig {
+    /// Pastes up to this many bytes are written in one go.
+    pub threshold: usize,
+    /// Bytes per write for larger pastes.
+    pub chunk_size: usize,
+    /// Pause between chunks.
+    pub chunk_delay: Duration,
+}
+
+impl Default for PasteConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 4096,
+            chunk_size: 1024,
+            chunk_delay: Duration::from_millis(1),
+        }
+    }
+}
+
+/// Text to paste into a terminal, such as the game's clipboard contents.
+///
+/// Registered as a message by `TerminalPlugin`. Unlike `TerminalWrite`, the
+/// text is bracketed when the program enabled bracketed paste, so a shell
+/// doesn't run pasted lines one by one.
+#[derive(Message, Clone, Debug, PartialEq, Eq)]
+pub struct TerminalPaste {
+    /// The terminal entity to paste into
+    pub terminal: Entity,
+    pub text: String,
+}
+
+impl TerminalPaste {
+    pub fn new(terminal: Entity, text: impl Into<String>) -> Self {
+        Self { terminal, text: text.into() }
+    }
+}
+
+/// Pastes `TerminalPaste` messages into their terminals' PTYs.
+///
+/// System: Update
+/// Runs: Every frame, after `apply_terminal_writes`
+pub fn apply_terminal_pastes(
+    mut pastes: MessageReader<TerminalPaste>,
+    terminals: Query<(&PtyResource, &TerminalState, &TerminalConfig)>,
+) {
+    for TerminalPaste { terminal, text } in pastes.read() {
+        let Ok((pty, term_state, config)) = terminals.get(*terminal) else {
+            continue;
+        };
+        if let Err(error) = pty.paste(text, term_state.bracketed_paste(), &config.paste) {
+            error!("❌ Failed to write paste to PTY: {}", error);
+        }
+    }
+}
+
+/// Bytes to send for a paste of `text`.
+///
+/// Bracketed pastes are wrapped in `\x1b[200~`/`\x1b[201~`, with ESC and
+/// the C1 CSI (U+009B) removed from the text, as xterm and alacritty do, so
+/// no end marker can be spelled inside it to break out early. Otherwise the
+/// text is typed out with `paste_keystrokes`.
+pub fn paste_bytes(text: &str, bracketed: bool) -> Vec<u8> {
+    if bracketed {
+        let mut bytes = BRACKETED_PASTE_START.to_vec();
+        let body: String = text.chars().filter(|&c| c != '\x1b' && c != '\u{9b}').collect();
+        bytes.extend_from_slice(body.as_bytes());
+        bytes.extend_from_slice(BRACKETED_PASTE_END);
+        bytes
+    } else {
+        paste_keystrokes(text)
+    }
+}
+
+/// The keystrokes that type `text`, for programs without bracketed paste.
+///
+/// Line breaks become Enter (`\r`). Other control characters except Tab
+/// are dropped: typed, they would be commands (Ctrl+C, Escape starting a
+/// key sequence) rather than text.
+pub fn paste_keystrokes(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(character) = chars.next() {
+        match character {
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                bytes.push(b'\r');
+            }
+            '\n' => bytes.push(b'\r'),
+            '\t' => bytes.push(b'\t'),
+            c if c.is_control() => {}
+            c => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+    bytes
+}
+
+/// Write `bytes` to `writer` in `chunk_size` pieces, flushing each and
+/// pausing `chunk_delay` in between.
+///
+/// Runs on the writer thread, which takes nothing else until it returns.
+pub fn write_chunked(
+    writer: &mut impl Write,
+    bytes: &[u8],
+    chunk_size: usize,
+    chunk_delay: Duration,
+) -> std::io::Result<()> {
+    for (index, chunk) in bytes.chunks(chunk_size.max(1)).enumerate() {
+        if index > 0 && !chunk_delay.is_zero() {
+            thread::sleep(chunk_delay);
+        }
+
+        writer.write_all(chunk)?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+impl PtyResource {
+    /// Paste `text` into the PTY.
+    ///
+    /// Pastes above `config.threshold` are handed to the writer thread to
+    /// write in chunks; input sent afterwards, such as keys typed meanwhile
+    /// or another paste, follows the last chunk. Pass
+    /// `TerminalState::bracketed_paste()` as `bracketed`.
+    pub fn paste(&self, text: &str, bracketed: bool, config: &PasteConfig) -> std::io::Result<()> {
+        let bytes = paste_bytes(text, bracketed);
+
+        // Input queued earlier this frame goes first
+        self.flush_writes()?;
+
+        if bytes.len() <= config.threshold {
+            return self.write_all(&bytes);
+        }
+
+        info!("📋 Pasting {} bytes in {}-byte chunks", bytes.len(), config.chunk_size);
+        self.send(WriterMessage::Paste {
+            bytes,
+            chunk_size: config.chunk_size,
+            chunk_delay: config.chunk_delay,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paste_bytes() {
+        assert_eq!(paste_bytes("a\nb\r\nc", false), b"a\rb\rc");
+        // Typed out, control characters would be commands
+        assert_eq!(paste_bytes("\x1b[31mé\x03\tz\r", false), "[31mé\tz\r".as_bytes());
+        assert_eq!(paste_bytes("a\nb", true), b"\x1b[200~a\nb\x1b[201~");
+        // An embedded end marker can't terminate the paste early, even one
+        // that removing the marker would put back together, or sent as C1
+        assert_eq!(paste_bytes("x\x1b[201~rm", true), b"\x1b[200~x[201~rm\x1b[201~");
+        assert_eq!(paste_bytes("\x1b[20\x1b[201~1~", true), b"\x1b[200~[20[201~1~\x1b[201~");
+        assert_eq!(paste_bytes("a\u{9b}201~b", true), b"\x1b[200~a201~b\x1b[201~");
+    }
+
+    /// Writer keeping each write separately.
+    #[derive(Default)]
+    struct Writes(Vec<Vec<u8>>);
+
+    impl Write for Writes {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.push(buf.to_vec());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_chunked() {
+        let mut writer = Writes::default();
+
+        let bytes: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        write_chunked(&mut writer, &bytes, 1024, Duration::ZERO).unwrap();
+
+        let writes = writer.0;
+        assert_eq!(writes.concat(), bytes);
+        assert_eq!(writes.len(), 10);
+        assert!(writes.iter().all(|chunk| chunk.len() <= 1024));
+    }
+}
@@ -1,83 +1,260 @@
 //! PTY lifecycle management and polling.
 //!
-//! PTYs are spawned in Startup system and run persistently.
-//! Polling system runs in Update to read PTY output and feed to terminal.
+//! Each terminal entity gets its shell once spawned (`spawn_pty`), and it
+//! runs until the entity is despawned or the app exits. Polling systems run
+//! in Update to read PTY output and feed it to the terminal.
 //! Uses portable-pty for cross-platform PTY spawning.
 //!
-//! ## Current Architecture: Arc<Mutex<>> Shared State
+//! ## Architecture: Channel-Based I/O Threads
 //!
-//! Reader/writer are wrapped in Arc<Mutex<>> for thread-safe access from Bevy systems.
-//! This is a straightforward approach suitable for the MVP.
+//! - A reader thread owns the PTY reader, blocks on reads and sends output
+//!   over an mpsc channel that `poll_pty` drains each frame
+//! - A writer thread owns the PTY writer and writes whatever arrives on the
+//!   `PtyResource::writer` channel, in order
 //!
-//! ## Future Alternative: Channel-Based I/O Thread
-//!
-//! For improved decoupling, consider migrating to:
-//! - Dedicated I/O thread owning PTY reader (blocking reads)
-//! - mpsc::channel to send data to main Bevy loop
-//! - Bevy system receives data via mpsc::Receiver
-//!
-//! Benefits: No mutex contention, clearer separation of concerns.
+//! Systems never wait on PTY I/O or on each other: sending input is a
+//! channel push, even while a large paste or a stalled program holds up
+//! the writes themselves.
 //! See: docs/reviews/phase-1.1-pty-spawning.md (Gemini's recommendation)
 
 use anyhow::{Context, Result};
 use bevy::prelude::*;
 use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
 use std::io::{Read, Write};
-use std::sync::{mpsc::{channel, Receiver}, Arc, Mutex};
-use std::thread;
-use log::{info, error};
-
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc::{channel, Receiver, Sender}, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use log::{debug, info, error, trace, warn};
+
+use crate::config::TerminalConfig;
+use crate::paste::write_chunked;
+use crate::events::{TerminalEvent, TerminalEventKind};
+use crate::shell_integration::{self, BashRcfile};
+use crate::stats::TerminalStats;
 use crate::terminal::TerminalState;
 
-/// Resource holding PTY handles for the terminal.
+/// How long `shutdown()` waits for the reader thread before detaching it.
 ///
-/// The PTY runs persistently from Startup until app shutdown.
+/// The reader only wakes once the PTY reports EOF/error after the child dies,
+/// which is near-instant on Unix but can lag on Windows ConPTY.
+const READER_JOIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A respawned shell that stays up this long resets the respawn backoff.
+const RESPAWN_STABLE_AFTER: Duration = Duration::from_secs(10);
+
+/// Component holding the PTY handles of a terminal entity.
+///
+/// The PTY runs persistently until the entity is despawned (which kills the
+/// shell) or the app exits.
 /// Reader is handled in a background thread to avoid blocking the main loop.
-#[derive(Resource)]
+#[derive(Component)]
+#[require(RespawnBackoff, ThroughputSample)]
 pub struct PtyResource {
     /// Channel receiver for PTY output (filled by background thread)
-    pub rx: Arc<Mutex<Receiver<Vec<u8>>>>,
-    /// Writer for sending input to the PTY
-    pub writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    pub rx: Arc<Mutex<Receiver<ReaderMessage>>>,
+    /// Input for the writer thread, which owns the PTY writer
+    pub writer: Sender<WriterMessage>,
+    /// Background writer thread. Joined by `shutdown()` (or on drop).
+    pub writer_thread: Option<JoinHandle<()>>,
+    /// Input queued by `queue_write` until the next `flush_writes`
+    pending: Mutex<Vec<u8>>,
     /// The child process (shell)
     pub child: Box<dyn Child + Send + Sync>,
+    /// Background reader thread. Joined by `shutdown()` (or on drop).
+    pub reader_thread: Option<JoinHandle<()>>,
+    /// Tells the reader thread to stop forwarding output.
+    shutdown_flag: Arc<AtomicBool>,
     /// Master PTY handle - kept alive for Windows ConPTY compatibility
     /// On Windows, ConPTY requires the master handle to persist for the session.
     /// Wrapped in Arc<Mutex<>> for thread safety (Bevy requires Sync).
-    #[allow(dead_code)]
-    _master: Arc<Mutex<Box<dyn MasterPty + Send>>>,
+    /// Taken (and closed) during shutdown so ConPTY unblocks the reader.
+    master: Option<Arc<Mutex<Box<dyn MasterPty + Send>>>>,
+    /// Bash startup file for shell integration, deleted once the shell exits
+    rcfile: Option<BashRcfile>,
+    /// Reader thread failure not yet reported as a `TerminalEvent`
+    pub(crate) failure: Option<String>,
+    /// `TerminalEventKind::ProcessExited` was already sent for this child
+    pub(crate) exit_reported: bool,
 }
 
-/// Spawns a persistent PTY running the default shell.
+/// Spawns a persistent PTY for each new terminal.
+///
+/// System: Update
+/// Runs: Every frame, first; acts on terminals spawned since the last run
 ///
-/// System: Startup
-/// Runs: Once at application start
+/// Each PTY is set up from its terminal's `TerminalConfig`:
+/// - Size: `TerminalState`'s grid (120 cols × 30 rows by default)
+/// - Shell: `shell` and `shell_args`, or the user's `$SHELL` (PowerShell on
+///   Windows), with `env` added and in `cwd`
+/// - Background threads handle reading and writing
 ///
-/// Configuration (MVP hardcoded):
-/// - Size: 120 cols × 30 rows
-/// - Shell: bash (Linux) / powershell (Windows)
-/// - Background thread handles reading
-pub fn spawn_pty(mut commands: Commands) {
-    match PtyResource::new() {
+/// Terminals with `TerminalConfig::spawn_pty` off are left alone. Sends
+/// `TerminalEventKind::Spawned` once the shell is running; if it can't be
+/// spawned the error is sent as `TerminalEventKind::Error` and the terminal
+/// runs without one.
+#[allow(clippy::type_complexity)]
+pub fn spawn_pty(
+    mut commands: Commands,
+    terminals: Query<(Entity, &TerminalState, &TerminalConfig), (Added<TerminalState>, Without<PtyResource>)>,
+    mut events: MessageWriter<TerminalEvent>,
+) {
+    for (terminal, term_state, config) in &terminals {
+        if !config.spawn_pty {
+            continue;
+        }
+        match PtyResource::with_config(term_state.cols, term_state.rows, config) {
+            Ok(pty_resource) => {
+                info!("✅ PTY spawned successfully");
+                commands.entity(terminal).insert(pty_resource);
+                events.write(TerminalEvent::new(terminal, TerminalEventKind::Spawned));
+            }
+            Err(error) => {
+                error!("❌ Failed to spawn PTY: {:#}", error);
+                events.write(TerminalEvent::new(terminal, TerminalEventKind::Error {
+                    message: format!("Failed to spawn shell: {:#}", error),
+                }));
+            }
+        }
+    }
+}
+
+/// Replaces exited shells with fresh ones (`TerminalConfig::respawn`).
+///
+/// System: Update
+/// Runs: Every frame, after exits have been reported; acts on terminals
+/// with respawn on
+///
+/// Waits for `ProcessExited` to go out and the backoff delay to pass, then
+/// resets the grid and spawns a new PTY at the current size. The delay
+/// starts at `TerminalConfig::respawn_delay` and doubles for each shell that
+/// exits within 10 seconds of the last respawn. After
+/// `TerminalConfig::respawn_limit` such respawns in a row, or if spawning
+/// fails, the error is sent as `TerminalEventKind::Error` and the terminal
+/// stays without a shell.
+pub fn respawn_exited_shell(
+    mut commands: Commands,
+    mut terminals: Query<(Entity, &PtyResource, &mut TerminalState, &TerminalConfig, &mut RespawnBackoff)>,
+    mut events: MessageWriter<TerminalEvent>,
+) {
+    for (terminal, pty, mut term_state, config, mut backoff) in &mut terminals {
+        if config.respawn && pty.exit_reported {
+            respawn_shell(commands.entity(terminal), &mut term_state, config, &mut backoff, &mut events);
+        }
+    }
+}
+
+/// `respawn_exited_shell` for one terminal whose shell has exited.
+fn respawn_shell(
+    mut commands: EntityCommands,
+    term_state: &mut TerminalState,
+    config: &TerminalConfig,
+    backoff: &mut RespawnBackoff,
+    events: &mut MessageWriter<TerminalEvent>,
+) {
+    let terminal = commands.id();
+
+    let now = Instant::now();
+    let retry_at = match backoff.retry_at {
+        Some(retry_at) => retry_at,
+        None => {
+            // A shell that stayed up a while isn't part of a crash loop
+            if backoff.spawned_at.is_some_and(|spawned_at| now - spawned_at >= RESPAWN_STABLE_AFTER) {
+                backoff.attempts = 0;
+            }
+            if backoff.attempts >= config.respawn_limit {
+                error!("❌ Shell exited {} times in a row, not respawning", backoff.attempts);
+                commands.remove::<PtyResource>();
+                events.write(TerminalEvent::new(terminal, TerminalEventKind::Error {
+                    message: format!("Shell exited {} times in a row; giving up on respawning", backoff.attempts),
+                }));
+                return;
+            }
+            let delay = backoff_delay(config.respawn_delay, backoff.attempts);
+            backoff.attempts += 1;
+            info!("🔁 Respawning shell in {:?} (attempt {})", delay, backoff.attempts);
+            *backoff.retry_at.insert(now + delay)
+        }
+    };
+    if now < retry_at {
+        return;
+    }
+    backoff.retry_at = None;
+
+    term_state.reset();
+    // Replacing the component drops the old PTY, joining its threads
+    match PtyResource::with_config(term_state.cols, term_state.rows, config) {
         Ok(pty_resource) => {
-            info!("✅ PTY spawned successfully");
-            commands.insert_resource(pty_resource);
+            info!("✅ PTY respawned successfully");
+            backoff.spawned_at = Some(Instant::now());
+            commands.insert(pty_resource);
+            events.write(TerminalEvent::new(terminal, TerminalEventKind::Spawned));
         }
         Err(error) => {
-            error!("❌ Failed to spawn PTY: {:#}", error);
-            panic!("Cannot continue without PTY");
+            error!("❌ Failed to respawn PTY: {:#}", error);
+            commands.remove::<PtyResource>();
+            events.write(TerminalEvent::new(terminal, TerminalEventKind::Error {
+                message: format!("Failed to respawn shell: {:#}", error),
+            }));
+        }
+    }
+}
+
+/// Respawns so far and when the next one is due, for `respawn_exited_shell`.
+///
+/// Kept on the terminal entity next to its `PtyResource`, outliving each
+/// shell it replaces.
+#[derive(Component, Default)]
+pub struct RespawnBackoff {
+    /// Respawns since the last shell that stayed up
+    attempts: u32,
+    /// When the current shell was respawned
+    spawned_at: Option<Instant>,
+    /// When the pending respawn happens
+    retry_at: Option<Instant>,
+}
+
+/// Delay before respawn number `attempt + 1`: `base` doubled per attempt.
+fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(1 << attempt.min(16))
+}
+
+/// Sends the input queued this frame to each PTY in one write.
+///
+/// System: Update
+/// Runs: Every frame, after input handling, so keystrokes still reach the
+/// PTY in the frame they were pressed
+pub fn flush_pty_writes(ptys: Query<&PtyResource>) {
+    for pty in &ptys {
+        match pty.flush_writes() {
+            Ok(0) => {}
+            Ok(bytes) => trace!("⌨️  Flushed {} bytes to PTY", bytes),
+            Err(error) => error!("❌ Failed to write to PTY: {}", error),
         }
     }
 }
 
 impl PtyResource {
+    /// Spawns the shell on a 120×30 PTY.
     pub fn new() -> Result<Self> {
+        Self::with_size(120, 30)
+    }
+
+    /// Spawns the shell on a `cols × rows` PTY.
+    pub fn with_size(cols: usize, rows: usize) -> Result<Self> {
+        Self::with_config(cols, rows, &TerminalConfig::default())
+    }
+
+    /// Spawns `config.shell` (or the detected shell) on a `cols × rows` PTY,
+    /// with `config.env` added to its environment, in `config.cwd`.
+    pub fn with_config(cols: usize, rows: usize, config: &TerminalConfig) -> Result<Self> {
         info!("🔧 Initializing PTY system...");
         let pty_system = native_pty_system();
 
         let pty_size = PtySize {
-            rows: 30,
-            cols: 120,
+            rows: rows as u16,
+            cols: cols as u16,
             pixel_width: 0,
             pixel_height: 0,
         };
@@ -87,54 +264,30 @@ impl PtyResource {
             .openpty(pty_size)
             .context("Failed to create PTY pair")?;
 
-        // Spawn default shell with robust fallback chain
-        #[cfg(unix)]
-        let shell_cmd = std::env::var("SHELL").unwrap_or_else(|_| {
-            // Fallback chain: bash → zsh → sh
-            if std::path::Path::new("/bin/bash").exists() {
-                "/bin/bash".to_string()
-            } else if std::path::Path::new("/bin/zsh").exists() {
-                "/bin/zsh".to_string()
-            } else {
-                "/bin/sh".to_string()
-            }
-        });
-
-        #[cfg(windows)]
-        let shell_cmd = {
-            // Prefer PowerShell over cmd.exe for better ConPTY compatibility
-            if let Ok(comspec) = std::env::var("COMSPEC") {
-                if comspec.to_lowercase().contains("powershell") {
-                    comspec
-                } else {
-                    // COMSPEC is cmd.exe, but we prefer PowerShell
-                    "powershell.exe".to_string()
-                }
-            } else {
-                "powershell.exe".to_string()
-            }
-        };
-
+        let shell_cmd = config.shell.clone().unwrap_or_else(default_shell);
         info!("🐚 Spawning shell: {}", shell_cmd);
         let mut cmd = CommandBuilder::new(&shell_cmd);
 
-        // Windows shells need explicit flags to stay alive in interactive mode
-        #[cfg(windows)]
-        {
-            if shell_cmd.to_lowercase().contains("powershell") {
-                // PowerShell: -NoExit keeps the shell alive, -NoLogo reduces startup noise
-                cmd.arg("-NoExit");
-                cmd.arg("-NoLogo");
-            } else {
-                // cmd.exe: /K keeps the shell alive after executing startup commands
-                cmd.arg("/K");
-            }
+        let rcfile = if config.shell_integration {
+            shell_integration::inject_prompt_marks(&mut cmd, &shell_cmd)?
+        } else {
+            None
+        };
+        match config.shell {
+            Some(_) => cmd.args(&config.shell_args),
+            None => default_shell_args(&mut cmd, &shell_cmd),
         }
 
         cmd.env("TERM", "xterm-256color");
+        for (key, value) in &config.env {
+            cmd.env(key, value);
+        }
 
         // Explicitly set CWD to avoid issues with weird startup paths
-        if let Ok(cwd) = std::env::current_dir() {
+        if let Some(cwd) = &config.cwd {
+            info!("📂 Using CWD: {}", cwd.display());
+            cmd.cwd(cwd);
+        } else if let Ok(cwd) = std::env::current_dir() {
             info!("📂 Using CWD: {}", cwd.display());
             cmd.cwd(cwd);
         } else {
@@ -159,85 +312,500 @@ impl PtyResource {
         info!("🔧 Setting up I/O threads...");
         
         // Clone reader for background thread
-        let mut reader = pair
+        let reader = pair
             .master
             .try_clone_reader()
             .context("Failed to clone PTY reader")?;
 
-        // Take writer for main thread
+        // Writer goes to its own thread
         let writer = pair
             .master
             .take_writer()
             .context("Failed to take PTY writer")?;
+        let (writer, writer_thread) = spawn_writer_thread("PTY", writer);
 
         // Channel for sending data from thread to main loop
         let (tx, rx) = channel();
+        let shutdown_flag = Arc::new(AtomicBool::new(false));
 
         // Spawn background reader thread
         // This avoids blocking the main game loop, critical for Windows ConPTY
-        thread::spawn(move || {
+        let reader_thread = spawn_reader_thread("PTY", reader, tx, shutdown_flag.clone());
+
+        info!("✅ PTY initialized successfully: {}", shell_cmd);
+
+        // Keep master alive for Windows ConPTY compatibility
+        // On Windows, ConPTY requires the master handle to persist for the session,
+        // even after we've cloned the reader and taken the writer.
+        Ok(PtyResource {
+            rx: Arc::new(Mutex::new(rx)),
+            writer,
+            writer_thread: Some(writer_thread),
+            pending: Mutex::new(Vec::new()),
+            child,
+            reader_thread: Some(reader_thread),
+            shutdown_flag,
+            master: Some(Arc::new(Mutex::new(pair.master))),
+            rcfile,
+            failure: None,
+            exit_reported: false,
+        })
+    }
+
+    /// Send bytes to the PTY.
+    ///
+    /// Never blocks: the writer thread writes and flushes them after
+    /// everything sent before. Fails once that thread has stopped, after
+    /// `shutdown()` or a write error.
+    pub fn write_all(&self, bytes: &[u8]) -> std::io::Result<()> {
+        self.send(WriterMessage::Bytes(bytes.to_vec()))
+    }
+
+    /// Send `message` to the writer thread, behind everything sent before.
+    pub(crate) fn send(&self, message: WriterMessage) -> std::io::Result<()> {
+        self.writer
+            .send(message)
+            .map_err(|_| std::io::Error::other("PTY writer thread stopped"))
+    }
+
+    /// Queue bytes for the PTY, to go out with everything else queued this
+    /// frame in one write and flush (see `flush_pty_writes`).
+    pub fn queue_write(&self, bytes: &[u8]) {
+        match self.pending.lock() {
+            Ok(mut pending) => pending.extend_from_slice(bytes),
+            Err(_) => error!("❌ PTY write queue lock poisoned, dropping {} bytes", bytes.len()),
+        }
+    }
+
+    /// Write everything queued by `queue_write` now, in order, with a single
+    /// write and flush. Returns the number of bytes written.
+    pub fn flush_writes(&self) -> std::io::Result<usize> {
+        let bytes = std::mem::take(
+            &mut *self
+                .pending
+                .lock()
+                .map_err(|_| std::io::Error::other("PTY write queue lock poisoned"))?,
+        );
+        if !bytes.is_empty() {
+            self.write_all(&bytes)?;
+        }
+        Ok(bytes.len())
+    }
+
+    /// Process ID of the shell, if the platform reports one.
+    pub fn pid(&self) -> Option<u32> {
+        self.child.process_id()
+    }
+
+    /// Exit code of the shell if it has exited, without blocking.
+    ///
+    /// `Some(None)` when it exited but the status couldn't be read.
+    pub fn try_exit_code(&mut self) -> Option<Option<i32>> {
+        match self.child.try_wait() {
+            Ok(Some(status)) => Some(Some(status.exit_code() as i32)),
+            Ok(None) => None,
+            Err(error) => {
+                warn!("⚠️  Failed to poll PTY child: {}", error);
+                Some(None)
+            }
+        }
+    }
+
+    /// PTY window size (cols, rows) as the kernel/ConPTY reports it.
+    ///
+    /// `None` after `shutdown()` or if the size can't be read.
+    pub fn size(&self) -> Option<(usize, usize)> {
+        let master = self.master.as_ref()?.lock().ok()?;
+        let size = master.get_size().ok()?;
+        Some((size.cols as usize, size.rows as usize))
+    }
+
+    /// Tell the PTY (and so the shell, via SIGWINCH) the window is now
+    /// `cols × rows`.
+    pub fn resize(&self, cols: usize, rows: usize) -> Result<()> {
+        let master = self.master.as_ref().context("PTY is shut down")?;
+        let master = master.lock().map_err(|_| anyhow::anyhow!("PTY master lock poisoned"))?;
+        master
+            .resize(PtySize {
+                rows: rows as u16,
+                cols: cols as u16,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .context("Failed to resize PTY")
+    }
+
+    /// Kills the child process and joins the reader and writer threads.
+    ///
+    /// Gives deterministic teardown instead of leaking the reader thread.
+    /// Idempotent: later calls (including the one from `Drop`) are no-ops.
+    pub fn shutdown(&mut self) {
+        let Some(handle) = self.reader_thread.take() else {
+            return;
+        };
+        let writer_thread = self.writer_thread.take();
+
+        info!("🛑 Shutting down PTY...");
+        self.shutdown_flag.store(true, Ordering::Release);
+
+        // Killing the shell closes the slave side, which unblocks the reader with EOF
+        if let Ok(None) = self.child.try_wait() {
+            if let Err(error) = self.child.kill() {
+                warn!("⚠️  Failed to kill PTY child: {}", error);
+            }
+        }
+        if let Err(error) = self.child.wait() {
+            warn!("⚠️  Failed to reap PTY child: {}", error);
+        }
+        self.rcfile.take();
+
+        // ConPTY only signals EOF once the pseudoconsole itself is closed
+        self.master.take();
+
+        join_thread("PTY reader", handle);
+
+        // Dropping the sender ends the writer once the queue drains
+        self.writer = channel().0;
+        if let Some(writer_thread) = writer_thread {
+            join_thread("PTY writer", writer_thread);
+        }
+    }
+}
+
+impl Drop for PtyResource {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// The user's shell, with a robust fallback chain.
+fn default_shell() -> String {
+    #[cfg(unix)]
+    let shell_cmd = std::env::var("SHELL").unwrap_or_else(|_| {
+        // Fallback chain: bash → zsh → sh
+        if std::path::Path::new("/bin/bash").exists() {
+            "/bin/bash".to_string()
+        } else if std::path::Path::new("/bin/zsh").exists() {
+            "/bin/zsh".to_string()
+        } else {
+            "/bin/sh".to_string()
+        }
+    });
+
+    #[cfg(windows)]
+    let shell_cmd = {
+        // Prefer PowerShell over cmd.exe for better ConPTY compatibility
+        if let Ok(comspec) = std::env::var("COMSPEC") {
+            if comspec.to_lowercase().contains("powershell") {
+                comspec
+            } else {
+                // COMSPEC is cmd.exe, but we prefer PowerShell
+                "powershell.exe".to_string()
+            }
+        } else {
+            "powershell.exe".to_string()
+        }
+    };
+
+    shell_cmd
+}
+
+/// Flags the detected shell needs to run interactively.
+#[cfg_attr(unix, allow(unused_variables))]
+fn default_shell_args(cmd: &mut CommandBuilder, shell_cmd: &str) {
+    // Windows shells need explicit flags to stay alive in interactive mode
+    #[cfg(windows)]
+    {
+        if shell_cmd.to_lowercase().contains("powershell") {
+            // PowerShell: -NoExit keeps the shell alive, -NoLogo reduces startup noise
+            cmd.arg("-NoExit");
+            cmd.arg("-NoLogo");
+        } else {
+            // cmd.exe: /K keeps the shell alive after executing startup commands
+            cmd.arg("/K");
+        }
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl PtyResource {
+    /// How long the channel must stay empty before `read_available` returns.
+    const QUIET_PERIOD: Duration = Duration::from_millis(50);
+
+    /// Block until output arrives (up to `timeout`), then drain it.
+    ///
+    /// Keeps collecting while chunks arrive within a short quiet period, so a
+    /// burst of output (banner + prompt) comes back together. Returns an
+    /// empty Vec if nothing arrived in time. Test helper: blocks the caller.
+    pub fn read_available(&self, timeout: Duration) -> Vec<u8> {
+        let deadline = Instant::now() + timeout;
+        let mut output = Vec::new();
+        let Ok(rx) = self.rx.lock() else {
+            return output;
+        };
+
+        let mut wait = timeout;
+        while let Ok(message) = rx.recv_timeout(wait) {
+            if let ReaderMessage::Output(bytes) = message {
+                output.extend_from_slice(&bytes);
+            }
+            wait = deadline
+                .saturating_duration_since(Instant::now())
+                .min(Self::QUIET_PERIOD);
+            if wait.is_zero() {
+                break;
+            }
+        }
+        output
+    }
+}
+
+/// Message from the main loop to a writer thread.
+#[derive(Debug)]
+pub enum WriterMessage {
+    /// Bytes written and flushed at once
+    Bytes(Vec<u8>),
+    /// A large paste, written in `chunk_size` pieces with `chunk_delay`
+    /// pauses in between (see `write_chunked`). Messages sent after it wait
+    /// for its last chunk.
+    Paste {
+        bytes: Vec<u8>,
+        chunk_size: usize,
+        chunk_delay: Duration,
+    },
+}
+
+/// Message from a reader thread to the main loop.
+#[derive(Debug)]
+pub enum ReaderMessage {
+    /// Bytes read from the PTY/source
+    Output(Vec<u8>),
+    /// The reader thread panicked; no more output will arrive
+    Failed(String),
+}
+
+/// Spawns a background thread forwarding everything `reader` produces over `tx`.
+///
+/// Blocking reads stay off the main loop. The thread exits on EOF, a read
+/// error, a dropped receiver, or once `shutdown_flag` is set. A panic is
+/// caught and reported as `ReaderMessage::Failed` so the terminal doesn't
+/// just go quiet.
+pub(crate) fn spawn_reader_thread(
+    label: &'static str,
+    mut reader: impl Read + Send + 'static,
+    tx: Sender<ReaderMessage>,
+    shutdown_flag: Arc<AtomicBool>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
             let mut buf = [0u8; 4096];
             loop {
+                if shutdown_flag.load(Ordering::Acquire) {
+                    break;
+                }
                 match reader.read(&mut buf) {
                     Ok(0) => {
                         // EOF
-                        eprintln!("🔚 PTY reader: EOF received");
+                        debug!("🔚 {} reader: EOF received", label);
                         break;
                     }
                     Ok(n) => {
-                        // Debug: log what we read
-                        eprintln!("📖 PTY reader: Read {} bytes", n);
+                        trace!("📖 {} reader: Read {} bytes", label, n);
                         // Send data to main thread
-                        if tx.send(buf[..n].to_vec()).is_err() {
+                        if tx.send(ReaderMessage::Output(buf[..n].to_vec())).is_err() {
                             // Receiver dropped, app probably closing
-                            eprintln!("❌ PTY reader: Channel send failed");
+                            debug!("❌ {} reader: Channel send failed", label);
                             break;
                         }
                     }
                     Err(e) => {
                         // Read error
-                        eprintln!("❌ PTY reader: Read error: {}", e);
+                        debug!("❌ {} reader: Read error: {}", label, e);
                         break;
                     }
                 }
             }
-            eprintln!("🛑 PTY reader thread exiting");
-        });
+        }));
+
+        if let Err(payload) = result {
+            let message = match payload.downcast_ref::<&str>() {
+                Some(message) => message.to_string(),
+                None => payload
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .unwrap_or_else(|| "unknown panic".to_string()),
+            };
+            error!("💥 {} reader thread panicked: {}", label, message);
+            let _ = tx.send(ReaderMessage::Failed(format!("{} reader thread panicked: {}", label, message)));
+        }
+        trace!("🛑 {} reader thread exiting", label);
+    })
+}
 
-        info!("✅ PTY initialized successfully: {}", shell_cmd);
+/// Spawns a background thread writing everything sent over the returned
+/// channel to `writer`, in order, flushing after each message (each chunk
+/// of a paste).
+///
+/// The thread exits once every sender is dropped or a write fails; later
+/// sends then fail. Pub so tests can record what reaches the PTY.
+pub fn spawn_writer_thread(
+    label: &'static str,
+    mut writer: impl Write + Send + 'static,
+) -> (Sender<WriterMessage>, JoinHandle<()>) {
+    let (tx, rx) = channel::<WriterMessage>();
+    let handle = thread::spawn(move || {
+        for message in rx {
+            let written = match message {
+                WriterMessage::Bytes(bytes) => writer.write_all(&bytes).and_then(|()| writer.flush()),
+                WriterMessage::Paste { bytes, chunk_size, chunk_delay } => {
+                    write_chunked(&mut writer, &bytes, chunk_size, chunk_delay)
+                }
+            };
+            if let Err(error) = written {
+                error!("❌ {} writer: Write error: {}", label, error);
+                break;
+            }
+        }
+        trace!("🛑 {} writer thread exiting", label);
+    });
+    (tx, handle)
+}
 
-        // Keep master alive for Windows ConPTY compatibility
-        // On Windows, ConPTY requires the master handle to persist for the session,
-        // even after we've cloned the reader and taken the writer.
-        Ok(PtyResource {
-            rx: Arc::new(Mutex::new(rx)),
-            writer: Arc::new(Mutex::new(Box::new(writer))),
-            child,
-            _master: Arc::new(Mutex::new(pair.master)),
-        })
+/// Joins a reader or writer thread, detaching it if it is still blocked
+/// after `READER_JOIN_TIMEOUT`. `label` names the thread in logs
+/// ("PTY reader").
+pub(crate) fn join_thread(label: &str, handle: JoinHandle<()>) {
+    let deadline = Instant::now() + READER_JOIN_TIMEOUT;
+    while !handle.is_finished() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(5));
+    }
+
+    if handle.is_finished() {
+        if handle.join().is_err() {
+            error!("❌ {} thread panicked", label);
+        }
+    } else {
+        warn!(
+            "⚠️  {} thread still blocked after {}ms, detaching",
+            label,
+            READER_JOIN_TIMEOUT.as_millis()
+        );
+    }
+}
+
+/// Run condition limiting polling to `TerminalConfig::poll_interval`.
+///
+/// Always true when no interval is configured. Reads the plugin's config
+/// resource: all terminals are polled together. Uses real time so polling
+/// continues while virtual time is paused.
+pub fn poll_interval_elapsed(
+    config: Option<Res<TerminalConfig>>,
+    time: Res<Time<Real>>,
+    mut last_poll: Local<Option<Duration>>,
+) -> bool {
+    let Some(interval) = config.and_then(|config| config.poll_interval) else {
+        return true;
+    };
+
+    let now = time.elapsed();
+    match *last_poll {
+        Some(last) if now.saturating_sub(last) < interval => false,
+        _ => {
+            *last_poll = Some(now);
+            true
+        }
+    }
+}
+
+/// Feeds everything the reader thread delivered into `term_state`, at most
+/// `budget` bytes of it parsed now, and returns the bytes read.
+fn drain_pty_output(pty: &mut PtyResource, term_state: &mut TerminalState, budget: Option<usize>) -> usize {
+    let mut read = 0;
+    if let Ok(rx) = pty.rx.try_lock() {
+        // Read all available chunks
+        while let Ok(message) = rx.try_recv() {
+            match message {
+                ReaderMessage::Output(bytes) => {
+                    read += bytes.len();
+                    term_state.feed_bytes(&bytes, budget.is_some());
+                }
+                ReaderMessage::Failed(reason) => {
+                    error!("❌ {}", reason);
+                    pty.failure = Some(reason);
+                }
+            }
+        }
     }
+
+    if let Some(budget) = budget {
+        term_state.process_pending(budget);
+    }
+    read
 }
 
-/// Polls the PTY channel for output and feeds bytes to the terminal.
+/// Polls each PTY channel for output and feeds bytes to its terminal.
 ///
 /// System: Update
 /// Runs: Every frame
 ///
 /// Drains the channel of any data read by the background thread.
-/// This is non-blocking and safe for the main loop.
-pub fn poll_pty(pty: Res<PtyResource>, mut term_state: ResMut<TerminalState>) {
-    if let Ok(rx) = pty.rx.try_lock() {
-        // Read all available chunks
-        while let Ok(bytes) = rx.try_recv() {
-            term_state.process_bytes(&bytes);
+/// This is non-blocking and safe for the main loop. A reader failure is kept
+/// for `report_reader_failures`. With `TerminalConfig::max_bytes_per_frame`
+/// set, output beyond the budget is queued for later frames.
+///
+/// Also records the bytes read in the terminal's
+/// `TerminalStats::bytes_per_second`, measured between polls so a poll
+/// interval doesn't skew it.
+pub fn poll_pty(
+    mut terminals: Query<(
+        &mut PtyResource,
+        &mut TerminalState,
+        &TerminalConfig,
+        Option<&mut TerminalStats>,
+        &mut ThroughputSample,
+    )>,
+    time: Res<Time<Real>>,
+) {
+    for (mut pty, mut term_state, config, stats, mut throughput) in &mut terminals {
+        let read = drain_pty_output(&mut pty, &mut term_state, config.max_bytes_per_frame);
+
+        let Some(mut stats) = stats else {
+            continue;
+        };
+        // Bytes from a poll with no measurable interval roll into the next one
+        let now = time.elapsed();
+        throughput.bytes += read;
+        let elapsed = throughput.since.map_or(Duration::ZERO, |since| now.saturating_sub(since));
+        if !elapsed.is_zero() {
+            stats.record_output(std::mem::take(&mut throughput.bytes), elapsed);
+            throughput.since = Some(now);
         }
+        throughput.since.get_or_insert(now);
     }
 }
 
+/// Bytes read since `since`, not yet in `TerminalStats`.
+#[derive(Component, Default)]
+pub struct ThroughputSample {
+    since: Option<Duration>,
+    bytes: usize,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_backoff_delay_doubles() {
+        let base = Duration::from_millis(250);
+        assert_eq!(backoff_delay(base, 0), base);
+        assert_eq!(backoff_delay(base, 1), Duration::from_millis(500));
+        assert_eq!(backoff_delay(base, 3), Duration::from_secs(2));
+        // Capped exponent, no overflow
+        assert_eq!(backoff_delay(base, 40), backoff_delay(base, 16));
+        assert_eq!(backoff_delay(Duration::MAX, 3), Duration::MAX);
+    }
+
     #[test]
     fn test_pty_spawns() {
         let result = PtyResource::new();
@@ -245,12 +813,24 @@ mod tests {
 
         let pty = result.unwrap();
 
-        // Verify we can lock reader/writer
+        // Verify we can lock the reader channel; the writer has its own thread
         assert!(pty.rx.lock().is_ok(), "Should be able to lock rx");
-        assert!(pty.writer.lock().is_ok(), "Should be able to lock writer");
+        assert!(pty.writer_thread.is_some(), "Writer thread should be running");
 
         // Child process should be running
         // Note: We don't check child status here as it might complete quickly
+        drop(pty);
+    }
+
+    #[test]
+    fn test_pty_pid() {
+        let pty = PtyResource::new().expect("PTY spawn failed");
+
+        let pid = pty.pid().expect("Spawned shell should report a PID");
+        assert_ne!(pid, 0);
+        assert_ne!(pid, std::process::id(), "PID should be the child's, not ours");
+
+        drop(pty);
     }
 
     #[test]
@@ -258,36 +838,22 @@ mod tests {
         let pty = PtyResource::new().expect("PTY spawn failed");
 
         // Write a command
-        {
-            let mut writer = pty.writer.lock().unwrap();
-            writer.write_all(b"echo test\n").expect("Write failed");
-            writer.flush().expect("Flush failed");
-        }
+        pty.write_all(b"echo test\n").expect("Write failed");
 
         // Poll for output with timeout
-        let timeout = std::time::Duration::from_secs(2);
-        let start = std::time::Instant::now();
+        let timeout = Duration::from_secs(2);
+        let start = Instant::now();
+        let mut output = String::new();
 
-        loop {
+        // Look for our echoed command or output
+        while !output.contains("test") {
             if start.elapsed() > timeout {
                 panic!("Timeout waiting for PTY output after {}ms", timeout.as_millis());
             }
-
-            let rx = pty.rx.lock().unwrap();
-            if let Ok(bytes) = rx.try_recv() {
-                let output = String::from_utf8_lossy(&bytes);
-
-                // Look for our echoed command or output
-                if output.contains("test") {
-                    // Success! We got output from the shell
-                    return;
-                }
-            }
-            drop(rx); // Unlock
-
-            // Brief sleep to avoid busy-waiting
-            std::thread::sleep(std::time::Duration::from_millis(10));
+            output.push_str(&String::from_utf8_lossy(&pty.read_available(Duration::from_millis(100))));
         }
+
+        drop(pty);
     }
 
     #[test]
@@ -295,11 +861,7 @@ mod tests {
         let mut pty = PtyResource::new().expect("PTY spawn failed");
 
         // Write exit command
-        {
-            let mut writer = pty.writer.lock().unwrap();
-            writer.write_all(b"exit\n").expect("Write failed");
-            writer.flush().expect("Flush failed");
-        }
+        pty.write_all(b"exit\n").expect("Write failed");
 
         // Poll for child exit with timeout
         let timeout = std::time::Duration::from_secs(2);
@@ -326,5 +888,57 @@ mod tests {
         };
 
         assert!(final_status.success(), "Child process should have exited successfully");
+        drop(pty);
+    }
+
+    #[test]
+    fn test_pty_shutdown_joins_reader() {
+        let mut pty = PtyResource::new().expect("PTY spawn failed");
+        assert!(pty.reader_thread.is_some(), "Reader thread should be running");
+
+        pty.shutdown();
+
+        assert!(pty.reader_thread.is_none(), "Reader thread should be joined");
+        assert!(pty.writer_thread.is_none(), "Writer thread should be joined");
+        assert!(pty.write_all(b"late").is_err(), "Writes after shutdown should fail");
+        assert!(
+            pty.child.try_wait().expect("try_wait failed").is_some(),
+            "Child should be reaped after shutdown"
+        );
+
+        // Second call is a no-op
+        pty.shutdown();
+    }
+
+    /// Writer recording everything written to it.
+    struct SharedWriter(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_writer_thread_keeps_order() {
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let (writer, handle) = spawn_writer_thread("test", SharedWriter(written.clone()));
+
+        // Hammer the channel faster than the thread can possibly keep up
+        let mut expected = Vec::new();
+        for i in 0..10_000 {
+            let bytes = format!("{},", i).into_bytes();
+            expected.extend_from_slice(&bytes);
+            writer.send(WriterMessage::Bytes(bytes)).expect("Writer thread stopped early");
+        }
+
+        drop(writer);
+        handle.join().expect("Writer thread panicked");
+        assert_eq!(*written.lock().unwrap(), expected);
     }
 }
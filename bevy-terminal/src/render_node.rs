@@ -1,25 +1,65 @@
 use bevy::{
+    platform::collections::{HashMap, HashSet},
     prelude::*,
     render::{
-        extract_resource::{ExtractResource, ExtractResourcePlugin},
         render_asset::RenderAssets,
-        render_graph::{self, Node, NodeRunError, RenderGraph, RenderGraphContext, RenderLabel},
+        render_graph::{Node, NodeRunError, RenderGraph, RenderGraphContext, RenderLabel},
         render_resource::*,
-        renderer::{RenderContext, RenderDevice, RenderQueue},
+        renderer::{RenderAdapter, RenderContext, RenderDevice, RenderQueue},
         texture::GpuImage,
-        Render, RenderApp, RenderSet,
+        Extract, ExtractSchedule, Render, RenderApp, RenderSystems,
     },
 };
+use bytemuck::Zeroable;
 use std::borrow::Cow;
-use crate::gpu_types::{GpuTerminalCell, TerminalUniforms};
-use crate::gpu_prep::TerminalCpuBuffer;
-use crate::renderer::TerminalTexture;
-use crate::atlas::GlyphAtlas;
-use crate::terminal::TERMINAL_SHADER_HANDLE;
+use log::{error, info, warn};
+use crate::config::{RenderBackend, StatusBarPosition, TerminalConfig, TerminalTheme};
+use crate::gpu_types::{
+    GpuTerminalCell, TerminalUniforms, STATUS_BAR_BOTTOM, STATUS_BAR_NONE, STATUS_BAR_TOP,
+};
+use crate::gpu_prep::{pack_color, pack_color_alpha, TerminalCpuBuffer};
+use crate::fragment::{self, TerminalFragmentPipeline, TerminalFragmentResources};
+use crate::renderer::{
+    compute_renderer_unsupported, render_format_supported, report_render_unsupported,
+    storage_format_supported, texture_format_shader_def, ActiveRenderBackend, TerminalRenderUnsupported, TerminalTexture, TerminalTextureFormat,
+    SUPPORTED_TEXTURE_FORMATS, TERMINAL_TEXTURE_FORMAT,
+};
+use crate::atlas::{AtlasRegion, AtlasUploads, GlyphAtlas};
+use crate::clock::TerminalClock;
+use crate::software;
+use crate::terminal::{TerminalState, TERMINAL_SHADER_HANDLE};
 
-#[derive(Resource, ExtractResource, Clone)]
+/// A terminal's render data for this frame, on its entity.
+#[derive(Component, Clone)]
 pub struct ExtractedTerminalData {
     pub cells: Vec<GpuTerminalCell>,
+    pub status_cells: Vec<GpuTerminalCell>,
+    pub status_bar: u32,
+    /// Watermark glyphs (`TerminalCpuBuffer::watermark_cells`)
+    pub watermark_cells: Vec<GpuTerminalCell>,
+    pub watermark_col: u32,
+    pub watermark_row: u32,
+    pub watermark_opacity: f32,
+    /// `TerminalClock::blink_phase` this frame
+    pub blink_phase: f32,
+    /// `CursorBlinkStyle::uniform`
+    pub cursor_blink: u32,
+    /// `TerminalState::cursor_color`, packed
+    pub cursor_color: u32,
+    /// `CursorStyle::uniform`
+    pub cursor_style: u32,
+    /// `TerminalTheme` selection color and opacity, packed
+    pub selection_color: u32,
+    /// `TerminalTheme::cursor_alpha`
+    pub cursor_alpha: f32,
+    /// Grid is all default-background spaces (see `TerminalCpuBuffer::blank`)
+    pub blank: bool,
+    /// Cell index of the drawn cursor (`TerminalCpuBuffer::cursor_cell`)
+    pub cursor_cell: Option<u32>,
+    /// Clear color for a blank grid (`TerminalCpuBuffer::background`)
+    pub background: [u8; 3],
+    /// Redraw this frame regardless of `blank` (`TerminalState::mark_dirty`)
+    pub force_redraw: bool,
     pub texture_handle: Handle<Image>,
     pub atlas_texture_handle: Handle<Image>,
     pub term_cols: u32,
@@ -30,69 +70,426 @@ pub struct ExtractedTerminalData {
     pub atlas_rows: u32,
 }
 
-#[derive(Resource)]
 pub struct TerminalGpuResources {
     pub cell_buffer: Buffer,
+    pub status_buffer: Buffer,
+    pub watermark_buffer: Buffer,
     pub uniform_buffer: Buffer,
     pub bind_group: BindGroup,
+    /// `ExtractedTerminalData::buffer_sizes` the buffers were allocated for
+    pub sizes: [u64; 3],
+    /// Output and atlas texture views the bind group points at
+    pub views: [TextureViewId; 2],
+}
+
+/// How the render node updates a terminal texture this frame.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TerminalDispatch {
+    /// Run the per-pixel glyph compute shader.
+    #[default]
+    Full,
+    /// Grid just became blank: clear the texture to the background color,
+    /// then draw the cursor cell (if any) on its own.
+    Clear,
+    /// Grid was already blank, without a cursor, and the texture already
+    /// cleared: do nothing.
+    Skip,
+}
+
+impl TerminalDispatch {
+    /// Pick the cheapest update that keeps the texture correct.
+    ///
+    /// The clear fast path only runs on the transition into a blank grid, and
+    /// needs a texture that can be a render attachment.
+    pub fn select(blank: bool, was_blank: bool, can_clear: bool) -> Self {
+        match (blank, was_blank, can_clear) {
+            (false, _, _) | (true, _, false) => Self::Full,
+            (true, false, true) => Self::Clear,
+            (true, true, true) => Self::Skip,
+        }
+    }
+}
+
+/// Render-world state of one terminal: this frame's data, its GPU buffers
+/// (for whichever backend is active) and how to update its texture.
+pub struct RenderTerminal {
+    pub data: ExtractedTerminalData,
+    pub gpu: Option<TerminalGpuResources>,
+    pub fragment: Option<TerminalFragmentResources>,
+    pub dispatch: TerminalDispatch,
+    /// The texture was cleared for a blank grid and still shows it
+    pub was_blank: bool,
 }
 
+impl RenderTerminal {
+    /// A terminal seen for the first time, with no GPU buffers yet.
+    pub fn new(data: ExtractedTerminalData) -> Self {
+        Self {
+            data,
+            gpu: None,
+            fragment: None,
+            dispatch: TerminalDispatch::default(),
+            was_blank: false,
+        }
+    }
+
+    /// Pick this frame's `TerminalDispatch` for an output texture that can
+    /// (or can't) be cleared as a render attachment.
+    pub fn select_dispatch(&mut self, can_clear: bool) {
+        // A forced redraw treats the texture as stale, so a blank grid clears
+        // again. So does a cursor: it blinks and moves, and the clear erases
+        // last frame's before it's drawn again.
+        let cursor = self.data.cursor_cell.is_some();
+        let was_blank = self.was_blank && !self.data.force_redraw && !cursor;
+        self.dispatch = TerminalDispatch::select(self.data.blank, was_blank, can_clear);
+        self.was_blank = self.data.blank && can_clear && !cursor;
+    }
+
+    /// Uniforms for this frame's dispatch. After a clear, only the cursor
+    /// cell is drawn, so its pixels start at the cell's corner.
+    pub fn uniforms(&self) -> TerminalUniforms {
+        let mut uniforms = self.data.uniforms();
+        if let (TerminalDispatch::Clear, Some(cursor)) = (self.dispatch, self.data.cursor_cell) {
+            let cols = self.data.term_cols.max(1);
+            uniforms.origin_x = cursor % cols * self.data.cell_width;
+            uniforms.origin_y = cursor / cols * self.data.cell_height;
+        }
+        uniforms
+    }
+}
+
+/// Every terminal the render world draws, by terminal entity.
+#[derive(Resource, Default)]
+pub struct RenderTerminals(pub HashMap<Entity, RenderTerminal>);
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
 pub struct TerminalComputeLabel;
 
-pub struct TerminalComputePlugin;
+/// Render-world half of the terminal: extraction, GPU buffers, render node.
+#[derive(Default)]
+pub struct TerminalComputePlugin {
+    /// Requested output format; falls back to `TERMINAL_TEXTURE_FORMAT` when
+    /// the device can't write (or, for the fragment backend, render) it.
+    pub texture_format: Option<TextureFormat>,
+    /// Requested backend; `Auto` picks compute when the device supports it.
+    pub backend: RenderBackend,
+}
 
 impl Plugin for TerminalComputePlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(ExtractResourcePlugin::<ExtractedTerminalData>::default());
-        app.add_systems(PostUpdate, update_extraction_resource);
+        app.add_systems(PostUpdate, update_terminal_extraction);
+        if self.backend == RenderBackend::Cpu {
+            app.add_systems(PostUpdate, software::render_terminal_to_texture.after(update_terminal_extraction));
+        }
     }
 
     fn finish(&self, app: &mut App) {
+        // Drawn in the main world; the render app only uploads the image
+        if self.backend == RenderBackend::Cpu {
+            let requested = self.texture_format.unwrap_or(TERMINAL_TEXTURE_FORMAT);
+            let format = if SUPPORTED_TEXTURE_FORMATS.contains(&requested) {
+                requested
+            } else {
+                error!("❌ Texture format {:?} can't be drawn by the CPU renderer, using {:?}", requested, TERMINAL_TEXTURE_FORMAT);
+                TERMINAL_TEXTURE_FORMAT
+            };
+            app.insert_resource(TerminalTextureFormat(format))
+                .insert_resource(ActiveRenderBackend(RenderBackend::Cpu));
+            return;
+        }
+
         let render_app = app.sub_app_mut(RenderApp);
-        render_app
-            .init_resource::<TerminalComputePipeline>()
-            .add_systems(
-                Render,
-                (
-                    prepare_gpu_resources.in_set(RenderSet::Prepare),
-                ),
+
+        let world = render_app.world();
+        let adapter = world.resource::<RenderAdapter>();
+        let device = world.resource::<RenderDevice>();
+
+        let requested = self.texture_format.unwrap_or(TERMINAL_TEXTURE_FORMAT);
+        let backend = match (self.backend, compute_renderer_unsupported(adapter, device)) {
+            (RenderBackend::Fragment, _) => RenderBackend::Fragment,
+            (RenderBackend::Cpu, _) => unreachable!("the CPU backend returned above"),
+            (RenderBackend::Auto, None) => RenderBackend::Compute,
+            (RenderBackend::Auto, Some(reason)) => {
+                info!("🔁 Terminal compute renderer unavailable ({}), using the fragment renderer", reason);
+                RenderBackend::Fragment
+            }
+            (RenderBackend::Compute, None) => RenderBackend::Compute,
+            (RenderBackend::Compute, Some(reason)) => {
+                error!("❌ Terminal compute renderer unavailable: {}", reason);
+                render_app.insert_resource(TerminalTextureFormat(TERMINAL_TEXTURE_FORMAT));
+                app.insert_resource(TerminalTextureFormat(TERMINAL_TEXTURE_FORMAT))
+                    .insert_resource(ActiveRenderBackend(RenderBackend::Compute))
+                    .insert_resource(TerminalRenderUnsupported { reason })
+                    .add_systems(Startup, report_render_unsupported);
+                return;
+            }
+        };
+
+        let format = if backend == RenderBackend::Fragment {
+            if render_format_supported(requested, adapter) {
+                requested
+            } else {
+                error!(
+                    "❌ Texture format {:?} can't be a blended render target on this device, using {:?}",
+                    requested, TERMINAL_TEXTURE_FORMAT
+                );
+                TERMINAL_TEXTURE_FORMAT
+            }
+        } else if storage_format_supported(requested, adapter, device) {
+            requested
+        } else {
+            error!(
+                "❌ Texture format {:?} can't be a storage texture on this device, using {:?}",
+                requested, TERMINAL_TEXTURE_FORMAT
             );
-            
+            TERMINAL_TEXTURE_FORMAT
+        };
+
+        render_app
+            .insert_resource(TerminalTextureFormat(format))
+            .insert_resource(ActiveRenderBackend(backend))
+            .init_resource::<RenderTerminals>()
+            .init_resource::<ExtractedAtlasUploads>()
+            .add_systems(ExtractSchedule, (extract_terminals, extract_atlas_uploads))
+            .add_systems(Render, write_atlas_uploads.in_set(RenderSystems::Prepare));
+        if backend == RenderBackend::Fragment {
+            render_app
+                .init_resource::<TerminalFragmentPipeline>()
+                .add_systems(
+                    Render,
+                    fragment::prepare_fragment_resources.in_set(RenderSystems::Prepare),
+                );
+        } else {
+            render_app
+                .init_resource::<TerminalComputePipeline>()
+                .add_systems(
+                    Render,
+                    (
+                        prepare_gpu_resources.in_set(RenderSystems::Prepare),
+                    ),
+                );
+        }
+
         // Add node to graph
         let mut render_graph = render_app.world_mut().resource_mut::<RenderGraph>();
         render_graph.add_node(TerminalComputeLabel, TerminalNode);
         render_graph.add_node_edge(TerminalComputeLabel, bevy::render::graph::CameraDriverLabel);
+
+        // The main world creates the texture in this format at Startup
+        app.insert_resource(TerminalTextureFormat(format))
+            .insert_resource(ActiveRenderBackend(backend));
     }
 }
 
-fn update_extraction_resource(
+impl ExtractedTerminalData {
+    /// Shader uniforms for this frame.
+    pub fn uniforms(&self) -> TerminalUniforms {
+        TerminalUniforms {
+            term_cols: self.term_cols,
+            term_rows: self.term_rows,
+            cell_width: self.cell_width,
+            cell_height: self.cell_height,
+            atlas_cols: self.atlas_cols,
+            atlas_rows: self.atlas_rows,
+            status_bar: self.status_bar,
+            watermark_col: self.watermark_col,
+            watermark_row: self.watermark_row,
+            watermark_len: self.watermark_cells.len() as u32,
+            watermark_opacity: self.watermark_opacity,
+            blink_phase: self.blink_phase,
+            cursor_blink: self.cursor_blink,
+            cursor_color: self.cursor_color,
+            cursor_style: self.cursor_style,
+            selection_color: self.selection_color,
+            cursor_opacity: self.cursor_alpha,
+            origin_x: 0,
+            origin_y: 0,
+            _padding: 0,
+        }
+    }
+
+    /// Byte sizes of the cell, status bar and watermark storage buffers.
+    ///
+    /// The GPU buffers are reused while these stay the same. The watermark
+    /// buffer always holds at least one cell, as storage bindings can't be
+    /// empty.
+    pub fn buffer_sizes(&self) -> [u64; 3] {
+        let bytes = |cells: usize| (cells * std::mem::size_of::<GpuTerminalCell>()) as u64;
+        [
+            bytes(self.cells.len()),
+            bytes(self.status_cells.len()),
+            bytes(self.watermark_cells.len().max(1)),
+        ]
+    }
+}
+
+/// Copies each terminal's data into `RenderTerminals`, and drops the GPU
+/// buffers of terminals that are gone (despawned, for example by
+/// `shutdown_terminal_on_exit`).
+///
+/// System: ExtractSchedule (render world)
+/// Runs: Every frame
+fn extract_terminals(
+    mut terminals: ResMut<RenderTerminals>,
+    extracted: Extract<Query<(Entity, &ExtractedTerminalData)>>,
+) {
+    let mut live = HashSet::new();
+    for (id, data) in &extracted {
+        live.insert(id);
+        match terminals.0.get_mut(&id) {
+            Some(terminal) => terminal.data = data.clone(),
+            None => {
+                terminals.0.insert(id, RenderTerminal::new(data.clone()));
+            }
+        }
+    }
+
+    let count = terminals.0.len();
+    terminals.0.retain(|id, _| live.contains(id));
+    if terminals.0.len() < count {
+        info!("🛑 Terminal GPU resources released");
+    }
+}
+
+/// Atlas slots to write into the atlas texture this frame.
+#[derive(Resource, Default)]
+struct ExtractedAtlasUploads {
+    handle: Option<Handle<Image>>,
+    regions: Vec<AtlasRegion>,
+}
+
+/// Takes the atlas slots rasterized in the main world this frame.
+///
+/// System: ExtractSchedule (render world)
+/// Runs: Every frame
+fn extract_atlas_uploads(
+    mut extracted: ResMut<ExtractedAtlasUploads>,
+    uploads: Extract<Option<Res<AtlasUploads>>>,
+) {
+    extracted.regions.clear();
+    if let Some(uploads) = uploads.as_ref().filter(|uploads| uploads.is_changed()) {
+        extracted.handle = uploads.handle.clone();
+        extracted.regions.extend_from_slice(&uploads.regions);
+    }
+}
+
+/// Writes extracted atlas slots into the atlas texture.
+///
+/// System: Render (Prepare)
+/// Runs: Every frame
+///
+/// An atlas without a `GpuImage` yet is skipped: it will be created from
+/// the main-world image, which `upload_atlas_slots` already updated.
+fn write_atlas_uploads(
+    uploads: Res<ExtractedAtlasUploads>,
+    gpu_images: Res<RenderAssets<GpuImage>>,
+    render_queue: Res<RenderQueue>,
+) {
+    let Some(atlas_gpu_image) = uploads.handle.as_ref().and_then(|handle| gpu_images.get(handle)) else {
+        return;
+    };
+    for region in &uploads.regions {
+        render_queue.write_texture(
+            TexelCopyTextureInfo {
+                texture: &atlas_gpu_image.texture,
+                mip_level: 0,
+                origin: Origin3d {
+                    x: region.x,
+                    y: region.y,
+                    z: 0,
+                },
+                aspect: TextureAspect::All,
+            },
+            &region.data,
+            TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(region.width * 4),
+                rows_per_image: None,
+            },
+            Extent3d {
+                width: region.width,
+                height: region.height,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+}
+
+/// Copies each terminal's CPU buffer and render parameters into its
+/// `ExtractedTerminalData` for extraction.
+///
+/// System: PostUpdate
+/// Runs: Every frame; terminals without a texture yet are left out
+pub fn update_terminal_extraction(
     mut commands: Commands,
-    cpu_buffer: Res<TerminalCpuBuffer>,
-    term_texture: Option<Res<TerminalTexture>>,
+    terminals: Query<(Entity, &TerminalCpuBuffer, &TerminalTexture, &TerminalState, &TerminalConfig)>,
     atlas: Option<Res<GlyphAtlas>>,
-    term_state: Option<Res<crate::terminal::TerminalState>>,
+    clock: Option<Res<TerminalClock>>,
 ) {
-    if let (Some(texture), Some(atlas), Some(state)) = (term_texture, atlas, term_state) {
-        let atlas_cols = atlas.atlas_width / atlas.cell_width;
-        let atlas_rows = atlas.atlas_height / atlas.cell_height;
-
-        if let Some(atlas_handle) = &atlas.texture_handle {
-            commands.insert_resource(ExtractedTerminalData {
-                cells: cpu_buffer.cells.clone(),
-                texture_handle: texture.handle.clone(),
-                atlas_texture_handle: atlas_handle.clone(),
-                term_cols: state.cols as u32,
-                term_rows: state.rows as u32,
-                cell_width: atlas.cell_width,
-                cell_height: atlas.cell_height,
-                atlas_cols,
-                atlas_rows,
-            });
+    let Some(atlas) = atlas else {
+        return;
+    };
+    for (entity, cpu_buffer, texture, state, config) in &terminals {
+        if let Some(data) = extract_terminal(cpu_buffer, texture, &atlas, state, Some(config), clock.as_deref()) {
+            commands.entity(entity).insert(data);
         }
     }
 }
 
+/// One terminal's render data for this frame, or None until the atlas has
+/// an image.
+pub fn extract_terminal(
+    cpu_buffer: &TerminalCpuBuffer,
+    texture: &TerminalTexture,
+    atlas: &GlyphAtlas,
+    state: &TerminalState,
+    config: Option<&TerminalConfig>,
+    clock: Option<&TerminalClock>,
+) -> Option<ExtractedTerminalData> {
+    let atlas_handle = atlas.texture_handle.as_ref()?;
+    let atlas_cols = atlas.atlas_width / atlas.cell_width;
+    let atlas_rows = atlas.atlas_height / atlas.cell_height;
+    let status_bar = match config.and_then(|config| config.status_bar.as_ref()) {
+        None => STATUS_BAR_NONE,
+        Some(status_bar) => match status_bar.position {
+            StatusBarPosition::Top => STATUS_BAR_TOP,
+            StatusBarPosition::Bottom => STATUS_BAR_BOTTOM,
+        },
+    };
+
+    let watermark = config.and_then(|config| config.watermark.as_ref());
+    let visible = state.visible_grid(config);
+    let theme = config.map_or_else(TerminalTheme::default, |config| config.theme);
+
+    Some(ExtractedTerminalData {
+        cells: cpu_buffer.cells.clone(),
+        status_cells: cpu_buffer.status_cells.clone(),
+        status_bar,
+        watermark_cells: cpu_buffer.watermark_cells.clone(),
+        watermark_col: watermark.map_or(0, |watermark| watermark.column),
+        watermark_row: watermark.map_or(0, |watermark| watermark.row),
+        watermark_opacity: watermark.map_or(0.0, |watermark| watermark.opacity.clamp(0.0, 1.0)),
+        blink_phase: clock.map_or(0.0, |clock| clock.blink_phase),
+        cursor_blink: config.map_or(0, |config| config.cursor_blink.uniform()),
+        cursor_color: pack_color(state.cursor_color()),
+        cursor_style: config.map_or(0, |config| config.cursor_style.uniform()),
+        selection_color: pack_color_alpha(theme.selection_color, theme.selection_alpha),
+        cursor_alpha: theme.cursor_alpha.clamp(0.0, 1.0),
+        blank: cpu_buffer.blank,
+        cursor_cell: cpu_buffer.cursor_cell.map(|index| index as u32),
+        background: cpu_buffer.background,
+        force_redraw: cpu_buffer.force_redraw,
+        texture_handle: texture.handle.clone(),
+        atlas_texture_handle: atlas_handle.clone(),
+        term_cols: visible.cols as u32,
+        term_rows: visible.rows as u32,
+        cell_width: atlas.cell_width,
+        cell_height: atlas.cell_height,
+        atlas_cols,
+        atlas_rows,
+    })
+}
+
 #[derive(Resource)]
 pub struct TerminalComputePipeline {
     pub layout: BindGroupLayout,
@@ -102,6 +499,7 @@ pub struct TerminalComputePipeline {
 
 impl FromWorld for TerminalComputePipeline {
     fn from_world(world: &mut World) -> Self {
+        let format = world.get_resource::<TerminalTextureFormat>().copied().unwrap_or_default().0;
         let render_device = world.resource::<RenderDevice>();
         let layout = render_device.create_bind_group_layout(
             Some("terminal_compute_layout"),
@@ -141,11 +539,31 @@ impl FromWorld for TerminalComputePipeline {
                     visibility: ShaderStages::COMPUTE,
                     ty: BindingType::StorageTexture {
                         access: StorageTextureAccess::WriteOnly,
-                        format: TextureFormat::Rgba8Unorm,
+                        format,
                         view_dimension: TextureViewDimension::D2,
                     },
                     count: None,
                 },
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None, // One row of status bar cells
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 5,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None, // Watermark cells
+                    },
+                    count: None,
+                },
             ],
         );
 
@@ -158,7 +576,9 @@ impl FromWorld for TerminalComputePipeline {
             layout: vec![layout.clone()],
             push_constant_ranges: vec![],
             shader: shader.clone(),
-            shader_defs: vec![],
+            shader_defs: texture_format_shader_def(format)
+                .map(|def| vec![def.into()])
+                .unwrap_or_default(),
             entry_point: Some(Cow::Borrowed("main")),
             zero_initialize_workgroup_memory: false,
         });
@@ -167,16 +587,24 @@ impl FromWorld for TerminalComputePipeline {
     }
 }
 
+/// Uploads this frame's uniforms and cells into the persistent GPU buffers.
+///
+/// System: Render (Prepare)
+/// Runs: Every frame, when the compute backend is active
+///
+/// Buffers and the bind group are allocated once and refilled with
+/// `RenderQueue::write_buffer`. They are only rebuilt when a buffer changes
+/// size (grid resize, status bar or watermark change) or a texture is
+/// re-uploaded, which gives it a new view.
+#[allow(clippy::too_many_arguments)]
 fn prepare_gpu_resources(
-    mut commands: Commands,
     render_device: Res<RenderDevice>,
-    _render_queue: Res<RenderQueue>,
-    pipeline_cache: Res<PipelineCache>,
+    render_queue: Res<RenderQueue>,
     compute_pipeline: Res<TerminalComputePipeline>,
-    extracted: Option<Res<ExtractedTerminalData>>,
     gpu_images: Res<RenderAssets<GpuImage>>,
+    mut terminals: ResMut<RenderTerminals>,
 ) {
-    let Some(data) = extracted else {
+    if terminals.0.is_empty() {
         static mut WARN_COUNT: u32 = 0;
         unsafe {
             WARN_COUNT += 1;
@@ -185,49 +613,89 @@ fn prepare_gpu_resources(
             }
         }
         return;
+    }
+
+    for terminal in terminals.0.values_mut() {
+        prepare_terminal_gpu_resources(&render_device, &render_queue, &compute_pipeline, &gpu_images, terminal);
+    }
+}
+
+/// `prepare_gpu_resources` for one terminal.
+fn prepare_terminal_gpu_resources(
+    render_device: &RenderDevice,
+    render_queue: &RenderQueue,
+    compute_pipeline: &TerminalComputePipeline,
+    gpu_images: &RenderAssets<GpuImage>,
+    terminal: &mut RenderTerminal,
+) {
+    // 1. Textures (target and atlas)
+    let Some(output_gpu_image) = gpu_images.get(&terminal.data.texture_handle) else {
+        warn!("⚠️  prepare_gpu_resources: Missing output_gpu_image!");
+        return;
     };
-    
-    // 1. Uniforms
-    let uniforms = TerminalUniforms {
-        term_cols: data.term_cols,
-        term_rows: data.term_rows,
-        cell_width: data.cell_width,
-        cell_height: data.cell_height,
-        atlas_cols: data.atlas_cols,
-        atlas_rows: data.atlas_rows,
-        _padding: [0, 0],
+    let Some(atlas_gpu_image) = gpu_images.get(&terminal.data.atlas_texture_handle) else {
+        warn!("⚠️  prepare_gpu_resources: Missing atlas_gpu_image!");
+        return;
     };
 
+    let can_clear = output_gpu_image
+        .texture
+        .usage()
+        .contains(TextureUsages::RENDER_ATTACHMENT);
+    terminal.select_dispatch(can_clear);
+
+    // 2. Uniforms for the dispatch just selected
+    let uniforms = terminal.uniforms();
+
     if uniforms.term_cols == 0 || uniforms.cell_width == 0 {
         error!("⚠️  GPU Prep: Invalid uniforms! cols={}, width={}", uniforms.term_cols, uniforms.cell_width);
+    }
+    let data = &terminal.data;
+
+    // Storage bindings can't be empty; watermark_len keeps the filler unused
+    let watermark_cells = if data.watermark_cells.is_empty() {
+        vec![GpuTerminalCell::zeroed()]
     } else {
-        // info!("GPU Prep: Uniforms: {}x{} grid, {}x{} px cells", uniforms.term_cols, uniforms.term_rows, uniforms.cell_width, uniforms.cell_height);
+        data.watermark_cells.clone()
+    };
+
+    // 3. Same layout as last frame: refill the existing buffers
+    let sizes = data.buffer_sizes();
+    let views = [output_gpu_image.texture_view.id(), atlas_gpu_image.texture_view.id()];
+    if let Some(resources) = terminal.gpu.as_ref().filter(|resources| resources.sizes == sizes && resources.views == views) {
+        render_queue.write_buffer(&resources.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+        render_queue.write_buffer(&resources.cell_buffer, 0, bytemuck::cast_slice(&data.cells));
+        render_queue.write_buffer(&resources.status_buffer, 0, bytemuck::cast_slice(&data.status_cells));
+        render_queue.write_buffer(&resources.watermark_buffer, 0, bytemuck::cast_slice(&watermark_cells));
+        return;
     }
 
+    // 4. Otherwise allocate them, filled with this frame's data
     let uniform_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
         label: Some("terminal_uniforms"),
         contents: bytemuck::bytes_of(&uniforms),
         usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
     });
 
-    // 2. Cell Grid
     let cell_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
         label: Some("terminal_grid"),
         contents: bytemuck::cast_slice(&data.cells),
         usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
     });
 
-    // 3. Textures (Target)
-    let Some(output_gpu_image) = gpu_images.get(&data.texture_handle) else {
-        warn!("⚠️  prepare_gpu_resources: Missing output_gpu_image!");
-        return;
-    };
-    let Some(atlas_gpu_image) = gpu_images.get(&data.atlas_texture_handle) else {
-        warn!("⚠️  prepare_gpu_resources: Missing atlas_gpu_image!");
-        return;
-    };
+    let status_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+        label: Some("terminal_status_bar"),
+        contents: bytemuck::cast_slice(&data.status_cells),
+        usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+    });
 
-    // 4. Bind Group (pipeline is already queued in TerminalComputePipeline::from_world)
+    let watermark_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+        label: Some("terminal_watermark"),
+        contents: bytemuck::cast_slice(&watermark_cells),
+        usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+    });
+
+    // 5. Bind Group (pipeline is already queued in TerminalComputePipeline::from_world)
     let bind_group = render_device.create_bind_group(
         Some("terminal_bind_group"),
         &compute_pipeline.layout,
@@ -248,22 +716,27 @@ fn prepare_gpu_resources(
                 binding: 3,
                 resource: BindingResource::TextureView(&output_gpu_image.texture_view),
             },
+            BindGroupEntry {
+                binding: 4,
+                resource: status_buffer.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 5,
+                resource: watermark_buffer.as_entire_binding(),
+            },
         ],
     );
 
-    commands.insert_resource(TerminalGpuResources {
+    info!("✅ TerminalGpuResources allocated for {}×{} cells", data.term_cols, data.term_rows);
+    terminal.gpu = Some(TerminalGpuResources {
         cell_buffer,
+        status_buffer,
+        watermark_buffer,
         uniform_buffer,
         bind_group,
+        sizes,
+        views,
     });
-
-    static mut PREP_COUNT: u32 = 0;
-    unsafe {
-        PREP_COUNT += 1;
-        if PREP_COUNT == 1 {
-            info!("✅ TerminalGpuResources prepared successfully!");
-        }
-    }
 }
 
 struct TerminalNode;
@@ -274,67 +747,112 @@ impl Node for TerminalNode {
         render_context: &mut RenderContext,
         world: &World,
     ) -> Result<(), NodeRunError> {
-        let Some(gpu_resources) = world.get_resource::<TerminalGpuResources>() else {
-            static mut WARN_COUNT: u32 = 0;
-            unsafe {
-                WARN_COUNT += 1;
-                if WARN_COUNT == 1 {
-                    warn!("⚠️  TerminalNode::run: Missing GpuResources!");
-                }
-            }
+        let Some(terminals) = world.get_resource::<RenderTerminals>() else {
             return Ok(());
         };
-        
-        // throttle logging
-        /*
-        if bevy::utils::SystemTime::now().duration_since(bevy::utils::SystemTime::UNIX_EPOCH).unwrap().as_secs() % 5 == 0 {
-             info!("TerminalNode::run executing");
+
+        for terminal in terminals.0.values() {
+            if terminal.fragment.is_some() {
+                fragment::run_fragment_pass(render_context, world, terminal);
+            } else {
+                run_compute_pass(render_context, world, terminal);
+            }
         }
-        */
+        Ok(())
+    }
+}
 
-        let pipeline_cache = world.resource::<PipelineCache>();
-        let compute_pipeline = world.resource::<TerminalComputePipeline>();
-        let Some(pipeline) = pipeline_cache.get_compute_pipeline(compute_pipeline.pipeline_id) else {
-            static mut PIPE_WARN: u32 = 0;
-            unsafe {
-                PIPE_WARN += 1;
-                if PIPE_WARN == 1 {
-                    warn!("⚠️  TerminalNode::run: Pipeline not ready yet!");
-                }
+/// Updates one terminal's texture with the compute shader.
+fn run_compute_pass(render_context: &mut RenderContext, world: &World, terminal: &RenderTerminal) {
+    let Some(gpu_resources) = &terminal.gpu else {
+        static mut WARN_COUNT: u32 = 0;
+        unsafe {
+            WARN_COUNT += 1;
+            if WARN_COUNT == 1 {
+                warn!("⚠️  TerminalNode::run: Missing GpuResources!");
             }
-            return Ok(());
-        };
-        let extracted = world.resource::<ExtractedTerminalData>();
-
-        // Calculate dispatch size
-        // One thread per pixel
-        let width = extracted.term_cols * extracted.cell_width;
-        let height = extracted.term_rows * extracted.cell_height;
-        let workgroup_size = 8;
-        let x_groups = (width + workgroup_size - 1) / workgroup_size;
-        let y_groups = (height + workgroup_size - 1) / workgroup_size;
-
-        let mut pass = render_context
-            .command_encoder()
-            .begin_compute_pass(&ComputePassDescriptor {
-                label: Some("terminal_compute_pass"),
-                ..default()
-            });
+        }
+        return;
+    };
 
-        pass.set_pipeline(pipeline);
-        pass.set_bind_group(0, &gpu_resources.bind_group, &[]);
-        pass.dispatch_workgroups(x_groups, y_groups, 1);
+    let extracted = &terminal.data;
+
+    let cursor_only = match terminal.dispatch {
+        TerminalDispatch::Full => false,
+        TerminalDispatch::Skip => return,
+        TerminalDispatch::Clear => {
+            // A load-op clear is far cheaper than one shader thread per pixel
+            let gpu_images = world.resource::<RenderAssets<GpuImage>>();
+            let Some(output) = gpu_images.get(&extracted.texture_handle) else {
+                return;
+            };
+            let [r, g, b] = extracted.background.map(|c| c as f32 / 255.0);
+            render_context.command_encoder().begin_render_pass(&RenderPassDescriptor {
+                label: Some("terminal_clear_pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &output.texture_view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(LinearRgba::new(r, g, b, 1.0).into()),
+                        store: StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            // Then the cursor cell, starting at the uniforms' origin
+            if extracted.cursor_cell.is_none() {
+                return;
+            }
+            true
+        }
+    };
 
-        // Debug: Log dispatch
-        static mut FRAME_COUNT: u32 = 0;
+    let pipeline_cache = world.resource::<PipelineCache>();
+    let compute_pipeline = world.resource::<TerminalComputePipeline>();
+    let Some(pipeline) = pipeline_cache.get_compute_pipeline(compute_pipeline.pipeline_id) else {
+        static mut PIPE_WARN: u32 = 0;
         unsafe {
-            FRAME_COUNT += 1;
-            if FRAME_COUNT == 1 {
-                info!("✅ Compute shader dispatched! workgroups: {}x{}, dims: {}x{}",
-                      x_groups, y_groups, width, height);
+            PIPE_WARN += 1;
+            if PIPE_WARN == 1 {
+                warn!("⚠️  TerminalNode::run: Pipeline not ready yet!");
             }
         }
+        return;
+    };
 
-        Ok(())
+    // Calculate dispatch size
+    // One thread per pixel, including the status bar row when enabled
+    let status_rows = u32::from(extracted.status_bar != STATUS_BAR_NONE);
+    let (width, height) = if cursor_only {
+        (extracted.cell_width, extracted.cell_height)
+    } else {
+        (extracted.term_cols * extracted.cell_width, (extracted.term_rows + status_rows) * extracted.cell_height)
+    };
+    let workgroup_size = 8;
+    let x_groups = width.div_ceil(workgroup_size);
+    let y_groups = height.div_ceil(workgroup_size);
+
+    let mut pass = render_context
+        .command_encoder()
+        .begin_compute_pass(&ComputePassDescriptor {
+            label: Some("terminal_compute_pass"),
+            ..default()
+        });
+
+    pass.set_pipeline(pipeline);
+    pass.set_bind_group(0, &gpu_resources.bind_group, &[]);
+    pass.dispatch_workgroups(x_groups, y_groups, 1);
+
+    // Debug: Log dispatch
+    static mut FRAME_COUNT: u32 = 0;
+    unsafe {
+        FRAME_COUNT += 1;
+        if FRAME_COUNT == 1 {
+            info!("✅ Compute shader dispatched! workgroups: {}x{}, dims: {}x{}",
+                  x_groups, y_groups, width, height);
+        }
     }
 }
\ No newline at end of file
@@ -1,22 +1,202 @@
 //! Render-to-texture system.
 //!
-//! Renders terminal grid to Image texture.
-//! Exposes Handle<Image> via TerminalTexture resource.
+//! Renders each terminal's grid to an Image texture.
+//! Exposes Handle<Image> via the terminal entity's TerminalTexture component.
+//!
+//! ## Platform support
+//!
+//! The grid is normally drawn by a compute shader writing a storage texture.
+//! Backends without compute shaders or storage textures (WebGL2, and some
+//! GLES 3.0 mobile drivers) can't run it, so `RenderBackend::Auto` switches
+//! to the fragment backend there (see `fragment`), which draws into the
+//! texture as a plain render target.
+//!
+//! When `RenderBackend::Compute` is forced on such a device the plugin skips
+//! the render node, inserts `TerminalRenderUnsupported`, creates the texture
+//! without `STORAGE_BINDING` (so device validation doesn't panic) and sends a
+//! `TerminalEventKind::Error` at startup. The texture shows only the
+//! background.
+//!
+//! `RenderBackend::Cpu` skips the GPU entirely and draws into the image's
+//! pixels (see `software`); it is never picked automatically.
 
+use anyhow::{bail, Result};
 use bevy::asset::RenderAssetUsages;
 use bevy::prelude::*;
-use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages};
-use log::info;
+use bevy::render::render_resource::{
+    DownlevelFlags, Extent3d, TextureDimension, TextureFormat, TextureFormatFeatureFlags,
+    TextureUsages, WgpuFeatures,
+};
+use bevy::render::renderer::{RenderAdapter, RenderDevice};
+use log::{error, info};
 
 use crate::atlas::GlyphAtlas;
-use crate::colors::TOKYO_NIGHT_BG;
+use crate::colors::TerminalPalette;
+use crate::config::{RenderBackend, TerminalConfig};
+use crate::events::{TerminalEvent, TerminalEventKind};
+use crate::font::FontMetrics;
 use crate::terminal::TerminalState;
 
-/// Resource exposing the terminal texture for game use.
+/// Default texture format written by the compute shader.
+pub const TERMINAL_TEXTURE_FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
+
+/// Formats the compute shader can write (see `texture_format_shader_def`).
+pub const SUPPORTED_TEXTURE_FORMATS: [TextureFormat; 4] = [
+    TextureFormat::Rgba8Unorm,
+    TextureFormat::Bgra8Unorm,
+    TextureFormat::Rgba16Float,
+    TextureFormat::Rgba32Float,
+];
+
+/// Texture format actually in use, after checking device support.
+///
+/// Inserted into both worlds by `TerminalComputePlugin` so the texture and
+/// the compute pipeline always agree.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TerminalTextureFormat(pub TextureFormat);
+
+impl Default for TerminalTextureFormat {
+    fn default() -> Self {
+        Self(TERMINAL_TEXTURE_FORMAT)
+    }
+}
+
+/// Backend drawing the grid, after checking device support.
+///
+/// Inserted into both worlds by `TerminalComputePlugin`; never `Auto`.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActiveRenderBackend(pub RenderBackend);
+
+/// Present when the device can't run the compute renderer at all.
+///
+/// Inserted into the main world by `TerminalComputePlugin`; the reason is
+/// also sent as a `TerminalEventKind::Error` at startup.
+#[derive(Resource, Clone, Debug)]
+pub struct TerminalRenderUnsupported {
+    pub reason: String,
+}
+
+/// Why the device can't run the compute renderer, or None if it can.
+///
+/// Needs compute shaders, at least one storage texture per shader stage, and
+/// `TERMINAL_TEXTURE_FORMAT` (the fallback format) usable as a storage texture.
+pub fn compute_renderer_unsupported(adapter: &RenderAdapter, device: &RenderDevice) -> Option<String> {
+    let backend = adapter.get_info().backend;
+    if !adapter
+        .get_downlevel_capabilities()
+        .flags
+        .contains(DownlevelFlags::COMPUTE_SHADERS)
+    {
+        return Some(format!("{:?} backend has no compute shaders", backend));
+    }
+    if device.limits().max_storage_textures_per_shader_stage == 0 {
+        return Some(format!("{:?} backend has no storage textures", backend));
+    }
+    if !storage_format_supported(TERMINAL_TEXTURE_FORMAT, adapter, device) {
+        return Some(format!(
+            "{:?} backend can't use {:?} as a storage texture",
+            backend, TERMINAL_TEXTURE_FORMAT
+        ));
+    }
+    None
+}
+
+/// Sends the `TerminalRenderUnsupported` reason as a
+/// `TerminalEventKind::Error` for every terminal.
+///
+/// System: Startup
+/// Runs: Once, when the compute renderer is unavailable
+pub fn report_render_unsupported(
+    unsupported: Res<TerminalRenderUnsupported>,
+    terminals: Query<Entity, With<TerminalState>>,
+    mut events: MessageWriter<TerminalEvent>,
+) {
+    for terminal in &terminals {
+        events.write(TerminalEvent::new(terminal, TerminalEventKind::Error {
+            message: format!("Terminal rendering unavailable: {}", unsupported.reason),
+        }));
+    }
+}
+
+/// Shader def selecting the storage texture format in `terminal.wgsl`.
+///
+/// None for the default `Rgba8Unorm` and for unsupported formats.
+pub fn texture_format_shader_def(format: TextureFormat) -> Option<&'static str> {
+    match format {
+        TextureFormat::Bgra8Unorm => Some("OUTPUT_BGRA8UNORM"),
+        TextureFormat::Rgba16Float => Some("OUTPUT_RGBA16FLOAT"),
+        TextureFormat::Rgba32Float => Some("OUTPUT_RGBA32FLOAT"),
+        _ => None,
+    }
+}
+
+/// Whether the device can write `format` as a storage texture from our shader.
+pub fn storage_format_supported(
+    format: TextureFormat,
+    adapter: &RenderAdapter,
+    device: &RenderDevice,
+) -> bool {
+    if !SUPPORTED_TEXTURE_FORMATS.contains(&format) {
+        return false;
+    }
+
+    // BGRA storage is an optional wgpu feature, even where the adapter reports it
+    if format == TextureFormat::Bgra8Unorm
+        && !device.features().contains(WgpuFeatures::BGRA8UNORM_STORAGE)
+    {
+        return false;
+    }
+
+    adapter
+        .get_texture_format_features(format)
+        .allowed_usages
+        .contains(TextureUsages::STORAGE_BINDING)
+}
+
+/// Whether the fragment backend can draw into `format` on this device.
+///
+/// Watermark glyphs are alpha blended, so the format must be blendable.
+pub fn render_format_supported(format: TextureFormat, adapter: &RenderAdapter) -> bool {
+    let features = adapter.get_texture_format_features(format);
+    SUPPORTED_TEXTURE_FORMATS.contains(&format)
+        && features.allowed_usages.contains(TextureUsages::RENDER_ATTACHMENT)
+        && features.flags.contains(TextureFormatFeatureFlags::BLENDABLE)
+}
+
+/// One pixel of the default palette's background color encoded in `format`.
+pub fn background_pixel(format: TextureFormat) -> Vec<u8> {
+    color_pixel(TerminalPalette::default().background, format)
+}
+
+/// One pixel of `rgb` encoded in `format`.
+///
+/// Matches what the shader writes: the raw sRGB bytes as unorm values.
+pub fn color_pixel([r, g, b]: [u8; 3], format: TextureFormat) -> Vec<u8> {
+    match format {
+        TextureFormat::Bgra8Unorm => vec![b, g, r, 255],
+        TextureFormat::Rgba16Float | TextureFormat::Rgba32Float => {
+            let zeroed = vec![0; format.block_copy_size(None).unwrap_or(4) as usize];
+            let mut pixel = Image::new_fill(
+                Extent3d::default(),
+                TextureDimension::D2,
+                &zeroed,
+                format,
+                RenderAssetUsages::MAIN_WORLD,
+            );
+            let color = LinearRgba::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0);
+            let _ = pixel.set_color_at(0, 0, color.into());
+            pixel.data.unwrap_or_default()
+        }
+        _ => vec![r, g, b, 255],
+    }
+}
+
+/// Component exposing a terminal's texture for game use.
 ///
 /// Contains a Handle<Image> that can be used as a sprite, UI element, or material.
-/// The texture updates every frame based on terminal grid state.
-#[derive(Resource)]
+/// The texture updates every frame based on terminal grid state. Added to
+/// the terminal entity once the glyph atlas exists (never when headless).
+#[derive(Component)]
 pub struct TerminalTexture {
     /// Bevy image handle for the terminal texture
     pub handle: Handle<Image>,
@@ -26,55 +206,292 @@ pub struct TerminalTexture {
     pub height: u32,
 }
 
-/// Initialize terminal texture resource.
+/// Gives each new terminal its `TerminalTexture`.
 ///
-/// Creates an RGBA texture sized to fit the terminal grid with current cell dimensions.
-/// Runs once at startup after atlas is ready.
-pub fn initialize_terminal_texture(
+/// System: Startup and Update
+/// Runs: Every frame once the atlas is ready, unless headless; acts on
+/// terminals without a texture
+///
+/// Creates a texture (in the device-checked `TerminalTextureFormat`) sized
+/// to fit the terminal grid with current cell dimensions. When the
+/// terminal's `TerminalConfig::target` is set, that image is used instead
+/// (after validation) and the game keeps control of its lifecycle.
+pub fn initialize_terminal_textures(
     mut commands: Commands,
+    terminals: Query<(Entity, &TerminalState, &TerminalConfig), Without<TerminalTexture>>,
     mut images: ResMut<Assets<Image>>,
     atlas: Res<GlyphAtlas>,
-    term_state: Res<TerminalState>,
+    format: Option<Res<TerminalTextureFormat>>,
+    backend: Option<Res<ActiveRenderBackend>>,
+    unsupported: Option<Res<TerminalRenderUnsupported>>,
 ) {
-    let width = atlas.cell_width * term_state.cols as u32;
-    let height = atlas.cell_height * term_state.rows as u32;
+    let format = format.map_or(TERMINAL_TEXTURE_FORMAT, |format| format.0);
+    let backend = backend.map_or(RenderBackend::Compute, |backend| backend.0);
+    let storage = unsupported.is_none() && backend == RenderBackend::Compute;
+    for (terminal, term_state, config) in &terminals {
+        let texture = create_terminal_texture(&mut images, &atlas, term_state, Some(config), format, backend, storage);
+        commands.entity(terminal).insert(texture);
+    }
+}
+
+/// Texture for one terminal: the validated `TerminalConfig::target`, or a
+/// new plugin-owned image sized to the grid.
+///
+/// `storage` adds `TextureUsages::STORAGE_BINDING` for the compute backend.
+fn create_terminal_texture(
+    images: &mut Assets<Image>,
+    atlas: &GlyphAtlas,
+    term_state: &TerminalState,
+    config: Option<&TerminalConfig>,
+    format: TextureFormat,
+    backend: RenderBackend,
+    storage: bool,
+) -> TerminalTexture {
+    let extra_rows = config.map_or(0, |config| config.extra_rows());
+    let visible = term_state.visible_grid(config);
+    let (width, height) = texture_dimensions(
+        atlas.cell_width,
+        atlas.cell_height,
+        visible.cols,
+        visible.rows + extra_rows,
+    );
+
+    if let Some(target) = config.and_then(|config| config.target.clone()) {
+        let validation = match images.get(&target) {
+            Some(image) => validate_target_image_for(image, width, height, format, backend),
+            None => Err(anyhow::anyhow!("target image is not loaded in Assets<Image>")),
+        };
+
+        match validation {
+            Ok(()) => {
+                info!("🖼️  Rendering terminal into user-provided target ({}×{})", width, height);
+                return TerminalTexture {
+                    handle: target,
+                    width,
+                    height,
+                };
+            }
+            Err(error) => {
+                error!("❌ Invalid terminal render target, using plugin-owned texture: {:#}", error);
+            }
+        }
+    }
 
     info!(
-        "🖼️  Creating terminal texture: {}×{} pixels ({}×{} cells)",
-        width, height, term_state.cols, term_state.rows
+        "🖼️  Creating terminal texture: {}×{} pixels ({}×{} cells, {:?})",
+        width, height, visible.cols, visible.rows, format
     );
 
-    // Create RGBA texture filled with background color
-    let bg = TOKYO_NIGHT_BG;
-    let mut texture_data = vec![0u8; (width * height * 4) as usize];
-    for pixel in texture_data.chunks_exact_mut(4) {
-        pixel[0] = bg[0];
-        pixel[1] = bg[1];
-        pixel[2] = bg[2];
-        pixel[3] = 255;
+    let handle = images.add(terminal_image(width, height, format, term_state.background_color(), storage));
+    info!("✅ Terminal texture initialized");
+
+    TerminalTexture {
+        handle,
+        width,
+        height,
     }
+}
 
-    let mut image = Image::new(
+/// Plugin-owned terminal texture filled with `background`.
+///
+/// `storage` adds `STORAGE_BINDING` for the compute shader; leave it off on
+/// devices that can't bind storage textures.
+fn terminal_image(width: u32, height: u32, format: TextureFormat, background: [u8; 3], storage: bool) -> Image {
+    let mut image = Image::new_fill(
         Extent3d {
             width,
             height,
             depth_or_array_layers: 1,
         },
         TextureDimension::D2,
-        texture_data,
-        TextureFormat::Rgba8Unorm,
+        &color_pixel(background, format),
+        format,
         RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
     );
-    image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::STORAGE_BINDING;
+    // RENDER_ATTACHMENT enables the cheap clear when the grid goes blank
+    image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+        | TextureUsages::COPY_DST
+        | TextureUsages::RENDER_ATTACHMENT;
+    if storage {
+        image.texture_descriptor.usage |= TextureUsages::STORAGE_BINDING;
+    }
+    image
+}
 
-    let handle = images.add(image);
+/// Rebuilds the atlas and resizes the terminal textures when `FontMetrics`
+/// changes.
+///
+/// System: Update
+/// Runs: Every frame, acting only when `FontMetrics` was modified
+///
+/// The atlas is regenerated with the same characters (so glyph indices in
+/// the CPU buffer stay valid) and swapped into the existing image handle; the
+/// textures are resized in place when the cell size changed. Both happen in
+/// the same frame, so the extracted cell size, atlas and textures always
+/// agree.
+/// A user-provided `TerminalConfig::target` can't be resized by the plugin;
+/// resize it yourself and it is picked up as-is.
+pub fn apply_font_change(
+    font_metrics: Option<Res<FontMetrics>>,
+    atlas: Option<ResMut<GlyphAtlas>>,
+    mut terminals: Query<(&mut TerminalTexture, &TerminalState, &TerminalConfig)>,
+    mut images: Option<ResMut<Assets<Image>>>,
+) {
+    let (Some(font_metrics), Some(mut atlas)) = (font_metrics, atlas) else {
+        return;
+    };
+    if !font_metrics.is_changed() || font_metrics.is_added() {
+        return;
+    }
 
-    commands.insert_resource(TerminalTexture {
-        handle,
-        width,
-        height,
-    });
+    let mut new_atlas = match GlyphAtlas::generate(&font_metrics, &atlas.chars()) {
+        Ok(new_atlas) => new_atlas,
+        Err(error) => {
+            error!("❌ Failed to regenerate glyph atlas: {:#}", error);
+            return;
+        }
+    };
+    // Headless there's no atlas image or texture to update
+    if let Some(handle) = atlas.texture_handle.take() {
+        if let Some(images) = images.as_deref_mut() {
+            if let Err(error) = images.insert(&handle, new_atlas.to_bevy_image()) {
+                error!("❌ Failed to upload regenerated atlas: {}", error);
+            }
+        }
+        new_atlas.texture_handle = Some(handle);
+    }
+    *atlas = new_atlas;
+    info!("🔤 Glyph atlas rebuilt: {}×{} cells", atlas.cell_width, atlas.cell_height);
 
-    info!("✅ Terminal texture initialized");
+    let Some(images) = images.as_deref_mut() else {
+        return;
+    };
+    for (mut texture, term_state, config) in &mut terminals {
+        resize_terminal_texture(&mut texture, images, &atlas, term_state, Some(config));
+    }
 }
 
+/// Resizes the terminal texture to fit the current grid (or its
+/// `TerminalConfig::viewport`) and cell size.
+///
+/// No-op when the size already matches. A user-provided
+/// `TerminalConfig::target` is left alone (with an error logged) since the
+/// game owns it.
+pub fn resize_terminal_texture(
+    texture: &mut TerminalTexture,
+    images: &mut Assets<Image>,
+    atlas: &GlyphAtlas,
+    term_state: &TerminalState,
+    config: Option<&TerminalConfig>,
+) {
+    let extra_rows = config.map_or(0, |config| config.extra_rows());
+    let visible = term_state.visible_grid(config);
+    let (width, height) = texture_dimensions(
+        atlas.cell_width,
+        atlas.cell_height,
+        visible.cols,
+        visible.rows + extra_rows,
+    );
+    if (width, height) == (texture.width, texture.height) {
+        return;
+    }
+
+    if config.and_then(|config| config.target.as_ref()) == Some(&texture.handle) {
+        error!(
+            "❌ Terminal render target must be resized to {}×{} to fit the grid",
+            width, height
+        );
+    } else {
+        // Same format and usages as the texture being replaced
+        let Some(descriptor) = images.get(&texture.handle).map(|image| image.texture_descriptor.clone()) else {
+            return;
+        };
+        let mut image = terminal_image(width, height, descriptor.format, term_state.background_color(), false);
+        image.texture_descriptor.usage = descriptor.usage;
+        if let Err(error) = images.insert(&texture.handle, image) {
+            error!("❌ Failed to resize terminal texture: {}", error);
+            return;
+        }
+        info!("🖼️  Terminal texture resized: {}×{} pixels", width, height);
+    }
+    texture.width = width;
+    texture.height = height;
+}
+
+/// Pixel size of a texture holding `cols × rows` cells.
+pub fn texture_dimensions(cell_width: u32, cell_height: u32, cols: usize, rows: usize) -> (u32, u32) {
+    (cell_width * cols as u32, cell_height * rows as u32)
+}
+
+/// Largest integer scale at which a `texture_size` texture fits in `target`.
+///
+/// Returns a whole number (1, 2, 3, ...) when the texture fits at least once,
+/// otherwise the largest `1/n` that fits. Either way every texel maps to a
+/// whole number of screen pixels (or vice versa), so with nearest sampling
+/// (`ImageSampler::nearest()`) a pixel-art CRT stays crisp. Apply it as a
+/// uniform sprite scale: `Transform::from_scale(Vec3::splat(scale))`.
+pub fn integer_scale(texture_size: UVec2, target: Rect) -> f32 {
+    let target = target.size();
+    if texture_size.x == 0 || texture_size.y == 0 {
+        return 1.0;
+    }
+
+    let fit = (target.x / texture_size.x as f32).min(target.y / texture_size.y as f32);
+    if fit >= 1.0 {
+        fit.floor()
+    } else if fit > 0.0 {
+        1.0 / (1.0 / fit).ceil()
+    } else {
+        1.0
+    }
+}
+
+/// Check that a user-provided image can be used as the terminal render target.
+///
+/// The compute shader writes it as a storage texture, so the format and
+/// usages must match, and it must cover the grid exactly.
+pub fn validate_target_image(image: &Image, width: u32, height: u32, format: TextureFormat) -> Result<()> {
+    validate_target_image_for(image, width, height, format, RenderBackend::Compute)
+}
+
+/// `validate_target_image` for `backend`: the fragment backend needs
+/// `RENDER_ATTACHMENT` instead of `STORAGE_BINDING`, and the CPU backend
+/// `COPY_DST` and the image's pixels kept in the main world.
+pub fn validate_target_image_for(
+    image: &Image,
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+    backend: RenderBackend,
+) -> Result<()> {
+    let descriptor = &image.texture_descriptor;
+
+    if descriptor.format != format {
+        bail!("format is {:?}, expected {:?}", descriptor.format, format);
+    }
+
+    let (usage, name) = match backend {
+        RenderBackend::Fragment => (TextureUsages::RENDER_ATTACHMENT, "RENDER_ATTACHMENT"),
+        RenderBackend::Auto | RenderBackend::Compute => (TextureUsages::STORAGE_BINDING, "STORAGE_BINDING"),
+        RenderBackend::Cpu => (TextureUsages::COPY_DST, "COPY_DST"),
+    };
+    if !descriptor.usage.contains(usage) {
+        bail!("usage {:?} is missing {}", descriptor.usage, name);
+    }
+    if backend == RenderBackend::Cpu && image.data.is_none() {
+        bail!("pixels aren't kept in the main world (needs RenderAssetUsages::MAIN_WORLD)");
+    }
+
+    let size = descriptor.size;
+    if size.width != width || size.height != height {
+        bail!(
+            "size is {}×{}, grid needs {}×{}",
+            size.width,
+            size.height,
+            width,
+            height
+        );
+    }
+
+    Ok(())
+}
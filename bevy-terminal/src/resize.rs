@@ -0,0 +1,93 @@
+//! Runtime terminal resize.
+//!
+//! Send `ResizeTerminal` for a terminal entity (e.g. from a window-resize or
+//! fullscreen toggle system) and `apply_terminal_resize` resizes everything
+//! of that terminal that depends on the grid size in one frame:
+//!
+//! - the alacritty grid (`TerminalState::resize_terminal`), reflowing text
+//! - the PTY (`PtyResource::resize`), so the shell sees SIGWINCH
+//! - the terminal texture (`renderer::resize_terminal_texture`)
+//!
+//! The CPU cell buffer and shader uniforms follow from `TerminalState`'s
+//! `cols`/`rows` on the next `prepare_terminal_cpu_buffer`.
+//!
+//! A `TerminalConfig::viewport` sizes the texture to the viewport instead
+//! of the grid, and `apply_viewport_resize` follows changes to it.
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use log::error;
+
+use crate::atlas::GlyphAtlas;
+use crate::config::TerminalConfig;
+use crate::pty::PtyResource;
+use crate::renderer::{self, TerminalTexture};
+use crate::terminal::TerminalState;
+
+/// Request to resize a terminal's grid to `cols × rows` cells.
+///
+/// Registered as a message by `TerminalPlugin`. When several for the same
+/// terminal arrive in one frame only the last is applied.
+#[derive(Message, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResizeTerminal {
+    /// The terminal entity to resize
+    pub terminal: Entity,
+    pub cols: usize,
+    pub rows: usize,
+}
+
+/// Applies the latest `ResizeTerminal` request of each terminal.
+///
+/// System: Update
+/// Runs: Every frame, before `prepare_terminal_cpu_buffer`
+pub fn apply_terminal_resize(
+    mut requests: MessageReader<ResizeTerminal>,
+    mut terminals: Query<(&mut TerminalState, &TerminalConfig, Option<&PtyResource>, Option<&mut TerminalTexture>)>,
+    atlas: Option<Res<GlyphAtlas>>,
+    mut images: Option<ResMut<Assets<Image>>>,
+) {
+    let latest: HashMap<Entity, (usize, usize)> = requests
+        .read()
+        .map(|&ResizeTerminal { terminal, cols, rows }| (terminal, (cols, rows)))
+        .collect();
+
+    for (terminal, (cols, rows)) in latest {
+        let Ok((mut term_state, config, pty, texture)) = terminals.get_mut(terminal) else {
+            continue;
+        };
+        if (cols.max(1), rows.max(1)) == (term_state.cols, term_state.rows) {
+            continue;
+        }
+
+        term_state.resize_terminal(cols, rows);
+        if let Some(pty) = pty {
+            if let Err(error) = pty.resize(term_state.cols, term_state.rows) {
+                error!("❌ Failed to resize PTY: {:#}", error);
+            }
+        }
+        if let (Some(mut texture), Some(atlas), Some(images)) = (texture, atlas.as_ref(), images.as_mut()) {
+            renderer::resize_terminal_texture(&mut texture, images, atlas, &term_state, Some(config));
+        }
+    }
+}
+
+/// Resizes a terminal's texture when its config changes what it shows
+/// (`TerminalConfig::viewport`, the status bar).
+///
+/// System: Update
+/// Runs: Every frame, before `prepare_terminal_cpu_buffer`; acts on
+/// terminals whose `TerminalConfig` changed
+///
+/// Panning the viewport keeps its size, so the texture stays as it is.
+pub fn apply_viewport_resize(
+    mut terminals: Query<(&TerminalConfig, &TerminalState, &mut TerminalTexture), Changed<TerminalConfig>>,
+    atlas: Option<Res<GlyphAtlas>>,
+    mut images: Option<ResMut<Assets<Image>>>,
+) {
+    let (Some(atlas), Some(images)) = (atlas, images.as_mut()) else {
+        return;
+    };
+    for (config, term_state, mut texture) in &mut terminals {
+        renderer::resize_terminal_texture(&mut texture, images, &atlas, term_state, Some(config));
+    }
+}
@@ -0,0 +1,333 @@
+//! Shell integration marks (OSC 133).
+//!
+//! Shells with integration enabled bracket each prompt and command:
+//!
+//! - `OSC 133 ; A` — prompt starts
+//! - `OSC 133 ; B` — prompt ends, the user types a command
+//! - `OSC 133 ; C` — command runs, its output follows
+//! - `OSC 133 ; D [; exit_code]` — command finished
+//!
+//! Alacritty's parser drops OSC strings it doesn't know without telling its
+//! handler or the `EventProxy`, so `TerminalState::process_bytes` hands the
+//! same bytes to a `PromptScanner`. The scanner isn't a second parser: it
+//! jumps from one ESC to the next and only looks inside OSC strings, so
+//! plain text and other escapes cost one byte search. The marks come out as
+//! `TerminalEvent`s, in output order, for gameplay that reacts to the shell
+//! (e.g. a door that opens once a command succeeds).
+//!
+//! Bash doesn't send the marks on its own; `TerminalConfig::shell_integration`
+//! injects them into its prompt (see `inject_prompt_marks`).
+
+use anyhow::{Context, Result};
+use bevy::prelude::*;
+use portable_pty::CommandBuilder;
+use std::fs::{DirBuilder, OpenOptions};
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use log::{info, warn};
+
+use crate::events::{TerminalEvent, TerminalEventKind};
+use crate::terminal::TerminalState;
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+
+/// Longest OSC string kept while looking for a mark. OSC 133 strings are
+/// short; anything longer (clipboard, hyperlinks) is skipped, not buffered.
+const MAX_MARK_LEN: usize = 64;
+
+/// Where the scanner is in the output, carried across reads.
+#[derive(Default)]
+enum ScanState {
+    /// Text or escapes other than OSC
+    #[default]
+    Ground,
+    /// After ESC
+    Escape,
+    /// Inside an OSC string that may be a mark, bytes so far
+    Osc(Vec<u8>),
+    /// Inside an OSC string that isn't a mark
+    SkipOsc,
+}
+
+/// Finds OSC 133 marks in terminal output.
+///
+/// Stateful like the main parser, so a mark split across reads is still
+/// found once.
+#[derive(Default)]
+pub(crate) struct PromptScanner {
+    state: ScanState,
+    marks: Vec<TerminalEventKind>,
+}
+
+impl PromptScanner {
+    /// Scan `bytes`, keeping at most `capacity` marks queued.
+    ///
+    /// Returns how many marks were dropped because the queue was full.
+    pub(crate) fn scan(&mut self, mut bytes: &[u8], capacity: usize) -> u64 {
+        let mut dropped = 0;
+        while !bytes.is_empty() {
+            let consumed = match &mut self.state {
+                ScanState::Ground => match bytes.iter().position(|&b| b == ESC) {
+                    Some(pos) => {
+                        self.state = ScanState::Escape;
+                        pos + 1
+                    }
+                    None => bytes.len(),
+                },
+                ScanState::Escape => {
+                    self.state = match bytes[0] {
+                        b']' => ScanState::Osc(Vec::new()),
+                        ESC => ScanState::Escape,
+                        _ => ScanState::Ground,
+                    };
+                    1
+                }
+                ScanState::Osc(string) => match bytes.iter().position(|&b| b == BEL || b == ESC) {
+                    Some(pos) if string.len() + pos <= MAX_MARK_LEN => {
+                        string.extend_from_slice(&bytes[..pos]);
+                        let mark = parse_osc(string);
+                        // ESC ends the string too; it starts the `ESC \` terminator
+                        self.state = if bytes[pos] == ESC { ScanState::Escape } else { ScanState::Ground };
+                        if let Some(mark) = mark {
+                            if self.marks.len() < capacity {
+                                self.marks.push(mark);
+                            } else {
+                                dropped += 1;
+                            }
+                        }
+                        pos + 1
+                    }
+                    None if string.len() + bytes.len() <= MAX_MARK_LEN => {
+                        string.extend_from_slice(bytes);
+                        bytes.len()
+                    }
+                    _ => {
+                        self.state = ScanState::SkipOsc;
+                        0
+                    }
+                },
+                ScanState::SkipOsc => match bytes.iter().position(|&b| b == BEL || b == ESC) {
+                    Some(pos) => {
+                        self.state = if bytes[pos] == ESC { ScanState::Escape } else { ScanState::Ground };
+                        pos + 1
+                    }
+                    None => bytes.len(),
+                },
+            };
+            bytes = &bytes[consumed..];
+        }
+        dropped
+    }
+
+    /// Take the marks found so far.
+    pub(crate) fn drain(&mut self) -> Vec<TerminalEventKind> {
+        std::mem::take(&mut self.marks)
+    }
+}
+
+/// The mark for a complete OSC string, if it is one.
+fn parse_osc(string: &[u8]) -> Option<TerminalEventKind> {
+    let params: Vec<&[u8]> = string.split(|&b| b == b';').collect();
+    parse_mark(&params)
+}
+
+/// The event for an OSC 133 string, split on `;`.
+///
+/// Extra `key=value` options some shells append are ignored, as is a
+/// missing or malformed exit code.
+fn parse_mark(params: &[&[u8]]) -> Option<TerminalEventKind> {
+    let [b"133", kind, rest @ ..] = params else {
+        return None;
+    };
+    match *kind {
+        b"A" => Some(TerminalEventKind::PromptStart),
+        b"B" => Some(TerminalEventKind::CommandStart),
+        b"C" => Some(TerminalEventKind::CommandExecuted),
+        b"D" => {
+            let exit_code = rest
+                .first()
+                .and_then(|code| std::str::from_utf8(code).ok())
+                .and_then(|code| code.parse().ok());
+            Some(TerminalEventKind::CommandEnd { exit_code })
+        }
+        _ => None,
+    }
+}
+
+/// Sends the shell integration marks found in each terminal's output this
+/// frame.
+///
+/// System: Update
+/// Runs: Every frame, after `dispatch_terminal_events`
+pub fn dispatch_prompt_marks(mut terminals: Query<(Entity, &mut TerminalState)>, mut events: MessageWriter<TerminalEvent>) {
+    for (terminal, mut term_state) in &mut terminals {
+        let marks = term_state.drain_prompt_marks();
+        if !marks.is_empty() {
+            events.write_batch(marks.into_iter().map(|kind| TerminalEvent::new(terminal, kind)));
+        }
+    }
+}
+
+/// Bash startup file for `inject_prompt_marks`: loads the user's
+/// `~/.bashrc` as usual, then adds the marks around whatever prompt it set.
+const BASH_INTEGRATION: &str = r#"[ -f ~/.bashrc ] && . ~/.bashrc
+
+__bevy_terminal_prompt() {
+    local status=$?
+    if [ -n "$__bevy_terminal_started" ]; then
+        printf '\033]133;D;%s\007' "$status"
+    fi
+    __bevy_terminal_started=1
+    printf '\033]133;A\007'
+    return $status
+}
+PROMPT_COMMAND="__bevy_terminal_prompt${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+PS1="$PS1"'\[\033]133;B\007\]'
+PS0='\033]133;C\007'"$PS0"
+"#;
+
+/// Makes `shell_cmd` send OSC 133 marks (`TerminalConfig::shell_integration`).
+///
+/// Bash is started with a generated `--rcfile` that wraps its prompt; other
+/// programs are left alone and logged, since zsh and fish users usually
+/// have integration of their own. Call before adding other arguments.
+///
+/// The rcfile lives in a fresh directory only the player can open, so no
+/// other user can swap its contents before bash sources it. Keep the
+/// returned `BashRcfile` until the shell exits; dropping it deletes both.
+pub(crate) fn inject_prompt_marks(cmd: &mut CommandBuilder, shell_cmd: &str) -> Result<Option<BashRcfile>> {
+    let program = Path::new(shell_cmd).file_stem().and_then(|name| name.to_str());
+    if program != Some("bash") {
+        info!("🐚 No prompt marks for {}; only bash is supported", shell_cmd);
+        return Ok(None);
+    }
+
+    let rcfile = BashRcfile::create()?;
+    cmd.arg("--rcfile");
+    cmd.arg(rcfile.path());
+    Ok(Some(rcfile))
+}
+
+/// Directories created by this process so far, for unique names.
+static RCFILE_DIRS: AtomicU32 = AtomicU32::new(0);
+
+/// `BASH_INTEGRATION` written to `<temp>/bevy-terminal-<pid>-<n>/bashrc`.
+///
+/// The directory is created with mode 0700 and the file with mode 0600,
+/// neither following nor reusing an existing path, so a name another user
+/// claimed first is an error rather than a file of theirs. Both are deleted
+/// on drop.
+pub(crate) struct BashRcfile {
+    dir: PathBuf,
+}
+
+impl BashRcfile {
+    fn create() -> Result<Self> {
+        let index = RCFILE_DIRS.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("bevy-terminal-{}-{}", std::process::id(), index));
+        let mut builder = DirBuilder::new();
+        #[cfg(unix)]
+        builder.mode(0o700);
+        builder
+            .create(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let rcfile = Self { dir };
+
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        options
+            .open(rcfile.path())
+            .and_then(|mut file| file.write_all(BASH_INTEGRATION.as_bytes()))
+            .with_context(|| format!("Failed to write {}", rcfile.path().display()))?;
+        Ok(rcfile)
+    }
+
+    /// The file to pass to `bash --rcfile`.
+    pub(crate) fn path(&self) -> PathBuf {
+        self.dir.join("bashrc")
+    }
+}
+
+impl Drop for BashRcfile {
+    fn drop(&mut self) {
+        if let Err(error) = std::fs::remove_dir_all(&self.dir) {
+            warn!("⚠️  Failed to delete {}: {}", self.dir.display(), error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(bytes: &[u8]) -> Vec<TerminalEventKind> {
+        let mut scanner = PromptScanner::default();
+        scanner.scan(bytes, usize::MAX);
+        scanner.drain()
+    }
+
+    #[test]
+    fn test_exit_code_parsing() {
+        let marks = scan(b"\x1b]133;D;1\x07\x1b]133;D\x07\x1b]133;D;oops\x07\x1b]133;D;0;aid=7\x1b\\");
+        let codes: Vec<_> = marks
+            .iter()
+            .map(|mark| match mark {
+                TerminalEventKind::CommandEnd { exit_code } => *exit_code,
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(codes, vec![Some(1), None, None, Some(0)]);
+    }
+
+    #[test]
+    fn test_other_osc_ignored() {
+        assert!(scan(b"\x1b]2;title\x07\x1b]133;Z\x07\x1b]1337;A\x07").is_empty());
+    }
+
+    #[test]
+    fn test_long_osc_skipped() {
+        // A clipboard write longer than any mark, split across reads
+        let mut scanner = PromptScanner::default();
+        let payload = format!("\x1b]52;c;{}", "QUJD".repeat(64));
+        let (first, second) = payload.as_bytes().split_at(100);
+        scanner.scan(first, usize::MAX);
+        scanner.scan(second, usize::MAX);
+        scanner.scan(b"\x07\x1b]133;A\x07", usize::MAX);
+        assert!(matches!(scanner.drain()[..], [TerminalEventKind::PromptStart]));
+    }
+
+    #[test]
+    fn test_rcfile_private_and_deleted() {
+        let rcfile = BashRcfile::create().unwrap();
+        let path = rcfile.path();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), BASH_INTEGRATION);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(&rcfile.dir), 0o700);
+            assert_eq!(mode(&path), 0o600);
+        }
+
+        // A second terminal's file doesn't reuse the path
+        let other = BashRcfile::create().unwrap();
+        assert_ne!(other.path(), path);
+
+        drop(rcfile);
+        assert!(!path.exists());
+        assert!(!path.parent().unwrap().exists());
+    }
+
+    #[test]
+    fn test_capacity_drops_extra_marks() {
+        let mut scanner = PromptScanner::default();
+        assert_eq!(scanner.scan(b"\x1b]133;A\x07\x1b]133;B\x07\x1b]133;C\x07", 2), 1);
+        assert_eq!(scanner.drain().len(), 2);
+    }
+}
@@ -0,0 +1,60 @@
+//! Orderly teardown when the app exits.
+//!
+//! On `AppExit`, `shutdown_terminal_on_exit` takes every terminal apart in
+//! order, so no shell outlives the game and "save on exit" logic gets a hook:
+//!
+//! 1. each shell is killed and its reader and writer threads joined
+//!    (`PtyResource::shutdown`)
+//! 2. `TerminalEvent`s with `TerminalEventKind::Shutdown` go out with each
+//!    terminal's transcript
+//! 3. plugin-owned textures are removed and the terminal entities despawned,
+//!    which joins custom sources' readers; the render world drops the GPU
+//!    buffers once the extracted data is gone
+
+use bevy::prelude::*;
+use log::info;
+
+use crate::config::TerminalConfig;
+use crate::events::{TerminalEvent, TerminalEventKind};
+use crate::pty::PtyResource;
+use crate::renderer::TerminalTexture;
+use crate::terminal::TerminalState;
+
+/// Tears the terminals down when an `AppExit` message arrives.
+///
+/// System: Last
+/// Runs: Every frame; acts once, on the first `AppExit`
+///
+/// Read the final `TerminalEventKind::Shutdown`s from a system in `Last`
+/// ordered after this one; the app stops once the frame ends.
+#[allow(clippy::type_complexity)]
+pub fn shutdown_terminal_on_exit(
+    mut commands: Commands,
+    mut exits: MessageReader<AppExit>,
+    mut terminals: Query<(Entity, &TerminalState, &TerminalConfig, Option<&mut PtyResource>, Option<&TerminalTexture>)>,
+    mut images: Option<ResMut<Assets<Image>>>,
+    mut events: MessageWriter<TerminalEvent>,
+) {
+    if exits.read().last().is_none() || terminals.is_empty() {
+        return;
+    }
+
+    info!("🛑 App exiting, shutting down terminals...");
+    for (terminal, term_state, config, pty, texture) in &mut terminals {
+        if let Some(mut pty) = pty {
+            pty.shutdown();
+        }
+
+        let transcript = term_state.transcript();
+        events.write(TerminalEvent::new(terminal, TerminalEventKind::Shutdown { transcript }));
+
+        if let Some(texture) = texture {
+            // A game-owned target outlives the terminal
+            let owned = config.target.as_ref() != Some(&texture.handle);
+            if let (true, Some(images)) = (owned, images.as_mut()) {
+                images.remove(&texture.handle);
+            }
+        }
+        commands.entity(terminal).despawn();
+    }
+}
@@ -0,0 +1,226 @@
+//! Saving and restoring terminal state, e.g. in a save game.
+//!
+//! `TerminalState::serialize` captures the screen and scrollback (characters,
+//! colors and attributes), the cursor, the terminal modes, color overrides
+//! and the title. `TerminalState::deserialize` puts them back.
+//!
+//! Only the display is restored: the program that drew it is gone, so the
+//! terminal shows the saved screen but nothing answers input until a new
+//! process attaches (a fresh PTY or `SourceResource`).
+
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::cell::{Cell, Flags};
+use alacritty_terminal::term::color::COUNT as COLOR_COUNT;
+use alacritty_terminal::term::TermMode;
+use alacritty_terminal::vte::ansi::{Color, Handler, Mode, NamedMode, NamedPrivateMode, PrivateMode, Rgb};
+use anyhow::{bail, Context, Result};
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::terminal::TerminalState;
+
+/// Bumped when the snapshot layout changes; older snapshots are rejected.
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Private modes restored from `TermMode` bits.
+const PRIVATE_MODES: [(TermMode, NamedPrivateMode); 12] = [
+    (TermMode::APP_CURSOR, NamedPrivateMode::CursorKeys),
+    (TermMode::ORIGIN, NamedPrivateMode::Origin),
+    (TermMode::LINE_WRAP, NamedPrivateMode::LineWrap),
+    (TermMode::SHOW_CURSOR, NamedPrivateMode::ShowCursor),
+    (TermMode::MOUSE_REPORT_CLICK, NamedPrivateMode::ReportMouseClicks),
+    (TermMode::MOUSE_DRAG, NamedPrivateMode::ReportCellMouseMotion),
+    (TermMode::MOUSE_MOTION, NamedPrivateMode::ReportAllMouseMotion),
+    (TermMode::FOCUS_IN_OUT, NamedPrivateMode::ReportFocusInOut),
+    (TermMode::UTF8_MOUSE, NamedPrivateMode::Utf8Mouse),
+    (TermMode::SGR_MOUSE, NamedPrivateMode::SgrMouse),
+    (TermMode::ALTERNATE_SCROLL, NamedPrivateMode::AlternateScroll),
+    (TermMode::BRACKETED_PASTE, NamedPrivateMode::BracketedPaste),
+];
+
+/// ANSI modes restored from `TermMode` bits.
+const ANSI_MODES: [(TermMode, NamedMode); 2] = [
+    (TermMode::INSERT, NamedMode::Insert),
+    (TermMode::LINE_FEED_NEW_LINE, NamedMode::LineFeedNewLine),
+];
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    version: u32,
+    cols: usize,
+    rows: usize,
+    /// Scrollback oldest first, then the screen
+    lines: Vec<Vec<CellRun>>,
+    /// Combining characters as (index into `lines`, column, characters)
+    zerowidth: Vec<(usize, usize, String)>,
+    /// Cursor as (screen row, column)
+    cursor: (usize, usize),
+    /// `TermMode` bits
+    modes: u32,
+    /// Program color overrides (OSC 4/10/11/12) as (color index, RGB)
+    colors: Vec<(usize, [u8; 3])>,
+    title: Option<String>,
+}
+
+/// Consecutive cells sharing colors and attributes, one char per cell.
+#[derive(Serialize, Deserialize)]
+struct CellRun {
+    text: String,
+    fg: Color,
+    bg: Color,
+    flags: Flags,
+}
+
+impl TerminalState {
+    /// Snapshot the terminal for `deserialize`, e.g. to store in a save game.
+    ///
+    /// Saves the active screen: on the alternate screen (a full-screen
+    /// program) that's what is showing, without scrollback, and it comes back
+    /// as the normal screen.
+    pub fn serialize(&self) -> Vec<u8> {
+        let term = self.term.lock();
+        let grid = term.grid();
+
+        let mut lines = Vec::with_capacity(grid.total_lines());
+        let mut zerowidth = Vec::new();
+        for line in grid.topmost_line().0..grid.screen_lines() as i32 {
+            let row = &grid[Line(line)];
+            let mut runs: Vec<CellRun> = Vec::new();
+            for col in 0..grid.columns() {
+                let cell = &row[Column(col)];
+                if let Some(chars) = cell.zerowidth() {
+                    zerowidth.push((lines.len(), col, chars.iter().collect()));
+                }
+                match runs.last_mut() {
+                    Some(run) if run.fg == cell.fg && run.bg == cell.bg && run.flags == cell.flags => {
+                        run.text.push(cell.c);
+                    }
+                    _ => runs.push(CellRun {
+                        text: cell.c.to_string(),
+                        fg: cell.fg,
+                        bg: cell.bg,
+                        flags: cell.flags,
+                    }),
+                }
+            }
+            lines.push(runs);
+        }
+
+        let colors = (0..COLOR_COUNT)
+            .filter_map(|index| term.colors()[index].map(|rgb| (index, [rgb.r, rgb.g, rgb.b])))
+            .collect();
+
+        let cursor = grid.cursor.point;
+        let snapshot = Snapshot {
+            version: SNAPSHOT_VERSION,
+            cols: grid.columns(),
+            rows: grid.screen_lines(),
+            lines,
+            zerowidth,
+            cursor: (cursor.line.0.max(0) as usize, cursor.column.0),
+            modes: (*term.mode() - TermMode::ALT_SCREEN).bits(),
+            colors,
+            title: self.title.clone(),
+        };
+
+        serde_json::to_vec(&snapshot).expect("terminal snapshot is always serializable")
+    }
+
+    /// Restore a snapshot made by `serialize`, replacing the grid,
+    /// scrollback, cursor, modes, colors and title.
+    ///
+    /// The grid keeps its current size: a snapshot taken at another size is
+    /// reflowed to fit, as on a resize. Display only; see the module docs.
+    pub fn deserialize(&mut self, bytes: &[u8]) -> Result<()> {
+        let snapshot: Snapshot = serde_json::from_slice(bytes).context("invalid terminal snapshot")?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            bail!("unsupported terminal snapshot version {}", snapshot.version);
+        }
+        if snapshot.cols == 0 || snapshot.rows == 0 || snapshot.lines.len() < snapshot.rows {
+            bail!("terminal snapshot is missing its screen");
+        }
+
+        let (cols, rows) = (self.cols, self.rows);
+        if (snapshot.cols, snapshot.rows) != (cols, rows) {
+            self.resize_terminal(snapshot.cols, snapshot.rows);
+        }
+
+        {
+            let mut term = self.term.lock();
+            term.reset_state();
+
+            let screen = Line(0)..Line(snapshot.rows as i32);
+            for (index, runs) in snapshot.lines.iter().enumerate() {
+                // The screen fills top to bottom, then each further line
+                // pushes the top one into history
+                let line = if index < snapshot.rows {
+                    Line(index as i32)
+                } else {
+                    term.grid_mut().scroll_up(&screen, 1);
+                    Line(snapshot.rows as i32 - 1)
+                };
+
+                let row = &mut term.grid_mut()[line];
+                let cells = runs.iter().flat_map(|run| run.text.chars().map(move |c| (c, run)));
+                for (col, (c, run)) in cells.take(snapshot.cols).enumerate() {
+                    row[Column(col)] = Cell {
+                        c,
+                        fg: run.fg,
+                        bg: run.bg,
+                        flags: run.flags,
+                        extra: None,
+                    };
+                }
+                let zerowidth = snapshot.zerowidth.iter().filter(|(i, col, _)| *i == index && *col < snapshot.cols);
+                for (_, col, chars) in zerowidth {
+                    for c in chars.chars() {
+                        row[Column(*col)].push_zerowidth(c);
+                    }
+                }
+            }
+
+            let modes = TermMode::from_bits_truncate(snapshot.modes);
+            for (mode, named) in PRIVATE_MODES {
+                if modes.contains(mode) {
+                    term.set_private_mode(PrivateMode::Named(named));
+                } else {
+                    term.unset_private_mode(PrivateMode::Named(named));
+                }
+            }
+            for (mode, named) in ANSI_MODES {
+                if modes.contains(mode) {
+                    term.set_mode(Mode::Named(named));
+                } else {
+                    term.unset_mode(Mode::Named(named));
+                }
+            }
+            if modes.contains(TermMode::APP_KEYPAD) {
+                term.set_keypad_application_mode();
+            }
+
+            for index in 0..COLOR_COUNT {
+                term.reset_color(index);
+            }
+            for &(index, [r, g, b]) in snapshot.colors.iter().filter(|(index, _)| *index < COLOR_COUNT) {
+                term.set_color(index, Rgb { r, g, b });
+            }
+
+            // After the modes: origin mode moves the cursor home
+            let (line, column) = snapshot.cursor;
+            term.grid_mut().cursor.point = Point::new(
+                Line(line.min(snapshot.rows - 1) as i32),
+                Column(column.min(snapshot.cols - 1)),
+            );
+        }
+
+        if (snapshot.cols, snapshot.rows) != (cols, rows) {
+            self.resize_terminal(cols, rows);
+        }
+        self.title = snapshot.title;
+        self.unseen_output = false;
+        self.mark_dirty();
+        info!("💾 Terminal state restored ({} lines)", snapshot.lines.len());
+        Ok(())
+    }
+}
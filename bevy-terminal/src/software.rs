@@ -0,0 +1,217 @@
+//! Software render backend (`RenderBackend::Cpu`).
+//!
+//! Shades every texel on the CPU with the compute shader's math, reading the
+//! extracted cells (`TerminalCpuBuffer`) and the `GlyphAtlas` pixels, and
+//! writes the result into the terminal texture's image data for Bevy to
+//! upload. It needs no compute shaders or render pipelines, and runs without
+//! a render app at all, so tests can check the actual pixels. It is slow:
+//! meant as a fallback and for golden-image tests, not for large terminals.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::TextureFormat;
+
+use crate::atlas::GlyphAtlas;
+use crate::config::{CursorBlinkStyle, CursorStyle};
+use crate::gpu_types::{
+    CELL_FLAG_BOLD, CELL_FLAG_COLOR_GLYPH, CELL_FLAG_CURSOR, CELL_FLAG_INVERSE, CELL_FLAG_ITALIC, CELL_FLAG_SELECTED, CELL_FLAG_UNDERLINE,
+    CELL_FLAG_WIDE, CELL_FLAG_WIDE_SPACER, CURSOR_BLINK_FADE, CURSOR_STYLE_BAR, CURSOR_STYLE_UNDERLINE, GLYPH_BLANK,
+    STATUS_BAR_BOTTOM, STATUS_BAR_NONE, STATUS_BAR_TOP,
+};
+use crate::gpu_prep::pack_color;
+use crate::render_node::ExtractedTerminalData;
+
+/// Horizontal shift per pixel of height for faux italic (`ITALIC_SLANT` in
+/// the shaders).
+const ITALIC_SLANT: f32 = 0.2;
+
+/// How far bold text moves toward white (`BOLD_LIGHTEN` in the shaders).
+const BOLD_LIGHTEN: f32 = 0.25;
+
+/// Draws every terminal into its texture on the CPU.
+///
+/// System: PostUpdate
+/// Runs: Every frame with `RenderBackend::Cpu`, after the extraction
+/// data is updated
+pub fn render_terminal_to_texture(
+    terminals: Query<&ExtractedTerminalData>,
+    atlas: Option<Res<GlyphAtlas>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let Some(atlas) = atlas else {
+        return;
+    };
+
+    for data in &terminals {
+        if let Some(image) = images.get_mut(&data.texture_handle) {
+            render_to_image(data, &atlas, image);
+        }
+    }
+}
+
+/// Shades one terminal into `image`, texel by texel.
+///
+/// Texels past the grid (and status bar) are left alone.
+pub fn render_to_image(data: &ExtractedTerminalData, atlas: &GlyphAtlas, image: &mut Image) {
+    let status_rows = u32::from(data.status_bar != STATUS_BAR_NONE);
+    let width = (data.term_cols * data.cell_width).min(image.width());
+    let height = ((data.term_rows + status_rows) * data.cell_height).min(image.height());
+
+    for y in 0..height {
+        for x in 0..width {
+            write_texel(image, x, y, shade_texel(data, atlas, x, y));
+        }
+    }
+}
+
+/// Color of texel (`x`, `y`) of the terminal texture, as the compute shader
+/// computes it.
+pub fn shade_texel(data: &ExtractedTerminalData, atlas: &GlyphAtlas, x: u32, y: u32) -> Vec4 {
+    let (cell_width, cell_height) = (data.cell_width.max(1), data.cell_height.max(1));
+    let (cell_x, cell_y) = (x / cell_width, y / cell_height);
+
+    // The status bar row takes the top or bottom texture row; the grid shifts around it
+    let status_row = match data.status_bar {
+        STATUS_BAR_TOP => Some(0),
+        STATUS_BAR_BOTTOM => Some(data.term_rows),
+        _ => None,
+    };
+    let cell = if status_row == Some(cell_y) {
+        data.status_cells.get(cell_x as usize)
+    } else {
+        let grid_y = cell_y - u32::from(data.status_bar == STATUS_BAR_TOP);
+        data.cells.get((grid_y * data.term_cols + cell_x) as usize)
+    };
+    // Nothing prepared yet: show the background
+    let Some(&cell) = cell else {
+        return unpack_color(pack_color(data.background));
+    };
+
+    let intra_x = x % cell_width;
+    let intra_y = y % cell_height;
+
+    // A wide character spans its cell and the spacer after it, the glyph
+    // two atlas slots wide; positions below are within that span
+    let spacer = cell.flags & CELL_FLAG_WIDE_SPACER != 0;
+    let span_x = intra_x + if spacer { cell_width } else { 0 };
+    let span_width = if spacer || cell.flags & CELL_FLAG_WIDE != 0 { 2 } else { 1 } * cell_width;
+
+    // Faux italic: shear the upright glyph
+    let mut glyph_x = span_x as i32;
+    if cell.flags & CELL_FLAG_ITALIC != 0 {
+        let from_middle = cell_height as f32 * 0.5 - intra_y as f32;
+        glyph_x -= round_ties_even(from_middle * ITALIC_SLANT) as i32;
+    }
+
+    let mut texel = Vec4::ZERO;
+    if cell.glyph_index != GLYPH_BLANK && glyph_x >= 0 && (glyph_x as u32) < span_width {
+        let glyph_x = glyph_x as u32;
+        let slot = cell.glyph_index + glyph_x / cell_width;
+        let atlas_cols = data.atlas_cols.max(1);
+        let atlas_x = (slot % atlas_cols) * cell_width + glyph_x % cell_width;
+        let atlas_y = (slot / atlas_cols) * cell_height + intra_y;
+        texel = atlas_texel(atlas, atlas_x, atlas_y);
+    }
+    let mut alpha = texel.w;
+    if cell.flags & CELL_FLAG_UNDERLINE != 0 && intra_y == cell_height - 1 {
+        alpha = 1.0;
+    }
+
+    let mut fg = unpack_color(cell.fg_color);
+    let mut bg = unpack_color(cell.bg_color);
+    if cell.flags & CELL_FLAG_BOLD != 0 {
+        fg = fg.truncate().lerp(Vec3::ONE, BOLD_LIGHTEN).extend(fg.w);
+    }
+    if cell.flags & CELL_FLAG_INVERSE != 0 {
+        std::mem::swap(&mut fg, &mut bg);
+    }
+    if cell.flags & CELL_FLAG_COLOR_GLYPH != 0 && texel.w > 0.0 {
+        fg = texel.truncate().extend(fg.w);
+    }
+
+    let cursor_style = match data.cursor_style {
+        CURSOR_STYLE_BAR => CursorStyle::Bar,
+        CURSOR_STYLE_UNDERLINE => CursorStyle::Underline,
+        _ => CursorStyle::Block,
+    };
+    let mut color = if cell.flags & CELL_FLAG_CURSOR != 0 && cursor_style.covers(span_x, intra_y, cell_width, cell_height) {
+        let blink_style = match data.cursor_blink {
+            CURSOR_BLINK_FADE => CursorBlinkStyle::Fade,
+            _ => CursorBlinkStyle::Hard,
+        };
+        let blink = blink_style.alpha(data.blink_phase) * data.cursor_alpha;
+        cursor_style.composite(fg, bg, unpack_color(data.cursor_color), blink, alpha)
+    } else {
+        bg.lerp(fg, alpha)
+    };
+
+    if cell.flags & CELL_FLAG_SELECTED != 0 {
+        let highlight = unpack_color(data.selection_color);
+        color = color.truncate().lerp(highlight.truncate(), highlight.w).extend(color.w);
+    }
+
+    // Watermark: upright glyphs blended over whatever is underneath
+    let mark = cell_x
+        .checked_sub(data.watermark_col)
+        .filter(|_| cell_y == data.watermark_row)
+        .and_then(|index| data.watermark_cells.get(index as usize));
+    if let Some(mark) = mark.filter(|mark| mark.glyph_index != GLYPH_BLANK) {
+        let atlas_cols = data.atlas_cols.max(1);
+        let mark_x = (mark.glyph_index % atlas_cols) * cell_width + intra_x;
+        let mark_y = (mark.glyph_index / atlas_cols) * cell_height + intra_y;
+        let mark_alpha = atlas_alpha(atlas, mark_x, mark_y);
+        color = color.lerp(unpack_color(mark.fg_color), mark_alpha * data.watermark_opacity);
+    }
+
+    color
+}
+
+/// WGSL's `round`: halfway cases go to the even neighbor.
+fn round_ties_even(value: f32) -> f32 {
+    if (value - value.trunc()).abs() == 0.5 {
+        2.0 * (value / 2.0).round()
+    } else {
+        value.round()
+    }
+}
+
+/// Glyph coverage at atlas texel (`x`, `y`), 0 outside the atlas.
+fn atlas_alpha(atlas: &GlyphAtlas, x: u32, y: u32) -> f32 {
+    atlas_texel(atlas, x, y).w
+}
+
+/// Atlas texel (`x`, `y`) as 0.0-1.0 RGBA, transparent outside the atlas.
+fn atlas_texel(atlas: &GlyphAtlas, x: u32, y: u32) -> Vec4 {
+    if x >= atlas.atlas_width || y >= atlas.atlas_height {
+        return Vec4::ZERO;
+    }
+    let index = ((y * atlas.atlas_width + x) * 4) as usize;
+    atlas.texture_data.get(index..index + 4).map_or(Vec4::ZERO, |texel| {
+        Vec4::new(texel[0] as f32, texel[1] as f32, texel[2] as f32, texel[3] as f32) / 255.0
+    })
+}
+
+/// Cell color packed as 0xAABBGGRR, as 0.0-1.0 channels.
+fn unpack_color(packed: u32) -> Vec4 {
+    let [r, g, b, a] = packed.to_le_bytes().map(|channel| channel as f32 / 255.0);
+    Vec4::new(r, g, b, a)
+}
+
+/// Store `color` at (`x`, `y`) the way a storage texture write does: raw
+/// values, 8-bit channels rounded to nearest.
+fn write_texel(image: &mut Image, x: u32, y: u32, color: Vec4) {
+    let format = image.texture_descriptor.format;
+    let width = image.width();
+    match format {
+        TextureFormat::Rgba8Unorm | TextureFormat::Bgra8Unorm => {
+            let [r, g, b, a] = color.to_array().map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+            let texel = if format == TextureFormat::Bgra8Unorm { [b, g, r, a] } else { [r, g, b, a] };
+            let offset = ((y * width + x) * 4) as usize;
+            if let Some(bytes) = image.data.as_mut().and_then(|data| data.get_mut(offset..offset + 4)) {
+                bytes.copy_from_slice(&texel);
+            }
+        }
+        _ => {
+            let _ = image.set_color_at(x, y, LinearRgba::from_vec4(color).into());
+        }
+    }
+}
@@ -0,0 +1,363 @@
+//! Custom input sources.
+//!
+//! Feeds the terminal from any `Read` (a serial port, a socket, a replay file)
+//! instead of, or alongside, the PTY. Bytes are read on a background thread
+//! exactly like PTY output and drained by `poll_source` each frame.
+//!
+//! Custom sources have no termios line discipline, so output using bare `\n`
+//! renders as staircase text. Enable newline translation for such sources.
+//!
+//! Sources carrying untrusted text (chat, player names) should enable input
+//! sanitizing: otherwise anyone who can put bytes in the stream can resize
+//! the terminal, retitle it or answer-back into a program.
+
+use bevy::prelude::*;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc::{channel, Receiver}, Arc, Mutex};
+use std::thread::JoinHandle;
+use log::error;
+
+use crate::config::TerminalConfig;
+use crate::pty::{join_thread, spawn_reader_thread, ReaderMessage};
+use crate::terminal::TerminalState;
+
+/// A byte stream the terminal can display.
+pub trait InputSource: Read + Send + 'static {}
+
+impl<T: Read + Send + 'static> InputSource for T {}
+
+/// Component holding a custom input source.
+///
+/// Insert it on a terminal entity to feed that terminal; the plugin polls it
+/// whenever present.
+#[derive(Component)]
+pub struct SourceResource {
+    /// Channel receiver for source output (filled by background thread)
+    pub rx: Arc<Mutex<Receiver<ReaderMessage>>>,
+    /// Background reader thread. Joined on drop.
+    pub reader_thread: Option<JoinHandle<()>>,
+    /// Translate bare `\n` into `\r\n` before processing (ONLCR).
+    ///
+    /// Off by default; a PTY's line discipline already does this.
+    pub translate_newlines: bool,
+    /// Strip escape sequences and control characters other than SGR colors
+    /// and basic line control (see `InputSanitizer`).
+    ///
+    /// Off by default, for trusted streams.
+    pub sanitize_input: bool,
+    /// Sanitizer state carried across chunks
+    sanitizer: InputSanitizer,
+    /// Tells the reader thread to stop forwarding output.
+    shutdown_flag: Arc<AtomicBool>,
+    /// Whether the last processed byte was `\r`, so a CRLF split across
+    /// chunks isn't doubled.
+    last_was_cr: bool,
+    /// Reader thread failure not yet reported as a `TerminalEvent`
+    pub(crate) failure: Option<String>,
+}
+
+impl SourceResource {
+    /// Starts reading `source` on a background thread.
+    pub fn new(source: impl InputSource) -> Self {
+        let (tx, rx) = channel();
+        let shutdown_flag = Arc::new(AtomicBool::new(false));
+        let reader_thread = spawn_reader_thread("Source", source, tx, shutdown_flag.clone());
+
+        Self {
+            rx: Arc::new(Mutex::new(rx)),
+            reader_thread: Some(reader_thread),
+            translate_newlines: false,
+            sanitize_input: false,
+            sanitizer: InputSanitizer::default(),
+            shutdown_flag,
+            last_was_cr: false,
+            failure: None,
+        }
+    }
+
+    /// Translate bare `\n` into `\r\n` for sources without a line discipline.
+    pub fn with_newline_translation(mut self, enabled: bool) -> Self {
+        self.translate_newlines = enabled;
+        self
+    }
+
+    /// Strip dangerous escape sequences, for sources carrying untrusted text.
+    pub fn with_input_sanitizing(mut self, enabled: bool) -> Self {
+        self.sanitize_input = enabled;
+        self
+    }
+}
+
+impl Drop for SourceResource {
+    fn drop(&mut self) {
+        if let Some(handle) = self.reader_thread.take() {
+            self.shutdown_flag.store(true, Ordering::Release);
+            join_thread("Source reader", handle);
+        }
+    }
+}
+
+/// Converts bare `\n` into `\r\n`, leaving existing `\r\n` pairs alone.
+///
+/// `last_was_cr` carries state across chunk boundaries.
+pub fn translate_newlines(bytes: &[u8], last_was_cr: &mut bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len() + bytes.len() / 8);
+    for &byte in bytes {
+        if byte == b'\n' && !*last_was_cr {
+            out.push(b'\r');
+        }
+        out.push(byte);
+        *last_was_cr = byte == b'\r';
+    }
+    out
+}
+
+/// Sanitizer state: where in an escape sequence the stream is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum SanitizeState {
+    #[default]
+    Ground,
+    /// After `ESC`
+    Escape,
+    /// After `ESC` and intermediates (`ESC ( 0`, ...)
+    EscapeIntermediate,
+    /// Collecting CSI parameters
+    Csi,
+    /// Inside an overlong CSI, dropped up to its final byte
+    CsiIgnore,
+    /// Inside an OSC, DCS, SOS, PM or APC string
+    String,
+    /// `ESC` inside a string: `\` ends it, anything else starts a new escape
+    StringEscape,
+}
+
+/// Longest CSI parameter string kept for an SGR sequence.
+const MAX_SGR_PARAMS: usize = 64;
+
+/// Reduces a byte stream to text, SGR colors and basic line control.
+///
+/// Kept: printable text (UTF-8), `\r`, `\n`, `\t`, backspace and SGR
+/// sequences (`ESC [ ... m`). Dropped: every other escape sequence (cursor
+/// movement, resizes, modes, OSC titles and colors, DCS, queries that make
+/// the terminal answer), other C0 controls and C1 controls in either
+/// encoding. Stateful, so sequences split across reads are still caught.
+#[derive(Default)]
+pub struct InputSanitizer {
+    state: SanitizeState,
+    /// Parameter and intermediate bytes of the current CSI
+    params: Vec<u8>,
+    /// Continuation bytes the current UTF-8 character still needs
+    utf8_remaining: u8,
+    /// A held `0xC2` lead byte, which starts a C1 control when followed by
+    /// `0x80`..=`0x9F`
+    held_c2: bool,
+}
+
+impl InputSanitizer {
+    /// Sanitize the next chunk of the stream.
+    pub fn sanitize(&mut self, bytes: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(bytes.len());
+        for &byte in bytes {
+            self.step(byte, &mut out);
+        }
+        out
+    }
+
+    fn step(&mut self, byte: u8, out: &mut Vec<u8>) {
+        const ESC: u8 = 0x1B;
+        // CAN and SUB abort any sequence
+        if matches!(byte, 0x18 | 0x1A) {
+            self.state = SanitizeState::Ground;
+            self.params.clear();
+            return;
+        }
+
+        match self.state {
+            SanitizeState::Ground => self.ground(byte, out),
+            SanitizeState::Escape => match byte {
+                b'[' => self.state = SanitizeState::Csi,
+                b']' | b'P' | b'X' | b'^' | b'_' => self.state = SanitizeState::String,
+                0x20..=0x2F => self.state = SanitizeState::EscapeIntermediate,
+                ESC | 0x00..=0x1F => {}
+                _ => self.state = SanitizeState::Ground,
+            },
+            SanitizeState::EscapeIntermediate => match byte {
+                ESC => self.state = SanitizeState::Escape,
+                0x30..=0x7E => self.state = SanitizeState::Ground,
+                _ => {}
+            },
+            SanitizeState::Csi => match byte {
+                ESC => {
+                    self.params.clear();
+                    self.state = SanitizeState::Escape;
+                }
+                0x20..=0x3F if self.params.len() >= MAX_SGR_PARAMS => {
+                    self.params.clear();
+                    self.state = SanitizeState::CsiIgnore;
+                }
+                0x20..=0x3F => self.params.push(byte),
+                0x40..=0x7E => {
+                    let sgr = byte == b'm'
+                        && self.params.iter().all(|&param| param.is_ascii_digit() || param == b';' || param == b':');
+                    if sgr {
+                        out.extend_from_slice(b"\x1b[");
+                        out.extend_from_slice(&self.params);
+                        out.push(b'm');
+                    }
+                    self.params.clear();
+                    self.state = SanitizeState::Ground;
+                }
+                _ => {}
+            },
+            SanitizeState::CsiIgnore => match byte {
+                ESC => self.state = SanitizeState::Escape,
+                0x40..=0x7E => self.state = SanitizeState::Ground,
+                _ => {}
+            },
+            SanitizeState::String => match byte {
+                0x07 => self.state = SanitizeState::Ground,
+                ESC => self.state = SanitizeState::StringEscape,
+                _ => {}
+            },
+            SanitizeState::StringEscape => {
+                if byte == b'\\' {
+                    self.state = SanitizeState::Ground;
+                } else {
+                    self.state = SanitizeState::Escape;
+                    self.step(byte, out);
+                }
+            }
+        }
+    }
+
+    /// Outside escape sequences: pass text, drop controls.
+    fn ground(&mut self, byte: u8, out: &mut Vec<u8>) {
+        if std::mem::take(&mut self.held_c2) {
+            if (0x80..=0x9F).contains(&byte) {
+                // UTF-8 encoded C1 control
+                self.utf8_remaining = 0;
+                return;
+            }
+            out.push(0xC2);
+        }
+
+        match byte {
+            0x1B => self.state = SanitizeState::Escape,
+            b'\n' | b'\r' | b'\t' | 0x08 => out.push(byte),
+            0x00..=0x1F | 0x7F => {}
+            0x80..=0xBF if self.utf8_remaining > 0 => {
+                self.utf8_remaining -= 1;
+                out.push(byte);
+            }
+            // Raw C1 control
+            0x80..=0x9F => {}
+            0xC2 => {
+                self.held_c2 = true;
+                self.utf8_remaining = 1;
+            }
+            _ => {
+                self.utf8_remaining = match byte {
+                    0xC3..=0xDF => 1,
+                    0xE0..=0xEF => 2,
+                    0xF0..=0xF4 => 3,
+                    _ => 0,
+                };
+                out.push(byte);
+            }
+        }
+    }
+}
+
+/// Polls each source channel and feeds bytes to its terminal.
+///
+/// System: Update
+/// Runs: Every frame
+///
+/// Honors `TerminalConfig::max_bytes_per_frame` like `poll_pty`.
+pub fn poll_source(mut terminals: Query<(&mut SourceResource, &mut TerminalState, &TerminalConfig)>) {
+    for (mut source, mut term_state, config) in &mut terminals {
+        drain_source(&mut source, &mut term_state, config.max_bytes_per_frame);
+    }
+}
+
+/// `poll_source` for one terminal.
+fn drain_source(source: &mut SourceResource, term_state: &mut TerminalState, budget: Option<usize>) {
+    let Ok(rx) = source.rx.try_lock() else {
+        return;
+    };
+
+    while let Ok(message) = rx.try_recv() {
+        let bytes = match message {
+            ReaderMessage::Output(bytes) => bytes,
+            ReaderMessage::Failed(reason) => {
+                error!("❌ {}", reason);
+                source.failure = Some(reason);
+                continue;
+            }
+        };
+
+        let bytes = if source.sanitize_input {
+            source.sanitizer.sanitize(&bytes)
+        } else {
+            bytes
+        };
+
+        if source.translate_newlines {
+            let translated = translate_newlines(&bytes, &mut source.last_was_cr);
+            term_state.feed_bytes(&translated, budget.is_some());
+        } else {
+            term_state.feed_bytes(&bytes, budget.is_some());
+        }
+    }
+
+    if let Some(budget) = budget {
+        term_state.process_pending(budget);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_bare_lf() {
+        let mut last_was_cr = false;
+        assert_eq!(translate_newlines(b"a\nb\n", &mut last_was_cr), b"a\r\nb\r\n");
+    }
+
+    #[test]
+    fn test_translate_keeps_crlf_across_chunks() {
+        let mut last_was_cr = false;
+        assert_eq!(translate_newlines(b"a\r", &mut last_was_cr), b"a\r");
+        assert_eq!(translate_newlines(b"\nb\r\n", &mut last_was_cr), b"\nb\r\n");
+    }
+
+    #[test]
+    fn test_sanitize_keeps_text_and_sgr() {
+        let mut sanitizer = InputSanitizer::default();
+        let input = "\x1b[1;38;2;255:0:0mé日\x1b[0m\tok\r\n".as_bytes();
+        assert_eq!(sanitizer.sanitize(input), input);
+    }
+
+    #[test]
+    fn test_sanitize_drops_other_sequences() {
+        let mut sanitizer = InputSanitizer::default();
+        let input = b"a\x1b[2J\x1b[?1049hb\x1b]2;pwned\x07c\x1bPq#0\x1b\\d\x1b(0e\x07\x1bcf\x9b6ng\xc2\x9b6nh";
+        // C1 controls go, but (as in the parser) don't start a sequence
+        assert_eq!(sanitizer.sanitize(input), b"abcdef6ng6nh");
+
+        // Private SGR-lookalikes are dropped too
+        assert_eq!(sanitizer.sanitize(b"\x1b[>4;2m\x1b[?5m."), b".");
+    }
+
+    #[test]
+    fn test_sanitize_across_chunks() {
+        let mut sanitizer = InputSanitizer::default();
+        assert_eq!(sanitizer.sanitize(b"x\x1b]0;ti"), b"x");
+        assert_eq!(sanitizer.sanitize(b"tle\x1b"), b"");
+        assert_eq!(sanitizer.sanitize(b"\\y\x1b[3"), b"y");
+        assert_eq!(sanitizer.sanitize(b"1mz\xc2"), b"\x1b[31mz");
+        assert_eq!(sanitizer.sanitize(b"\xa9"), "©".as_bytes());
+    }
+}
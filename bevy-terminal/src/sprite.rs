@@ -0,0 +1,130 @@
+//! One-call sprite setup for showing the terminal.
+//!
+//! The terminal texture is an ordinary `Image`, so any sprite, UI node or
+//! material can show it. `spawn_terminal_sprite` covers the common case of a
+//! 2D sprite in the world:
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use bevy_terminal::prelude::*;
+//!
+//! /// Tiny CRT on the character's head; zoom by changing the transform's scale.
+//! fn spawn_crt(mut commands: Commands, terminals: Query<(Entity, &TerminalTexture), Added<TerminalTexture>>) {
+//!     for (terminal, texture) in &terminals {
+//!         let transform = Transform::from_xyz(0.0, 0.0, 1.0).with_scale(Vec3::splat(0.05));
+//!         spawn_terminal_sprite(&mut commands, terminal, texture, transform);
+//!     }
+//! }
+//!
+//! App::new()
+//!     .add_plugins((DefaultPlugins.set(ImagePlugin::default_nearest()), TerminalPlugin::default()))
+//!     .add_systems(Update, spawn_crt)
+//!     .run();
+//! ```
+//!
+//! Add `TerminalAutosize` to the sprite to size the grid to whatever fits
+//! the sprite's on-screen area instead of the window.
+
+use bevy::prelude::*;
+
+use crate::atlas::GlyphAtlas;
+use crate::config::TerminalConfig;
+use crate::renderer::TerminalTexture;
+use crate::resize::ResizeTerminal;
+use crate::terminal::TerminalState;
+
+/// Marks sprites spawned by `spawn_terminal_sprite`, with the terminal
+/// entity they show. Mouse input over the sprite goes to that terminal.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TerminalSprite(pub Entity);
+
+/// Spawns a sprite showing `terminal`'s texture and returns its entity.
+///
+/// At scale 1.0 one texel covers one world unit; see
+/// `renderer::integer_scale` for a crisp whole-number fit to a window. Add
+/// your own components with `commands.entity(entity).insert(...)`.
+///
+/// The `GlobalTransform` starts at `transform` rather than identity, so a
+/// `TerminalAutosize` added to a root sprite fits the grid at the initial
+/// scale on the first frame instead of resizing twice.
+pub fn spawn_terminal_sprite(
+    commands: &mut Commands,
+    terminal: Entity,
+    texture: &TerminalTexture,
+    transform: Transform,
+) -> Entity {
+    commands
+        .spawn((
+            Sprite {
+                image: texture.handle.clone(),
+                ..default()
+            },
+            transform,
+            GlobalTransform::from(transform),
+            TerminalSprite(terminal),
+        ))
+        .id()
+}
+
+/// Resizes `terminal`'s grid to fill `width` × `height` world units.
+///
+/// Put it on the entity showing the terminal texture. World units per texel
+/// come from the entity's `GlobalTransform` scale, as for a sprite (one unit
+/// per texel at scale 1.0); on a mesh, scale the entity the same way. The
+/// grid gets as many whole cells as fit, so a zoom that animates the scale
+/// resizes the grid as it goes.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct TerminalAutosize {
+    /// The terminal entity to resize
+    pub terminal: Entity,
+    /// Width to fill, in world units
+    pub width: f32,
+    /// Height to fill, in world units (status bar included); `None` keeps
+    /// the current row count
+    pub height: Option<f32>,
+}
+
+/// Whole cells of `cell_size` texels that fit in `size` world units, at
+/// `units_per_texel` world units per texel. At least one.
+pub fn cells_fitting(size: f32, cell_size: u32, units_per_texel: f32) -> usize {
+    let cells = size / (cell_size as f32 * units_per_texel);
+    if cells.is_finite() && cells >= 1.0 {
+        cells as usize
+    } else {
+        1
+    }
+}
+
+/// Sends `ResizeTerminal` when a `TerminalAutosize` target fits a different grid.
+///
+/// System: Update
+/// Runs: Every frame, before `apply_terminal_resize`; only acts on terminals
+/// some entity has a `TerminalAutosize` for (the first one wins)
+pub fn autosize_terminal(
+    targets: Query<(&TerminalAutosize, &GlobalTransform)>,
+    atlas: Res<GlyphAtlas>,
+    terminals: Query<(&TerminalState, &TerminalConfig)>,
+    mut resize: MessageWriter<ResizeTerminal>,
+) {
+    let mut sized = Vec::new();
+    for (autosize, transform) in &targets {
+        let terminal = autosize.terminal;
+        let Ok((term_state, config)) = terminals.get(terminal) else {
+            continue;
+        };
+        if sized.contains(&terminal) {
+            continue;
+        }
+        sized.push(terminal);
+
+        let scale = transform.compute_transform().scale;
+        let cols = cells_fitting(autosize.width, atlas.cell_width, scale.x);
+        let rows = autosize.height.map_or(term_state.rows, |height| {
+            cells_fitting(height, atlas.cell_height, scale.y).saturating_sub(config.extra_rows()).max(1)
+        });
+
+        if (cols, rows) != (term_state.cols, term_state.rows) {
+            resize.write(ResizeTerminal { terminal, cols, rows });
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Runtime counters for monitoring the terminal.
+//!
+//! Each terminal entity carries a `TerminalStats`, refreshed every frame
+//! from its `TerminalState`; games read it for debug overlays or to tune
+//! settings like `TerminalConfig::event_capacity`.
+
+use bevy::prelude::*;
+use log::warn;
+use std::time::Duration;
+
+use crate::terminal::TerminalState;
+
+/// Time constant of the `TerminalStats::bytes_per_second` smoothing.
+///
+/// A burst shows up within a few frames and fades over about a second.
+pub const THROUGHPUT_SMOOTHING: Duration = Duration::from_millis(250);
+
+/// Counters of one terminal, updated each frame by `update_terminal_stats`.
+#[derive(Component, Clone, Debug, Default)]
+pub struct TerminalStats {
+    /// alacritty events dropped because the event channel was full
+    /// (see `TerminalConfig::event_capacity`)
+    pub dropped_events: u64,
+    /// PTY output rate, smoothed over roughly `THROUGHPUT_SMOOTHING`.
+    /// Updated by `poll_pty`; drive "data streaming" effects with it.
+    pub bytes_per_second: f32,
+    /// Total bytes read from the PTY
+    pub output_bytes: u64,
+}
+
+impl TerminalStats {
+    /// Fold `bytes` read over the last `elapsed` into the throughput.
+    ///
+    /// Exponential smoothing weighted by `elapsed`, so the result doesn't
+    /// depend on the frame or poll rate. Zero `elapsed` only counts the bytes;
+    /// `poll_pty` avoids that by carrying them into the next sample.
+    pub fn record_output(&mut self, bytes: usize, elapsed: Duration) {
+        self.output_bytes += bytes as u64;
+        let dt = elapsed.as_secs_f32();
+        if dt <= 0.0 {
+            return;
+        }
+        let weight = 1.0 - (-dt / THROUGHPUT_SMOOTHING.as_secs_f32()).exp();
+        self.bytes_per_second += (bytes as f32 / dt - self.bytes_per_second) * weight;
+    }
+}
+
+/// Copies each terminal's counters into its `TerminalStats`.
+///
+/// System: Update
+/// Runs: Every frame, after `dispatch_terminal_events`
+pub fn update_terminal_stats(mut terminals: Query<(&TerminalState, &mut TerminalStats)>) {
+    for (term_state, mut stats) in &mut terminals {
+        let dropped_events = term_state.dropped_events();
+        if dropped_events > stats.dropped_events {
+            warn!(
+                "⚠️  Event channel full, dropped {} terminal events",
+                dropped_events - stats.dropped_events
+            );
+            stats.dropped_events = dropped_events;
+        }
+    }
+}
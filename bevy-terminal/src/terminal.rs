@@ -1,28 +1,59 @@
 //! Core terminal plugin definition and terminal state management.
 
 use alacritty_terminal::event::{Event as AlacEvent, EventListener};
-use alacritty_terminal::grid::Dimensions;
-use alacritty_terminal::index::{Column, Line};
+use alacritty_terminal::grid::{Dimensions, Scroll};
+use alacritty_terminal::index::{Column, Line, Point, Side};
+use alacritty_terminal::selection::{Selection, SelectionType};
 use alacritty_terminal::sync::FairMutex;
-use alacritty_terminal::term::{Config as AlacConfig, Term};
-use alacritty_terminal::vte::ansi::Processor;
-use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
-use bevy::asset::{RenderAssetUsages, load_internal_asset, uuid_handle};
+use alacritty_terminal::grid::Row;
+use alacritty_terminal::term::cell::{Cell, Flags};
+use alacritty_terminal::term::color::COUNT as COLOR_COUNT;
+use alacritty_terminal::term::{Config as AlacConfig, Term, TermMode};
+use alacritty_terminal::vte::ansi::{CursorShape, Handler, NamedColor, Processor};
+use bevy::render::render_resource::TextureFormat;
+use bevy::asset::{load_internal_asset, uuid_handle};
+use bevy::input::mouse::{MouseButtonInput, MouseWheel};
+use bevy::window::{CursorMoved, Ime};
 use bevy::prelude::*;
-use std::sync::Arc;
-use log::info;
+use std::path::PathBuf;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use log::{error, info, warn};
 
-use crate::atlas::GlyphAtlas;
-use crate::font::FontMetrics;
-use crate::input;
+use crate::atlas::{self, AtlasUploads, GlyphAtlas};
+use crate::clock::{self, TerminalClock};
+use crate::colors::{default_color, resolve_color, resolve_dim_color, TerminalPalette};
+use crate::config::{env_grid_size, CursorBlinkStyle, CursorStyle, FallbackGlyph, GridViewport, LoadingIndicator, RenderBackend, StatusBarConfig, TerminalConfig, TerminalTheme, WatermarkConfig};
+use crate::events::{self, TerminalEvent, TerminalEventKind};
+use crate::font::{FontSource, GlyphPlacement};
+use crate::input::{self, MouseMode, TerminalWrite};
+use crate::instance::PrimaryTerminal;
+use crate::paste::{self, TerminalPaste};
 use crate::pty;
+#[cfg(any(test, feature = "test-util"))]
+use crate::pty::PtyResource;
 use crate::renderer;
-use crate::gpu_prep;
+use crate::resize::{self, ResizeTerminal};
+use crate::shell_integration::{self, PromptScanner};
+use crate::shutdown;
+use crate::gpu_prep::{self, TerminalCpuBuffer};
+use crate::source;
+use crate::stats::{self, TerminalStats};
 use crate::render_node;
+use crate::sprite;
 
 pub const TERMINAL_SHADER_HANDLE: Handle<Shader> = uuid_handle!("be77e7aa-0000-0000-0000-000000000001");
+pub const TERMINAL_FRAGMENT_SHADER_HANDLE: Handle<Shader> = uuid_handle!("be77e7aa-0000-0000-0000-000000000002");
 
-/// Simple dimensions struct for MVP (hardcoded 120×30).
+/// Default cap on queued alacritty events (see `TerminalConfig::event_capacity`).
+pub const DEFAULT_EVENT_CAPACITY: usize = 4096;
+
+/// Scrollback line cap (alacritty's default `scrolling_history`).
+const MAX_SCROLLBACK_LINES: usize = 10_000;
+
+/// Grid size handed to alacritty (120×30 at startup).
 struct TerminalDimensions {
     cols: usize,
     rows: usize,
@@ -46,23 +77,88 @@ impl Dimensions for TerminalDimensions {
 ///
 /// Integrates alacritty's ANSI/VT parser and grid management.
 /// The Term is updated by feeding bytes from the PTY.
-#[derive(Resource)]
+/// A component on each terminal entity (see `instance`).
+#[derive(Component)]
+#[require(TerminalConfig, TerminalCpuBuffer, TerminalStats)]
 pub struct TerminalState {
     pub term: Arc<FairMutex<Term<EventProxy>>>,
     pub processor: Processor,
     pub cols: usize,
     pub rows: usize,
+    /// Events emitted by alacritty through `EventProxy`, drained each frame
+    events: Mutex<Receiver<AlacEvent>>,
+    /// Capacity and counters shared with the `EventProxy`
+    event_queue: Arc<EventQueue>,
+    /// Latest title set by the program (OSC 0/2), if any
+    pub(crate) title: Option<String>,
+    /// Jump the viewport back to the bottom whenever output arrives
+    pub scroll_on_output: bool,
+    /// Output arrived while scrolled up in history (and the view stayed put)
+    pub(crate) unseen_output: bool,
+    /// Whether any output has been processed yet
+    received_output: bool,
+    /// Bumped by `mark_dirty` to force a full redraw
+    redraw_generation: u64,
+    /// Output queued by `queue_bytes`, not yet parsed
+    pending: Vec<u8>,
+    /// Shell integration marks (OSC 133) found in parsed output
+    prompt_marks: PromptScanner,
+    /// Colors used where the program hasn't set its own
+    palette: TerminalPalette,
+    /// Scrollback cap in bytes from `with_history_budget`, reapplied on resize
+    history_budget: Option<usize>,
+}
+
+/// A grid cell with its attributes resolved to concrete values.
+///
+/// Colors are RGB after palette lookup, with reverse video already applied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellInfo {
+    /// Character in the cell (space when empty)
+    pub c: char,
+    /// Foreground color (RGB)
+    pub fg: [u8; 3],
+    /// Background color (RGB)
+    pub bg: [u8; 3],
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
 }
 
 /// Event proxy for alacritty terminal events.
 ///
-/// Currently no-op, but allows future integration with Bevy's event system.
+/// Forwards events over a channel so Bevy systems can drain them each frame.
+///
+/// The channel is bounded by `TerminalState::with_event_capacity`. Events are
+/// raised while parsing output, on the same thread that drains them, so
+/// waiting for room would deadlock: once full, new events are dropped and
+/// counted (`TerminalState::dropped_events`) until the next drain.
 #[derive(Clone)]
-pub struct EventProxy;
+pub struct EventProxy {
+    sender: Sender<AlacEvent>,
+    queue: Arc<EventQueue>,
+}
+
+/// Event channel bookkeeping shared by `EventProxy` and `TerminalState`.
+struct EventQueue {
+    capacity: AtomicUsize,
+    queued: AtomicUsize,
+    dropped: AtomicU64,
+}
 
 impl EventListener for EventProxy {
-    fn send_event(&self, _event: AlacEvent) {
-        // Future: Forward to Bevy events
+    fn send_event(&self, event: AlacEvent) {
+        let queue = &self.queue;
+        if queue.queued.fetch_add(1, Ordering::Relaxed) >= queue.capacity.load(Ordering::Relaxed) {
+            queue.queued.fetch_sub(1, Ordering::Relaxed);
+            queue.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
+        // Receiver lives in TerminalState; sends only fail during teardown
+        if self.sender.send(event).is_err() {
+            queue.queued.fetch_sub(1, Ordering::Relaxed);
+        }
     }
 }
 
@@ -71,7 +167,7 @@ impl TerminalState {
     ///
     /// Configuration:
     /// - Size: 120 cols × 30 rows
-    /// - Colors: Tokyo Night theme
+    /// - Colors: Tokyo Night palette (see `set_palette`)
     /// - Scrollback: 10,000 lines
     pub fn new() -> Self {
         const COLS: usize = 120;
@@ -83,7 +179,17 @@ impl TerminalState {
             rows: ROWS,
         };
 
-        let term = Term::new(config, &dimensions, EventProxy);
+        let (sender, receiver) = channel();
+        let event_queue = Arc::new(EventQueue {
+            capacity: AtomicUsize::new(DEFAULT_EVENT_CAPACITY),
+            queued: AtomicUsize::new(0),
+            dropped: AtomicU64::new(0),
+        });
+        let proxy = EventProxy {
+            sender,
+            queue: Arc::clone(&event_queue),
+        };
+        let term = Term::new(config, &dimensions, proxy);
 
         info!("📋 Terminal grid initialized: {}×{}", COLS, ROWS);
 
@@ -92,15 +198,491 @@ impl TerminalState {
             processor: Processor::new(),
             cols: COLS,
             rows: ROWS,
+            events: Mutex::new(receiver),
+            event_queue,
+            title: None,
+            scroll_on_output: true,
+            unseen_output: false,
+            received_output: false,
+            redraw_generation: 0,
+            pending: Vec::new(),
+            prompt_marks: PromptScanner::default(),
+            palette: TerminalPalette::default(),
+            history_budget: None,
         }
     }
 
+    /// Cap scrollback memory at roughly `budget` bytes.
+    ///
+    /// Converted into a line limit for the current width (never above the
+    /// 10,000 line default) and recomputed on resize; once full, the oldest
+    /// history lines are evicted as new ones scroll in.
+    pub fn with_history_budget(mut self, budget: usize) -> Self {
+        self.history_budget = Some(budget);
+        self.apply_history_budget();
+        self
+    }
+
+    /// Set the scrollback line limit from the history budget, if any.
+    fn apply_history_budget(&mut self) {
+        let Some(budget) = self.history_budget else {
+            return;
+        };
+        let max_lines = (budget / Self::line_bytes(self.cols)).min(MAX_SCROLLBACK_LINES);
+        self.term.lock().set_options(AlacConfig {
+            scrolling_history: max_lines,
+            ..AlacConfig::default()
+        });
+        info!("📜 Scrollback capped at {} lines ({} byte budget)", max_lines, budget);
+    }
+
+    /// Approximate memory held by scrollback history, in bytes.
+    ///
+    /// Counts grid cells and row headers; rare per-cell extras (combining
+    /// characters, hyperlinks) are not included.
+    pub fn history_bytes(&self) -> usize {
+        self.term.lock().grid().history_size() * Self::line_bytes(self.cols)
+    }
+
+    /// Memory used by one grid line of `cols` cells.
+    fn line_bytes(cols: usize) -> usize {
+        cols * std::mem::size_of::<Cell>() + std::mem::size_of::<Row<Cell>>()
+    }
+
+    /// Grid size (cols, rows) as alacritty's `Term` sees it.
+    ///
+    /// Should always equal the stored `cols`/`rows`; see `check_dimensions`.
+    pub fn actual_dimensions(&self) -> (usize, usize) {
+        let term = self.term.lock();
+        (term.columns(), term.screen_lines())
+    }
+
+    /// Resize the grid to `cols × rows` (each at least 1), reflowing content.
+    ///
+    /// Only the grid: `ResizeTerminal` also resizes the PTY and texture.
+    pub fn resize_terminal(&mut self, cols: usize, rows: usize) {
+        let (cols, rows) = (cols.max(1), rows.max(1));
+        self.term.lock().resize(TerminalDimensions { cols, rows });
+        self.cols = cols;
+        self.rows = rows;
+        // Wider lines cost more, so fewer of them fit the budget
+        self.apply_history_budget();
+        self.mark_dirty();
+        info!("📐 Terminal grid resized: {}×{}", cols, rows);
+    }
+
+    /// Debug-asserts that the `Term` grid matches the stored `cols`/`rows`.
+    ///
+    /// Everything sizing the cell buffer and texture trusts the stored values,
+    /// so drift would index past the grid. No-op in release builds.
+    pub fn check_dimensions(&self) {
+        debug_assert_eq!(
+            self.actual_dimensions(),
+            (self.cols, self.rows),
+            "Terminal grid size drifted from stored cols/rows"
+        );
+    }
+
+    /// Queue at most `capacity` alacritty events between drains (at least 1).
+    ///
+    /// Further events are dropped; see `EventProxy` for why this doesn't block.
+    pub fn with_event_capacity(self, capacity: usize) -> Self {
+        self.event_queue.capacity.store(capacity.max(1), Ordering::Relaxed);
+        self
+    }
+
+    /// Events dropped so far because the event channel was full.
+    pub fn dropped_events(&self) -> u64 {
+        self.event_queue.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Choose whether new output resets a scrolled-up viewport to the bottom.
+    pub fn with_scroll_on_output(mut self, scroll_on_output: bool) -> Self {
+        self.scroll_on_output = scroll_on_output;
+        self
+    }
+
     /// Process bytes from PTY through VTE parser into terminal grid.
     ///
     /// Handles locking internally for clean API.
     pub fn process_bytes(&mut self, bytes: &[u8]) {
         let mut term = self.term.lock();
         self.processor.advance(&mut *term, bytes);
+        self.received_output |= !bytes.is_empty();
+
+        let capacity = self.event_queue.capacity.load(Ordering::Relaxed);
+        let dropped = self.prompt_marks.scan(bytes, capacity);
+        self.event_queue.dropped.fetch_add(dropped, Ordering::Relaxed);
+
+        if term.grid().display_offset() != 0 {
+            if self.scroll_on_output {
+                term.scroll_display(Scroll::Bottom);
+            } else if !bytes.is_empty() {
+                self.unseen_output = true;
+            }
+        }
+    }
+
+    /// Queue output for `process_pending` instead of parsing it now.
+    pub fn queue_bytes(&mut self, bytes: &[u8]) {
+        self.pending.extend_from_slice(bytes);
+    }
+
+    /// Parse up to `max_bytes` of queued output, keeping the rest queued.
+    ///
+    /// Spreads a large burst over several frames instead of one long one.
+    /// The parser is stateful, so splitting mid escape sequence or mid UTF-8
+    /// character gives the same grid as processing everything at once.
+    /// A `max_bytes` of 0 is treated as 1 so queued output always drains.
+    /// Returns the number of bytes processed.
+    pub fn process_pending(&mut self, max_bytes: usize) -> usize {
+        let count = self.pending.len().min(max_bytes.max(1));
+        if count == 0 {
+            return 0;
+        }
+
+        let rest = self.pending.split_off(count);
+        let chunk = std::mem::replace(&mut self.pending, rest);
+        self.process_bytes(&chunk);
+        count
+    }
+
+    /// Bytes queued but not yet processed.
+    pub fn pending_bytes(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Process `bytes` now, or queue them when output is budgeted per frame.
+    ///
+    /// Queued output is always parsed first so ordering is preserved.
+    pub(crate) fn feed_bytes(&mut self, bytes: &[u8], budgeted: bool) {
+        if budgeted || !self.pending.is_empty() {
+            self.queue_bytes(bytes);
+            if !budgeted {
+                self.process_pending(usize::MAX);
+            }
+        } else {
+            self.process_bytes(bytes);
+        }
+    }
+
+    /// Current title: the last one set by the program (OSC 0/2) or by
+    /// `set_title`, empty when none is set.
+    ///
+    /// Program titles are applied by `dispatch_terminal_events`, which also
+    /// sends `TerminalEventKind::TitleChanged`; a title reset empties it again.
+    pub fn title(&self) -> String {
+        self.title.clone().unwrap_or_default()
+    }
+
+    /// Set the title, e.g. a HUD name before the shell sets its own.
+    ///
+    /// The program can still replace it with OSC 0/2.
+    pub fn set_title(&mut self, title: &str) {
+        self.title = Some(title.to_string());
+    }
+
+    /// Palette the terminal draws with where the program hasn't set colors.
+    pub fn palette(&self) -> &TerminalPalette {
+        &self.palette
+    }
+
+    /// Switch palettes, repainting the whole grid next frame.
+    ///
+    /// The plugin keeps this in sync with the `TerminalPalette` resource.
+    pub fn set_palette(&mut self, palette: TerminalPalette) {
+        self.palette = palette;
+        self.mark_dirty();
+    }
+
+    /// Current color for an alacritty color index: the program's override
+    /// (OSC 4/10/11/12) if set, otherwise the palette's.
+    pub fn color(&self, index: usize) -> [u8; 3] {
+        if index >= COLOR_COUNT {
+            return default_color(index, &self.palette);
+        }
+        match self.term.lock().colors()[index] {
+            Some(rgb) => [rgb.r, rgb.g, rgb.b],
+            None => default_color(index, &self.palette),
+        }
+    }
+
+    /// Current default background (OSC 11 sets it, OSC 111 resets it).
+    ///
+    /// Blank cells and the texture's clear color use it.
+    pub fn background_color(&self) -> [u8; 3] {
+        self.color(NamedColor::Background as usize)
+    }
+
+    /// Current cursor color (OSC 12 sets it, OSC 112 resets it).
+    pub fn cursor_color(&self) -> [u8; 3] {
+        self.color(NamedColor::Cursor as usize)
+    }
+
+    /// Force a full re-render next frame, even if the grid didn't change.
+    ///
+    /// Use after swapping the texture target, changing colors, or anything
+    /// else the renderer can't see. Rebuilds the cell buffer (even mid
+    /// synchronized update) and dispatches the shader for a blank grid too.
+    pub fn mark_dirty(&mut self) {
+        self.redraw_generation = self.redraw_generation.wrapping_add(1);
+    }
+
+    /// Counter bumped by every `mark_dirty` call.
+    pub fn redraw_generation(&self) -> u64 {
+        self.redraw_generation
+    }
+
+    /// Whether any output has arrived since the terminal was created (or
+    /// last `reset`).
+    pub fn has_received_output(&self) -> bool {
+        self.received_output
+    }
+
+    /// Back to a fresh terminal at the current size: screen, history,
+    /// modes, color overrides and title are cleared, and queued output is
+    /// dropped. Used when the shell is respawned.
+    pub fn reset(&mut self) {
+        {
+            let mut term = self.term.lock();
+            term.reset_state();
+            for index in 0..COLOR_COUNT {
+                term.reset_color(index);
+            }
+        }
+        self.processor = Processor::new();
+        self.title = None;
+        self.unseen_output = false;
+        self.received_output = false;
+        self.pending.clear();
+        self.prompt_marks = PromptScanner::default();
+        self.mark_dirty();
+    }
+
+    /// Whether a synchronized update (`\x1b[?2026h`) is holding back output.
+    ///
+    /// The parser buffers everything until the matching `\x1b[?2026l`, so the
+    /// grid never shows a half-drawn frame.
+    pub fn in_synchronized_update(&self) -> bool {
+        self.processor.sync_timeout().sync_timeout().is_some()
+    }
+
+    /// Whether an open synchronized update has outlived its timeout.
+    pub fn synchronized_update_expired(&self) -> bool {
+        self.processor
+            .sync_timeout()
+            .sync_timeout()
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Apply the buffered output of a synchronized update that never closed.
+    ///
+    /// Called each frame by the plugin once the update times out (150ms).
+    pub fn flush_synchronized_update(&mut self) {
+        let mut term = self.term.lock();
+        self.processor.stop_sync(&mut *term);
+    }
+
+    /// Whether a full-screen program switched to the alternate screen (`\x1b[?1049h`).
+    ///
+    /// The alternate screen has no scrollback.
+    pub fn is_alt_screen(&self) -> bool {
+        self.term.lock().mode().contains(TermMode::ALT_SCREEN)
+    }
+
+    /// Whether the program enabled bracketed paste (`\x1b[?2004h`).
+    pub fn bracketed_paste(&self) -> bool {
+        self.term.lock().mode().contains(TermMode::BRACKETED_PASTE)
+    }
+
+    /// Whether the keypad is in application mode (`\x1b=`, DECKPAM).
+    pub fn app_keypad(&self) -> bool {
+        self.term.lock().mode().contains(TermMode::APP_KEYPAD)
+    }
+
+    /// Mouse tracking the program turned on (DECSET 1000/1002/1003/1006).
+    pub fn mouse_mode(&self) -> MouseMode {
+        let term = self.term.lock();
+        let mode = term.mode();
+        MouseMode {
+            clicks: mode.intersects(TermMode::MOUSE_MODE),
+            drag: mode.contains(TermMode::MOUSE_DRAG),
+            motion: mode.contains(TermMode::MOUSE_MOTION),
+            sgr: mode.contains(TermMode::SGR_MOUSE),
+        }
+    }
+
+    /// Cursor cell `(col, row)` on the screen, row 0 at the top.
+    ///
+    /// Unaffected by scrolling the viewport into history; add
+    /// `display_offset` to find it in the viewport.
+    pub fn cursor(&self) -> (usize, usize) {
+        let point = self.term.lock().grid().cursor.point;
+        (point.column.0, point.line.0 as usize)
+    }
+
+    /// Whether the program shows the cursor (`\x1b[?25h`) rather than hiding
+    /// it (`\x1b[?25l`, or a hidden cursor shape).
+    pub fn cursor_visible(&self) -> bool {
+        let term = self.term.lock();
+        term.mode().contains(TermMode::SHOW_CURSOR) && term.cursor_style().shape != CursorShape::Hidden
+    }
+
+    /// Cursor shape the program asked for (DECSCUSR, `\x1b[5 q`), block by
+    /// default.
+    ///
+    /// A hollow block counts as a block; the drawn cursor still follows
+    /// `TerminalConfig::cursor_style`.
+    pub fn cursor_shape(&self) -> CursorStyle {
+        match self.term.lock().cursor_style().shape {
+            CursorShape::Beam => CursorStyle::Bar,
+            CursorShape::Underline => CursorStyle::Underline,
+            CursorShape::Block | CursorShape::HollowBlock | CursorShape::Hidden => CursorStyle::Block,
+        }
+    }
+
+    /// Lines the viewport is scrolled up into history (0 = at the bottom).
+    pub fn display_offset(&self) -> usize {
+        self.term.lock().grid().display_offset()
+    }
+
+    /// Scroll the viewport by `delta` lines (positive = up into history).
+    ///
+    /// Reaching the bottom clears the unseen-output indicator.
+    pub fn scroll_viewport(&mut self, delta: i32) {
+        let mut term = self.term.lock();
+        term.scroll_display(Scroll::Delta(delta));
+        if term.grid().display_offset() == 0 {
+            self.unseen_output = false;
+        }
+    }
+
+    /// Cells drawn into the texture: `TerminalConfig::viewport` fitted to the
+    /// grid, or the whole grid without a config.
+    pub fn visible_grid(&self, config: Option<&TerminalConfig>) -> GridViewport {
+        config.map_or(GridViewport::full(self.cols, self.rows), |config| {
+            config.visible_grid(self.cols, self.rows)
+        })
+    }
+
+    /// Grid cell `(column, line)` shown at viewport cell `col`, `row`.
+    ///
+    /// Line 0 is the top of the screen and negative lines are scrollback, so
+    /// the result stays put while the viewport scrolls.
+    pub fn viewport_to_grid(&self, col: usize, row: usize) -> (usize, i32) {
+        (col, row as i32 - self.display_offset() as i32)
+    }
+
+    /// Select the text between two grid cells (see `viewport_to_grid`), both
+    /// included, in either order.
+    ///
+    /// Selected cells are drawn highlighted. Alacritty moves the selection
+    /// along as output scrolls and drops it once it scrolls out of history.
+    pub fn set_selection(&mut self, start: (usize, i32), end: (usize, i32)) {
+        let point = |(column, line): (usize, i32)| Point::new(Line(line), Column(column));
+        let (start, end) = (point(start).min(point(end)), point(start).max(point(end)));
+        let mut selection = Selection::new(SelectionType::Simple, start, Side::Left);
+        selection.update(end, Side::Right);
+        self.term.lock().selection = Some(selection);
+    }
+
+    /// Remove the selection, if any.
+    pub fn clear_selection(&mut self) {
+        self.term.lock().selection = None;
+    }
+
+    /// Whether any text is selected.
+    pub fn has_selection(&self) -> bool {
+        self.term.lock().selection.as_ref().is_some_and(|selection| !selection.is_empty())
+    }
+
+    /// Text of the selection, including any part scrolled into history.
+    ///
+    /// Rows end in a newline unless the line wrapped, and trailing blanks are
+    /// trimmed, as alacritty copies. Empty when nothing is selected.
+    pub fn copy_selection(&self) -> String {
+        self.term.lock().selection_to_string().unwrap_or_default()
+    }
+
+    /// Whether output arrived while scrolled up with `scroll_on_output` off.
+    ///
+    /// Games can flash a "new output" indicator while this is set.
+    pub fn has_unseen_output(&self) -> bool {
+        self.unseen_output
+    }
+
+    /// Feed PTY output into the grid, waiting up to `timeout` for it to arrive.
+    ///
+    /// Returns the number of bytes processed. Test helper; see
+    /// `PtyResource::read_available`.
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn pump(&mut self, pty: &PtyResource, timeout: Duration) -> usize {
+        let bytes = pty.read_available(timeout);
+        self.process_bytes(&bytes);
+        bytes.len()
+    }
+
+    /// Drain events emitted by alacritty since the last call.
+    ///
+    /// Includes `PtyWrite` replies to terminal queries (cursor position report
+    /// `\x1b[6n`, device attributes, ...) which programs block on until the
+    /// answer arrives on their stdin. Normally drained each frame by the plugin.
+    pub fn drain_events(&self) -> Vec<AlacEvent> {
+        let events: Vec<AlacEvent> = match self.events.lock() {
+            Ok(events) => events.try_iter().collect(),
+            Err(_) => Vec::new(),
+        };
+        self.event_queue.queued.fetch_sub(events.len(), Ordering::Relaxed);
+        events
+    }
+
+    /// Drain shell integration marks (OSC 133) found since the last call, in
+    /// output order.
+    ///
+    /// Queued up to the event capacity, like alacritty events. Normally sent
+    /// as `TerminalEvent`s each frame by `dispatch_prompt_marks`.
+    pub fn drain_prompt_marks(&mut self) -> Vec<TerminalEventKind> {
+        self.prompt_marks.drain()
+    }
+
+    /// Iterate every visible cell as `(col, row, CellInfo)`, row by row.
+    ///
+    /// The grid is read under a single lock and the result owns its data, so
+    /// the terminal keeps running while the caller iterates.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize, CellInfo)> {
+        let term = self.term.lock();
+        let grid = term.grid();
+        let mut cells = Vec::with_capacity(self.cols * self.rows);
+
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let cell = &grid[Line(row as i32)][Column(col)];
+                let mut fg = if cell.flags.contains(Flags::DIM) {
+                    resolve_dim_color(cell.fg, term.colors(), &self.palette)
+                } else {
+                    resolve_color(cell.fg, term.colors(), &self.palette)
+                };
+                let mut bg = resolve_color(cell.bg, term.colors(), &self.palette);
+                if cell.flags.contains(Flags::INVERSE) {
+                    std::mem::swap(&mut fg, &mut bg);
+                }
+
+                cells.push((
+                    col,
+                    row,
+                    CellInfo {
+                        c: if cell.c == '\0' { ' ' } else { cell.c },
+                        fg,
+                        bg,
+                        bold: cell.flags.contains(Flags::BOLD),
+                        italic: cell.flags.contains(Flags::ITALIC),
+                        underline: cell.flags.intersects(Flags::ALL_UNDERLINES),
+                    },
+                ));
+            }
+        }
+
+        cells.into_iter()
     }
 
     /// Extract visible text from terminal grid for testing/debugging.
@@ -131,6 +713,18 @@ impl TerminalState {
         result
     }
 
+    /// Text of the scrollback history and the screen, oldest line first.
+    ///
+    /// Wrapped lines are joined and trailing blanks trimmed, as alacritty
+    /// copies; empty rows below the last text are left out.
+    pub fn transcript(&self) -> String {
+        let term = self.term.lock();
+        let grid = term.grid();
+        let start = Point::new(grid.topmost_line(), Column(0));
+        let end = Point::new(grid.bottommost_line(), grid.last_column());
+        term.bounds_to_string(start, end).trim_end_matches('\n').to_string()
+    }
+
     /// Get a compact summary of non-empty lines for debugging.
     ///
     /// Returns only lines that contain non-whitespace characters,
@@ -160,91 +754,474 @@ impl Default for TerminalState {
 
 /// Bevy plugin for terminal emulation.
 ///
-/// MVP: Hardcoded configuration
-/// - Font: Cascadia Mono Regular, 14pt
-/// - Size: 120 cols × 30 rows
-/// - Colors: Tokyo Night
-/// - Shell: bash (or default shell)
+/// Defaults, each changed by a `with_*` builder:
+/// - Font: Cascadia Mono Regular, 14pt (`with_font`, `with_font_size`)
+/// - Size: 120 cols × 30 rows (`with_size_from_env`, or `ResizeTerminal`)
+/// - Colors: Tokyo Night (`TerminalPalette`)
+/// - Shell: the user's `$SHELL` (PowerShell on Windows) in the current
+///   directory (`with_shell`, `with_env`, `with_cwd`)
 ///
-/// PTY is spawned in Startup system and runs persistently.
+/// Spawns the primary terminal entity (`PrimaryTerminal`); its PTY is
+/// spawned on the first update and runs persistently.
 /// Terminal state updates continuously in background.
-/// Renders to texture exposed via `TerminalTexture` resource.
-pub struct TerminalPlugin;
+/// Renders to texture exposed via the terminal's `TerminalTexture` component.
+///
+/// Options live in `TerminalConfig`; set them with the `with_*` builders.
+#[derive(Default, Clone)]
+pub struct TerminalPlugin {
+    pub config: TerminalConfig,
+}
+
+impl TerminalPlugin {
+    /// Render into a game-owned image instead of a plugin-owned texture.
+    ///
+    /// See `TerminalConfig::target` for the image requirements.
+    pub fn with_target(mut self, target: Handle<Image>) -> Self {
+        self.config.target = Some(target);
+        self
+    }
+
+    /// Don't spawn a shell; the terminal displays only a custom `SourceResource`.
+    pub fn without_pty(mut self) -> Self {
+        self.config.spawn_pty = false;
+        self
+    }
+
+    /// Run without rendering; see `TerminalConfig::headless`.
+    pub fn headless(mut self) -> Self {
+        self.config.headless = true;
+        self
+    }
+
+    /// Run `program` with `args` in the PTY instead of the detected shell.
+    pub fn with_shell(mut self, program: impl Into<String>, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.config.shell = Some(program.into());
+        self.config.shell_args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set an environment variable for the PTY program (`TERM` included).
+    pub fn with_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.env.insert(key.into(), value.into());
+        self
+    }
+
+    /// Have bash mark prompts and commands with OSC 133; see
+    /// `TerminalConfig::shell_integration`.
+    pub fn with_shell_integration(mut self, enabled: bool) -> Self {
+        self.config.shell_integration = enabled;
+        self
+    }
+
+    /// Start the PTY program in `cwd` instead of the game's directory.
+    pub fn with_cwd(mut self, cwd: impl Into<PathBuf>) -> Self {
+        self.config.cwd = Some(cwd.into());
+        self
+    }
+
+    /// Restart the shell whenever it exits; see `TerminalConfig::respawn`.
+    pub fn with_respawn(mut self, respawn: bool) -> Self {
+        self.config.respawn = respawn;
+        self
+    }
+
+    /// Back off respawns starting at `delay`, giving up after `limit` in a
+    /// row; see `TerminalConfig::respawn_delay` and `respawn_limit`.
+    pub fn with_respawn_backoff(mut self, delay: Duration, limit: u32) -> Self {
+        self.config.respawn_delay = delay;
+        self.config.respawn_limit = limit;
+        self
+    }
+
+    /// Keep a scrolled-up viewport in place when output arrives.
+    pub fn with_scroll_on_output(mut self, scroll_on_output: bool) -> Self {
+        self.config.scroll_on_output = scroll_on_output;
+        self
+    }
+
+    /// Poll PTY output at most once per `interval` instead of every frame.
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.config.poll_interval = Some(interval);
+        self
+    }
+
+    /// Parse at most `max_bytes` of output per frame, carrying the rest over.
+    ///
+    /// See `TerminalConfig::max_bytes_per_frame`.
+    pub fn with_max_bytes_per_frame(mut self, max_bytes: usize) -> Self {
+        self.config.max_bytes_per_frame = Some(max_bytes);
+        self
+    }
+
+    /// Use a custom font: a `PathBuf` to a TTF/OTF file or its bytes
+    /// (`Vec<u8>`). Without this the embedded Cascadia Mono is used.
+    pub fn with_font(mut self, font: impl Into<FontSource>) -> Self {
+        self.config.font = font.into();
+        self
+    }
+
+    /// Draw flags and other multi-codepoint emoji from a color bitmap font
+    /// (see `TerminalConfig::emoji_font`).
+    #[cfg(feature = "emoji")]
+    pub fn with_emoji_font(mut self, font: impl Into<FontSource>) -> Self {
+        self.config.emoji_font = Some(font.into());
+        self
+    }
+
+    /// Render text at `points` instead of 14pt; cells and the texture scale with it.
+    pub fn with_font_size(mut self, points: f32) -> Self {
+        self.config.font_size = points;
+        self
+    }
+
+    /// Position glyphs vertically by `placement` (default: font baseline).
+    pub fn with_glyph_placement(mut self, placement: GlyphPlacement) -> Self {
+        self.config.glyph_placement = placement;
+        self
+    }
+
+    /// Anti-alias box-drawing and block characters like text (`false`, the
+    /// default) or render them solid (`true`).
+    pub fn with_crisp_box_drawing(mut self, enabled: bool) -> Self {
+        self.config.crisp_box_drawing = enabled;
+        self
+    }
+
+    /// Write the terminal texture in `format` (e.g. `Rgba16Float` for HDR/bloom).
+    ///
+    /// See `TerminalConfig::texture_format`.
+    pub fn with_texture_format(mut self, format: TextureFormat) -> Self {
+        self.config.texture_format = format;
+        self
+    }
+
+    /// Draw the grid with `backend` instead of picking one per device.
+    ///
+    /// See `TerminalConfig::render_backend`.
+    pub fn with_render_backend(mut self, backend: RenderBackend) -> Self {
+        self.config.render_backend = backend;
+        self
+    }
+
+    /// Cap scrollback memory at roughly `budget` bytes.
+    pub fn with_history_budget(mut self, budget: usize) -> Self {
+        self.config.history_budget = Some(budget);
+        self
+    }
+
+    /// Animate `indicator` on the empty screen until the first output arrives.
+    pub fn with_loading_indicator(mut self, indicator: LoadingIndicator) -> Self {
+        self.config.loading_indicator = Some(indicator);
+        self
+    }
+
+    /// Draw the block cursor (default) or leave it out of the texture.
+    pub fn with_cursor(mut self, show: bool) -> Self {
+        self.config.show_cursor = show;
+        self
+    }
+
+    /// Blink the cursor hard on/off (default) or fade it in and out.
+    pub fn with_cursor_blink(mut self, style: CursorBlinkStyle) -> Self {
+        self.config.cursor_blink = style;
+        self
+    }
+
+    /// Draw the cursor as a block over the text (default), or as a bar or
+    /// underline beneath it.
+    pub fn with_cursor_style(mut self, style: CursorStyle) -> Self {
+        self.config.cursor_style = style;
+        self
+    }
+
+    /// Selection highlight color and opacity, and cursor opacity.
+    pub fn with_theme(mut self, theme: TerminalTheme) -> Self {
+        self.config.theme = theme;
+        self
+    }
+
+    /// Draw only `viewport` of the grid, e.g. a window onto a 200×60 grid.
+    pub fn with_viewport(mut self, viewport: GridViewport) -> Self {
+        self.config.viewport = Some(viewport);
+        self
+    }
+
+    /// Start with the grid size in `COLUMNS`/`LINES`, when both are set.
+    pub fn with_size_from_env(mut self, enabled: bool) -> Self {
+        self.config.size_from_env = enabled;
+        self
+    }
+
+    /// Choose what characters without an atlas glyph draw as.
+    pub fn with_fallback_glyph(mut self, fallback: FallbackGlyph) -> Self {
+        self.config.fallback_glyph = fallback;
+        self
+    }
+
+    /// Queue at most `capacity` alacritty events per frame; extras are dropped
+    /// and counted in `TerminalStats::dropped_events`.
+    pub fn with_event_capacity(mut self, capacity: usize) -> Self {
+        self.config.event_capacity = capacity;
+        self
+    }
+
+    /// Bake a status bar row (title or custom text) into the terminal texture.
+    pub fn with_status_bar(mut self, status_bar: StatusBarConfig) -> Self {
+        self.config.status_bar = Some(status_bar);
+        self
+    }
+
+    /// Blend a floating text overlay over the terminal texture.
+    pub fn with_watermark(mut self, watermark: WatermarkConfig) -> Self {
+        self.config.watermark = Some(watermark);
+        self
+    }
+}
+
+impl TerminalPlugin {
+    /// Terminal state configured from `self.config`, as the plugin inserts it.
+    pub fn initial_terminal_state(&self) -> TerminalState {
+        let mut term_state = TerminalState::new()
+            .with_scroll_on_output(self.config.scroll_on_output)
+            .with_event_capacity(self.config.event_capacity);
+        if let Some((cols, rows)) = env_grid_size().filter(|_| self.config.size_from_env) {
+            term_state.resize_terminal(cols, rows);
+        }
+        match self.config.history_budget {
+            Some(budget) => term_state.with_history_budget(budget),
+            None => term_state,
+        }
+    }
+}
 
 impl Plugin for TerminalPlugin {
     fn build(&self, app: &mut App) {
         info!("🖥️  Initializing TerminalPlugin (render-to-texture)");
 
-        load_internal_asset!(
-            app,
-            TERMINAL_SHADER_HANDLE,
-            "../assets/shaders/terminal.wgsl",
-            Shader::from_wgsl
-        );
+        app.insert_resource(self.config.clone());
+        app.add_message::<TerminalEvent>();
+        // Kept if the game inserted its own palette first
+        app.init_resource::<TerminalPalette>();
+
+        // Phase 1.1-1.2: The primary terminal; its PTY is spawned on the first update
+        let primary = app
+            .world_mut()
+            .spawn((self.initial_terminal_state(), self.config.clone(), PrimaryTerminal))
+            .id();
+        info!("🖥️  Primary terminal: {:?}", primary);
 
         app
-            // Phase 1.1: PTY Spawning
-            .add_systems(Startup, pty::spawn_pty)
-            // Phase 1.2: Terminal State
-            .init_resource::<TerminalState>()
             // Phase 1.3-1.4: PTY Polling and Input
+            // Terminal responses are written before this frame's keystrokes
             .add_systems(Update, (
-                pty::poll_pty,
-                input::handle_keyboard_input,
-            ))
+                pty::spawn_pty,
+                pty::poll_pty.run_if(pty::poll_interval_elapsed),
+                source::poll_source.run_if(pty::poll_interval_elapsed),
+                flush_expired_synchronized_updates,
+                events::report_reader_failures,
+                events::report_process_exit,
+                pty::respawn_exited_shell,
+                events::dispatch_terminal_events,
+                shell_integration::dispatch_prompt_marks,
+                input::apply_terminal_writes,
+                paste::apply_terminal_pastes,
+                input::handle_keyboard_input.run_if(resource_exists::<ButtonInput<KeyCode>>),
+                input::handle_ime_input.run_if(resource_exists::<Messages<Ime>>),
+            ).chain())
+            .add_message::<TerminalWrite>()
+            // One PTY write per frame for everything the input systems queued
+            .add_systems(Update, pty::flush_pty_writes
+                .after(input::handle_ime_input)
+                .after(input::handle_mouse_input))
+            .add_message::<TerminalPaste>()
+            .add_systems(Update, input::scroll_viewport_on_wheel
+                .before(gpu_prep::prepare_terminal_cpu_buffer)
+                .run_if(resource_exists::<Messages<MouseWheel>>))
+            // Mouse reporting needs a window to point with and a texture to point at
+            .add_systems(Update, input::handle_mouse_input
+                .after(input::handle_keyboard_input)
+                .run_if(resource_exists::<GlyphAtlas>)
+                .run_if(resource_exists::<Messages<MouseButtonInput>>)
+                .run_if(resource_exists::<Messages<MouseWheel>>)
+                .run_if(resource_exists::<Messages<CursorMoved>>))
+            .add_systems(Update, input::handle_mouse_selection
+                .after(input::handle_mouse_input)
+                .before(gpu_prep::prepare_terminal_cpu_buffer)
+                .run_if(resource_exists::<GlyphAtlas>)
+                .run_if(resource_exists::<Messages<MouseButtonInput>>)
+                .run_if(resource_exists::<Messages<CursorMoved>>))
+            .add_systems(Startup, apply_terminal_palette)
+            .add_systems(Update, apply_terminal_palette
+                .before(events::dispatch_terminal_events)
+                .before(gpu_prep::prepare_terminal_cpu_buffer))
+            .add_systems(Update, stats::update_terminal_stats.after(events::dispatch_terminal_events))
             // Phase 2: Font and Atlas
             .add_systems(Startup, initialize_font_and_atlas)
-            // Phase 3: Render to Texture
-            .add_systems(Startup, renderer::initialize_terminal_texture.after(initialize_font_and_atlas))
-            // .add_systems(Update, renderer::render_terminal_to_texture) // CPU Renderer disabled
-            
+            // Effect clock shared by blink/flash visuals
+            .insert_resource(TerminalClock::new(self.config.clock_timestep, self.config.blink_period))
+            .add_systems(Update, clock::advance_terminal_clock)
+            .add_systems(Update, renderer::apply_font_change.before(gpu_prep::prepare_terminal_cpu_buffer))
+            // Runtime resize: grid, PTY and texture together
+            .add_message::<ResizeTerminal>()
+            .add_systems(Update, resize::apply_terminal_resize
+                .after(renderer::apply_font_change)
+                .before(gpu_prep::prepare_terminal_cpu_buffer))
+            // Kill the shells and free the terminals on AppExit
+            .add_systems(Last, shutdown::shutdown_terminal_on_exit)
+            .add_systems(Update, resize::apply_viewport_resize
+                .after(resize::apply_terminal_resize)
+                .before(gpu_prep::prepare_terminal_cpu_buffer))
+            .add_systems(Update, sprite::autosize_terminal
+                .before(resize::apply_terminal_resize)
+                .run_if(resource_exists::<GlyphAtlas>))
             // Phase 3.5: GPU Rendering
-            .init_resource::<gpu_prep::TerminalCpuBuffer>()
-            .add_systems(Update, gpu_prep::prepare_terminal_cpu_buffer.after(pty::poll_pty))
-            .add_plugins(render_node::TerminalComputePlugin)
+            .add_systems(Update, gpu_prep::prepare_terminal_cpu_buffer
+                .after(source::poll_source)
+                .run_if(resource_exists::<GlyphAtlas>))
             ;
 
+        if !self.config.headless {
+            load_internal_asset!(
+                app,
+                TERMINAL_SHADER_HANDLE,
+                "../assets/shaders/terminal.wgsl",
+                Shader::from_wgsl
+            );
+            load_internal_asset!(
+                app,
+                TERMINAL_FRAGMENT_SHADER_HANDLE,
+                "../assets/shaders/terminal_fragment.wgsl",
+                Shader::from_wgsl
+            );
+
+            app
+                // Phase 3: Render to Texture, for the primary terminal at
+                // startup and for spawned ones as they appear
+                .add_systems(Startup, renderer::initialize_terminal_textures
+                    .after(initialize_font_and_atlas)
+                    .after(apply_terminal_palette)
+                    .run_if(resource_exists::<GlyphAtlas>))
+                .add_systems(Update, renderer::initialize_terminal_textures
+                    .after(renderer::apply_font_change)
+                    .before(gpu_prep::prepare_terminal_cpu_buffer)
+                    .run_if(resource_exists::<GlyphAtlas>))
+                .add_plugins(render_node::TerminalComputePlugin {
+                    texture_format: Some(self.config.texture_format),
+                    backend: self.config.render_backend,
+                });
+
+            #[cfg(feature = "crt")]
+            app.add_plugins(crate::crt::CrtPlugin);
+        }
+
+        // Characters outside the generated set are rasterized as they appear,
+        // before the cells are built; new slots are uploaded once the
+        // clusters found while building them are rasterized too
+        app.init_resource::<AtlasUploads>().add_systems(
+            Update,
+            (
+                atlas::rasterize_missing_glyphs
+                    .after(source::poll_source)
+                    .after(renderer::apply_font_change)
+                    .before(gpu_prep::prepare_terminal_cpu_buffer),
+                atlas::upload_atlas_slots
+                    .after(atlas::rasterize_missing_glyphs)
+                    .after(gpu_prep::prepare_terminal_cpu_buffer),
+            ),
+        );
+
+        // Multi-codepoint emoji get their own atlas glyphs
+        #[cfg(feature = "emoji")]
+        app.add_systems(
+            Update,
+            crate::grapheme::rasterize_clusters
+                .after(gpu_prep::prepare_terminal_cpu_buffer)
+                .before(atlas::upload_atlas_slots),
+        );
+
         info!("✅ TerminalPlugin initialized");
     }
 }
 
-impl Default for TerminalPlugin {
-    fn default() -> Self {
-        Self
+/// Flushes synchronized updates left unbalanced past their timeout.
+///
+/// System: Update
+/// Runs: Every frame, after polling
+pub fn flush_expired_synchronized_updates(mut terminals: Query<&mut TerminalState>) {
+    for mut term_state in &mut terminals {
+        if term_state.synchronized_update_expired() {
+            warn!("⚠️  Synchronized update timed out, rendering buffered output");
+            term_state.flush_synchronized_update();
+        }
+    }
+}
+
+/// Copies the `TerminalPalette` resource into every terminal when it differs.
+///
+/// System: Startup and Update
+/// Runs: Every frame, before events are dispatched and cells are built
+///
+/// A terminal whose `TerminalTheme::cursor_color` is set gets that instead
+/// of the palette's cursor.
+pub fn apply_terminal_palette(
+    palette: Res<TerminalPalette>,
+    mut terminals: Query<(&mut TerminalState, &TerminalConfig)>,
+) {
+    for (mut term_state, config) in &mut terminals {
+        let palette = TerminalPalette {
+            cursor: config.theme.cursor_color.unwrap_or(palette.cursor),
+            ..*palette
+        };
+        if term_state.palette != palette {
+            term_state.set_palette(palette);
+        }
     }
 }
 
 /// Startup system to initialize font metrics and glyph atlas.
 ///
-/// Loads Cascadia Mono and generates the full glyph atlas with
-/// ASCII, box-drawing, and block element characters.
-fn initialize_font_and_atlas(
+/// Loads the configured font (Cascadia Mono by default) and generates the
+/// full glyph atlas with ASCII, box-drawing, and block element characters.
+///
+/// Failures are sent to every terminal as `TerminalEventKind::Error`;
+/// without a font and atlas no terminal texture is created.
+pub fn initialize_font_and_atlas(
     mut commands: Commands,
-    mut images: ResMut<Assets<Image>>,
+    images: Option<ResMut<Assets<Image>>>,
+    config: Res<TerminalConfig>,
+    terminals: Query<Entity, With<TerminalState>>,
+    mut events: MessageWriter<TerminalEvent>,
 ) {
     info!("🔤 Loading font and generating glyph atlas...");
 
-    let font_metrics = FontMetrics::load_cascadia_mono()
-        .expect("Failed to load Cascadia Mono font");
-
-    let mut atlas = GlyphAtlas::generate_mvp(&font_metrics)
-        .expect("Failed to generate glyph atlas");
-
-    // Create GPU texture for atlas
-    let atlas_image = Image::new(
-        Extent3d {
-            width: atlas.atlas_width,
-            height: atlas.atlas_height,
-            depth_or_array_layers: 1,
-        },
-        TextureDimension::D2,
-        atlas.texture_data.clone(),
-        TextureFormat::Rgba8Unorm,
-        RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
-    );
-    
-    let atlas_handle = images.add(atlas_image);
-    atlas.texture_handle = Some(atlas_handle);
+    let loaded = config.font.load(config.font_size).and_then(|font_metrics| {
+        let font_metrics = font_metrics
+            .with_placement(config.glyph_placement)
+            .with_crisp_box_drawing(config.crisp_box_drawing);
+        #[cfg(feature = "emoji")]
+        let font_metrics = match &config.emoji_font {
+            Some(source) => font_metrics.with_emoji_font(crate::grapheme::EmojiFont::load(source)?),
+            None => font_metrics,
+        };
+        let atlas = GlyphAtlas::generate_mvp(&font_metrics)?;
+        Ok((font_metrics, atlas))
+    });
+    let (font_metrics, mut atlas) = match loaded {
+        Ok(loaded) => loaded,
+        Err(error) => {
+            error!("❌ Failed to load terminal font: {:#}", error);
+            let message = format!("Failed to load terminal font: {:#}", error);
+            events.write_batch(terminals.iter().map(|terminal| {
+                TerminalEvent::new(terminal, TerminalEventKind::Error { message: message.clone() })
+            }));
+            return;
+        }
+    };
+
+    // Create GPU texture for atlas; headless only needs the glyph indices
+    if let (false, Some(mut images)) = (config.headless, images) {
+        atlas.texture_handle = Some(images.add(atlas.to_bevy_image()));
+    }
 
     info!(
         "✅ Font and atlas ready: {}×{} cells, {} glyphs",
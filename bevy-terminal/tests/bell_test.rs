@@ -0,0 +1,73 @@
+//! Tests for bell detection.
+//!
+//! BEL (`\x07`) rings the bell on its own but also terminates OSC strings
+//! (`\x1b]0;title\x07`). Bells come from alacritty's parser rather than a raw
+//! byte scan, so only standalone BELs are reported.
+
+mod common;
+
+use alacritty_terminal::event::Event as AlacEvent;
+use bevy_terminal::TerminalState;
+
+/// Number of bells drained from the terminal.
+fn bells(term_state: &TerminalState) -> usize {
+    term_state
+        .drain_events()
+        .into_iter()
+        .filter(|event| matches!(event, AlacEvent::Bell))
+        .count()
+}
+
+#[test]
+fn test_standalone_bel_rings() {
+    let mut term_state = TerminalState::new();
+
+    term_state.process_bytes(b"beep\x07");
+    assert_eq!(bells(&term_state), 1);
+
+    term_state.process_bytes(b"\x07\x07");
+    assert_eq!(bells(&term_state), 2);
+}
+
+#[test]
+fn test_osc_terminator_does_not_ring() {
+    let mut term_state = TerminalState::new();
+
+    // Title set terminated by BEL, as bash/zsh prompts do
+    term_state.process_bytes(b"\x1b]0;user@host: ~\x07");
+    assert_eq!(bells(&term_state), 0);
+
+    // Terminator split across reads
+    term_state.process_bytes(b"\x1b]2;split");
+    term_state.process_bytes(b"\x07");
+    assert_eq!(bells(&term_state), 0);
+
+    // ST-terminated OSC followed by a real bell
+    term_state.process_bytes(b"\x1b]2;st\x1b\\\x07");
+    assert_eq!(bells(&term_state), 1);
+}
+
+#[test]
+fn test_bell_sent_as_terminal_event() {
+    use bevy::prelude::*;
+    use bevy_terminal::events::{dispatch_terminal_events, TerminalEvent, TerminalEventKind};
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_message::<TerminalEvent>()
+        .add_systems(Update, dispatch_terminal_events);
+    let terminal = app.world_mut().spawn(TerminalState::new()).id();
+
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"\x1b]0;title\x07done\x07");
+    app.update();
+
+    // The OSC's BEL only ends the title; the one after "done" rings
+    let events: Vec<_> = app.world_mut().resource_mut::<Messages<TerminalEvent>>().drain().collect();
+    assert!(events.iter().all(|event| event.terminal == terminal));
+    let events: Vec<_> = events.into_iter().map(|event| event.kind).collect();
+    assert!(
+        matches!(&events[..], [TerminalEventKind::TitleChanged { title }, TerminalEventKind::Bell] if title == "title"),
+        "got {:?}",
+        events
+    );
+}
@@ -0,0 +1,104 @@
+//! Tests for spreading large output bursts over several frames.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_terminal::source::{poll_source, SourceResource};
+use bevy_terminal::{TerminalConfig, TerminalState};
+use std::io::Cursor;
+use std::time::{Duration, Instant};
+
+/// Colored, multi-line output with multi-byte characters, large enough to scroll.
+fn burst() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for line in 0..200 {
+        bytes.extend_from_slice(
+            format!("\x1b[3{}mline {:03} ─ naïve café ✓\x1b[0m\r\n", line % 8, line).as_bytes(),
+        );
+    }
+    bytes
+}
+
+#[test]
+fn test_split_feed_matches_whole_feed() {
+    let bytes = burst();
+
+    let mut whole = TerminalState::new();
+    whole.process_bytes(&bytes);
+
+    // Split at every kind of boundary: mid escape sequence, mid UTF-8 character
+    for split in [bytes.len() / 2, 3, 4, 5, bytes.iter().position(|&b| b >= 0x80).unwrap() + 1] {
+        let mut halves = TerminalState::new();
+        halves.process_bytes(&bytes[..split]);
+        halves.process_bytes(&bytes[split..]);
+        assert_eq!(halves.get_visible_text(), whole.get_visible_text(), "split at {}", split);
+        assert!(halves.cells().eq(whole.cells()), "attributes differ for split at {}", split);
+    }
+}
+
+#[test]
+fn test_process_pending_in_slices() {
+    let bytes = burst();
+
+    let mut whole = TerminalState::new();
+    whole.process_bytes(&bytes);
+
+    let mut sliced = TerminalState::new();
+    sliced.queue_bytes(&bytes);
+    assert_eq!(sliced.pending_bytes(), bytes.len());
+
+    let mut frames = 0;
+    while sliced.pending_bytes() > 0 {
+        assert!(sliced.process_pending(1000) <= 1000);
+        frames += 1;
+    }
+    assert_eq!(frames, bytes.len().div_ceil(1000));
+    assert_eq!(sliced.process_pending(1000), 0);
+    assert!(sliced.cells().eq(whole.cells()));
+}
+
+#[test]
+fn test_zero_budget_still_drains() {
+    let mut term_state = TerminalState::new();
+    term_state.queue_bytes(b"ok");
+
+    assert_eq!(term_state.process_pending(0), 1);
+    assert_eq!(term_state.process_pending(0), 1);
+    assert_eq!(term_state.pending_bytes(), 0);
+    assert_eq!(term_state.get_content_summary(), vec![(0, "ok".to_string())]);
+}
+
+#[test]
+fn test_poll_respects_frame_budget() {
+    let bytes = burst();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_systems(Update, poll_source);
+    let config = TerminalConfig {
+        max_bytes_per_frame: Some(1024),
+        ..default()
+    };
+    let source = SourceResource::new(Cursor::new(bytes.clone()));
+    app.world_mut().spawn((TerminalState::new(), config, source));
+
+    let mut expected = TerminalState::new();
+    expected.process_bytes(&bytes);
+
+    // Wait for the reader thread to deliver everything, one budget per frame
+    let deadline = Instant::now() + Duration::from_secs(2);
+    let mut frames = 0;
+    loop {
+        app.update();
+        let term_state = common::component::<TerminalState>(&app);
+        let done = term_state.has_received_output() && term_state.pending_bytes() == 0;
+        frames += 1;
+        if done && term_state.cells().eq(expected.cells()) {
+            break;
+        }
+        assert!(Instant::now() < deadline, "Output never fully processed");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    assert!(frames >= bytes.len().div_ceil(1024), "Burst should span several frames");
+}
@@ -0,0 +1,47 @@
+//! Tests for iterating terminal cells with resolved attributes.
+
+use bevy_terminal::{CellInfo, TerminalState};
+
+fn cell_at(cells: &[(usize, usize, CellInfo)], col: usize, row: usize) -> CellInfo {
+    cells
+        .iter()
+        .find(|(c, r, _)| *c == col && *r == row)
+        .map(|(_, _, info)| *info)
+        .expect("cell out of range")
+}
+
+#[test]
+fn test_cells_iterates_whole_grid() {
+    let term_state = TerminalState::new();
+    let cells: Vec<_> = term_state.cells().collect();
+
+    assert_eq!(cells.len(), term_state.cols * term_state.rows);
+    // Row-major order
+    assert_eq!((cells[0].0, cells[0].1), (0, 0));
+    assert_eq!((cells[1].0, cells[1].1), (1, 0));
+    assert_eq!((cells[term_state.cols].0, cells[term_state.cols].1), (0, 1));
+    assert!(cells.iter().all(|(_, _, info)| info.c == ' '));
+}
+
+#[test]
+fn test_cells_resolve_attributes() {
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes(b"ab\r\n\x1b[1;3;4;31mX\x1b[0m\x1b[7mR\x1b[0m");
+
+    let cells: Vec<_> = term_state.cells().collect();
+    let plain = cell_at(&cells, 0, 0);
+    assert_eq!(plain.c, 'a');
+    assert_eq!(cell_at(&cells, 1, 0).c, 'b');
+    assert!(!plain.bold && !plain.italic && !plain.underline);
+
+    let styled = cell_at(&cells, 0, 1);
+    assert_eq!(styled.c, 'X');
+    assert!(styled.bold && styled.italic && styled.underline);
+    assert_ne!(styled.fg, plain.fg, "SGR 31 should resolve to a different foreground");
+    assert_eq!(styled.bg, plain.bg);
+
+    // Reverse video is applied to the colors
+    let inverse = cell_at(&cells, 1, 1);
+    assert_eq!(inverse.c, 'R');
+    assert_eq!((inverse.fg, inverse.bg), (plain.bg, plain.fg));
+}
@@ -0,0 +1,88 @@
+//! Tests for the fixed-step effect clock.
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy_terminal::clock::advance_terminal_clock;
+use bevy_terminal::TerminalClock;
+use std::time::Duration;
+
+fn clock() -> TerminalClock {
+    TerminalClock::new(Duration::from_millis(10), Duration::from_secs(1))
+}
+
+#[test]
+fn test_fixed_steps_independent_of_frame_rate() {
+    // One long frame vs. many short ones covering the same time
+    let mut slow = clock();
+    slow.advance(Duration::from_millis(250));
+
+    let mut fast = clock();
+    for _ in 0..50 {
+        fast.advance(Duration::from_millis(5));
+    }
+
+    assert_eq!(slow.time, Duration::from_millis(250));
+    assert_eq!(slow.time, fast.time);
+    assert_eq!(slow.blink_phase, fast.blink_phase);
+
+    // Partial steps carry over instead of being lost
+    let mut jittery = clock();
+    for _ in 0..3 {
+        jittery.advance(Duration::from_millis(7));
+    }
+    assert_eq!(jittery.time, Duration::from_millis(20));
+    jittery.advance(Duration::from_millis(9));
+    assert_eq!(jittery.time, Duration::from_millis(30));
+}
+
+#[test]
+fn test_blink_phase() {
+    let mut clock = clock();
+    assert!(clock.blink_on());
+
+    clock.set_time(Duration::from_millis(250));
+    assert_eq!(clock.blink_phase, 0.25);
+    assert!(clock.blink_on());
+
+    clock.set_time(Duration::from_millis(750));
+    assert!(!clock.blink_on());
+
+    // Wraps every period
+    clock.set_time(Duration::from_millis(2_100));
+    assert!((clock.blink_phase - 0.1).abs() < 1e-6);
+}
+
+#[test]
+fn test_system_advances_clock() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+        .insert_resource(clock())
+        .add_systems(Update, advance_terminal_clock);
+
+    // The first update only initializes Time
+    for _ in 0..6 {
+        app.update();
+    }
+    assert_eq!(app.world().resource::<TerminalClock>().time, Duration::from_millis(500));
+}
+
+#[test]
+fn test_manual_clock_is_left_alone() {
+    let mut app = App::new();
+    let mut manual = clock();
+    manual.manual = true;
+    manual.set_time(Duration::from_millis(600));
+
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+        .insert_resource(manual)
+        .add_systems(Update, advance_terminal_clock);
+
+    for _ in 0..5 {
+        app.update();
+    }
+    let clock = app.world().resource::<TerminalClock>();
+    assert_eq!(clock.time, Duration::from_millis(600));
+    assert!(!clock.blink_on());
+}
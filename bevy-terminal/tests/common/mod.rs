@@ -0,0 +1,142 @@
+//! Fixtures shared by the integration tests.
+//!
+//! Each test binary compiles this module on its own and uses a different
+//! part of it, hence the `dead_code` allowance.
+
+#![allow(dead_code)]
+
+use bevy::ecs::component::Mutable;
+use bevy::prelude::*;
+use bevy_terminal::atlas::GlyphAtlas;
+use bevy_terminal::config::TerminalConfig;
+use bevy_terminal::font::FontMetrics;
+use bevy_terminal::gpu_prep::prepare_terminal_cpu_buffer;
+use bevy_terminal::render_node::{update_terminal_extraction, ExtractedTerminalData};
+use bevy_terminal::renderer::initialize_terminal_textures;
+use bevy_terminal::software::render_to_image;
+use bevy_terminal::{TerminalState, TerminalTexture};
+
+/// The bundled Cascadia Mono, at the default size.
+pub fn font_metrics() -> FontMetrics {
+    FontMetrics::load_cascadia_mono().expect("Font load failed")
+}
+
+/// Printable ASCII, the glyph set most tests generate.
+pub fn ascii_chars() -> Vec<char> {
+    (32..=126).map(|c| c as u8 as char).collect()
+}
+
+/// An atlas of printable ASCII.
+pub fn ascii_atlas() -> GlyphAtlas {
+    GlyphAtlas::generate(&font_metrics(), &ascii_chars()).expect("Atlas failed")
+}
+
+/// The plugin's default atlas: ASCII plus box drawing and blocks.
+pub fn mvp_atlas() -> GlyphAtlas {
+    GlyphAtlas::generate_mvp(&font_metrics()).expect("Atlas failed")
+}
+
+/// The app's only terminal entity.
+pub fn terminal(app: &mut App) -> Entity {
+    let world = app.world_mut();
+    world
+        .query_filtered::<Entity, With<TerminalState>>()
+        .single(world)
+        .expect("Expected one terminal")
+}
+
+/// The only `T` component in the app, such as the terminal's state.
+pub fn component<T: Component>(app: &App) -> &T {
+    let world = app.world();
+    let mut query = world.try_query::<&T>().expect("Component not registered");
+    query.single(world).expect("Expected one entity with the component")
+}
+
+/// The only `T` component in the app, for changing it.
+pub fn component_mut<T: Component<Mutability = Mutable>>(app: &mut App) -> Mut<'_, T> {
+    let world = app.world_mut();
+    world.query::<&mut T>().single_mut(world).expect("Expected one entity with the component")
+}
+
+/// A headless app that prepares `term_state`'s cells into its
+/// `TerminalCpuBuffer` on every update, using the ASCII atlas.
+pub fn prep_app(term_state: TerminalState) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(ascii_atlas())
+        .add_systems(Update, prepare_terminal_cpu_buffer);
+    app.world_mut().spawn(term_state);
+    app
+}
+
+/// A headless app with asset storage for images.
+pub fn image_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default())).init_asset::<Image>();
+    app
+}
+
+/// Adds `atlas`'s texture to the app's images and inserts the atlas.
+pub fn insert_atlas(app: &mut App, mut atlas: GlyphAtlas) {
+    let image = atlas.to_bevy_image();
+    atlas.texture_handle = Some(app.world_mut().resource_mut::<Assets<Image>>().add(image));
+    app.insert_resource(atlas);
+}
+
+/// One frame of a terminal texture, shaded by the CPU backend.
+pub struct CpuFrame {
+    pub image: Image,
+    pub cell_width: u32,
+    pub cell_height: u32,
+}
+
+impl CpuFrame {
+    /// RGBA of texel (`x`, `y`).
+    pub fn texel(&self, x: u32, y: u32) -> [u8; 4] {
+        let offset = ((y * self.image.width() + x) * 4) as usize;
+        let data = self.image.data.as_ref().expect("Texture has no data");
+        data[offset..offset + 4].try_into().unwrap()
+    }
+
+    /// Texels of grid cell (`col`, `row`), row by row.
+    pub fn cell(&self, col: u32, row: u32) -> Vec<[u8; 4]> {
+        let (left, top) = (col * self.cell_width, row * self.cell_height);
+        (top..top + self.cell_height)
+            .flat_map(|y| (left..left + self.cell_width).map(move |x| (x, y)))
+            .map(|(x, y)| self.texel(x, y))
+            .collect()
+    }
+}
+
+/// Prepares and extracts one frame of `term_state` under `config` with the
+/// default atlas, then shades it with `software::render_to_image`.
+pub fn render_cpu(term_state: TerminalState, config: TerminalConfig) -> CpuFrame {
+    render_cpu_with_font(term_state, config, font_metrics())
+}
+
+/// `render_cpu` with the default atlas of `font_metrics`. With the `emoji`
+/// feature, grapheme clusters are rasterized from it too.
+pub fn render_cpu_with_font(term_state: TerminalState, config: TerminalConfig, font_metrics: FontMetrics) -> CpuFrame {
+    let atlas = GlyphAtlas::generate_mvp(&font_metrics).expect("Atlas failed");
+    let (cell_width, cell_height) = (atlas.cell_width, atlas.cell_height);
+
+    let mut app = image_app();
+    insert_atlas(&mut app, atlas);
+    app.world_mut().spawn((term_state, config));
+    app.insert_resource(font_metrics)
+        .add_systems(Startup, initialize_terminal_textures)
+        .add_systems(Update, prepare_terminal_cpu_buffer)
+        .add_systems(PostUpdate, update_terminal_extraction);
+    #[cfg(feature = "emoji")]
+    app.add_systems(
+        Update,
+        bevy_terminal::grapheme::rasterize_clusters.after(prepare_terminal_cpu_buffer),
+    );
+    app.update();
+
+    let handle = &component::<TerminalTexture>(&app).handle;
+    let world = app.world();
+    let mut image = world.resource::<Assets<Image>>().get(handle).expect("Texture missing").clone();
+    render_to_image(component::<ExtractedTerminalData>(&app), world.resource::<GlyphAtlas>(), &mut image);
+    CpuFrame { image, cell_width, cell_height }
+}
@@ -0,0 +1,126 @@
+//! Tests for the CRT material quad (`crt` feature).
+
+mod common;
+
+use bevy::prelude::*;
+use bevy::camera::primitives::MeshAabb;
+use bevy::sprite_render::MeshMaterial2d;
+use bevy_terminal::crt::{spawn_terminal_crt, CrtMaterial, CrtPlugin, CrtSettings, TerminalCrt};
+use bevy_terminal::TerminalTexture;
+
+fn crt_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_asset::<Image>()
+        .init_asset::<Mesh>()
+        .init_asset::<Shader>()
+        .add_plugins(CrtPlugin);
+    let handle = app.world_mut().resource_mut::<Assets<Image>>().add(Image::default());
+    app.world_mut().spawn(TerminalTexture {
+        handle,
+        width: 960,
+        height: 420,
+    });
+    app.add_systems(
+        Startup,
+        |mut commands: Commands,
+         mut meshes: ResMut<Assets<Mesh>>,
+         mut materials: ResMut<Assets<CrtMaterial>>,
+         terminal: Single<(Entity, &TerminalTexture)>| {
+            let (terminal, texture) = *terminal;
+            spawn_terminal_crt(&mut commands, &mut meshes, &mut materials, terminal, texture, CrtSettings::default(), Transform::default());
+        },
+    );
+    app.update();
+    app
+}
+
+/// Size of the CRT quad's mesh.
+fn quad_size(app: &mut App) -> Vec2 {
+    let mesh = app
+        .world_mut()
+        .query_filtered::<&Mesh2d, With<TerminalCrt>>()
+        .single(app.world())
+        .expect("One CRT quad")
+        .0
+        .clone();
+    let aabb = app.world().resource::<Assets<Mesh>>().get(&mesh).unwrap().compute_aabb().unwrap();
+    aabb.half_extents.truncate() * 2.0
+}
+
+#[test]
+fn test_crt_quad_follows_texture() {
+    let mut app = crt_app();
+
+    // One world unit per texel, showing the terminal texture
+    assert_eq!(quad_size(&mut app), Vec2::new(960.0, 420.0));
+    let texture = common::component::<TerminalTexture>(&app).handle.clone();
+    let material = app
+        .world_mut()
+        .query_filtered::<&MeshMaterial2d<CrtMaterial>, With<TerminalCrt>>()
+        .single(app.world())
+        .unwrap()
+        .0
+        .clone();
+    let materials = app.world().resource::<Assets<CrtMaterial>>();
+    assert_eq!(materials.get(&material).unwrap().texture, texture);
+
+    // A resized terminal resizes the quad
+    let mut texture = common::component_mut::<TerminalTexture>(&mut app);
+    texture.width = 480;
+    texture.height = 210;
+    app.update();
+    assert_eq!(quad_size(&mut app), Vec2::new(480.0, 210.0));
+}
+
+#[test]
+fn test_crt_material_uniforms() {
+    use bevy::render::render_resource::encase::UniformBuffer;
+
+    // The quad's material carries the settings it was spawned with
+    let mut app = crt_app();
+    let material = app
+        .world_mut()
+        .query_filtered::<&MeshMaterial2d<CrtMaterial>, With<TerminalCrt>>()
+        .single(app.world())
+        .unwrap()
+        .0
+        .clone();
+    let materials = app.world().resource::<Assets<CrtMaterial>>();
+    assert_eq!(materials.get(&material).unwrap().settings, CrtSettings::default());
+
+    // Uploaded as four f32s, in the order the shader's struct declares them
+    let settings = CrtSettings {
+        scanline_intensity: 0.1,
+        curvature: 0.2,
+        bloom: 0.3,
+        vignette: 0.4,
+    };
+    let mut buffer = UniformBuffer::new(Vec::<u8>::new());
+    buffer.write(&settings).expect("Uniform encoding failed");
+    let floats: Vec<f32> = buffer
+        .into_inner()
+        .chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+        .collect();
+    assert_eq!(floats, [0.1, 0.2, 0.3, 0.4]);
+
+    let shader = include_str!("../assets/shaders/crt.wgsl");
+    let declared = shader.split("struct CrtSettings {").nth(1).and_then(|rest| rest.split("};").next()).unwrap();
+    let fields: Vec<&str> = declared
+        .split(',')
+        .filter_map(|field| field.split(':').next())
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect();
+    assert_eq!(fields, ["scanline_intensity", "curvature", "bloom", "vignette"]);
+
+    // Bound where `AsBindGroup` puts the material's fields
+    for binding in [
+        "@group(2) @binding(0) var<uniform> settings: CrtSettings;",
+        "@group(2) @binding(1) var screen_texture: texture_2d<f32>;",
+        "@group(2) @binding(2) var screen_sampler: sampler;",
+    ] {
+        assert!(shader.contains(binding), "crt.wgsl is missing {:?}", binding);
+    }
+}
@@ -0,0 +1,44 @@
+//! Tests for reading the cursor position, visibility and shape.
+
+use bevy_terminal::{CursorStyle, TerminalState};
+
+#[test]
+fn test_cursor_position() {
+    let mut term_state = TerminalState::new();
+    assert_eq!(term_state.cursor(), (0, 0));
+
+    term_state.process_bytes(b"hello");
+    assert_eq!(term_state.cursor(), (5, 0));
+
+    // CUP is 1-based, row first
+    term_state.process_bytes(b"\x1b[10;5H");
+    assert_eq!(term_state.cursor(), (4, 9));
+}
+
+#[test]
+fn test_cursor_visibility() {
+    let mut term_state = TerminalState::new();
+    assert!(term_state.cursor_visible());
+
+    term_state.process_bytes(b"\x1b[?25l");
+    assert!(!term_state.cursor_visible());
+
+    term_state.process_bytes(b"\x1b[?25h");
+    assert!(term_state.cursor_visible());
+}
+
+#[test]
+fn test_cursor_shape() {
+    let mut term_state = TerminalState::new();
+    assert_eq!(term_state.cursor_shape(), CursorStyle::Block);
+
+    // DECSCUSR: 5 = blinking bar, 4 = steady underline, 0 = default
+    term_state.process_bytes(b"\x1b[5 q");
+    assert_eq!(term_state.cursor_shape(), CursorStyle::Bar);
+
+    term_state.process_bytes(b"\x1b[4 q");
+    assert_eq!(term_state.cursor_shape(), CursorStyle::Underline);
+
+    term_state.process_bytes(b"\x1b[0 q");
+    assert_eq!(term_state.cursor_shape(), CursorStyle::Block);
+}
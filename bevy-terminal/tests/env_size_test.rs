@@ -0,0 +1,43 @@
+//! Tests for taking the initial grid size from `COLUMNS`/`LINES`.
+//!
+//! The only test in this binary that touches the environment, so nothing
+//! else reads it concurrently.
+
+use bevy_terminal::config::{env_grid_size, ENV_COLS_RANGE, ENV_ROWS_RANGE};
+use bevy_terminal::TerminalPlugin;
+
+#[test]
+fn test_initial_size_from_env() {
+    std::env::remove_var("COLS");
+    std::env::set_var("COLUMNS", "100");
+    std::env::set_var("LINES", "40");
+    assert_eq!(env_grid_size(), Some((100, 40)));
+
+    // Opt-in: the default keeps 120×30
+    let term_state = TerminalPlugin::default().initial_terminal_state();
+    assert_eq!((term_state.cols, term_state.rows), (120, 30));
+
+    let term_state = TerminalPlugin::default().with_size_from_env(true).initial_terminal_state();
+    assert_eq!((term_state.cols, term_state.rows), (100, 40));
+
+    // Out-of-range sizes are clamped
+    std::env::set_var("COLUMNS", "100000");
+    std::env::set_var("LINES", "1");
+    assert_eq!(env_grid_size(), Some((*ENV_COLS_RANGE.end(), *ENV_ROWS_RANGE.start())));
+
+    // Both are needed, as positive integers; COLS stands in for COLUMNS
+    std::env::set_var("LINES", "abc");
+    assert_eq!(env_grid_size(), None);
+    std::env::remove_var("COLUMNS");
+    std::env::set_var("COLS", "80");
+    std::env::set_var("LINES", "24");
+    assert_eq!(env_grid_size(), Some((80, 24)));
+    std::env::set_var("COLS", "0");
+    assert_eq!(env_grid_size(), None);
+
+    // Nothing set: the default size even when opted in
+    std::env::remove_var("COLS");
+    std::env::remove_var("LINES");
+    let term_state = TerminalPlugin::default().with_size_from_env(true).initial_terminal_state();
+    assert_eq!((term_state.cols, term_state.rows), (120, 30));
+}
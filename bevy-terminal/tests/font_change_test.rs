@@ -0,0 +1,143 @@
+//! Tests for rebuilding the atlas and texture when the font changes at runtime.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_terminal::atlas::GlyphAtlas;
+use bevy_terminal::font::FontMetrics;
+use bevy_terminal::renderer::{apply_font_change, initialize_terminal_textures};
+use bevy_terminal::{TerminalState, TerminalTexture};
+
+const CASCADIA_MONO: &[u8] = include_bytes!("../assets/fonts/CascadiaMono-Regular.ttf");
+
+fn setup_app(font_size: f32) -> App {
+    let font_metrics = FontMetrics::load(CASCADIA_MONO, font_size).expect("Font load failed");
+    let mut atlas = GlyphAtlas::generate_mvp(&font_metrics).expect("Atlas failed");
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_asset::<Image>();
+    atlas.texture_handle = Some(
+        app.world_mut()
+            .resource_mut::<Assets<Image>>()
+            .add(atlas.to_bevy_image()),
+    );
+
+    app.insert_resource(font_metrics)
+        .insert_resource(atlas)
+        .add_systems(Startup, initialize_terminal_textures)
+        .add_systems(Update, apply_font_change);
+    app.world_mut().spawn(TerminalState::new());
+    app.update();
+    app
+}
+
+fn image_size(app: &App, handle: &Handle<Image>) -> (u32, u32) {
+    let image = app.world().resource::<Assets<Image>>().get(handle).expect("Image missing");
+    (image.width(), image.height())
+}
+
+#[test]
+fn test_font_size_change_resizes_texture() {
+    let mut app = setup_app(14.0);
+
+    let texture = common::component::<TerminalTexture>(&app);
+    let (handle, before) = (texture.handle.clone(), (texture.width, texture.height));
+    let atlas = app.world().resource::<GlyphAtlas>();
+    let atlas_handle = atlas.texture_handle.clone().unwrap();
+    let glyph_a = atlas.get_glyph_index('A');
+    assert_eq!(image_size(&app, &handle), before);
+
+    // Nothing changed: nothing rebuilt
+    app.update();
+    assert_eq!(image_size(&app, &handle), before);
+
+    app.insert_resource(FontMetrics::load(CASCADIA_MONO, 28.0).expect("Font load failed"));
+    app.update();
+
+    let atlas = app.world().resource::<GlyphAtlas>();
+    let (cell_width, cell_height) = (atlas.cell_width, atlas.cell_height);
+    assert_eq!(atlas.texture_handle.as_ref(), Some(&atlas_handle), "Atlas handle should be reused");
+    assert_eq!(atlas.get_glyph_index('A'), glyph_a, "Glyph indices should be stable");
+    assert_eq!(image_size(&app, &atlas_handle), (atlas.atlas_width, atlas.atlas_height));
+
+    let texture = common::component::<TerminalTexture>(&app);
+    assert_eq!(texture.handle, handle, "Texture handle should be reused");
+    assert_eq!((texture.width, texture.height), (cell_width * 120, cell_height * 30));
+    assert!(texture.width > before.0 && texture.height > before.1);
+    assert_eq!(image_size(&app, &handle), (texture.width, texture.height));
+}
+
+#[test]
+fn test_font_load_failure_reports_error() {
+    use bevy_terminal::config::TerminalConfig;
+    use bevy_terminal::events::{TerminalEvent, TerminalEventKind};
+    use bevy_terminal::font::FontSource;
+    use bevy_terminal::initialize_font_and_atlas;
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_asset::<Image>()
+        .add_message::<TerminalEvent>()
+        .insert_resource(TerminalConfig {
+            font: FontSource::Path("/nonexistent/pixel.ttf".into()),
+            ..Default::default()
+        })
+        .add_systems(Startup, initialize_font_and_atlas);
+    let terminal = app.world_mut().spawn(TerminalState::new()).id();
+    app.update();
+
+    // No panic and no atlas, just an error the game can show
+    assert!(!app.world().contains_resource::<GlyphAtlas>());
+    let events: Vec<_> = app
+        .world_mut()
+        .resource_mut::<Messages<TerminalEvent>>()
+        .drain()
+        .collect();
+    match events.as_slice() {
+        [TerminalEvent { terminal: sender, kind: TerminalEventKind::Error { message } }] => {
+            assert_eq!(*sender, terminal);
+            assert!(message.contains("pixel.ttf"), "{}", message);
+        }
+        other => panic!("Expected one Error, got {:?}", other),
+    }
+
+    // Font bytes work like the embedded font
+    app.insert_resource(TerminalConfig {
+        font: FontSource::Bytes(CASCADIA_MONO.to_vec()),
+        ..Default::default()
+    })
+    .add_systems(Update, initialize_font_and_atlas.run_if(run_once));
+    app.update();
+    assert!(app.world().contains_resource::<GlyphAtlas>());
+    assert!(app.world().contains_resource::<FontMetrics>());
+}
+
+#[test]
+fn test_configured_font_size_scales_cells() {
+    use bevy_terminal::config::TerminalConfig;
+    use bevy_terminal::events::TerminalEvent;
+    use bevy_terminal::initialize_font_and_atlas;
+
+    let startup = |font_size: f32| {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Image>()
+            .add_message::<TerminalEvent>()
+            .insert_resource(TerminalConfig { font_size, ..Default::default() })
+            .add_systems(Startup, (initialize_font_and_atlas, initialize_terminal_textures).chain());
+        app.world_mut().spawn(TerminalState::new());
+        app.update();
+        let atlas = app.world().resource::<GlyphAtlas>();
+        let texture = common::component::<TerminalTexture>(&app);
+        (atlas.cell_width, atlas.cell_height, texture.width, texture.height)
+    };
+
+    let (width_14, height_14, _, _) = startup(14.0);
+    let (width_28, height_28, texture_width, texture_height) = startup(28.0);
+
+    // Rounding to whole pixels keeps it from being exactly double
+    assert!(width_28.abs_diff(width_14 * 2) <= 1, "{} vs {}", width_28, width_14);
+    assert!(height_28.abs_diff(height_14 * 2) <= 1, "{} vs {}", height_28, height_14);
+    assert_eq!((texture_width, texture_height), (width_28 * 120, height_28 * 30));
+}
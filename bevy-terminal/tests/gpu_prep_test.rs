@@ -1,37 +1,34 @@
 use bevy::prelude::*;
+use std::time::Duration;
 use bevy_terminal::TerminalState;
 use bevy_terminal::atlas::GlyphAtlas;
-use bevy_terminal::font::FontMetrics;
+use bevy_terminal::TerminalClock;
+use bevy_terminal::config::{LoadingIndicator, StatusBarConfig, StatusBarText, TerminalConfig, WatermarkConfig};
+use bevy_terminal::events::{dispatch_terminal_events, TerminalEvent};
+use bevy_terminal::gpu_types::{
+    CELL_FLAG_BOLD, CELL_FLAG_CURSOR, CELL_FLAG_INVERSE, CELL_FLAG_ITALIC, CELL_FLAG_UNDERLINE,
+    GLYPH_BLANK,
+};
 // Import the preparation logic. We need to expose it in lib.rs first.
 use bevy_terminal::gpu_prep::{prepare_terminal_cpu_buffer, TerminalCpuBuffer};
 
+mod common;
+
 #[test]
 fn test_gpu_prep_system() {
     println!("\n🧪 Testing GPU Prep System: Grid → GpuBuffer\n");
 
-    // 1. Setup Dependencies
-    let font_metrics = FontMetrics::load_cascadia_mono().expect("Font load failed");
-    let chars: Vec<char> = (32..=126).map(|c| c as u8 as char).collect();
-    let atlas = GlyphAtlas::generate(&font_metrics, &chars).expect("Atlas failed");
-    
-    // 2. Setup Terminal State
+    // 1. Setup Terminal State
     let mut term_state = TerminalState::new();
     let test_str = "GPU_TEST";
     term_state.process_bytes(test_str.as_bytes());
 
-    // 3. Setup App
-    let mut app = App::new();
-    app.add_plugins(MinimalPlugins);
-    app.insert_resource(term_state);
-    app.insert_resource(atlas);
-    app.insert_resource(TerminalCpuBuffer::default());
-
-    // 4. Run System
-    app.add_systems(Update, prepare_terminal_cpu_buffer);
+    // 2. Setup App and run the system
+    let mut app = common::prep_app(term_state);
     app.update();
 
-    // 5. Verify Buffer Content
-    let buffer = app.world().resource::<TerminalCpuBuffer>();
+    // 3. Verify Buffer Content
+    let buffer = common::component::<TerminalCpuBuffer>(&app);
     let cells = &buffer.cells;
 
     assert_eq!(cells.len(), 120 * 30, "Buffer size mismatch");
@@ -56,8 +53,671 @@ fn test_gpu_prep_system() {
     
     // Verify empty space (index 8 should be space)
     let space_cell = cells[test_str.len()];
-    let space_index = atlas_ref.get_glyph_index(' ').unwrap();
-    assert_eq!(space_cell.glyph_index, space_index, "Space glyph mismatch");
+    assert_eq!(space_cell.glyph_index, GLYPH_BLANK, "Space glyph mismatch");
 
     println!("\n✅ TEST PASSED: GPU Prep system populates buffer correctly!");
 }
+
+fn status_bar_app(config: TerminalConfig, term_state: TerminalState) -> App {
+    let mut app = common::prep_app(term_state);
+    *common::component_mut::<TerminalConfig>(&mut app) = config;
+    app.add_message::<TerminalEvent>();
+    app.add_systems(Update, dispatch_terminal_events.before(prepare_terminal_cpu_buffer));
+    app
+}
+
+fn status_text(app: &App) -> String {
+    let buffer = common::component::<TerminalCpuBuffer>(app);
+    let atlas = app.world().resource::<GlyphAtlas>();
+    buffer
+        .status_cells
+        .iter()
+        .map(|cell| {
+            atlas
+                .glyph_index_map
+                .iter()
+                .find(|(_, index)| **index == cell.glyph_index)
+                .map(|(c, _)| *c)
+                .unwrap_or('\0')
+        })
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+#[test]
+fn test_status_bar_custom_text() {
+    let config = TerminalConfig {
+        status_bar: Some(StatusBarConfig {
+            text: StatusBarText::Custom("CRT-01".into()),
+            fg: [0xff, 0xff, 0xff],
+            bg: [0x00, 0x00, 0xff],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let mut app = status_bar_app(config, TerminalState::new());
+    app.update();
+
+    let buffer = common::component::<TerminalCpuBuffer>(&app);
+    assert_eq!(buffer.status_cells.len(), 120, "Status bar should span one row");
+    assert_eq!(buffer.status_cells[0].fg_color, 0xFFFFFFFF);
+    assert_eq!(buffer.status_cells[0].bg_color, 0xFFFF0000);
+    assert_eq!(status_text(&app), "CRT-01");
+}
+
+#[test]
+fn test_status_bar_follows_title() {
+    let config = TerminalConfig {
+        status_bar: Some(StatusBarConfig::default()),
+        ..Default::default()
+    };
+    let mut term_state = TerminalState::new();
+    // Push the (unset) title, then set one
+    term_state.process_bytes(b"\x1b[22t\x1b]0;~/projects\x07");
+
+    let mut app = status_bar_app(config, term_state);
+    app.update();
+    assert_eq!(status_text(&app), "~/projects");
+
+    // Popping back to the unset title resets the bar
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"\x1b[23t");
+    app.update();
+    assert_eq!(status_text(&app), "");
+}
+
+#[test]
+fn test_watermark_cells() {
+    let config = TerminalConfig {
+        watermark: Some(WatermarkConfig {
+            text: "DEMO".into(),
+            column: 100,
+            row: 2,
+            color: [0xff, 0x00, 0x00],
+            opacity: 0.5,
+        }),
+        ..Default::default()
+    };
+    let mut app = status_bar_app(config, TerminalState::new());
+    app.update();
+
+    let buffer = common::component::<TerminalCpuBuffer>(&app);
+    let atlas = app.world().resource::<GlyphAtlas>();
+    let glyphs: Vec<u32> = buffer.watermark_cells.iter().map(|cell| cell.glyph_index).collect();
+    let expected: Vec<u32> = "DEMO".chars().map(|c| atlas.get_glyph_index(c).unwrap()).collect();
+    assert_eq!(glyphs, expected);
+    assert!(buffer.watermark_cells.iter().all(|cell| cell.fg_color == 0xFF0000FF));
+
+    // An empty grid still has the overlay to draw, so it isn't blank
+    assert!(!buffer.blank);
+
+    // Off by default (cursor hidden so nothing else is drawn)
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes(b"\x1b[?25l");
+    let mut app = status_bar_app(TerminalConfig::default(), term_state);
+    app.update();
+    let buffer = common::component::<TerminalCpuBuffer>(&app);
+    assert!(buffer.watermark_cells.is_empty());
+    assert!(buffer.blank);
+}
+
+#[test]
+fn test_blank_grid_detection() {
+    let mut app = common::prep_app(TerminalState::new());
+
+    // Fresh terminal: nothing but background
+    app.update();
+    assert!(common::component::<TerminalCpuBuffer>(&app).blank);
+
+    // Output makes it non-blank
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"hello");
+    app.update();
+    assert!(!common::component::<TerminalCpuBuffer>(&app).blank);
+
+    // A space with a colored background is content too
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"\x1b[2J\x1b[H\x1b[41m \x1b[0m");
+    app.update();
+    assert!(!common::component::<TerminalCpuBuffer>(&app).blank);
+    // ...drawn as background only, in its own color
+    let cell = common::component::<TerminalCpuBuffer>(&app).cells[0];
+    assert_eq!(cell.glyph_index, GLYPH_BLANK);
+    assert_ne!(cell.bg_color, 0xFF261B1A);
+
+    // Clearing the screen returns to blank
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"\x1b[2J");
+    app.update();
+    assert!(common::component::<TerminalCpuBuffer>(&app).blank);
+}
+
+#[test]
+fn test_full_reset_clears_buffer() {
+    let mut app = common::prep_app(TerminalState::new());
+
+    // Colored text on both screens, cursor moved away from home
+    common::component_mut::<TerminalState>(&mut app)
+        .process_bytes(b"\x1b[31;44mred on blue\r\nmore\x1b[?1049h\x1b[42mALT\x1b[10;20H");
+    app.update();
+    assert!(!common::component::<TerminalCpuBuffer>(&app).blank);
+
+    // RIS: full reset to initial state
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"\x1bc");
+    app.update();
+
+    let buffer = common::component::<TerminalCpuBuffer>(&app);
+    assert!(buffer.blank, "RIS should leave a blank grid");
+    for (i, cell) in buffer.cells.iter().enumerate() {
+        assert_eq!(cell.glyph_index, GLYPH_BLANK, "Cell {} not cleared", i);
+        assert_eq!(cell.fg_color, 0xFFF5CAC0, "Cell {} FG not default", i);
+        assert_eq!(cell.bg_color, 0xFF261B1A, "Cell {} BG not default", i);
+    }
+
+    // Cursor home, primary screen, default attributes for new output
+    let term_state = common::component::<TerminalState>(&app);
+    {
+        let term = term_state.term.lock();
+        let cursor = term.grid().cursor.point;
+        assert_eq!((cursor.line.0, cursor.column.0), (0, 0));
+    }
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"x");
+    app.update();
+    let buffer = common::component::<TerminalCpuBuffer>(&app);
+    assert_eq!(buffer.cells[0].fg_color, 0xFFF5CAC0);
+    assert_eq!(buffer.cells[0].bg_color, 0xFF261B1A);
+}
+
+#[test]
+fn test_loading_indicator_until_first_output() {
+    let atlas = common::mvp_atlas();
+    let block = atlas.get_glyph_index('█').unwrap();
+
+    let mut clock = TerminalClock::default();
+    clock.manual = true;
+
+    let mut app = common::prep_app(TerminalState::new());
+    *common::component_mut::<TerminalConfig>(&mut app) = TerminalConfig {
+        loading_indicator: Some(LoadingIndicator::Cursor),
+        ..default()
+    };
+    app.insert_resource(clock);
+    app.insert_resource(atlas);
+
+    // Visible half of the blink cycle
+    app.update();
+    assert_eq!(common::component::<TerminalCpuBuffer>(&app).cells[0].glyph_index, block);
+
+    // Hidden half
+    app.world_mut().resource_mut::<TerminalClock>().set_time(Duration::from_millis(600));
+    app.update();
+    assert_eq!(common::component::<TerminalCpuBuffer>(&app).cells[0].glyph_index, GLYPH_BLANK);
+
+    // First output replaces the placeholder for good
+    app.world_mut().resource_mut::<TerminalClock>().set_time(Duration::ZERO);
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"\x1b[m");
+    app.update();
+    assert!(common::component::<TerminalState>(&app).has_received_output());
+    assert_eq!(common::component::<TerminalCpuBuffer>(&app).cells[0].glyph_index, GLYPH_BLANK);
+}
+
+#[test]
+fn test_italic_flag() {
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes(b"\x1b[3mIt\x1b[23mUp\x1b[1;3mB\x1b[0m");
+
+    let mut app = common::prep_app(term_state);
+    app.update();
+
+    let cells = &common::component::<TerminalCpuBuffer>(&app).cells;
+    let italic: Vec<bool> = cells[..5].iter().map(|cell| cell.flags & CELL_FLAG_ITALIC != 0).collect();
+    assert_eq!(italic, [true, true, false, false, true]);
+    // Untouched cells stay upright
+    assert_eq!(cells[5].flags & CELL_FLAG_ITALIC, 0);
+}
+
+#[test]
+fn test_attribute_flags() {
+    let mut term_state = TerminalState::new();
+    // bold, underline, curly underline, inverse, bold+inverse, plain
+    term_state.process_bytes(b"\x1b[1mB\x1b[0;4mU\x1b[4:3mC\x1b[0;7mR\x1b[1mX\x1b[0mP");
+
+    let mut app = common::prep_app(term_state);
+    app.update();
+
+    let flags: Vec<u32> = common::component::<TerminalCpuBuffer>(&app).cells[..6]
+        .iter()
+        .map(|cell| cell.flags)
+        .collect();
+    assert_eq!(
+        flags,
+        [
+            CELL_FLAG_BOLD,
+            CELL_FLAG_UNDERLINE,
+            CELL_FLAG_UNDERLINE,
+            CELL_FLAG_INVERSE,
+            CELL_FLAG_BOLD | CELL_FLAG_INVERSE,
+            0,
+        ]
+    );
+
+    // An inverse or underlined space is visible, so the grid isn't blank
+    for sgr in [&b"7"[..], b"4"] {
+        let mut term_state = common::component_mut::<TerminalState>(&mut app);
+        term_state.process_bytes(b"\x1b[2J\x1b[H\x1b[");
+        term_state.process_bytes(sgr);
+        term_state.process_bytes(b"m \x1b[0m");
+        app.update();
+        assert!(!common::component::<TerminalCpuBuffer>(&app).blank);
+    }
+}
+
+#[test]
+fn test_dim_text_colors() {
+    use bevy_terminal::TerminalPalette;
+
+    // Dim red, plain red, dim orange, dim default foreground
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes(b"\x1b[2;31mR\x1b[22mR\x1b[2;38;2;255;128;0mO\x1b[39mF\x1b[0m");
+
+    let mut app = common::prep_app(term_state);
+    app.update();
+
+    let palette = TerminalPalette::default();
+    let dim = |rgb: [u8; 3]| rgb.map(|channel| (channel as f32 * 0.66) as u8);
+    let pack = |[r, g, b]: [u8; 3]| u32::from_le_bytes([r, g, b, 0xff]);
+    let fg: Vec<u32> = common::component::<TerminalCpuBuffer>(&app).cells[..4].iter().map(|cell| cell.fg_color).collect();
+    assert_eq!(
+        fg,
+        [dim(palette.ansi[1]), palette.ansi[1], [0xa8, 0x54, 0x00], dim(palette.foreground)].map(pack)
+    );
+}
+
+#[test]
+fn test_mark_dirty_forces_redraw() {
+    use bevy_terminal::render_node::{update_terminal_extraction, ExtractedTerminalData, RenderTerminal, TerminalDispatch};
+    use bevy_terminal::renderer::initialize_terminal_textures;
+
+    let mut app = common::image_app();
+    common::insert_atlas(&mut app, common::ascii_atlas());
+    // No cursor, so a blank grid has nothing to draw
+    let config = TerminalConfig { show_cursor: false, ..Default::default() };
+    app.world_mut().spawn((TerminalState::new(), config));
+    app.add_systems(Startup, initialize_terminal_textures);
+    app.add_systems(Update, prepare_terminal_cpu_buffer);
+    app.add_systems(PostUpdate, update_terminal_extraction);
+
+    // Each frame's data through the render node's own dispatch choice
+    let mut render: Option<RenderTerminal> = None;
+    let mut dispatch = |app: &mut App| {
+        app.update();
+        let data = common::component::<ExtractedTerminalData>(app).clone();
+        let terminal = render.get_or_insert_with(|| RenderTerminal::new(data.clone()));
+        terminal.data = data;
+        terminal.select_dispatch(true);
+        terminal.dispatch
+    };
+
+    // Idle blank terminal: cleared once, then skipped
+    assert_eq!(dispatch(&mut app), TerminalDispatch::Clear);
+    assert_eq!(dispatch(&mut app), TerminalDispatch::Skip);
+    assert!(!common::component::<TerminalCpuBuffer>(&app).force_redraw);
+
+    common::component_mut::<TerminalState>(&mut app).mark_dirty();
+    assert_eq!(dispatch(&mut app), TerminalDispatch::Clear);
+    assert!(common::component::<TerminalCpuBuffer>(&app).force_redraw);
+
+    // One redraw per call
+    assert_eq!(dispatch(&mut app), TerminalDispatch::Skip);
+    assert!(!common::component::<TerminalCpuBuffer>(&app).force_redraw);
+}
+
+#[test]
+fn test_mark_dirty_rebuilds_during_synchronized_update() {
+    let mut app = common::prep_app(TerminalState::new());
+    app.update();
+
+    // Open a synchronized update, then scribble over the buffer
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"\x1b[?2026hpending");
+    common::component_mut::<TerminalCpuBuffer>(&mut app).cells[0].glyph_index = 999;
+    app.update();
+    assert_eq!(common::component::<TerminalCpuBuffer>(&app).cells[0].glyph_index, 999);
+
+    // A forced redraw rebuilds from the (still unchanged) grid
+    common::component_mut::<TerminalState>(&mut app).mark_dirty();
+    app.update();
+    assert_eq!(common::component::<TerminalCpuBuffer>(&app).cells[0].glyph_index, GLYPH_BLANK);
+}
+
+#[test]
+fn test_cursor_flag() {
+    let cursor_cells = |app: &App| -> Vec<usize> {
+        let buffer = common::component::<TerminalCpuBuffer>(app);
+        (0..buffer.cells.len())
+            .filter(|&i| buffer.cells[i].flags & CELL_FLAG_CURSOR != 0)
+            .collect()
+    };
+
+    let mut app = status_bar_app(TerminalConfig::default(), TerminalState::new());
+    app.update();
+    assert_eq!(cursor_cells(&app), [0]);
+    // Drawn on its own, so the cursor alone doesn't make the grid non-blank
+    let buffer = common::component::<TerminalCpuBuffer>(&app);
+    assert!(buffer.blank);
+    assert_eq!(buffer.cursor_cell, Some(0));
+
+    // Follows the cursor, on exactly one cell
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"ab\x1b[3;10H");
+    app.update();
+    assert_eq!(cursor_cells(&app), [2 * 120 + 9]);
+
+    // Programs can hide it (DECTCEM)
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"\x1b[?25l");
+    app.update();
+    assert!(cursor_cells(&app).is_empty());
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"\x1b[?25h");
+    app.update();
+    assert_eq!(cursor_cells(&app), [2 * 120 + 9]);
+
+    // And games can turn it off
+    let config = TerminalConfig { show_cursor: false, ..Default::default() };
+    let mut app = status_bar_app(config, TerminalState::new());
+    app.update();
+    assert!(cursor_cells(&app).is_empty());
+}
+
+#[test]
+fn test_dec_special_graphics_render_as_box_drawing() {
+    // `\x1b(0` maps ASCII to line drawing until `\x1b(B`. Alacritty stores
+    // the mapped codepoint in the cell, so the atlas's box glyphs cover it.
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes(b"\x1b(0lqqqk\x1b(Bq\r\n");
+    // Insert mode (IRM) shifts the rest of the line right
+    term_state.process_bytes(b"abc\r\x1b[4hX\x1b[4l");
+    let text = term_state.get_visible_text();
+    let mut lines = text.lines();
+    assert!(lines.next().unwrap().starts_with("┌───┐q "));
+    assert!(lines.next().unwrap().starts_with("Xabc "));
+
+    let mut app = common::prep_app(term_state);
+    app.insert_resource(common::mvp_atlas());
+    app.update();
+
+    let atlas = app.world().resource::<GlyphAtlas>();
+    let glyph = |c| atlas.get_glyph_index(c).expect("Char missing from atlas");
+    let cells = &common::component::<TerminalCpuBuffer>(&app).cells;
+    let row: Vec<u32> = cells[..6].iter().map(|cell| cell.glyph_index).collect();
+    assert_eq!(row, ['┌', '─', '─', '─', '┐', 'q'].map(glyph));
+}
+
+#[test]
+fn test_osc_default_colors_repaint_cells() {
+    let mut app = common::prep_app(TerminalState::new());
+    app.update();
+    let theme_bg = common::component::<TerminalCpuBuffer>(&app).cells[0].bg_color;
+
+    // "Set dark background" the way theme scripts do, plus a foreground
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"\x1b]11;rgb:12/34/56\x07\x1b]10;#abcdef\x07hi");
+    app.update();
+    let buffer = common::component::<TerminalCpuBuffer>(&app);
+    assert_eq!(buffer.background, [0x12, 0x34, 0x56]);
+    assert!(buffer.force_redraw, "New background must redraw the whole texture");
+    assert!(buffer.cells.iter().all(|cell| cell.bg_color == 0xFF563412));
+    assert_eq!(buffer.cells[0].fg_color, 0xFFEFCDAB);
+    assert_eq!(
+        common::component::<TerminalState>(&app).background_color(),
+        [0x12, 0x34, 0x56]
+    );
+
+    // Unchanged next frame: no forced redraw
+    app.update();
+    assert!(!common::component::<TerminalCpuBuffer>(&app).force_redraw);
+
+    // OSC 111 restores the theme background
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"\x1b]111\x07");
+    app.update();
+    let buffer = common::component::<TerminalCpuBuffer>(&app);
+    assert!(buffer.force_redraw);
+    assert!(buffer.cells.iter().all(|cell| cell.bg_color == theme_bg));
+}
+
+#[test]
+fn test_viewport_renders_sub_window() {
+    use bevy_terminal::config::GridViewport;
+
+    let atlas = common::ascii_atlas();
+    let glyph = |c| if c == ' ' { GLYPH_BLANK } else { atlas.get_glyph_index(c).unwrap() };
+    let (row_1, row_2): (Vec<u32>, Vec<u32>) = ("bcd".chars().map(glyph).collect(), "ghi".chars().map(glyph).collect());
+    let panned: Vec<u32> = "de ij ".chars().map(glyph).collect();
+
+    // Text at columns 2-6 of rows 1-2; the cursor ends at column 7 of row 2
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes(b"\x1b[2;3Habcde\x1b[3;3Hfghij");
+
+    let viewport = GridViewport { col: 3, row: 1, cols: 3, rows: 2 };
+    let mut app = common::prep_app(term_state);
+    common::component_mut::<TerminalConfig>(&mut app).viewport = Some(viewport);
+    app.update();
+
+    // Only the 3×2 window is rendered, and the cursor is outside it
+    let cells = |app: &App| common::component::<TerminalCpuBuffer>(app).cells.clone();
+    let glyphs: Vec<u32> = cells(&app).iter().map(|cell| cell.glyph_index).collect();
+    assert_eq!(glyphs, [row_1, row_2].concat());
+    assert!(cells(&app).iter().all(|cell| cell.flags & CELL_FLAG_CURSOR == 0));
+
+    // Pan right: same size, now showing the cursor
+    common::component_mut::<TerminalConfig>(&mut app).viewport = Some(GridViewport { col: 5, ..viewport });
+    app.update();
+    let glyphs: Vec<u32> = cells(&app).iter().map(|cell| cell.glyph_index).collect();
+    assert_eq!(glyphs, panned);
+    let cursor: Vec<usize> = (0..6).filter(|&i| cells(&app)[i].flags & CELL_FLAG_CURSOR != 0).collect();
+    assert_eq!(cursor, [5]);
+
+    // Windows off the edge or bigger than the grid are pulled back inside
+    assert_eq!(
+        GridViewport { col: 118, row: 0, cols: 5, rows: 2 }.fit(120, 30),
+        GridViewport { col: 115, row: 0, cols: 5, rows: 2 }
+    );
+    assert_eq!(GridViewport { col: 7, row: 9, cols: 500, rows: 80 }.fit(120, 30), GridViewport::full(120, 30));
+}
+
+#[test]
+fn test_missing_glyphs_rasterized_on_demand() {
+    use bevy_terminal::atlas::{rasterize_missing_glyphs, upload_atlas_slots, AtlasUploads};
+
+    let mut app = common::image_app();
+    common::insert_atlas(&mut app, common::ascii_atlas());
+    let handle = app.world().resource::<GlyphAtlas>().texture_handle.clone().unwrap();
+
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes("café".as_bytes());
+    app.world_mut().spawn(term_state);
+    app.insert_resource(common::font_metrics())
+        .init_resource::<AtlasUploads>()
+        .add_systems(
+            Update,
+            (rasterize_missing_glyphs, upload_atlas_slots, prepare_terminal_cpu_buffer).chain(),
+        );
+    app.update();
+
+    // 'é' gets its own glyph instead of '?'
+    let atlas = app.world().resource::<GlyphAtlas>();
+    let accented = atlas.get_glyph_index('é').expect("Should be rasterized");
+    assert!(accented >= common::ascii_chars().len() as u32, "Runtime glyphs follow the generated set");
+    let cells = &common::component::<TerminalCpuBuffer>(&app).cells;
+    assert_eq!(cells[3].glyph_index, accented);
+
+    // Only its slot is queued for the GPU, and the main-world image has it
+    let uploads = app.world().resource::<AtlasUploads>();
+    assert_eq!(uploads.handle, Some(handle.clone()));
+    assert_eq!(uploads.regions.len(), 1);
+    assert_eq!(uploads.regions[0].data, atlas.slot_region(accented).data);
+    let image = app.world().resource::<Assets<Image>>().get(&handle).unwrap();
+    assert_eq!(image.data.as_deref(), Some(atlas.texture_data.as_slice()));
+}
+
+#[test]
+fn test_fallback_glyph_policy() {
+    use bevy_terminal::FallbackGlyph;
+
+    let glyphs = |fallback: FallbackGlyph| {
+        let mut atlas = common::ascii_atlas();
+        let tofu = atlas.insert_tofu().expect("Should have room");
+
+        let mut term_state = TerminalState::new();
+        term_state.process_bytes("a日".as_bytes());
+        let config = TerminalConfig {
+            fallback_glyph: fallback,
+            watermark: Some(WatermarkConfig {
+                text: "日".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut app = status_bar_app(config, term_state);
+        app.insert_resource(atlas);
+        app.update();
+
+        let atlas = app.world().resource::<GlyphAtlas>();
+        let buffer = common::component::<TerminalCpuBuffer>(&app);
+        let a = atlas.get_glyph_index('a').unwrap();
+        assert_eq!(buffer.cells[0].glyph_index, a);
+        assert_eq!(buffer.watermark_cells[0].glyph_index, buffer.cells[1].glyph_index, "Watermark falls back alike");
+        let glyph = |c| atlas.get_glyph_index(c).unwrap();
+        (buffer.cells[1].glyph_index, [glyph('?'), glyph('~'), tofu])
+    };
+
+    let (glyph, [question, _, _]) = glyphs(FallbackGlyph::default());
+    assert_eq!(glyph, question);
+    let (glyph, [_, tilde, _]) = glyphs(FallbackGlyph::Char('~'));
+    assert_eq!(glyph, tilde);
+    let (glyph, [_, _, tofu]) = glyphs(FallbackGlyph::Tofu);
+    assert_eq!(glyph, tofu);
+    let (glyph, _) = glyphs(FallbackGlyph::Blank);
+    assert_eq!(glyph, GLYPH_BLANK);
+}
+
+#[test]
+fn test_wide_characters_span_two_cells() {
+    use bevy_terminal::atlas::{rasterize_missing_glyphs, upload_atlas_slots, AtlasUploads};
+    use bevy_terminal::gpu_types::{CELL_FLAG_WIDE, CELL_FLAG_WIDE_SPACER};
+
+    // Cursor back on the first character
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes("日本語a\x1b[1G".as_bytes());
+
+    let mut app = common::image_app();
+    app.world_mut().spawn(term_state);
+    app.insert_resource(common::font_metrics())
+        .insert_resource(common::ascii_atlas())
+        .init_resource::<AtlasUploads>()
+        .add_systems(
+            Update,
+            (rasterize_missing_glyphs, upload_atlas_slots, prepare_terminal_cpu_buffer).chain(),
+        );
+    app.update();
+
+    // Each character takes two consecutive slots
+    let atlas = app.world().resource::<GlyphAtlas>();
+    let glyphs: Vec<u32> = ['日', '本', '語']
+        .into_iter()
+        .map(|character| {
+            assert!(atlas.is_wide(character), "{:?} should be wide", character);
+            atlas.get_glyph_index(character).expect("Should be rasterized")
+        })
+        .collect();
+    assert_eq!(app.world().resource::<AtlasUploads>().regions.len(), 6);
+    for &glyph in &glyphs {
+        for slot in [glyph, glyph + 1] {
+            let region = atlas.slot_region(slot);
+            assert!(region.data.chunks_exact(4).any(|texel| texel[3] > 0), "Slot {} is empty", slot);
+        }
+    }
+
+    // Two cells per character, the spacer repeating its glyph; 'a' follows
+    // in column 6
+    let cells = &common::component::<TerminalCpuBuffer>(&app).cells;
+    let wide = CELL_FLAG_WIDE | CELL_FLAG_WIDE_SPACER;
+    let layout: Vec<(u32, u32)> = cells[..7].iter().map(|cell| (cell.glyph_index, cell.flags & wide)).collect();
+    assert_eq!(
+        layout,
+        vec![
+            (glyphs[0], CELL_FLAG_WIDE),
+            (glyphs[0], CELL_FLAG_WIDE_SPACER),
+            (glyphs[1], CELL_FLAG_WIDE),
+            (glyphs[1], CELL_FLAG_WIDE_SPACER),
+            (glyphs[2], CELL_FLAG_WIDE),
+            (glyphs[2], CELL_FLAG_WIDE_SPACER),
+            (atlas.get_glyph_index('a').unwrap(), 0),
+        ]
+    );
+    assert_eq!(cells[1].fg_color, cells[0].fg_color);
+
+    // The cursor covers the whole character
+    let cursor: Vec<bool> = cells[..3].iter().map(|cell| cell.flags & CELL_FLAG_CURSOR != 0).collect();
+    assert_eq!(cursor, [true, true, false]);
+}
+
+#[test]
+fn test_cell_background_fills_whole_cell() {
+    // A status-bar style cell: bright text on a colored background
+    let term_state = || {
+        let mut term_state = TerminalState::new();
+        term_state.process_bytes(b"\x1b[38;2;0;255;0;48;2;255;0;128mg\x1b[0m");
+        term_state
+    };
+
+    let mut app = common::prep_app(term_state());
+    app.insert_resource(common::mvp_atlas());
+    app.update();
+    let cell = common::component::<TerminalCpuBuffer>(&app).cells[0];
+    assert_eq!(cell.fg_color, 0xFF00FF00);
+    assert_eq!(cell.bg_color, 0xFF8000FF);
+
+    // Shaded by the CPU backend: the corners are outside the glyph and show
+    // the background over the full cell rectangle
+    let frame = common::render_cpu(term_state(), TerminalConfig { show_cursor: false, ..Default::default() });
+    let (right, bottom) = (frame.cell_width - 1, frame.cell_height - 1);
+    for (x, y) in [(0, 0), (right, 0), (0, bottom), (right, bottom)] {
+        assert_eq!(frame.texel(x, y), [0xFF, 0x00, 0x80, 0xFF], "Corner ({}, {})", x, y);
+    }
+
+    // The glyph's solid texels are (close to) the foreground color
+    assert!(
+        frame.cell(0, 0).iter().any(|texel| texel[0] < 0x40 && texel[1] > 0xC0),
+        "Glyph not drawn in the foreground color"
+    );
+}
+
+#[test]
+fn test_palette_swapped_at_runtime() {
+    use bevy_terminal::{apply_terminal_palette, TerminalPalette};
+
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes(b"plain \x1b[32mgreen");
+
+    let mut app = common::prep_app(term_state);
+    app.init_resource::<TerminalPalette>();
+    app.add_systems(Update, apply_terminal_palette.before(prepare_terminal_cpu_buffer));
+    app.update();
+    assert_eq!(common::component::<TerminalCpuBuffer>(&app).background, [0x1a, 0x1b, 0x26]);
+
+    // Green phosphor
+    let mut palette = TerminalPalette {
+        foreground: [0x33, 0xff, 0x33],
+        background: [0x00, 0x11, 0x00],
+        ..Default::default()
+    };
+    palette.ansi[2] = [0x00, 0xcc, 0x00];
+    app.insert_resource(palette);
+    app.update();
+
+    let buffer = common::component::<TerminalCpuBuffer>(&app);
+    assert!(buffer.force_redraw, "New palette must redraw the whole texture");
+    assert_eq!(buffer.background, [0x00, 0x11, 0x00]);
+    assert!(buffer.cells.iter().all(|cell| cell.bg_color == 0xFF001100));
+    assert_eq!(buffer.cells[0].fg_color, 0xFF33FF33);
+    assert_eq!(buffer.cells[6].fg_color, 0xFF00CC00);
+    assert_eq!(common::component::<TerminalState>(&app).color(2), [0x00, 0xcc, 0x00]);
+
+    // Unchanged next frame: no forced redraw
+    app.update();
+    assert!(!common::component::<TerminalCpuBuffer>(&app).force_redraw);
+}
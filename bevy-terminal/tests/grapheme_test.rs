@@ -0,0 +1,187 @@
+//! Tests for grapheme cluster segmentation and cluster glyphs (`emoji` feature).
+#![cfg(feature = "emoji")]
+
+use bevy::prelude::*;
+use bevy_terminal::atlas::GlyphAtlas;
+use bevy_terminal::config::TerminalConfig;
+use bevy_terminal::font::{FontMetrics, FontSource};
+use bevy_terminal::gpu_prep::{prepare_terminal_cpu_buffer, TerminalCpuBuffer};
+use bevy_terminal::gpu_types::CELL_FLAG_COLOR_GLYPH;
+use bevy_terminal::grapheme::{rasterize_clusters, EmojiFont, GraphemeCluster};
+use bevy_terminal::TerminalState;
+
+mod common;
+
+fn cluster(column: usize, width: usize, text: &str) -> GraphemeCluster {
+    GraphemeCluster {
+        column,
+        width,
+        text: text.to_string(),
+    }
+}
+
+#[test]
+fn test_flag_occupies_two_cells() {
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes("a🇺🇸b".as_bytes());
+
+    let clusters = term_state.grapheme_clusters(0);
+    assert_eq!(clusters[..3], [cluster(0, 1, "a"), cluster(1, 2, "🇺🇸"), cluster(3, 1, "b")]);
+    // The rest of the row is blank
+    assert!(clusters[3..].iter().all(|cluster| cluster.text == " " && cluster.width == 1));
+    assert_eq!(clusters.len(), 120 - 1);
+}
+
+#[test]
+fn test_zwj_sequence_is_one_cluster() {
+    let family = "👨\u{200D}👩\u{200D}👧";
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes(format!("{}x", family).as_bytes());
+
+    let clusters = term_state.grapheme_clusters(0);
+    // Three wide emoji joined into one cluster
+    assert_eq!(clusters[..2], [cluster(0, 6, family), cluster(6, 1, "x")]);
+}
+
+#[test]
+fn test_cluster_glyphs() {
+    let font_metrics = FontMetrics::load_cascadia_mono().expect("Font load failed");
+    let chars: Vec<char> = (32..=126).map(|c| c as u8 as char).collect();
+    let atlas = GlyphAtlas::generate(&font_metrics, &chars).expect("Atlas failed");
+
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes("a🇺🇸b🇺🇸".as_bytes());
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_asset::<Image>()
+        .insert_resource(font_metrics)
+        .insert_resource(atlas)
+        .add_systems(Update, (prepare_terminal_cpu_buffer, rasterize_clusters).chain());
+    app.world_mut().spawn(term_state);
+    app.update();
+
+    let atlas = app.world().resource::<GlyphAtlas>();
+    let first = atlas.get_cluster_index("🇺🇸").expect("Flag should be rasterized");
+    assert!(first >= chars.len() as u32, "Cluster slots follow the characters");
+
+    // Both flags use the same two slots, one per cell
+    let cells = &common::component::<TerminalCpuBuffer>(&app).cells;
+    let glyphs: Vec<u32> = cells[..6].iter().map(|cell| cell.glyph_index).collect();
+    let a = atlas.get_glyph_index('a').unwrap();
+    let b = atlas.get_glyph_index('b').unwrap();
+    assert_eq!(glyphs, [a, first, first + 1, b, first, first + 1]);
+
+    // Drawn as the letters U and S
+    let slot_pixels = |index: u32| {
+        let cells_per_row = atlas.atlas_width / atlas.cell_width;
+        let x0 = (index % cells_per_row) * atlas.cell_width;
+        let y0 = (index / cells_per_row) * atlas.cell_height;
+        let mut alpha = Vec::new();
+        for y in y0..y0 + atlas.cell_height {
+            for x in x0..x0 + atlas.cell_width {
+                alpha.push(atlas.texture_data[((y * atlas.atlas_width + x) * 4 + 3) as usize]);
+            }
+        }
+        alpha
+    };
+    assert_eq!(slot_pixels(first), slot_pixels(atlas.get_glyph_index('U').unwrap()));
+    assert_eq!(slot_pixels(first + 1), slot_pixels(atlas.get_glyph_index('S').unwrap()));
+    assert!(!atlas.is_color_cluster("🇺🇸"));
+    assert!(cells[..6].iter().all(|cell| cell.flags & CELL_FLAG_COLOR_GLYPH == 0));
+}
+
+/// A font with a cmap, a GSUB table and color bitmaps (CBDT/CBLC), and
+/// nothing else: 🇺 and 🇸 map to glyphs 1 and 2, which ligate into
+/// glyph 3, a 4×4 bitmap red on the left half and blue on the right.
+fn flag_font() -> Vec<u8> {
+    fn be16(values: &[u16]) -> Vec<u8> {
+        values.iter().flat_map(|value| value.to_be_bytes()).collect()
+    }
+    fn be32(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|value| value.to_be_bytes()).collect()
+    }
+
+    // 5 bytes of small glyph metrics, then premultiplied BGRA rows
+    let mut bitmap = vec![4, 4, 0, 4, 4];
+    for _ in 0..4 {
+        bitmap.extend([[0, 0, 255, 255], [0, 0, 255, 255], [255, 0, 0, 255], [255, 0, 0, 255]].concat());
+    }
+    let cbdt = [be32(&[0x0003_0000]), bitmap.clone()].concat();
+
+    // One strike (ppem 4, 32-bit color) with an index subtable for glyph 3
+    let mut bitmap_size = be32(&[8 + 48, 24, 1, 0]);
+    bitmap_size.extend([0; 24]);
+    bitmap_size.extend(be16(&[3, 3]));
+    bitmap_size.extend([4, 4, 32, 1]);
+    let cblc = [
+        be32(&[0x0003_0000, 1]),
+        bitmap_size,
+        be16(&[3, 3]),
+        be32(&[8]),
+        be16(&[1, 1]),
+        be32(&[4, 0, bitmap.len() as u32]),
+    ]
+    .concat();
+
+    // Empty script and feature lists, one ligature lookup: 1 2 -> 3
+    let gsub = be16(&[1, 0, 10, 12, 14, 0, 0, 1, 4, 4, 0, 1, 8, 1, 8, 1, 14, 1, 1, 1, 1, 4, 3, 2, 2]);
+
+    let cmap = [
+        be16(&[0, 1, 3, 10]),
+        be32(&[12]),
+        be16(&[12, 0]),
+        be32(&[16 + 2 * 12, 0, 2, 0x1F1F8, 0x1F1F8, 2, 0x1F1FA, 0x1F1FA, 1]),
+    ]
+    .concat();
+
+    let mut head = vec![0; 54];
+    head[..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+    head[12..16].copy_from_slice(&0x5F0F_3CF5u32.to_be_bytes());
+    head[18..20].copy_from_slice(&64u16.to_be_bytes());
+    let mut hhea = vec![0; 36];
+    hhea[..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+    let maxp = [be32(&[0x0000_5000]), be16(&[4])].concat();
+
+    // Table directory sorted by tag, tables 4-byte aligned after it
+    let tables: [(&[u8; 4], Vec<u8>); 7] = [
+        (b"CBDT", cbdt),
+        (b"CBLC", cblc),
+        (b"GSUB", gsub),
+        (b"cmap", cmap),
+        (b"head", head),
+        (b"hhea", hhea),
+        (b"maxp", maxp),
+    ];
+    let mut font = [be32(&[0x0001_0000]), be16(&[tables.len() as u16, 0, 0, 0])].concat();
+    let directory_len = font.len() + 16 * tables.len();
+    let mut data = Vec::new();
+    for (tag, table) in &tables {
+        font.extend(*tag);
+        font.extend(be32(&[0, (directory_len + data.len()) as u32, table.len() as u32]));
+        data.extend(table);
+        data.resize(data.len().next_multiple_of(4), 0);
+    }
+    font.extend(data);
+    font
+}
+
+#[test]
+fn test_flag_from_emoji_font() {
+    let emoji_font = EmojiFont::load(&FontSource::Bytes(flag_font())).expect("Emoji font load failed");
+    let font_metrics = common::font_metrics().with_emoji_font(emoji_font);
+
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes("🇺🇸".as_bytes());
+    let frame = common::render_cpu_with_font(term_state, TerminalConfig::default(), font_metrics);
+
+    // The bitmap's own colors, scaled across both cells
+    let center = |col: u32| frame.texel(col * frame.cell_width + frame.cell_width / 2, frame.cell_height / 2);
+    assert_eq!(center(0), [255, 0, 0, 255]);
+    assert_eq!(center(1), [0, 0, 255, 255]);
+}
+
+#[test]
+fn test_emoji_font_without_bitmaps_is_rejected() {
+    assert!(EmojiFont::load(&FontSource::Embedded).is_err());
+}
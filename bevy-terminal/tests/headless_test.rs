@@ -0,0 +1,74 @@
+//! Tests for running the whole plugin without rendering.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_terminal::gpu_prep::TerminalCpuBuffer;
+use bevy_terminal::prelude::*;
+use bevy_terminal::TerminalState;
+use std::io::Cursor;
+use std::time::{Duration, Instant};
+
+fn headless_app(plugin: TerminalPlugin) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, plugin.headless()));
+    app
+}
+
+/// Update until the screen contains `expected`, then return it.
+fn wait_for_text(app: &mut App, expected: &str) -> String {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        app.update();
+        let text = common::component::<TerminalState>(app).get_visible_text();
+        if text.contains(expected) {
+            return text;
+        }
+        assert!(Instant::now() < deadline, "Never saw {:?}. Got:\n{}", expected, text);
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_headless_source_updates_cpu_buffer() {
+    let mut app = headless_app(TerminalPlugin::default().without_pty());
+    let terminal = common::terminal(&mut app);
+    app.world_mut()
+        .entity_mut(terminal)
+        .insert(SourceResource::new(Cursor::new(b"no gpu here".to_vec())));
+
+    wait_for_text(&mut app, "no gpu here");
+    app.update();
+
+    // Cells are built without a texture or an atlas image
+    let terminal = app.world().entity(terminal);
+    assert!(!terminal.contains::<TerminalTexture>());
+    let buffer = terminal.get::<TerminalCpuBuffer>().unwrap();
+    let term_state = terminal.get::<TerminalState>().unwrap();
+    assert_eq!(buffer.cells.len(), term_state.cols * term_state.rows);
+    assert!(!buffer.blank);
+}
+
+#[test]
+fn test_headless_pty_round_trip() {
+    let mut app = headless_app(TerminalPlugin::default());
+    app.update();
+    let terminal = common::terminal(&mut app);
+    app.world_mut().write_message(TerminalWrite::new(terminal, "echo headless_$((6*7))\n"));
+
+    wait_for_text(&mut app, "headless_42");
+    assert!(!app.world().entity(terminal).contains::<TerminalTexture>());
+}
+
+#[test]
+fn test_headless_resize() {
+    let mut app = headless_app(TerminalPlugin::default().without_pty());
+    app.update();
+    let terminal = common::terminal(&mut app);
+    app.world_mut().write_message(ResizeTerminal { terminal, cols: 40, rows: 10 });
+    app.update();
+
+    let term_state = common::component::<TerminalState>(&app);
+    assert_eq!((term_state.cols, term_state.rows), (40, 10));
+    assert_eq!(common::component::<TerminalCpuBuffer>(&app).cells.len(), 40 * 10);
+}
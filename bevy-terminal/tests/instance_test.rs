@@ -0,0 +1,140 @@
+//! Tests for terminals spawned as entities.
+
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy_terminal::atlas::GlyphAtlas;
+use bevy_terminal::gpu_prep::{prepare_terminal_cpu_buffer, TerminalCpuBuffer};
+use bevy_terminal::prelude::*;
+use bevy_terminal::renderer::initialize_terminal_textures;
+use bevy_terminal::resize::apply_terminal_resize;
+use bevy_terminal::TerminalState;
+use std::time::{Duration, Instant};
+
+mod common;
+
+fn spawn(app: &mut App, config: TerminalConfig) -> Entity {
+    let entity = spawn_terminal(&mut app.world_mut().commands(), config);
+    app.world_mut().flush();
+    entity
+}
+
+/// Config for a terminal with no shell, built like the plugin's.
+fn without_pty() -> TerminalConfig {
+    TerminalPlugin::default().without_pty().config
+}
+
+fn texture_app() -> App {
+    let mut app = common::image_app();
+    app.insert_resource(common::ascii_atlas())
+        .add_message::<ResizeTerminal>()
+        .add_systems(
+            Update,
+            (apply_terminal_resize, initialize_terminal_textures, prepare_terminal_cpu_buffer).chain(),
+        );
+    app
+}
+
+/// Update until `entity`'s screen contains `expected`.
+fn wait_for_text(app: &mut App, entity: Entity, expected: &str) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        app.update();
+        let text = app.world().get::<TerminalState>(entity).unwrap().get_visible_text();
+        if text.contains(expected) {
+            return;
+        }
+        assert!(Instant::now() < deadline, "Never saw {:?}. Got:\n{}", expected, text);
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// The plugin's terminal.
+fn primary(app: &mut App) -> Entity {
+    let world = app.world_mut();
+    world.query_filtered::<Entity, With<PrimaryTerminal>>().single(world).expect("No primary terminal")
+}
+
+#[test]
+fn test_terminals_are_independent() {
+    let mut app = texture_app();
+    let left = spawn(&mut app, without_pty());
+    let right = spawn(&mut app, without_pty());
+
+    app.world_mut().get_mut::<TerminalState>(left).unwrap().process_bytes(b"left side");
+    app.world_mut().write_message(ResizeTerminal { terminal: right, cols: 40, rows: 10 });
+    app.update();
+    app.update();
+
+    let world = app.world();
+    assert!(world.get::<TerminalState>(left).unwrap().get_visible_text().starts_with("left side"));
+    assert!(!world.get::<TerminalState>(right).unwrap().get_visible_text().contains("left side"));
+
+    // Each draws its own grid into its own texture
+    let atlas = world.resource::<GlyphAtlas>();
+    let left_texture = world.get::<TerminalTexture>(left).unwrap();
+    let right_texture = world.get::<TerminalTexture>(right).unwrap();
+    assert_ne!(left_texture.handle, right_texture.handle);
+    assert_eq!((right_texture.width, right_texture.height), (40 * atlas.cell_width, 10 * atlas.cell_height));
+    assert_eq!(world.get::<TerminalCpuBuffer>(left).unwrap().cells.len(), 120 * 30);
+    assert_eq!(world.get::<TerminalCpuBuffer>(right).unwrap().cells.len(), 40 * 10);
+    assert!(!world.get::<TerminalCpuBuffer>(left).unwrap().blank);
+}
+
+#[test]
+fn test_spawned_terminal_pty_round_trip() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, TerminalPlugin::default().without_pty().headless()));
+    app.update();
+    let entity = spawn(&mut app, TerminalConfig::default());
+    app.update();
+
+    // Its events name it
+    let events: Vec<_> = app.world_mut().resource_mut::<Messages<TerminalEvent>>().drain().collect();
+    assert!(
+        matches!(&events[..], [TerminalEvent { terminal, kind: TerminalEventKind::Spawned }] if *terminal == entity),
+        "got {:?}",
+        events
+    );
+    assert!(app.world().get::<TerminalStats>(entity).is_some());
+
+    app.world_mut().write_message(TerminalWrite::new(entity, "echo spawned_$((6*7))\n"));
+    wait_for_text(&mut app, entity, "spawned_42");
+
+    // The plugin's terminal never saw it
+    let primary = primary(&mut app);
+    assert!(!app.world().get::<TerminalState>(primary).unwrap().get_visible_text().contains("spawned_42"));
+
+    app.world_mut().despawn(entity);
+    app.update();
+}
+
+#[test]
+fn test_keyboard_follows_focus() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, TerminalPlugin::default().headless()))
+        .init_resource::<ButtonInput<KeyCode>>()
+        .add_message::<KeyboardInput>();
+    let entity = spawn(&mut app, TerminalConfig::default());
+    app.insert_resource(TerminalFocus(Some(entity)));
+    app.update();
+
+    let press = |key_code, logical_key, text: &str| KeyboardInput {
+        key_code,
+        logical_key,
+        state: ButtonState::Pressed,
+        text: Some(text.into()),
+        repeat: false,
+        window: Entity::PLACEHOLDER,
+    };
+    let keys: Vec<_> = "echo typed_$((6*7))"
+        .chars()
+        .map(|c| press(KeyCode::KeyA, Key::Character(c.to_string().into()), &c.to_string()))
+        .chain([press(KeyCode::Enter, Key::Enter, "\r")])
+        .collect();
+    app.world_mut().write_message_batch(keys);
+    wait_for_text(&mut app, entity, "typed_42");
+
+    let primary = primary(&mut app);
+    assert!(!app.world().get::<TerminalState>(primary).unwrap().get_visible_text().contains("typed_"));
+}
@@ -0,0 +1,155 @@
+//! Tests for pasting large blobs into the PTY.
+
+mod common;
+
+use bevy_terminal::paste::PasteConfig;
+use bevy_terminal::pty::{spawn_writer_thread, PtyResource};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Run `command` in the PTY's shell with the line discipline in raw mode (no
+/// echo, no line-length limit, output untouched), once the shell has
+/// switched to it.
+#[cfg(unix)]
+fn run_raw(pty: &PtyResource, command: &str) {
+    // The quotes keep the echoed command line from matching
+    pty.write_all(format!("stty raw -echo; echo RAW''READY; {}\n", command).as_bytes())
+        .expect("Write failed");
+
+    let start = Instant::now();
+    let mut output = String::new();
+    while !output.contains("RAWREADY") {
+        assert!(start.elapsed() < Duration::from_secs(10), "Shell never switched to raw mode. Got:\n{}", output);
+        output.push_str(&String::from_utf8_lossy(&pty.read_available(Duration::from_millis(100))));
+    }
+}
+
+/// Read from the PTY until the output contains `expected`.
+#[cfg(unix)]
+fn read_until(pty: &PtyResource, expected: &str) -> String {
+    let start = Instant::now();
+    let mut output = String::new();
+    while !output.contains(expected) {
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "Expected {:?} to arrive. Got:\n{}",
+            expected,
+            output
+        );
+        output.push_str(&String::from_utf8_lossy(&pty.read_available(Duration::from_millis(100))));
+    }
+    output
+}
+
+#[cfg(unix)]
+#[test]
+fn test_large_paste_arrives_intact() {
+    const PASTE_SIZE: usize = 100 * 1024;
+
+    let pty = PtyResource::new().expect("Failed to create PTY");
+    // `head` writes the paste straight back out
+    run_raw(&pty, &format!("head -c {}", PASTE_SIZE));
+
+    // Letters with a line break every 80 bytes (typed as Enter, so the size is unchanged)
+    let text: String = (0..PASTE_SIZE)
+        .map(|i| if i % 80 == 79 { '\n' } else { (b'a' + (i % 26) as u8) as char })
+        .collect();
+
+    pty.paste(&text, false, &PasteConfig::default()).expect("Paste failed");
+
+    // Every byte, in order
+    let expected = text.replace('\n', "\r");
+    let output = read_until(&pty, &expected);
+    assert_eq!(output.matches(&expected).count(), 1);
+
+    drop(pty);
+}
+
+/// Writer keeping each write separately.
+struct RecordingWriter(Arc<Mutex<Vec<Vec<u8>>>>);
+
+impl Write for RecordingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().push(buf.to_vec());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_typing_waits_for_chunked_paste() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut pty = PtyResource::new().expect("Failed to create PTY");
+    let (writer, writer_thread) = spawn_writer_thread("recording", RecordingWriter(log.clone()));
+    pty.writer = writer;
+
+    // Keys typed and a second paste sent while the first is still going out
+    let config = PasteConfig { chunk_delay: Duration::from_millis(5), ..Default::default() };
+    let (first, second) = ("a".repeat(10 * 1024), "b".repeat(8 * 1024));
+    pty.paste(&first, true, &config).expect("Paste failed");
+    pty.queue_write(b"typed");
+    pty.flush_writes().expect("Write failed");
+    pty.paste(&second, false, &config).expect("Paste failed");
+    pty.paste("small", false, &config).expect("Paste failed");
+
+    // Dropping the PTY ends the writer thread once it has written everything
+    drop(pty);
+    writer_thread.join().expect("Writer thread panicked");
+    let writes = log.lock().unwrap().clone();
+
+    // Each paste whole and in its own chunks, the keys after the bracket
+    let expected = format!("\x1b[200~{}\x1b[201~typed{}small", first, second);
+    assert_eq!(String::from_utf8(writes.concat()).unwrap(), expected);
+    assert!(writes.iter().all(|chunk| chunk.len() <= config.chunk_size));
+    assert_eq!(writes.len(), 11 + 1 + 8 + 1);
+}
+
+/// Paste `text` with a `TerminalPaste` message into a shell running
+/// `command` in raw mode, after the terminal processed `output`.
+#[cfg(unix)]
+fn paste_message(command: &str, output: &[u8], text: &str) -> bevy::prelude::App {
+    use bevy::prelude::*;
+    use bevy_terminal::paste::{apply_terminal_pastes, TerminalPaste};
+    use bevy_terminal::TerminalState;
+
+    let pty = PtyResource::new().expect("Failed to create PTY");
+    run_raw(&pty, command);
+
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes(output);
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_message::<TerminalPaste>()
+        .add_systems(Update, apply_terminal_pastes);
+    let terminal = app.world_mut().spawn((term_state, pty)).id();
+    app.world_mut().write_message(TerminalPaste::new(terminal, text));
+    app.update();
+    app
+}
+
+#[cfg(unix)]
+#[test]
+fn test_paste_message_is_bracketed() {
+    // `od -c` shows exactly what arrived, escape and markers included.
+    // As if the shell had turned bracketed paste on.
+    let app = paste_message("head -c 15 | od -c", b"\x1b[?2004h", "a\nb");
+
+    let output = read_until(common::component::<PtyResource>(&app), "1   ~");
+    assert!(output.contains("033   [   2   0   0   ~   a  \\n   b 033   [   2   0   1   ~"), "Got:\n{}", output);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_paste_message_without_brackets_is_typed() {
+    // Without bracketed paste, the text arrives as the keys that type it:
+    // Enter for the line break, no escape or Ctrl+C
+    let app = paste_message("head -c 9 | od -c", b"", "a\x1b[31m\x03\nb\tc");
+
+    let output = read_until(common::component::<PtyResource>(&app), "\\t   c");
+    assert!(output.contains("a   [   3   1   m  \\r   b  \\t   c"), "Got:\n{}", output);
+}
@@ -0,0 +1,61 @@
+//! Tests for the configurable PTY poll interval.
+
+use bevy::prelude::*;
+use bevy_terminal::pty::poll_interval_elapsed;
+use bevy_terminal::TerminalConfig;
+use std::time::Duration;
+
+#[derive(Resource, Default)]
+struct PollCount(usize);
+
+fn count_polls(mut count: ResMut<PollCount>) {
+    count.0 += 1;
+}
+
+fn polls_after_updates(config: TerminalConfig, updates: usize) -> usize {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(config)
+        .init_resource::<PollCount>()
+        .add_systems(Update, count_polls.run_if(poll_interval_elapsed));
+
+    for _ in 0..updates {
+        app.update();
+    }
+    app.world().resource::<PollCount>().0
+}
+
+#[test]
+fn test_polls_every_frame_by_default() {
+    assert_eq!(polls_after_updates(TerminalConfig::default(), 10), 10);
+}
+
+#[test]
+fn test_poll_interval_limits_rate() {
+    let config = TerminalConfig {
+        poll_interval: Some(Duration::from_secs(3600)),
+        ..default()
+    };
+
+    // First frame polls, the rest fall inside the interval
+    assert_eq!(polls_after_updates(config, 10), 1);
+}
+
+#[test]
+fn test_poll_interval_elapses() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TerminalConfig {
+            poll_interval: Some(Duration::from_millis(20)),
+            ..default()
+        })
+        .init_resource::<PollCount>()
+        .add_systems(Update, count_polls.run_if(poll_interval_elapsed));
+
+    app.update();
+    std::thread::sleep(Duration::from_millis(30));
+    app.update();
+    app.update();
+
+    assert!(app.world().resource::<PollCount>().0 >= 2);
+}
@@ -1,5 +1,7 @@
+mod common;
+
 use bevy_terminal::pty::PtyResource;
-use std::io::Write;
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
@@ -10,51 +12,31 @@ fn test_pty_integration_echo() {
 
     // 2. Write command that works on both Windows (cmd.exe) and Linux (bash/sh)
     // 'echo' is built-in for both.
-    let command = "echo hello_world\n";
-    {
-        let mut writer = pty.writer.lock().unwrap();
-        writer.write_all(command.as_bytes()).expect("Write failed");
-        writer.flush().expect("Flush failed");
-    }
+    pty.write_all(b"echo hello_world\n").expect("Write failed");
 
     // 3. Read output via channel (integration test for the background thread)
     let start = Instant::now();
     let timeout = Duration::from_secs(5);
     let mut output_acc = String::new();
 
-    loop {
+    while !output_acc.contains("hello_world") {
         if start.elapsed() > timeout {
             panic!("Timeout waiting for 'hello_world'. Got:\n{}", output_acc);
         }
 
-        let rx = pty.rx.lock().unwrap();
-        match rx.try_recv() {
-            Ok(bytes) => {
-                let chunk = String::from_utf8_lossy(&bytes);
-                output_acc.push_str(&chunk);
-                
-                if output_acc.contains("hello_world") {
-                    break; // Success
-                }
-            }
-            Err(_) => {
-                // Empty, wait a bit
-                drop(rx);
-                thread::sleep(Duration::from_millis(50));
-            }
-        }
+        let bytes = pty.read_available(Duration::from_millis(100));
+        output_acc.push_str(&String::from_utf8_lossy(&bytes));
     }
+
+    // 4. Tear down: kills the shell and joins the reader thread
+    drop(pty);
 }
 
 #[test]
 fn test_pty_integration_exit() {
     let mut pty = PtyResource::new().expect("Failed to create PTY");
 
-    {
-        let mut writer = pty.writer.lock().unwrap();
-        writer.write_all(b"exit\n").expect("Write failed");
-        writer.flush().expect("Flush failed");
-    }
+    pty.write_all(b"exit\n").expect("Write failed");
 
     let start = Instant::now();
     let timeout = Duration::from_secs(5);
@@ -75,4 +57,255 @@ fn test_pty_integration_exit() {
             Err(e) => panic!("Wait error: {}", e),
         }
     }
+
+    drop(pty);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_custom_shell_and_env() {
+    use bevy_terminal::prelude::*;
+
+    let config = TerminalPlugin::default()
+        .with_shell("/bin/sh", ["-c", "echo \"$GREETING from $0\"; sleep 5"])
+        .with_env("GREETING", "hello_env")
+        .config;
+    let pty = PtyResource::with_config(80, 24, &config).expect("Failed to create PTY");
+
+    let start = Instant::now();
+    let mut output_acc = String::new();
+    while !output_acc.contains("hello_env from /bin/sh") {
+        if start.elapsed() > Duration::from_secs(5) {
+            panic!("Timeout waiting for the custom program. Got:\n{}", output_acc);
+        }
+        let bytes = pty.read_available(Duration::from_millis(100));
+        output_acc.push_str(&String::from_utf8_lossy(&bytes));
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_custom_cwd_and_term() {
+    use bevy_terminal::prelude::*;
+
+    let cwd = std::env::temp_dir().canonicalize().expect("No temp dir");
+    let config = TerminalPlugin::default()
+        .with_shell("/bin/sh", ["-c", "echo \"cwd=$(pwd -P) term=$TERM\"; sleep 5"])
+        .with_env("TERM", "dumb")
+        .with_cwd(&cwd)
+        .config;
+    let pty = PtyResource::with_config(80, 24, &config).expect("Failed to create PTY");
+
+    let expected = format!("cwd={} term=dumb", cwd.display());
+    let start = Instant::now();
+    let mut output_acc = String::new();
+    while !output_acc.contains(&expected) {
+        if start.elapsed() > Duration::from_secs(5) {
+            panic!("Timeout waiting for {:?}. Got:\n{}", expected, output_acc);
+        }
+        let bytes = pty.read_available(Duration::from_millis(100));
+        output_acc.push_str(&String::from_utf8_lossy(&bytes));
+    }
+}
+
+#[test]
+fn test_spawn_and_exit_events() {
+    use bevy::prelude::*;
+    use bevy_terminal::events::{report_process_exit, TerminalEvent, TerminalEventKind};
+    use bevy_terminal::pty::spawn_pty;
+    use bevy_terminal::TerminalState;
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_message::<TerminalEvent>()
+        .add_systems(Update, (spawn_pty, report_process_exit).chain());
+    let terminal = app.world_mut().spawn(TerminalState::new()).id();
+    app.update();
+
+    let drain = |app: &mut App| -> Vec<TerminalEventKind> {
+        let events = app.world_mut().resource_mut::<Messages<TerminalEvent>>().drain().collect::<Vec<_>>();
+        assert!(events.iter().all(|event| event.terminal == terminal));
+        events.into_iter().map(|event| event.kind).collect()
+    };
+    let events = drain(&mut app);
+    assert!(matches!(events[..], [TerminalEventKind::Spawned]), "got {:?}", events);
+
+    common::component::<PtyResource>(&app).write_all(b"exit 3\n").expect("Write failed");
+
+    let start = Instant::now();
+    let events = loop {
+        if start.elapsed() > Duration::from_secs(5) {
+            panic!("Timeout waiting for ProcessExited");
+        }
+        app.update();
+        let events = drain(&mut app);
+        if !events.is_empty() {
+            break events;
+        }
+        thread::sleep(Duration::from_millis(50));
+    };
+    assert!(
+        matches!(events[..], [TerminalEventKind::ProcessExited { exit_code: Some(3) }]),
+        "got {:?}",
+        events
+    );
+
+    // Reported once, not every frame after
+    app.update();
+    assert!(drain(&mut app).is_empty());
+}
+
+#[test]
+fn test_respawn_after_exit() {
+    use bevy::prelude::*;
+    use bevy_terminal::prelude::*;
+    use bevy_terminal::TerminalState;
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, TerminalPlugin::default().headless().with_respawn(true)));
+    app.update();
+    app.world_mut().resource_mut::<Messages<TerminalEvent>>().clear();
+    let terminal = common::terminal(&mut app);
+
+    app.world_mut().write_message(TerminalWrite::new(terminal, "echo before_exit; exit 3\n"));
+    let start = Instant::now();
+    let mut events = Vec::new();
+    while !events.iter().any(|event| matches!(event, TerminalEventKind::Spawned)) {
+        if start.elapsed() > Duration::from_secs(5) {
+            panic!("Timeout waiting for the respawn. Got {:?}", events);
+        }
+        app.update();
+        events.extend(app.world_mut().resource_mut::<Messages<TerminalEvent>>().drain().map(|event| event.kind));
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert!(
+        matches!(events[..], [TerminalEventKind::ProcessExited { exit_code: Some(3) }, TerminalEventKind::Spawned]),
+        "got {:?}",
+        events
+    );
+    assert!(!common::component::<TerminalState>(&app).transcript().contains("before_exit"));
+
+    // The new shell answers
+    app.world_mut().write_message(TerminalWrite::new(terminal, "echo respawned_$((6*7))\n"));
+    let start = Instant::now();
+    while !common::component::<TerminalState>(&app).get_visible_text().contains("respawned_42") {
+        assert!(start.elapsed() < Duration::from_secs(5), "New shell never answered");
+        app.update();
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_respawn_gives_up_after_limit() {
+    use bevy::prelude::*;
+    use bevy_terminal::prelude::*;
+
+    // A "shell" that exits straight away respawns in a loop until the limit
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TerminalPlugin::default()
+            .headless()
+            .with_shell("sh", ["-c", "exit 1"])
+            .with_respawn(true)
+            .with_respawn_backoff(Duration::from_millis(20), 2),
+    ));
+
+    let start = Instant::now();
+    let mut events = Vec::new();
+    while !events.iter().any(|event| matches!(event, TerminalEventKind::Error { .. })) {
+        assert!(start.elapsed() < Duration::from_secs(10), "Never gave up. Got {:?}", events);
+        app.update();
+        events.extend(app.world_mut().resource_mut::<Messages<TerminalEvent>>().drain().map(|event| event.kind));
+        thread::sleep(Duration::from_millis(5));
+    }
+    let exits = events.iter().filter(|event| matches!(event, TerminalEventKind::ProcessExited { .. })).count();
+    let spawns = events.iter().filter(|event| matches!(event, TerminalEventKind::Spawned)).count();
+    // The initial shell plus two respawns, each of which exited
+    assert_eq!((spawns, exits), (3, 3), "got {:?}", events);
+    // The second respawn waited twice as long as the first
+    assert!(start.elapsed() >= Duration::from_millis(60));
+
+    // Stopped for good
+    let world = app.world_mut();
+    assert!(world.query::<&PtyResource>().iter(world).next().is_none());
+    for _ in 0..10 {
+        app.update();
+    }
+    assert!(app.world_mut().resource_mut::<Messages<TerminalEvent>>().drain().next().is_none());
+}
+
+#[test]
+fn test_scripted_terminal_write() {
+    use bevy::prelude::*;
+    use bevy_terminal::input::{apply_terminal_writes, TerminalWrite};
+    use bevy_terminal::pty::flush_pty_writes;
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_message::<TerminalWrite>()
+        .add_systems(Update, (apply_terminal_writes, flush_pty_writes).chain());
+    let terminal = app.world_mut().spawn(PtyResource::new().expect("Failed to create PTY")).id();
+
+    // Arithmetic so the echoed command line can't satisfy the check
+    app.world_mut().write_message(TerminalWrite::new(terminal, "echo scripted_"));
+    app.world_mut().write_message(TerminalWrite::new(terminal, b"$((40+2))\n".to_vec()));
+    app.update();
+
+    let pty = common::component::<PtyResource>(&app);
+    let start = Instant::now();
+    let mut output = String::new();
+    while !output.contains("scripted_42") {
+        if start.elapsed() > Duration::from_secs(5) {
+            panic!("Timeout waiting for scripted output. Got:\n{}", output);
+        }
+        output.push_str(&String::from_utf8_lossy(&pty.read_available(Duration::from_millis(100))));
+    }
+}
+
+/// Writer recording each write and flush, in order.
+struct RecordingWriter(Arc<Mutex<Vec<String>>>);
+
+impl std::io::Write for RecordingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().push(format!("write {}", String::from_utf8_lossy(buf)));
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.lock().unwrap().push("flush".to_string());
+        Ok(())
+    }
+}
+
+#[test]
+fn test_writes_coalesced_per_frame() {
+    use bevy::prelude::*;
+    use bevy_terminal::input::{apply_terminal_writes, TerminalWrite};
+    use bevy_terminal::pty::flush_pty_writes;
+
+    use bevy_terminal::pty::spawn_writer_thread;
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut pty = PtyResource::new().expect("Failed to create PTY");
+    let (writer, writer_thread) = spawn_writer_thread("recording", RecordingWriter(log.clone()));
+    pty.writer = writer;
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_message::<TerminalWrite>()
+        .add_systems(Update, (apply_terminal_writes, flush_pty_writes).chain());
+    let terminal = app.world_mut().spawn(pty).id();
+
+    for text in ["first ", "second ", "third\n"] {
+        app.world_mut().write_message(TerminalWrite::new(terminal, text));
+    }
+    app.update();
+    // Nothing queued, nothing written
+    app.update();
+
+    // Dropping the PTY ends the writer thread once it has written everything
+    drop(app);
+    writer_thread.join().expect("Writer thread panicked");
+    assert_eq!(*log.lock().unwrap(), ["write first second third\n", "flush"]);
 }
@@ -0,0 +1,647 @@
+//! Tests for the terminal render target.
+
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages};
+use bevy_terminal::events::{TerminalEvent, TerminalEventKind};
+use bevy_terminal::renderer::{
+    background_pixel, initialize_terminal_textures, integer_scale, report_render_unsupported,
+    texture_format_shader_def, validate_target_image, TerminalRenderUnsupported,
+    SUPPORTED_TEXTURE_FORMATS,
+};
+use bevy_terminal::{TerminalState, TerminalTexture};
+
+mod common;
+
+fn make_image(width: u32, height: u32, format: TextureFormat, usage: TextureUsages) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &background_pixel(format),
+        format,
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+    );
+    image.texture_descriptor.usage = usage;
+    image
+}
+
+const STORAGE_USAGE: TextureUsages = TextureUsages::TEXTURE_BINDING
+    .union(TextureUsages::COPY_DST)
+    .union(TextureUsages::STORAGE_BINDING);
+
+#[test]
+fn test_valid_target_accepted() {
+    let image = make_image(960, 420, TextureFormat::Rgba8Unorm, STORAGE_USAGE);
+    assert!(validate_target_image(&image, 960, 420, TextureFormat::Rgba8Unorm).is_ok());
+}
+
+#[test]
+fn test_target_wrong_format_rejected() {
+    let image = make_image(960, 420, TextureFormat::Rgba8UnormSrgb, STORAGE_USAGE);
+    let error = validate_target_image(&image, 960, 420, TextureFormat::Rgba8Unorm).unwrap_err();
+    assert!(error.to_string().contains("format"), "unexpected error: {}", error);
+}
+
+#[test]
+fn test_target_missing_storage_binding_rejected() {
+    let usage = TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST;
+    let image = make_image(960, 420, TextureFormat::Rgba8Unorm, usage);
+    let error = validate_target_image(&image, 960, 420, TextureFormat::Rgba8Unorm).unwrap_err();
+    assert!(error.to_string().contains("STORAGE_BINDING"), "unexpected error: {}", error);
+}
+
+#[test]
+fn test_target_wrong_size_rejected() {
+    let image = make_image(800, 600, TextureFormat::Rgba8Unorm, STORAGE_USAGE);
+    let error = validate_target_image(&image, 960, 420, TextureFormat::Rgba8Unorm).unwrap_err();
+    assert!(error.to_string().contains("size"), "unexpected error: {}", error);
+}
+
+#[test]
+fn test_target_matches_configured_format() {
+    let image = make_image(960, 420, TextureFormat::Rgba16Float, STORAGE_USAGE);
+    assert!(validate_target_image(&image, 960, 420, TextureFormat::Rgba16Float).is_ok());
+
+    let error = validate_target_image(&image, 960, 420, TextureFormat::Rgba8Unorm).unwrap_err();
+    assert!(error.to_string().contains("format"), "unexpected error: {}", error);
+}
+
+#[test]
+fn test_texture_format_shader_defs() {
+    // Default format needs no def; every other supported format gets its own
+    assert_eq!(texture_format_shader_def(TextureFormat::Rgba8Unorm), None);
+    assert_eq!(texture_format_shader_def(TextureFormat::Bgra8Unorm), Some("OUTPUT_BGRA8UNORM"));
+    assert_eq!(texture_format_shader_def(TextureFormat::Rgba16Float), Some("OUTPUT_RGBA16FLOAT"));
+    assert_eq!(texture_format_shader_def(TextureFormat::Rgba32Float), Some("OUTPUT_RGBA32FLOAT"));
+    assert_eq!(texture_format_shader_def(TextureFormat::Rgba8UnormSrgb), None);
+}
+
+#[test]
+fn test_background_pixel_per_format() {
+    // Tokyo Night background #1a1b26
+    assert_eq!(background_pixel(TextureFormat::Rgba8Unorm), vec![0x1a, 0x1b, 0x26, 255]);
+    assert_eq!(background_pixel(TextureFormat::Bgra8Unorm), vec![0x26, 0x1b, 0x1a, 255]);
+
+    for format in SUPPORTED_TEXTURE_FORMATS {
+        let pixel = background_pixel(format);
+        assert_eq!(pixel.len() as u32, format.block_copy_size(None).unwrap(), "{:?}", format);
+    }
+
+    let float = background_pixel(TextureFormat::Rgba32Float);
+    let red = f32::from_le_bytes(float[0..4].try_into().unwrap());
+    assert!((red - 0x1a as f32 / 255.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_status_bar_adds_texture_row() {
+    use bevy_terminal::config::{StatusBarConfig, TerminalConfig};
+    use bevy_terminal::renderer::texture_dimensions;
+
+    let plain = TerminalConfig::default();
+    assert_eq!(texture_dimensions(8, 14, 120, 30 + plain.extra_rows()), (960, 420));
+
+    let with_bar = TerminalConfig {
+        status_bar: Some(StatusBarConfig::default()),
+        ..Default::default()
+    };
+    assert_eq!(texture_dimensions(8, 14, 120, 30 + with_bar.extra_rows()), (960, 434));
+}
+
+#[test]
+fn test_blank_grid_dispatch_selection() {
+    use bevy_terminal::render_node::TerminalDispatch;
+
+    // Content always needs the full shader
+    assert_eq!(TerminalDispatch::select(false, false, true), TerminalDispatch::Full);
+    assert_eq!(TerminalDispatch::select(false, true, true), TerminalDispatch::Full);
+
+    // Transition into blank clears once, then idles
+    assert_eq!(TerminalDispatch::select(true, false, true), TerminalDispatch::Clear);
+    assert_eq!(TerminalDispatch::select(true, true, true), TerminalDispatch::Skip);
+
+    // Targets that can't be render attachments keep using the shader
+    assert_eq!(TerminalDispatch::select(true, false, false), TerminalDispatch::Full);
+}
+
+#[test]
+fn test_blank_grid_draws_only_cursor_cell() {
+    use bevy_terminal::gpu_prep::{prepare_terminal_cpu_buffer, TerminalCpuBuffer};
+    use bevy_terminal::render_node::{update_terminal_extraction, ExtractedTerminalData, RenderTerminal, TerminalDispatch};
+
+    let atlas = common::mvp_atlas();
+    let (cell_width, cell_height) = (atlas.cell_width, atlas.cell_height);
+
+    let mut app = common::image_app();
+    common::insert_atlas(&mut app, atlas);
+    // Default config: the cursor is shown
+    app.world_mut().spawn(TerminalState::new());
+    app.add_systems(Startup, initialize_terminal_textures)
+        .add_systems(Update, prepare_terminal_cpu_buffer)
+        .add_systems(PostUpdate, update_terminal_extraction);
+
+    // One frame through the render node's dispatch choice: the dispatch and
+    // the pixel its compute threads start at
+    let mut render: Option<RenderTerminal> = None;
+    let mut frame = |app: &mut App| {
+        app.update();
+        let data = common::component::<ExtractedTerminalData>(app).clone();
+        let terminal = render.get_or_insert_with(|| RenderTerminal::new(data.clone()));
+        terminal.data = data;
+        terminal.select_dispatch(true);
+        let uniforms = terminal.uniforms();
+        (terminal.dispatch, (uniforms.origin_x, uniforms.origin_y))
+    };
+
+    // Fresh terminal: cleared, then only the cursor cell is drawn, every
+    // frame so it can blink
+    assert_eq!(frame(&mut app), (TerminalDispatch::Clear, (0, 0)));
+    assert!(common::component::<TerminalCpuBuffer>(&app).blank);
+    assert_eq!(frame(&mut app), (TerminalDispatch::Clear, (0, 0)));
+
+    // A moved cursor is drawn at its new cell; the clear erases the old one
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"\x1b[3;10H");
+    assert_eq!(frame(&mut app), (TerminalDispatch::Clear, (9 * cell_width, 2 * cell_height)));
+
+    // Hidden: one last clear to erase it, then nothing to do
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"\x1b[?25l");
+    assert_eq!(frame(&mut app).0, TerminalDispatch::Clear);
+    assert_eq!(frame(&mut app).0, TerminalDispatch::Skip);
+
+    // Content needs every pixel again, from the corner
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"hello");
+    assert_eq!(frame(&mut app), (TerminalDispatch::Full, (0, 0)));
+}
+
+#[test]
+fn test_integer_scale() {
+    let texture = UVec2::new(960, 420);
+
+    // Exact and partial fits round down to whole multiples
+    assert_eq!(integer_scale(texture, Rect::new(0.0, 0.0, 1920.0, 840.0)), 2.0);
+    assert_eq!(integer_scale(texture, Rect::new(0.0, 0.0, 2560.0, 1440.0)), 2.0);
+    assert_eq!(integer_scale(texture, Rect::new(-1000.0, -700.0, 1000.0, 700.0)), 2.0);
+    assert_eq!(integer_scale(texture, Rect::new(0.0, 0.0, 3840.0, 2160.0)), 4.0);
+
+    // Too small: whole-number reductions only
+    assert_eq!(integer_scale(texture, Rect::new(0.0, 0.0, 800.0, 600.0)), 0.5);
+    assert_eq!(integer_scale(texture, Rect::new(0.0, 0.0, 320.0, 240.0)), 1.0 / 3.0);
+
+    // Degenerate inputs don't produce NaN or zero
+    assert_eq!(integer_scale(UVec2::ZERO, Rect::new(0.0, 0.0, 800.0, 600.0)), 1.0);
+    assert_eq!(integer_scale(texture, Rect::default()), 1.0);
+}
+
+/// Runs texture initialization, optionally as if the device lacked storage textures.
+fn init_texture_app(unsupported: bool) -> App {
+    let mut app = common::image_app();
+    common::insert_atlas(&mut app, common::ascii_atlas());
+    app.world_mut().spawn(TerminalState::new());
+    app.add_message::<TerminalEvent>()
+        .add_systems(Startup, initialize_terminal_textures);
+    if unsupported {
+        app.insert_resource(TerminalRenderUnsupported {
+            reason: "Gl backend has no compute shaders".to_string(),
+        })
+        .add_systems(Startup, report_render_unsupported);
+    }
+    app.update();
+    app
+}
+
+fn texture_usage(app: &App) -> TextureUsages {
+    let texture = common::component::<TerminalTexture>(app);
+    let images = app.world().resource::<Assets<Image>>();
+    images.get(&texture.handle).expect("Texture missing").texture_descriptor.usage
+}
+
+#[test]
+fn test_texture_without_storage_support() {
+    let mut app = init_texture_app(true);
+
+    // No STORAGE_BINDING, so wgpu validation doesn't panic on such devices
+    let usage = texture_usage(&app);
+    assert!(!usage.contains(TextureUsages::STORAGE_BINDING));
+    assert!(usage.contains(TextureUsages::TEXTURE_BINDING));
+
+    let events: Vec<_> = app
+        .world_mut()
+        .resource_mut::<Messages<TerminalEvent>>()
+        .drain()
+        .collect();
+    assert_eq!(events.len(), 1);
+    match &events[0] {
+        TerminalEvent { kind: TerminalEventKind::Error { message }, .. } => assert!(message.contains("no compute shaders")),
+        other => panic!("Expected Error, got {:?}", other),
+    }
+
+    // Supported devices keep the storage texture
+    let app = init_texture_app(false);
+    assert!(texture_usage(&app).contains(TextureUsages::STORAGE_BINDING));
+}
+
+/// Pixels of the terminal texture as created, before any shader runs.
+fn initial_pixels(app: &App) -> (TextureFormat, Vec<u8>) {
+    let texture = common::component::<TerminalTexture>(app);
+    let image = app.world().resource::<Assets<Image>>().get(&texture.handle).expect("Texture missing");
+    (image.texture_descriptor.format, image.data.clone().expect("Texture has no data"))
+}
+
+#[test]
+fn test_initial_texture_is_background() {
+    use bevy_terminal::renderer::color_pixel;
+    use bevy_terminal::TerminalPalette;
+
+    // No red (or any other) placeholder frame before the shader's first pass
+    let app = init_texture_app(false);
+    let (format, data) = initial_pixels(&app);
+    let pixel = background_pixel(format);
+    assert!(!data.is_empty());
+    assert!(data.chunks_exact(pixel.len()).all(|texel| texel == pixel));
+
+    // A custom palette's background is used instead of the default one
+    let mut term_state = TerminalState::new();
+    term_state.set_palette(TerminalPalette {
+        background: [0x00, 0x11, 0x00],
+        ..Default::default()
+    });
+
+    let mut app = common::image_app();
+    app.world_mut().spawn(term_state);
+    app.insert_resource(common::mvp_atlas())
+        .add_systems(Startup, initialize_terminal_textures);
+    app.update();
+
+    let (format, data) = initial_pixels(&app);
+    let pixel = color_pixel([0x00, 0x11, 0x00], format);
+    assert!(data.chunks_exact(pixel.len()).all(|texel| texel == pixel));
+}
+
+#[test]
+fn test_spawn_terminal_sprite() {
+    use bevy::ecs::world::CommandQueue;
+    use bevy_terminal::sprite::{spawn_terminal_sprite, TerminalSprite};
+
+    let mut app = init_texture_app(false);
+    let transform = Transform::from_xyz(0.0, 0.0, 1.0).with_scale(Vec3::splat(0.05));
+    let terminal = common::terminal(&mut app);
+    let mut queue = CommandQueue::default();
+    let texture = common::component::<TerminalTexture>(&app);
+    let mut commands = Commands::new(&mut queue, app.world());
+    let entity = spawn_terminal_sprite(&mut commands, terminal, texture, transform);
+    queue.apply(app.world_mut());
+
+    let handle = common::component::<TerminalTexture>(&app).handle.clone();
+    let sprite = app.world().entity(entity);
+    assert_eq!(sprite.get::<Sprite>().unwrap().image, handle);
+    assert_eq!(*sprite.get::<Transform>().unwrap(), transform);
+    assert_eq!(sprite.get::<TerminalSprite>().unwrap().0, terminal);
+}
+
+#[test]
+fn test_blink_phase_uniform_varies() {
+    use bevy_terminal::config::{CursorBlinkStyle, TerminalConfig};
+    use bevy_terminal::gpu_types::CURSOR_BLINK_FADE;
+    use bevy_terminal::render_node::{update_terminal_extraction, ExtractedTerminalData};
+    use bevy_terminal::TerminalClock;
+    use std::time::Duration;
+
+    let mut app = init_texture_app(false);
+    let mut clock = TerminalClock::new(Duration::ZERO, Duration::from_secs(1));
+    clock.manual = true;
+    *common::component_mut::<TerminalConfig>(&mut app) = TerminalConfig {
+        cursor_blink: CursorBlinkStyle::Fade,
+        ..Default::default()
+    };
+    app.insert_resource(clock)
+        .add_systems(PostUpdate, update_terminal_extraction);
+
+    let mut uniforms_at = |millis: u64| {
+        app.world_mut().resource_mut::<TerminalClock>().set_time(Duration::from_millis(millis));
+        app.update();
+        common::component::<ExtractedTerminalData>(&app).uniforms()
+    };
+
+    let start = uniforms_at(0);
+    let quarter = uniforms_at(250);
+    let half = uniforms_at(500);
+    assert_eq!(start.cursor_blink, CURSOR_BLINK_FADE);
+    assert_eq!((start.blink_phase, quarter.blink_phase, half.blink_phase), (0.0, 0.25, 0.5));
+
+    // Fade passes through partial alpha; hard blink only on/off
+    assert_eq!(CursorBlinkStyle::Fade.alpha(start.blink_phase), 1.0);
+    assert!((CursorBlinkStyle::Fade.alpha(quarter.blink_phase) - 0.5).abs() < 1e-6);
+    assert!(CursorBlinkStyle::Fade.alpha(half.blink_phase) < 1e-6);
+    assert_eq!(CursorBlinkStyle::Hard.alpha(quarter.blink_phase), 1.0);
+    assert_eq!(CursorBlinkStyle::Hard.alpha(half.blink_phase), 0.0);
+}
+
+#[test]
+fn test_cursor_style_compositing() {
+    use bevy_terminal::config::{CursorStyle, TerminalConfig};
+    use bevy_terminal::gpu_types::CURSOR_STYLE_BAR;
+    use bevy_terminal::render_node::{update_terminal_extraction, ExtractedTerminalData};
+
+    let mut app = init_texture_app(false);
+    common::component_mut::<TerminalConfig>(&mut app).cursor_style = CursorStyle::Bar;
+    app.add_systems(PostUpdate, update_terminal_extraction);
+    app.update();
+    assert_eq!(common::component::<ExtractedTerminalData>(&app).uniforms().cursor_style, CURSOR_STYLE_BAR);
+
+    // White-on-black "A█" under a red cursor, shaded by the CPU backend
+    let (fg, bg, cursor) = ([0xff, 0xff, 0xff, 0xff], [0x00, 0x00, 0x00, 0xff], [0xff, 0x00, 0x00, 0xff]);
+    let render = |style: Option<CursorStyle>, column: u32| -> Vec<[u8; 4]> {
+        let mut term_state = TerminalState::new();
+        term_state.process_bytes("\x1b]12;#ff0000\x07\x1b[38;2;255;255;255;48;2;0;0;0mA█".as_bytes());
+        term_state.process_bytes(format!("\x1b[{}G", column + 1).as_bytes());
+        let config = match style {
+            Some(cursor_style) => TerminalConfig { cursor_style, ..Default::default() },
+            None => TerminalConfig { show_cursor: false, ..Default::default() },
+        };
+        common::render_cpu(term_state, config).cell(column, 0)
+    };
+
+    // The full block is solid but for the cell's last column, which its
+    // advance only partly covers, so the checks below can't pass vacuously
+    let block = render(None, 1);
+    assert!(block.iter().filter(|&&texel| texel == fg).count() * 4 > block.len() * 3);
+
+    // Wherever the glyph is solid, a block cursor inverts it to the
+    // background color while a bar or underline stays hidden beneath it
+    for (column, character) in ['A', '█'].into_iter().enumerate() {
+        let plain = render(None, column as u32);
+        for style in [CursorStyle::Block, CursorStyle::Bar, CursorStyle::Underline] {
+            let expected = if style == CursorStyle::Block { bg } else { fg };
+            let shaded = render(Some(style), column as u32);
+            for (plain, shaded) in plain.iter().zip(&shaded) {
+                if *plain == fg {
+                    assert_eq!(*shaded, expected, "{:?} cursor over {:?}", style, character);
+                }
+            }
+        }
+    }
+
+    // Around the glyph the bar shows in the cursor color
+    let bar = render(Some(CursorStyle::Bar), 0);
+    assert!(bar.contains(&cursor));
+    assert!(!bar.iter().all(|&texel| texel == cursor));
+}
+
+#[test]
+fn test_selection_highlight_blending() {
+    use bevy_terminal::config::{TerminalConfig, TerminalTheme};
+    use bevy_terminal::render_node::{update_terminal_extraction, ExtractedTerminalData};
+    use bevy_terminal::TerminalPalette;
+
+    let theme = TerminalTheme {
+        selection_color: [0x33, 0x46, 0x7c],
+        selection_alpha: 0.5,
+        cursor_alpha: 0.25,
+        cursor_color: None,
+    };
+    let mut app = init_texture_app(false);
+    common::component_mut::<TerminalConfig>(&mut app).theme = theme;
+    app.add_systems(PostUpdate, update_terminal_extraction);
+    app.update();
+
+    // Opacity rides in the color's alpha byte, cursor opacity as is
+    let uniforms = common::component::<ExtractedTerminalData>(&app).uniforms();
+    assert_eq!(uniforms.selection_color, 0x807c4633);
+    assert_eq!(uniforms.cursor_opacity, 0.25);
+
+    // White-on-black "A█", both selected, shaded by the CPU backend
+    let render = |theme: TerminalTheme| {
+        let mut term_state = TerminalState::new();
+        term_state.process_bytes("\x1b[38;2;255;255;255;48;2;0;0;0mA█".as_bytes());
+        term_state.set_selection(term_state.viewport_to_grid(0, 0), term_state.viewport_to_grid(1, 0));
+        let config = TerminalConfig { theme, show_cursor: false, ..Default::default() };
+        common::render_cpu(term_state, config)
+    };
+    let red = TerminalTheme { selection_color: [0xff, 0x00, 0x00], ..theme };
+    let frame = render(red);
+
+    // The highlight tints the cell without hiding the text: half red over
+    // the black background and the white glyph alike. The full block leaves
+    // the cell's last column partly uncovered.
+    let (highlight_bg, highlight_fg) = ([0x80, 0x00, 0x00, 0xff], [0xff, 0x7f, 0x7f, 0xff]);
+    let block = frame.cell(1, 0);
+    let mut solid = block.chunks(frame.cell_width as usize).flat_map(|row| &row[..row.len() - 1]);
+    assert!(solid.all(|&texel| texel == highlight_fg));
+    let text = frame.cell(0, 0);
+    assert_eq!(text[0], highlight_bg);
+    assert!(text.iter().any(|texel| texel[1] > 0x40), "Text hidden by the highlight");
+    // Unselected cells keep their colors
+    let [r, g, b] = TerminalPalette::default().background;
+    assert_eq!(frame.cell(2, 0)[0], [r, g, b, 0xff]);
+
+    // Fully opaque, the text is gone
+    let frame = render(TerminalTheme { selection_alpha: 1.0, ..red });
+    assert!(frame.cell(0, 0).iter().all(|&texel| texel == [0xff, 0x00, 0x00, 0xff]));
+}
+
+#[test]
+fn test_fragment_target_needs_render_attachment() {
+    use bevy_terminal::config::RenderBackend;
+    use bevy_terminal::renderer::validate_target_image_for;
+
+    let format = TextureFormat::Rgba8Unorm;
+    let render_usage = TextureUsages::TEXTURE_BINDING | TextureUsages::RENDER_ATTACHMENT;
+    let image = make_image(960, 420, format, render_usage);
+    assert!(validate_target_image_for(&image, 960, 420, format, RenderBackend::Fragment).is_ok());
+    assert!(validate_target_image_for(&image, 960, 420, format, RenderBackend::Compute).is_err());
+
+    let image = make_image(960, 420, format, STORAGE_USAGE);
+    let error = validate_target_image_for(&image, 960, 420, format, RenderBackend::Fragment).unwrap_err();
+    assert!(error.to_string().contains("RENDER_ATTACHMENT"), "unexpected error: {}", error);
+}
+
+#[test]
+fn test_fragment_backend_texture() {
+    use bevy_terminal::config::RenderBackend;
+    use bevy_terminal::renderer::ActiveRenderBackend;
+
+    let mut app = common::image_app();
+    app.insert_resource(common::mvp_atlas())
+        .insert_resource(ActiveRenderBackend(RenderBackend::Fragment))
+        .add_systems(Startup, initialize_terminal_textures);
+    app.world_mut().spawn(TerminalState::new());
+    app.update();
+
+    // Drawn as a render target, never bound as storage
+    let usage = texture_usage(&app);
+    assert!(usage.contains(TextureUsages::RENDER_ATTACHMENT));
+    assert!(!usage.contains(TextureUsages::STORAGE_BINDING));
+}
+
+#[test]
+fn test_fragment_cell_instances() {
+    use bevy_terminal::fragment::cell_instances;
+    use bevy_terminal::gpu_types::{
+        GpuTerminalCell, GLYPH_BLANK, INSTANCE_CELL, INSTANCE_WATERMARK, STATUS_BAR_BOTTOM,
+        STATUS_BAR_TOP,
+    };
+    use bevy_terminal::render_node::ExtractedTerminalData;
+
+    let cell = |glyph_index| GpuTerminalCell {
+        glyph_index,
+        fg_color: 0xFFFFFFFF,
+        bg_color: 0xFF000000,
+        flags: 0,
+    };
+    let mut data = ExtractedTerminalData {
+        cells: (0..6).map(cell).collect(),
+        status_cells: (10..13).map(cell).collect(),
+        status_bar: STATUS_BAR_TOP,
+        watermark_cells: vec![cell(20), cell(GLYPH_BLANK), cell(21), cell(22)],
+        watermark_col: 0,
+        watermark_row: 1,
+        watermark_opacity: 0.5,
+        blink_phase: 0.0,
+        cursor_blink: 0,
+        cursor_color: 0,
+        cursor_style: 0,
+        selection_color: 0,
+        cursor_alpha: 1.0,
+        blank: false,
+        cursor_cell: None,
+        background: [0; 3],
+        force_redraw: false,
+        texture_handle: Handle::default(),
+        atlas_texture_handle: Handle::default(),
+        term_cols: 3,
+        term_rows: 2,
+        cell_width: 8,
+        cell_height: 14,
+        atlas_cols: 16,
+        atlas_rows: 16,
+    };
+
+    let placement = |data: &ExtractedTerminalData| -> Vec<(u32, u32, u32, u32)> {
+        cell_instances(data)
+            .iter()
+            .map(|instance| (instance.cell.glyph_index, instance.column, instance.row, instance.kind))
+            .collect()
+    };
+
+    // Top status bar takes row 0 and pushes the grid down; the blank and
+    // off-texture watermark glyphs are dropped, the rest come last
+    assert_eq!(
+        placement(&data),
+        vec![
+            (0, 0, 1, INSTANCE_CELL),
+            (1, 1, 1, INSTANCE_CELL),
+            (2, 2, 1, INSTANCE_CELL),
+            (3, 0, 2, INSTANCE_CELL),
+            (4, 1, 2, INSTANCE_CELL),
+            (5, 2, 2, INSTANCE_CELL),
+            (10, 0, 0, INSTANCE_CELL),
+            (11, 1, 0, INSTANCE_CELL),
+            (12, 2, 0, INSTANCE_CELL),
+            (20, 0, 1, INSTANCE_WATERMARK),
+            (21, 2, 1, INSTANCE_WATERMARK),
+        ]
+    );
+
+    // Bottom status bar goes below the grid
+    data.status_bar = STATUS_BAR_BOTTOM;
+    data.watermark_cells.clear();
+    let rows: Vec<u32> = placement(&data).iter().map(|&(_, _, row, _)| row).collect();
+    assert_eq!(rows, vec![0, 0, 0, 1, 1, 1, 2, 2, 2]);
+
+    // GPU buffers are sized by cell count; an empty watermark still takes
+    // one cell, so it matches a one-cell watermark
+    let cell_bytes = std::mem::size_of::<GpuTerminalCell>() as u64;
+    assert_eq!(data.buffer_sizes(), [6 * cell_bytes, 3 * cell_bytes, cell_bytes]);
+    data.watermark_cells.push(cell(20));
+    assert_eq!(data.buffer_sizes(), [6 * cell_bytes, 3 * cell_bytes, cell_bytes]);
+    data.cells.extend((6..9).map(cell));
+    assert_eq!(data.buffer_sizes()[0], 9 * cell_bytes);
+}
+
+#[test]
+fn test_cpu_backend_draws_pixels() {
+    use bevy_terminal::config::{RenderBackend, TerminalConfig};
+    use bevy_terminal::gpu_prep::prepare_terminal_cpu_buffer;
+    use bevy_terminal::render_node::update_terminal_extraction;
+    use bevy_terminal::renderer::{color_pixel, validate_target_image_for, ActiveRenderBackend};
+    use bevy_terminal::software::render_terminal_to_texture;
+    use bevy_terminal::TerminalPalette;
+
+    let atlas = common::mvp_atlas();
+    let (cell_width, cell_height) = (atlas.cell_width, atlas.cell_height);
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes(b"\x1b[38;2;255;255;255;48;2;200;40;10mW\x1b[0m");
+
+    let mut app = common::image_app();
+    common::insert_atlas(&mut app, atlas);
+    let config = TerminalConfig {
+        render_backend: RenderBackend::Cpu,
+        ..Default::default()
+    };
+    app.world_mut().spawn((term_state, config));
+    app.insert_resource(ActiveRenderBackend(RenderBackend::Cpu))
+        .add_systems(Startup, initialize_terminal_textures)
+        .add_systems(Update, prepare_terminal_cpu_buffer)
+        .add_systems(PostUpdate, (update_terminal_extraction, render_terminal_to_texture).chain());
+    app.update();
+
+    // Uploaded from the CPU: copied into, never bound as storage
+    let usage = texture_usage(&app);
+    assert!(usage.contains(TextureUsages::COPY_DST));
+    assert!(!usage.contains(TextureUsages::STORAGE_BINDING));
+
+    let texture = common::component::<TerminalTexture>(&app);
+    let image = app.world().resource::<Assets<Image>>().get(&texture.handle).expect("Texture missing");
+    let format = image.texture_descriptor.format;
+    let width = image.width();
+    let data = image.data.as_ref().expect("Texture has no data");
+    let texel_size = background_pixel(format).len();
+    let texel = |x: u32, y: u32| {
+        let offset = (y * width + x) as usize * texel_size;
+        &data[offset..offset + texel_size]
+    };
+
+    // The colored cell's corners are its background, the glyph its foreground
+    let bg = color_pixel([200, 40, 10], format);
+    assert_eq!(texel(0, 0), bg.as_slice());
+    assert_eq!(texel(cell_width - 1, cell_height - 1), bg.as_slice());
+    let fg = color_pixel([255, 255, 255], format);
+    let glyph = (0..cell_height).flat_map(|y| (0..cell_width).map(move |x| (x, y)));
+    // Coverage tops out just under 255, so the stem is a shade off fg
+    let near_fg = |texel: &[u8]| texel.iter().zip(&fg).all(|(a, b)| a.abs_diff(*b) <= 2);
+    assert!(glyph.clone().any(|(x, y)| near_fg(texel(x, y))));
+
+    // Blank cells are the palette background
+    let blank = color_pixel(TerminalPalette::default().background, format);
+    assert!(glyph.map(|(x, y)| (x + 5 * cell_width, y + 3 * cell_height)).all(|(x, y)| texel(x, y) == blank.as_slice()));
+
+    // A CPU target needs to be copied into, not STORAGE_BINDING
+    let render_usage = TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST;
+    let target = make_image(960, 420, format, render_usage);
+    assert!(validate_target_image_for(&target, 960, 420, format, RenderBackend::Cpu).is_ok());
+    let target = make_image(960, 420, format, TextureUsages::TEXTURE_BINDING);
+    let error = validate_target_image_for(&target, 960, 420, format, RenderBackend::Cpu).unwrap_err();
+    assert!(error.to_string().contains("COPY_DST"), "unexpected error: {}", error);
+}
+
+#[test]
+fn test_glyph_edges_have_no_dark_fringe() {
+    use bevy_terminal::config::TerminalConfig;
+
+    // Yellow text on white, shaded by the CPU backend
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes(b"\x1b[38;2;255;255;0;48;2;255;255;255mW@g\x1b[0m");
+    let frame = common::render_cpu(term_state, TerminalConfig { show_cursor: false, ..Default::default() });
+
+    // Anti-aliased edges fade from yellow to white: red and green never
+    // drop, only blue varies between the two
+    let texels: Vec<[u8; 4]> = (0..3).flat_map(|col| frame.cell(col, 0)).collect();
+    for texel in &texels {
+        assert_eq!(texel[..2], [0xff, 0xff], "Dark fringe texel {:?}", texel);
+    }
+    assert!(texels.iter().any(|texel| texel[2] < 0x10), "Glyph not drawn");
+    assert!(texels.iter().any(|texel| texel[2] > 0x00 && texel[2] < 0xff), "No anti-aliased edge");
+}
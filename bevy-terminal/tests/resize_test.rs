@@ -0,0 +1,114 @@
+//! Tests for resizing the terminal at runtime.
+
+mod common;
+
+use alacritty_terminal::grid::Dimensions;
+use bevy::prelude::*;
+use bevy_terminal::atlas::GlyphAtlas;
+use bevy_terminal::font::FontMetrics;
+use bevy_terminal::pty::PtyResource;
+use bevy_terminal::renderer::initialize_terminal_textures;
+use bevy_terminal::resize::{apply_terminal_resize, ResizeTerminal};
+use bevy_terminal::{TerminalState, TerminalTexture};
+
+fn setup_app() -> App {
+    let font_metrics = FontMetrics::load_cascadia_mono().expect("Font load failed");
+    let atlas = GlyphAtlas::generate_mvp(&font_metrics).expect("Atlas failed");
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_asset::<Image>()
+        .add_message::<ResizeTerminal>()
+        .insert_resource(atlas)
+        .add_systems(Startup, initialize_terminal_textures)
+        .add_systems(Update, apply_terminal_resize);
+    app.world_mut().spawn(TerminalState::new());
+    app.update();
+    app
+}
+
+#[test]
+fn test_resize_grid_and_texture() {
+    let mut app = setup_app();
+    let (cell_width, cell_height) = {
+        let atlas = app.world().resource::<GlyphAtlas>();
+        (atlas.cell_width, atlas.cell_height)
+    };
+    let terminal = common::terminal(&mut app);
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"0123456789abcdef");
+
+    // Narrower: the line is rewrapped at the new width. The cursor line
+    // stays put, so the wrapped head moves up into scrollback.
+    app.world_mut().write_message(ResizeTerminal { terminal, cols: 10, rows: 30 });
+    app.update();
+    let term_state = common::component::<TerminalState>(&app);
+    assert!(term_state.get_visible_text().starts_with("abcdef    \n"));
+    assert_eq!(term_state.term.lock().grid().history_size(), 1);
+
+    // Only the last request in a frame applies
+    app.world_mut().write_message(ResizeTerminal { terminal, cols: 100, rows: 20 });
+    app.world_mut().write_message(ResizeTerminal { terminal, cols: 40, rows: 5 });
+    app.update();
+
+    let term_state = common::component::<TerminalState>(&app);
+    assert_eq!((term_state.cols, term_state.rows), (40, 5));
+    assert_eq!(term_state.actual_dimensions(), (40, 5));
+
+    let texture = common::component::<TerminalTexture>(&app);
+    assert_eq!((texture.width, texture.height), (cell_width * 40, cell_height * 5));
+    let image = app.world().resource::<Assets<Image>>().get(&texture.handle).expect("Image missing");
+    assert_eq!((image.width(), image.height()), (texture.width, texture.height));
+
+    // Zero-sized requests clamp to one cell
+    app.world_mut().write_message(ResizeTerminal { terminal, cols: 0, rows: 0 });
+    app.update();
+    let term_state = common::component::<TerminalState>(&app);
+    assert_eq!((term_state.cols, term_state.rows), (1, 1));
+}
+
+#[test]
+fn test_resize_pty() {
+    let pty = PtyResource::new().expect("Failed to create PTY");
+    assert_eq!(pty.size(), Some((120, 30)));
+
+    pty.resize(80, 24).expect("Resize failed");
+    assert_eq!(pty.size(), Some((80, 24)));
+}
+
+#[test]
+fn test_autosize_from_sprite_size() {
+    use bevy::ecs::world::CommandQueue;
+    use bevy_terminal::sprite::{autosize_terminal, cells_fitting, spawn_terminal_sprite, TerminalAutosize};
+
+    // 960 world units of 8-texel cells at one unit per texel, then at 2×
+    assert_eq!(cells_fitting(960.0, 8, 1.0), 120);
+    assert_eq!(cells_fitting(965.0, 8, 1.0), 120);
+    assert_eq!(cells_fitting(960.0, 8, 2.0), 60);
+    // Degenerate sizes still leave one cell
+    assert_eq!(cells_fitting(3.0, 8, 1.0), 1);
+    assert_eq!(cells_fitting(960.0, 8, 0.0), 1);
+
+    let mut app = setup_app();
+    app.add_systems(Update, autosize_terminal.before(apply_terminal_resize));
+    let (cell_width, cell_height) = {
+        let atlas = app.world().resource::<GlyphAtlas>();
+        (atlas.cell_width, atlas.cell_height)
+    };
+
+    // Half-scale sprite: twice the cells fit in the same world size, from
+    // the first frame (before transform propagation has run)
+    let terminal = common::terminal(&mut app);
+    let mut queue = CommandQueue::default();
+    let texture = common::component::<TerminalTexture>(&app);
+    let mut commands = Commands::new(&mut queue, app.world());
+    let sprite = spawn_terminal_sprite(&mut commands, terminal, texture, Transform::from_scale(Vec3::splat(0.5)));
+    commands.entity(sprite).insert(TerminalAutosize {
+        terminal,
+        width: 40.0 * cell_width as f32,
+        height: Some(10.0 * cell_height as f32),
+    });
+    queue.apply(app.world_mut());
+    app.update();
+    let term_state = common::component::<TerminalState>(&app);
+    assert_eq!((term_state.cols, term_state.rows), (80, 20));
+}
@@ -0,0 +1,219 @@
+//! Tests for viewport behavior when output arrives while scrolled up.
+
+use bevy_terminal::TerminalState;
+
+mod common;
+
+/// Fill the screen and push some lines into history.
+fn fill_history(term_state: &mut TerminalState) {
+    for i in 0..100 {
+        term_state.process_bytes(format!("line {}\r\n", i).as_bytes());
+    }
+}
+
+#[test]
+fn test_scroll_on_output_resets_viewport() {
+    let mut term_state = TerminalState::new();
+    fill_history(&mut term_state);
+
+    term_state.scroll_viewport(10);
+    assert_eq!(term_state.display_offset(), 10);
+
+    term_state.process_bytes(b"new output\r\n");
+    assert_eq!(term_state.display_offset(), 0);
+    assert!(!term_state.has_unseen_output());
+}
+
+#[test]
+fn test_viewport_stays_without_scroll_on_output() {
+    let mut term_state = TerminalState::new().with_scroll_on_output(false);
+    fill_history(&mut term_state);
+
+    term_state.scroll_viewport(10);
+    assert!(!term_state.has_unseen_output());
+
+    // Two new lines push the view further from the bottom so it stays on the same content
+    term_state.process_bytes(b"new output\r\nmore\r\n");
+    assert_eq!(term_state.display_offset(), 12);
+    assert!(term_state.has_unseen_output());
+
+    // Returning to the bottom clears the indicator
+    term_state.scroll_viewport(-100);
+    assert_eq!(term_state.display_offset(), 0);
+    assert!(!term_state.has_unseen_output());
+}
+
+#[test]
+fn test_history_budget_bounds_memory() {
+    const BUDGET: usize = 256 * 1024;
+
+    let mut unbounded = TerminalState::new();
+    let mut bounded = TerminalState::new().with_history_budget(BUDGET);
+
+    // Wide lines, far more than the budget can hold
+    let line = format!("{}\r\n", "x".repeat(119));
+    for _ in 0..2_000 {
+        unbounded.process_bytes(line.as_bytes());
+        bounded.process_bytes(line.as_bytes());
+    }
+
+    assert!(unbounded.history_bytes() > BUDGET);
+    assert!(
+        bounded.history_bytes() <= BUDGET,
+        "history uses {} bytes, budget is {}",
+        bounded.history_bytes(),
+        BUDGET
+    );
+
+    // Still keeps as much history as fits, evicting only the oldest lines
+    assert!(bounded.history_bytes() > BUDGET / 2);
+    bounded.process_bytes(b"newest");
+    assert!(bounded.get_visible_text().lines().last().unwrap().starts_with("newest"));
+    assert!(bounded.history_bytes() <= BUDGET);
+}
+
+#[test]
+fn test_history_budget_holds_after_resize() {
+    const BUDGET: usize = 256 * 1024;
+
+    let mut term_state = TerminalState::new().with_history_budget(BUDGET);
+    let line = format!("{}\r\n", "x".repeat(119));
+    for _ in 0..2_000 {
+        term_state.process_bytes(line.as_bytes());
+    }
+
+    // Doubling the width doubles the cost of every history line
+    term_state.resize_terminal(240, 30);
+    assert!(term_state.history_bytes() <= BUDGET, "{} bytes after resize", term_state.history_bytes());
+
+    let wide = format!("{}\r\n", "x".repeat(239));
+    for _ in 0..2_000 {
+        term_state.process_bytes(wide.as_bytes());
+    }
+    assert!(term_state.history_bytes() <= BUDGET, "{} bytes after wide output", term_state.history_bytes());
+    assert!(term_state.history_bytes() > BUDGET / 2);
+}
+
+#[test]
+fn test_alt_screen_flag() {
+    let mut term_state = TerminalState::new();
+    assert!(!term_state.is_alt_screen());
+
+    // What vim/less do on startup and exit
+    term_state.process_bytes(b"\x1b[?1049h");
+    assert!(term_state.is_alt_screen());
+
+    term_state.process_bytes(b"\x1b[?1049l");
+    assert!(!term_state.is_alt_screen());
+}
+
+#[test]
+fn test_scrolled_viewport_renders_history() {
+    use bevy::prelude::*;
+    use bevy_terminal::gpu_prep::TerminalCpuBuffer;
+    use bevy_terminal::gpu_types::GLYPH_BLANK;
+
+    let atlas = common::ascii_atlas();
+    let glyphs = |text: &str| -> Vec<u32> {
+        text.chars()
+            .map(|c| if c == ' ' { GLYPH_BLANK } else { atlas.get_glyph_index(c).unwrap() })
+            .collect()
+    };
+    let (line_71, line_61) = (glyphs("line 71"), glyphs("line 61"));
+
+    // 100 lines on a 30-row screen leave "line 71" on the top row
+    let mut term_state = TerminalState::new();
+    fill_history(&mut term_state);
+
+    let mut app = common::prep_app(term_state);
+
+    let top_row = |app: &App| -> Vec<u32> {
+        let cells = &common::component::<TerminalCpuBuffer>(app).cells;
+        cells[..7].iter().map(|cell| cell.glyph_index).collect()
+    };
+    app.update();
+    assert_eq!(top_row(&app), line_71);
+
+    common::component_mut::<TerminalState>(&mut app).scroll_viewport(10);
+    app.update();
+    assert_eq!(top_row(&app), line_61);
+}
+
+#[test]
+fn test_wheel_scrolls_viewport() {
+    use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+    use bevy::prelude::*;
+    use bevy_terminal::input::scroll_viewport_on_wheel;
+    use bevy_terminal::PrimaryTerminal;
+
+    let mut term_state = TerminalState::new();
+    fill_history(&mut term_state);
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_message::<MouseWheel>();
+    app.world_mut().spawn((term_state, PrimaryTerminal));
+    app.add_systems(Update, scroll_viewport_on_wheel);
+
+    let scroll = |app: &mut App, unit, y| {
+        app.world_mut().write_message(MouseWheel { unit, x: 0.0, y, window: Entity::PLACEHOLDER });
+        app.update();
+        common::component::<TerminalState>(app).display_offset()
+    };
+
+    // One notch up is three lines; pixel deltas add up until they make a line
+    assert_eq!(scroll(&mut app, MouseScrollUnit::Line, 1.0), 3);
+    assert_eq!(scroll(&mut app, MouseScrollUnit::Pixel, 0.5), 3);
+    assert_eq!(scroll(&mut app, MouseScrollUnit::Pixel, 0.5), 4);
+
+    // Clamped at the bottom of history
+    assert_eq!(scroll(&mut app, MouseScrollUnit::Line, -10.0), 0);
+
+    // Programs tracking the mouse get the wheel instead
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"\x1b[?1000h");
+    assert_eq!(scroll(&mut app, MouseScrollUnit::Line, 1.0), 0);
+}
+
+#[test]
+fn test_selection_spans_scrollback() {
+    use bevy::prelude::*;
+    use bevy_terminal::gpu_prep::TerminalCpuBuffer;
+    use bevy_terminal::gpu_types::CELL_FLAG_SELECTED;
+
+    let mut term_state = TerminalState::new();
+    fill_history(&mut term_state);
+    assert_eq!(term_state.copy_selection(), "");
+
+    // Press on "line 61" scrolled back, release on "line 71" at the bottom
+    term_state.scroll_viewport(10);
+    let start = term_state.viewport_to_grid(0, 0);
+    term_state.scroll_viewport(-10);
+    let end = term_state.viewport_to_grid(3, 0);
+    term_state.set_selection(end, start);
+    assert!(term_state.has_selection());
+
+    let expected: String = (61..71).map(|i| format!("line {}\n", i)).collect::<String>() + "line";
+    assert_eq!(term_state.copy_selection(), expected);
+
+    let mut app = common::prep_app(term_state);
+
+    let selected = |app: &App, row: usize| -> Vec<bool> {
+        let cols = common::component::<TerminalState>(app).cols;
+        let cells = &common::component::<TerminalCpuBuffer>(app).cells;
+        cells[row * cols..row * cols + 8].iter().map(|cell| cell.flags & CELL_FLAG_SELECTED != 0).collect()
+    };
+
+    // At the bottom only "line" of the top row is selected
+    app.update();
+    assert_eq!(selected(&app, 0), [true, true, true, true, false, false, false, false]);
+    assert_eq!(selected(&app, 1), [false; 8]);
+
+    // Scrolled back, the selection runs to the end of each history row
+    common::component_mut::<TerminalState>(&mut app).scroll_viewport(10);
+    app.update();
+    assert_eq!(selected(&app, 0), [true; 8]);
+
+    common::component_mut::<TerminalState>(&mut app).clear_selection();
+    app.update();
+    assert_eq!(selected(&app, 0), [false; 8]);
+}
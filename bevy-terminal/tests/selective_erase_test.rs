@@ -0,0 +1,57 @@
+//! Tests for DECSCA protected cells and selective erase (DECSED/DECSEL).
+//!
+//! alacritty doesn't track the protected attribute and ignores selective
+//! erase, so `CSI ? J`/`CSI ? K` are no-ops. That errs on the safe side:
+//! protected fields in legacy form-based TUIs are never wiped, and the
+//! sequences must not leak into the grid as text.
+
+use bevy_terminal::TerminalState;
+
+/// Trimmed text of `row`.
+fn row_text(term_state: &TerminalState, row: usize) -> String {
+    term_state
+        .get_visible_text()
+        .lines()
+        .nth(row)
+        .unwrap_or("")
+        .trim_end()
+        .to_string()
+}
+
+#[test]
+fn test_selective_erase_display_keeps_protected() {
+    let mut term_state = TerminalState::new();
+
+    // Protected label, unprotected field, then selective erase of the display
+    term_state.process_bytes(b"\x1b[1\"qName:\x1b[0\"q ____\r\nNext");
+    term_state.process_bytes(b"\x1b[?2J");
+
+    assert_eq!(row_text(&term_state, 0), "Name: ____");
+    assert_eq!(row_text(&term_state, 1), "Next");
+
+    // Parameters 0 and 1 (below/above cursor) are equally harmless
+    term_state.process_bytes(b"\x1b[1;1H\x1b[?0J\x1b[?1J");
+    assert_eq!(row_text(&term_state, 0), "Name: ____");
+}
+
+#[test]
+fn test_selective_erase_line_keeps_protected() {
+    let mut term_state = TerminalState::new();
+
+    term_state.process_bytes(b"\x1b[1\"qID:\x1b[0\"q 42");
+    term_state.process_bytes(b"\x1b[1;3H\x1b[?0K\x1b[?1K\x1b[?2K");
+
+    assert_eq!(row_text(&term_state, 0), "ID: 42");
+}
+
+#[test]
+fn test_regular_erase_still_clears() {
+    let mut term_state = TerminalState::new();
+
+    // DECSCA only guards against selective erase; ED/EL clear everything
+    term_state.process_bytes(b"\x1b[1\"qkeep\x1b[0\"q\r\nline");
+    term_state.process_bytes(b"\x1b[2K\x1b[1;1H\x1b[2K");
+
+    assert_eq!(row_text(&term_state, 0), "");
+    assert_eq!(row_text(&term_state, 1), "");
+}
@@ -0,0 +1,91 @@
+//! Tests for shell integration marks (OSC 133) becoming terminal events.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_terminal::events::{TerminalEvent, TerminalEventKind};
+use bevy_terminal::shell_integration::dispatch_prompt_marks;
+use bevy_terminal::TerminalState;
+
+fn marks_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_message::<TerminalEvent>()
+        .add_systems(Update, dispatch_prompt_marks);
+    app.world_mut().spawn(TerminalState::new());
+    app
+}
+
+/// Feed `bytes` in one frame and describe the events it sent.
+fn process(app: &mut App, bytes: &[u8]) -> Vec<String> {
+    common::component_mut::<TerminalState>(app).process_bytes(bytes);
+    app.update();
+    app.world_mut()
+        .resource_mut::<Messages<TerminalEvent>>()
+        .drain()
+        .map(|event| match event.kind {
+            TerminalEventKind::PromptStart => "prompt".to_string(),
+            TerminalEventKind::CommandStart => "command".to_string(),
+            TerminalEventKind::CommandExecuted => "executed".to_string(),
+            TerminalEventKind::CommandEnd { exit_code } => format!("end {:?}", exit_code),
+            other => panic!("unexpected event {:?}", other),
+        })
+        .collect()
+}
+
+#[test]
+fn test_osc_133_events_in_order() {
+    let mut app = marks_app();
+
+    // One command cycle as bash/zsh integration emits it
+    let events = process(
+        &mut app,
+        b"\x1b]133;A\x07~/src $ \x1b]133;B\x07ls\r\n\x1b]133;C\x07README.md\r\n\x1b]133;D;0\x07",
+    );
+    assert_eq!(events, vec!["prompt", "command", "executed", "end Some(0)"]);
+
+    // The marks don't draw anything
+    let text = common::component::<TerminalState>(&app).get_visible_text();
+    let lines: Vec<&str> = text.lines().take(2).map(str::trim_end).collect();
+    assert_eq!(lines, vec!["~/src $ ls", "README.md"]);
+
+    // A mark split across reads fires once, when complete
+    assert!(process(&mut app, b"\x1b]133;D;").is_empty());
+    assert_eq!(process(&mut app, b"127\x1b\\\x1b]133;A\x07"), vec!["end Some(127)", "prompt"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_bash_prompt_marks_injected() {
+    use bevy_terminal::pty::PtyResource;
+    use bevy_terminal::TerminalPlugin;
+    use std::time::{Duration, Instant};
+
+    // An empty home keeps the user's own ~/.bashrc out of the test
+    let home = std::env::temp_dir().join(format!("bevy-terminal-marks-{}", std::process::id()));
+    std::fs::create_dir_all(&home).unwrap();
+    let config = TerminalPlugin::default()
+        .with_shell("bash", Vec::<String>::new())
+        .with_env("HOME", home.to_string_lossy())
+        .with_shell_integration(true)
+        .config;
+    let pty = PtyResource::with_config(80, 24, &config).expect("Failed to create PTY");
+    let mut app = marks_app();
+
+    // Run a failing command and collect marks until the next prompt is up
+    let mut events = Vec::new();
+    let start = Instant::now();
+    while !events.contains(&"command".to_string()) {
+        assert!(start.elapsed() < Duration::from_secs(5), "No prompt, got {:?}", events);
+        events.extend(process(&mut app, &pty.read_available(Duration::from_millis(100))));
+    }
+    pty.write_all(b"false\r").expect("Write failed");
+    while events.iter().filter(|event| *event == "command").count() < 2 {
+        assert!(start.elapsed() < Duration::from_secs(5), "No second prompt, got {:?}", events);
+        events.extend(process(&mut app, &pty.read_available(Duration::from_millis(100))));
+    }
+
+    assert_eq!(events, vec!["prompt", "command", "executed", "end Some(1)", "prompt", "command"]);
+    drop(pty);
+    let _ = std::fs::remove_dir_all(home);
+}
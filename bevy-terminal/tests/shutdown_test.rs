@@ -0,0 +1,47 @@
+//! Tests for tearing the terminal down on AppExit.
+
+use bevy::prelude::*;
+use bevy_terminal::events::{TerminalEvent, TerminalEventKind};
+use bevy_terminal::pty::PtyResource;
+use bevy_terminal::shutdown::shutdown_terminal_on_exit;
+use bevy_terminal::TerminalState;
+
+#[test]
+fn test_app_exit_kills_shell() {
+    let pty = PtyResource::new().expect("Failed to create PTY");
+    let pid = pty.pid().expect("Spawned shell should report a PID");
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes(b"first line\r\nsecond line\r\n");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_message::<TerminalEvent>()
+        .add_systems(Last, shutdown_terminal_on_exit);
+    let terminal = app.world_mut().spawn((term_state, pty)).id();
+
+    // Business as usual until the app exits
+    app.update();
+    assert!(app.world().entity(terminal).contains::<PtyResource>());
+
+    app.world_mut().write_message(AppExit::Success);
+    app.update();
+    assert!(app.world().get_entity(terminal).is_err(), "Terminal should be despawned");
+
+    // The final event carries the transcript
+    let events: Vec<TerminalEvent> = app.world_mut().resource_mut::<Messages<TerminalEvent>>().drain().collect();
+    assert!(
+        matches!(&events[..], [TerminalEvent { terminal: sender, kind: TerminalEventKind::Shutdown { transcript } }]
+            if *sender == terminal && transcript == "first line\nsecond line"),
+        "Expected one Shutdown event, got {:?}",
+        events
+    );
+
+    // Killed and reaped: signal 0 finds no such process
+    let alive = std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .expect("Failed to run kill")
+        .success();
+    assert!(!alive, "Shell {} should be gone after AppExit", pid);
+}
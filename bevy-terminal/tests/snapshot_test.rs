@@ -0,0 +1,63 @@
+//! Tests for saving and restoring terminal state.
+
+use bevy_terminal::TerminalState;
+
+/// Colored, bold and combining text on screen, history above it, the cursor
+/// mid-line and a few modes and overrides set.
+fn draw_session(term_state: &mut TerminalState) {
+    for i in 0..50 {
+        term_state.process_bytes(format!("history {}\r\n", i).as_bytes());
+    }
+    term_state.process_bytes(b"\x1b[1;31mred bold\x1b[0m plain \x1b[44mblue bg\x1b[0m\r\n");
+    term_state.process_bytes("e\u{301}t\u{e9} \u{4e16}\u{754c}\r\n".as_bytes());
+    term_state.process_bytes(b"\x1b[?2004h\x1b[?1h\x1b]11;rgb:12/34/56\x1b\\prompt> ");
+    term_state.set_title("save game");
+}
+
+#[test]
+fn test_snapshot_round_trip() {
+    let mut original = TerminalState::new();
+    draw_session(&mut original);
+    let bytes = original.serialize();
+
+    let mut restored = TerminalState::new();
+    restored.process_bytes(b"\x1b[?25lleftover output");
+    restored.deserialize(&bytes).unwrap();
+
+    assert_eq!(restored.get_visible_text(), original.get_visible_text());
+    assert_eq!(restored.transcript(), original.transcript());
+    assert_eq!(restored.cells().collect::<Vec<_>>(), original.cells().collect::<Vec<_>>());
+
+    let cursor = |state: &TerminalState| state.term.lock().grid().cursor.point;
+    assert_eq!(cursor(&restored), cursor(&original));
+    assert_eq!(restored.term.lock().mode(), original.term.lock().mode());
+    assert!(restored.bracketed_paste());
+    assert_eq!(restored.background_color(), [0x12, 0x34, 0x56]);
+    assert_eq!(restored.title(), "save game");
+
+    // Round-trips again unchanged
+    assert_eq!(restored.serialize(), bytes);
+}
+
+#[test]
+fn test_snapshot_reflows_to_current_size() {
+    let mut original = TerminalState::new();
+    draw_session(&mut original);
+
+    let mut restored = TerminalState::new();
+    restored.resize_terminal(60, 20);
+    restored.deserialize(&original.serialize()).unwrap();
+
+    assert_eq!((restored.cols, restored.rows), (60, 20));
+    restored.check_dimensions();
+    assert_eq!(restored.transcript(), original.transcript());
+}
+
+#[test]
+fn test_snapshot_rejects_garbage() {
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes(b"still here");
+
+    assert!(term_state.deserialize(b"not a snapshot").is_err());
+    assert!(term_state.get_visible_text().starts_with("still here"));
+}
@@ -0,0 +1,162 @@
+//! Tests for custom input sources feeding the terminal.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_terminal::events::{dispatch_terminal_events, report_reader_failures, TerminalEvent, TerminalEventKind};
+use bevy_terminal::source::{poll_source, SourceResource};
+use bevy_terminal::TerminalState;
+use std::io::{Cursor, Read};
+use std::time::{Duration, Instant};
+
+/// Run `poll_source` until the terminal shows `expected_rows` non-empty rows.
+fn render_source(source: SourceResource, expected_rows: usize) -> Vec<String> {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_systems(Update, poll_source);
+    app.world_mut().spawn((TerminalState::new(), source));
+
+    let deadline = Instant::now() + Duration::from_secs(2);
+    loop {
+        app.update();
+        let text = common::component::<TerminalState>(&app).get_visible_text();
+        let rows: Vec<String> = text
+            .lines()
+            .take_while(|line| !line.trim().is_empty())
+            .map(|line| line.trim_end().to_string())
+            .collect();
+        if rows.len() >= expected_rows || Instant::now() > deadline {
+            return rows;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn test_bare_lf_translated() {
+    let source = SourceResource::new(Cursor::new(b"line1\nline2\nline3".to_vec()))
+        .with_newline_translation(true);
+
+    let rows = render_source(source, 3);
+    assert_eq!(rows, vec!["line1", "line2", "line3"]);
+}
+
+#[test]
+fn test_bare_lf_untranslated_staircases() {
+    let source = SourceResource::new(Cursor::new(b"line1\nline2".to_vec()));
+
+    let rows = render_source(source, 2);
+    assert_eq!(rows, vec!["line1", "     line2"]);
+}
+
+#[test]
+fn test_crlf_not_doubled() {
+    let source = SourceResource::new(Cursor::new(b"one\r\ntwo\r\n".to_vec()))
+        .with_newline_translation(true);
+
+    let rows = render_source(source, 2);
+    assert_eq!(rows, vec!["one", "two"]);
+}
+
+/// Reader that yields some output, then panics.
+struct PanickingReader {
+    sent: bool,
+}
+
+impl Read for PanickingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.sent {
+            panic!("simulated reader failure");
+        }
+        self.sent = true;
+        buf[..5].copy_from_slice(b"alive");
+        Ok(5)
+    }
+}
+
+#[test]
+fn test_reader_panic_reported() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_message::<TerminalEvent>()
+        .add_systems(Update, (poll_source, report_reader_failures).chain());
+    let source = SourceResource::new(PanickingReader { sent: false });
+    let terminal = app.world_mut().spawn((TerminalState::new(), source)).id();
+
+    let deadline = Instant::now() + Duration::from_secs(2);
+    let mut events = Vec::new();
+    while events.is_empty() && Instant::now() < deadline {
+        app.update();
+        events.extend(app.world_mut().resource_mut::<Messages<TerminalEvent>>().drain());
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert!(events.iter().all(|event| event.terminal == terminal));
+    let events: Vec<_> = events.into_iter().map(|event| event.kind).collect();
+
+    // Output before the panic still made it through
+    let text = common::component::<TerminalState>(&app).get_visible_text();
+    assert!(text.starts_with("alive"));
+
+    assert_eq!(events.len(), 2, "Expected Error + ProcessExited, got {:?}", events);
+    match &events[0] {
+        TerminalEventKind::Error { message } => assert!(message.contains("simulated reader failure")),
+        other => panic!("Expected Error, got {:?}", other),
+    }
+    assert!(matches!(events[1], TerminalEventKind::ProcessExited { exit_code: None }));
+
+    // Reported once
+    app.update();
+    assert!(app.world_mut().resource_mut::<Messages<TerminalEvent>>().drain().next().is_none());
+}
+
+#[test]
+fn test_sanitized_source_neutralizes_escapes() {
+    // Retitle (OSC 2), alternate screen and a window resize (XTWINOPS 8),
+    // around colored text
+    const HOSTILE: &[u8] = b"\x1b]2;pwned\x07\x1b[?1049h\x1b[8;10;40t\x1b[31mred\x1b[0m ok";
+
+    let run = |sanitize: bool| {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_message::<TerminalEvent>()
+            .add_systems(Update, (poll_source, dispatch_terminal_events).chain());
+        let source = SourceResource::new(Cursor::new(HOSTILE.to_vec())).with_input_sanitizing(sanitize);
+        app.world_mut().spawn((TerminalState::new(), source));
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while !common::component::<TerminalState>(&app).get_visible_text().contains("ok") && Instant::now() < deadline {
+            app.update();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        app
+    };
+    let titles = |app: &mut App| -> Vec<String> {
+        app.world_mut()
+            .resource_mut::<Messages<TerminalEvent>>()
+            .drain()
+            .filter_map(|event| match event.kind {
+                TerminalEventKind::TitleChanged { title } => Some(title),
+                _ => None,
+            })
+            .collect()
+    };
+
+    let size = TerminalState::new().actual_dimensions();
+
+    // Unsanitized, the escapes take effect (the emulator ignores the resize)
+    let mut app = run(false);
+    assert_eq!(titles(&mut app), vec!["pwned"]);
+    let term_state = common::component::<TerminalState>(&app);
+    assert_eq!(term_state.title(), "pwned");
+    assert!(term_state.is_alt_screen());
+
+    // Sanitized, only the text and its color get through
+    let mut app = run(true);
+    assert!(titles(&mut app).is_empty());
+    let term_state = common::component::<TerminalState>(&app);
+    assert!(term_state.get_visible_text().starts_with("red ok"));
+    assert_eq!(term_state.title(), "");
+    assert!(!term_state.is_alt_screen());
+    assert_eq!(term_state.actual_dimensions(), size);
+    let fg = |col: usize| term_state.cells().find(|&(c, row, _)| (c, row) == (col, 0)).unwrap().2.fg;
+    assert_ne!(fg(0), fg(4), "SGR color should survive");
+}
@@ -0,0 +1,107 @@
+//! Tests for `TerminalStats` and the bounded event channel.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_terminal::events::{dispatch_terminal_events, TerminalEvent};
+use bevy_terminal::stats::update_terminal_stats;
+use bevy_terminal::{TerminalState, TerminalStats, DEFAULT_EVENT_CAPACITY};
+
+/// Device status report: each one makes alacritty queue a `PtyWrite` reply.
+const DSR: &[u8] = b"\x1b[5n";
+
+#[test]
+fn test_event_channel_drops_when_full() {
+    let mut term_state = TerminalState::new().with_event_capacity(4);
+    term_state.process_bytes(&DSR.repeat(10));
+
+    // Oldest events are kept, the overflow is counted
+    assert_eq!(term_state.drain_events().len(), 4);
+    assert_eq!(term_state.dropped_events(), 6);
+
+    // Draining makes room again
+    term_state.process_bytes(&DSR.repeat(3));
+    assert_eq!(term_state.drain_events().len(), 3);
+    assert_eq!(term_state.dropped_events(), 6);
+}
+
+#[test]
+fn test_default_capacity_is_generous() {
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes(&DSR.repeat(DEFAULT_EVENT_CAPACITY));
+    assert_eq!(term_state.drain_events().len(), DEFAULT_EVENT_CAPACITY);
+    assert_eq!(term_state.dropped_events(), 0);
+}
+
+#[test]
+fn test_stats_report_dropped_events() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_message::<TerminalEvent>()
+        .add_systems(Update, (dispatch_terminal_events, update_terminal_stats).chain());
+    app.world_mut().spawn(TerminalState::new().with_event_capacity(2));
+
+    app.update();
+    assert_eq!(common::component::<TerminalStats>(&app).dropped_events, 0);
+
+    common::component_mut::<TerminalState>(&mut app).process_bytes(&DSR.repeat(5));
+    app.update();
+    assert_eq!(common::component::<TerminalStats>(&app).dropped_events, 3);
+
+    // The count is cumulative
+    common::component_mut::<TerminalState>(&mut app).process_bytes(&DSR.repeat(3));
+    app.update();
+    assert_eq!(common::component::<TerminalStats>(&app).dropped_events, 4);
+}
+
+#[test]
+fn test_throughput_smoothing() {
+    use std::time::Duration;
+
+    let frame = Duration::from_millis(16);
+    let mut stats = TerminalStats::default();
+
+    // A 64 KiB burst registers immediately
+    stats.record_output(64 * 1024, frame);
+    let burst = stats.bytes_per_second;
+    assert!(burst > 0.0);
+    assert_eq!(stats.output_bytes, 64 * 1024);
+
+    // ...and fades once output stops
+    for _ in 0..10 {
+        stats.record_output(0, frame);
+    }
+    assert!(stats.bytes_per_second < burst * 0.6);
+    for _ in 0..300 {
+        stats.record_output(0, frame);
+    }
+    assert!(stats.bytes_per_second < 1.0);
+
+    // A steady stream converges on its real rate
+    for _ in 0..300 {
+        stats.record_output(1000, Duration::from_millis(100));
+    }
+    assert!((stats.bytes_per_second - 10_000.0).abs() < 1.0);
+}
+
+#[test]
+fn test_poll_pty_measures_throughput() {
+    use bevy_terminal::pty::{poll_pty, PtyResource};
+    use std::time::{Duration, Instant};
+
+    let pty = PtyResource::new().expect("Failed to create PTY");
+    pty.write_all(b"echo throughput_test\n").expect("Write failed");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_systems(Update, poll_pty);
+    app.world_mut().spawn((TerminalState::new(), pty));
+
+    let start = Instant::now();
+    while common::component::<TerminalStats>(&app).output_bytes == 0 {
+        assert!(start.elapsed() < Duration::from_secs(5), "No PTY output");
+        std::thread::sleep(Duration::from_millis(10));
+        app.update();
+    }
+    assert!(common::component::<TerminalStats>(&app).bytes_per_second > 0.0);
+}
@@ -0,0 +1,58 @@
+//! Tests for synchronized updates (`\x1b[?2026h` ... `\x1b[?2026l`).
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_terminal::TerminalState;
+use std::time::Duration;
+
+const BSU: &[u8] = b"\x1b[?2026h";
+const ESU: &[u8] = b"\x1b[?2026l";
+
+fn first_row(term_state: &TerminalState) -> String {
+    term_state
+        .get_visible_text()
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim_end()
+        .to_string()
+}
+
+#[test]
+fn test_synchronized_update_defers_output() {
+    let mut term_state = TerminalState::new();
+    term_state.process_bytes(b"old frame");
+
+    // Redraw in two halves inside one synchronized update
+    term_state.process_bytes(BSU);
+    term_state.process_bytes(b"\x1b[2J\x1b[Hnew ");
+    assert!(term_state.in_synchronized_update());
+    assert_eq!(first_row(&term_state), "old frame", "half-drawn frame must not show");
+
+    term_state.process_bytes(b"frame");
+    term_state.process_bytes(ESU);
+
+    assert!(!term_state.in_synchronized_update());
+    assert_eq!(first_row(&term_state), "new frame");
+}
+
+#[test]
+fn test_unbalanced_synchronized_update_times_out() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_systems(Update, bevy_terminal::flush_expired_synchronized_updates);
+    app.world_mut().spawn(TerminalState::new());
+
+    common::component_mut::<TerminalState>(&mut app).process_bytes(b"\x1b[?2026hnever closed");
+    app.update();
+    assert_eq!(first_row(common::component::<TerminalState>(&app)), "");
+
+    // Past the 150ms timeout the buffered output is applied anyway
+    std::thread::sleep(Duration::from_millis(200));
+    app.update();
+
+    let term_state = common::component::<TerminalState>(&app);
+    assert!(!term_state.in_synchronized_update());
+    assert_eq!(first_row(term_state), "never closed");
+}
@@ -0,0 +1,140 @@
+//! Tests for terminal responses that must be written back to the PTY.
+//!
+//! Programs probe the terminal (cursor position, device attributes, ...) and
+//! block until the reply arrives on stdin. These verify alacritty's replies
+//! reach the PTY writer through `dispatch_terminal_events`.
+
+use bevy::prelude::*;
+use bevy_terminal::config::{TerminalConfig, TerminalTheme};
+use bevy_terminal::events::{dispatch_terminal_events, TerminalEvent};
+use bevy_terminal::pty::{spawn_writer_thread, PtyResource};
+use bevy_terminal::{apply_terminal_palette, TerminalPalette, TerminalState, DEFAULT_CURSOR_COLOR};
+use std::sync::{Arc, Mutex};
+
+/// Stands in for the PTY, keeping every byte written to it.
+struct RecordingWriter(Arc<Mutex<Vec<u8>>>);
+
+impl std::io::Write for RecordingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Feed `output` to the terminal, run one frame of palette and event
+/// dispatch under `config`, and return the bytes written to the PTY.
+fn pty_replies_with(term_state: &mut TerminalState, config: TerminalConfig, output: &[u8]) -> Vec<u8> {
+    let written = Arc::new(Mutex::new(Vec::new()));
+    let mut pty = PtyResource::new().expect("Failed to create PTY");
+    let (writer, writer_thread) = spawn_writer_thread("recording", RecordingWriter(written.clone()));
+    pty.writer = writer;
+
+    term_state.process_bytes(output);
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_message::<TerminalEvent>()
+        .init_resource::<TerminalPalette>()
+        .add_systems(Update, (apply_terminal_palette, dispatch_terminal_events).chain());
+    let terminal = app.world_mut().spawn((std::mem::take(term_state), config, pty)).id();
+    app.update();
+    *term_state = app.world_mut().entity_mut(terminal).take::<TerminalState>().unwrap();
+
+    // Dropping the PTY ends the writer thread once it has written everything
+    drop(app);
+    writer_thread.join().expect("Writer thread panicked");
+    let replies = written.lock().unwrap().clone();
+    replies
+}
+
+/// `pty_replies_with` under the default config.
+fn pty_replies(term_state: &mut TerminalState, output: &[u8]) -> Vec<u8> {
+    pty_replies_with(term_state, TerminalConfig::default(), output)
+}
+
+#[test]
+fn test_cursor_position_report() {
+    let mut term_state = TerminalState::new();
+
+    // CPR request at the home position
+    assert_eq!(pty_replies(&mut term_state, b"\x1b[6n"), b"\x1b[1;1R");
+
+    // Move to row 10, column 5 and ask again
+    assert_eq!(pty_replies(&mut term_state, b"\x1b[10;5H\x1b[6n"), b"\x1b[10;5R");
+
+    // Responses are written exactly once
+    assert!(pty_replies(&mut term_state, b"").is_empty());
+}
+
+#[test]
+fn test_device_attributes() {
+    let mut term_state = TerminalState::new();
+
+    // Primary DA: alacritty identifies as a VT102
+    assert_eq!(pty_replies(&mut term_state, b"\x1b[c"), b"\x1b[?6c");
+
+    // Device status report: "terminal OK"
+    assert_eq!(pty_replies(&mut term_state, b"\x1b[5n"), b"\x1b[0n");
+}
+
+#[test]
+fn test_responses_preserve_order() {
+    let mut term_state = TerminalState::new();
+    assert_eq!(pty_replies(&mut term_state, b"\x1b[c\x1b[3;7H\x1b[6n"), b"\x1b[?6c\x1b[3;7R");
+}
+
+#[test]
+fn test_no_response_without_query() {
+    let mut term_state = TerminalState::new();
+    assert!(pty_replies(&mut term_state, b"plain output\r\n").is_empty());
+}
+
+#[test]
+fn test_text_area_size_report() {
+    let mut term_state = TerminalState::new();
+
+    // XTWINOPS 18: report the text area size in characters
+    assert_eq!(pty_replies(&mut term_state, b"\x1b[18t"), b"\x1b[8;30;120t");
+}
+
+#[test]
+fn test_cursor_color_query() {
+    let mut term_state = TerminalState::new();
+    assert_eq!(term_state.cursor_color(), DEFAULT_CURSOR_COLOR);
+
+    // Query answers with the palette default, using the query's terminator
+    assert_eq!(pty_replies(&mut term_state, b"\x1b]12;?\x07"), b"\x1b]12;rgb:c0c0/caca/f5f5\x07");
+
+    // Setting the color changes later replies
+    let replies = pty_replies(&mut term_state, b"\x1b]12;#ff8000\x07\x1b]12;?\x1b\\");
+    assert_eq!(term_state.cursor_color(), [0xff, 0x80, 0x00]);
+    assert_eq!(replies, b"\x1b]12;rgb:ffff/8080/0000\x1b\\");
+
+    // OSC 112 restores the default
+    pty_replies(&mut term_state, b"\x1b]112\x07");
+    assert_eq!(term_state.cursor_color(), DEFAULT_CURSOR_COLOR);
+}
+
+#[test]
+fn test_theme_cursor_color() {
+    let theme = TerminalTheme {
+        cursor_color: Some([0x12, 0x34, 0x56]),
+        ..Default::default()
+    };
+    let config = || TerminalConfig { theme, ..Default::default() };
+    let mut term_state = TerminalState::new();
+
+    // The theme's color replaces the palette's, and queries report it
+    let replies = pty_replies_with(&mut term_state, config(), b"\x1b]12;?\x07");
+    assert_eq!(replies, b"\x1b]12;rgb:1212/3434/5656\x07");
+    assert_eq!(term_state.cursor_color(), [0x12, 0x34, 0x56]);
+
+    // Programs can still override it, and OSC 112 comes back to the theme
+    pty_replies_with(&mut term_state, config(), b"\x1b]12;#ff8000\x07");
+    assert_eq!(term_state.cursor_color(), [0xff, 0x80, 0x00]);
+    pty_replies_with(&mut term_state, config(), b"\x1b]112\x07");
+    assert_eq!(term_state.cursor_color(), [0x12, 0x34, 0x56]);
+}
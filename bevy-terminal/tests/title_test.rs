@@ -0,0 +1,81 @@
+//! Tests for reading and setting the terminal title.
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_terminal::events::{dispatch_terminal_events, TerminalEvent, TerminalEventKind};
+use bevy_terminal::TerminalState;
+
+fn title_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_message::<TerminalEvent>()
+        .add_systems(Update, dispatch_terminal_events);
+    app.world_mut().spawn(TerminalState::new());
+    app
+}
+
+fn process(app: &mut App, bytes: &[u8]) {
+    common::component_mut::<TerminalState>(app).process_bytes(bytes);
+    app.update();
+}
+
+fn title(app: &App) -> String {
+    common::component::<TerminalState>(app).title()
+}
+
+#[test]
+fn test_title_from_osc() {
+    let mut app = title_app();
+    assert_eq!(title(&app), "");
+
+    process(&mut app, b"\x1b]2;vim README.md\x07");
+    assert_eq!(title(&app), "vim README.md");
+
+    // OSC 0 sets it too, ST-terminated
+    process(&mut app, b"\x1b]0;~/src\x1b\\");
+    assert_eq!(title(&app), "~/src");
+}
+
+/// Titles sent as `TerminalEventKind::TitleChanged` since the last call.
+fn title_events(app: &mut App) -> Vec<String> {
+    app.world_mut()
+        .resource_mut::<Messages<TerminalEvent>>()
+        .drain()
+        .filter_map(|event| match event.kind {
+            TerminalEventKind::TitleChanged { title } => Some(title),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_title_changed_events() {
+    let mut app = title_app();
+
+    // Save the (unset) title first, to restore at the end
+    process(&mut app, b"\x1b[22t\x1b]2;~/src\x07");
+    assert_eq!(title_events(&mut app), ["~/src"]);
+
+    // Prompts re-send the same title every time; only changes are reported
+    process(&mut app, b"\x1b]2;~/src\x07\x1b]0;~/src/game\x07");
+    assert_eq!(title_events(&mut app), ["~/src/game"]);
+
+    // Restoring the unset title resets to the default (empty) one
+    process(&mut app, b"\x1b[23t");
+    assert_eq!(title_events(&mut app), [""]);
+    assert_eq!(title(&app), "");
+}
+
+#[test]
+fn test_set_title() {
+    let mut app = title_app();
+
+    common::component_mut::<TerminalState>(&mut app).set_title("CRT-01");
+    app.update();
+    assert_eq!(title(&app), "CRT-01");
+
+    // The program can replace it
+    process(&mut app, b"\x1b]2;htop\x07");
+    assert_eq!(title(&app), "htop");
+}
@@ -2,7 +2,8 @@
 //!
 //! Demonstrates:
 //! - Character with CRT head showing real terminal
-//! - Terminal texture rendered tiny on sprite
+//! - Terminal texture rendered tiny through the CRT material (scanlines,
+//!   curvature, bloom)
 //! - Zoom interaction (E key to fullscreen)
 //! - Persistent terminal state
 //!
@@ -13,8 +14,7 @@
 use bevy::prelude::*;
 
 use bevy::window::{WindowMode, MonitorSelection};
-use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
-use bevy::asset::RenderAssetUsages;
+use bevy_terminal::crt::{spawn_terminal_crt, CrtMaterial, CrtSettings, TerminalCrt};
 use bevy_terminal::prelude::*;
 use clap::Parser;
 
@@ -55,7 +55,7 @@ fn main() {
 
     app.add_plugins(bevy_brp_extras::BrpExtrasPlugin)
         .insert_resource(args)
-        .add_plugins(TerminalPlugin)
+        .add_plugins(TerminalPlugin::default())
         .add_systems(Startup, setup)
         .add_systems(
             Update,
@@ -80,9 +80,6 @@ struct HelpText;
 #[derive(Component)]
 struct AtlasDebugView;
 
-#[derive(Component)]
-struct TerminalSprite;
-
 /// Current zoom state of the terminal
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ZoomState {
@@ -134,7 +131,7 @@ fn setup(
     commands.insert_resource(TerminalState::default());
 
     // Disable terminal input initially (enabled when zoomed in)
-    commands.insert_resource(TerminalInputEnabled { enabled: false });
+    commands.insert_resource(TerminalInputEnabled { enabled: false, ..default() });
 
     info!("🎮 Claude CRT example ready");
     info!("📺 Terminal will appear when ready (tiny CRT)");
@@ -184,7 +181,7 @@ fn handle_zoom_input(
 fn animate_zoom_transition(
     time: Res<Time>,
     mut terminal_state: ResMut<TerminalState>,
-    mut query: Query<&mut Transform, With<TerminalSprite>>,
+    mut query: Query<&mut Transform, With<TerminalCrt>>,
 ) {
     // Lerp current scale toward target
     let delta = terminal_state.target_scale - terminal_state.current_scale;
@@ -228,22 +225,25 @@ fn update_help_text(
 
 /// Spawn terminal view once the terminal texture is ready.
 ///
-/// Creates a tiny sprite showing the live terminal output (0.05 scale).
+/// Creates a tiny CRT screen showing the live terminal output (0.05 scale).
 /// Use E key to zoom to fullscreen.
 fn spawn_terminal_view(
-    terminal_texture: Option<Res<TerminalTexture>>,
+    terminal: Option<Single<(Entity, &TerminalTexture), With<PrimaryTerminal>>>,
     terminal_state: Res<TerminalState>,
     mut debug_state: ResMut<DebugState>,
     mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<CrtMaterial>>,
 ) {
-    // Only spawn once when resource is available
+    // Only spawn once when the texture is available
     if debug_state.terminal_spawned {
         return;
     }
 
-    let Some(terminal_texture) = terminal_texture else {
+    let Some(terminal) = terminal else {
         return;
     };
+    let (terminal, terminal_texture) = *terminal;
 
     info!("📺 Spawning tiny CRT terminal");
 
@@ -254,15 +254,16 @@ fn spawn_terminal_view(
         terminal_texture.width, terminal_texture.height, scale
     );
 
-    // Spawn tiny sprite showing terminal (CRT on character's head)
-    commands.spawn((
-        Sprite {
-            image: terminal_texture.handle.clone(),
-            ..default()
-        },
+    // Spawn tiny CRT screen showing terminal (on character's head)
+    spawn_terminal_crt(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        terminal,
+        terminal_texture,
+        CrtSettings::default(),
         Transform::from_xyz(0.0, 0.0, 1.0).with_scale(Vec3::splat(scale)),
-        TerminalSprite,
-    ));
+    );
 
     debug_state.terminal_spawned = true;
     info!("✅ Tiny CRT terminal spawned - press 'E' to zoom in!");
@@ -294,20 +295,8 @@ fn spawn_atlas_debug_view(
 
     info!("🐛 Spawning atlas debug view");
 
-    // Create Bevy Image from atlas texture data
-    let image = Image::new(
-        Extent3d {
-            width: atlas.atlas_width,
-            height: atlas.atlas_height,
-            depth_or_array_layers: 1,
-        },
-        TextureDimension::D2,
-        atlas.texture_data.clone(),
-        TextureFormat::Rgba8UnormSrgb,
-        RenderAssetUsages::RENDER_WORLD,
-    );
-
-    let image_handle = images.add(image);
+    // Same image the plugin uploads for the shader
+    let image_handle = images.add(atlas.to_bevy_image());
 
     // Calculate scale to fit atlas on screen (assuming 1920×1080 window)
     let window_height = 1080.0;